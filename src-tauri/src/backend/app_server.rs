@@ -225,6 +225,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
     let mut command = build_codex_command_with_bin(codex_bin);
     apply_codex_args(&mut command, codex_args.as_deref())?;
     command.current_dir(&entry.path);
+    command.envs(&entry.settings.env);
     command.arg("app-server");
     if let Some(codex_home) = codex_home {
         command.env("CODEX_HOME", codex_home);
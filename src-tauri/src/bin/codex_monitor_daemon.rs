@@ -13,6 +13,9 @@ mod file_io;
 mod file_ops;
 #[path = "../files/policy.rs"]
 mod file_policy;
+#[allow(dead_code)]
+#[path = "../git_utils.rs"]
+mod git_utils;
 #[path = "../rules.rs"]
 mod rules;
 #[path = "../storage.rs"]
@@ -26,6 +29,10 @@ mod workspace_settings;
 #[allow(dead_code)]
 #[path = "../types.rs"]
 mod types;
+#[path = "codex_monitor_daemon/rate_limit.rs"]
+mod rate_limit;
+#[path = "codex_monitor_daemon/rpc.rs"]
+mod rpc;
 
 // Provide feature-style module paths for shared cores when compiled in the daemon.
 mod codex {
@@ -60,26 +67,60 @@ use std::fs::File;
 use std::io::Read;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use ignore::WalkBuilder;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
+use tracing::level_filters::LevelFilter;
+use tracing::Instrument;
+use tracing_subscriber::EnvFilter;
 
 use backend::app_server::{
     spawn_workspace_session, WorkspaceSession,
 };
 use backend::events::{AppServerEvent, EventSink, TerminalExit, TerminalOutput};
+use rate_limit::{RateLimitConfig, RateLimiter};
 use storage::{read_settings, read_workspaces};
-use shared::{codex_core, files_core, git_core, settings_core, workspaces_core, worktree_core};
+use shared::{
+    codex_core, files_core, git_core, git_ui_core, git_undo_core, github_core, process_core,
+    settings_core, workspaces_core, worktree_core,
+};
 use shared::codex_core::CodexLoginCancelState;
+use shared::compression_core::{encode_frame, COMPRESSION_GZIP};
 use workspace_settings::apply_workspace_settings_update;
 use types::{
-    AppSettings, WorkspaceEntry, WorkspaceInfo, WorkspaceSettings, WorktreeSetupStatus,
+    AppSettings, GitDiffStreamEvent, WorkspaceEntry, WorkspaceInfo, WorkspaceSettings,
+    WorktreeSetupStatus,
 };
 
 const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:4732";
+/// Wire protocol spoken by this daemon. Clients that omit `protocolVersion` in
+/// `auth` are treated as speaking the oldest supported version.
+const DAEMON_PROTOCOL_VERSION: u32 = 1;
+const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+const DAEMON_CAPABILITIES: &[&str] = &[
+    "compression",
+    "events",
+    "events.filter",
+    "rpc.list",
+    "workspaces.list",
+];
+
+const DEFAULT_MAX_CONNECTIONS: usize = 32;
+const TOO_MANY_CONNECTIONS_MESSAGE: &str = "too many connections";
+const DEFAULT_RATE_LIMIT_PER_SECOND: f64 = 50.0;
+const DEFAULT_RATE_LIMIT_BURST: f64 = 100.0;
+// Unauthenticated clients can only call `auth`, so they get a much smaller budget.
+const UNAUTHENTICATED_RATE_LIMIT: RateLimitConfig = RateLimitConfig {
+    per_second: 1.0,
+    burst: 5.0,
+};
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
 
 fn spawn_with_client(
     event_sink: DaemonEventSink,
@@ -113,6 +154,56 @@ enum DaemonEvent {
     TerminalExit(TerminalExit),
 }
 
+impl DaemonEvent {
+    /// The notification method the event is sent as; doubles as its filter kind.
+    fn kind(&self) -> &'static str {
+        match self {
+            DaemonEvent::AppServer(_) => "app-server-event",
+            DaemonEvent::TerminalOutput(_) => "terminal-output",
+            DaemonEvent::TerminalExit(_) => "terminal-exit",
+        }
+    }
+
+    fn workspace_id(&self) -> &str {
+        match self {
+            DaemonEvent::AppServer(payload) => &payload.workspace_id,
+            DaemonEvent::TerminalOutput(payload) => &payload.workspace_id,
+            DaemonEvent::TerminalExit(payload) => &payload.workspace_id,
+        }
+    }
+}
+
+/// Per-connection event subscription. `None` means "no restriction", so a
+/// client that never sends a filter keeps receiving every event.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct EventFilter {
+    kinds: Option<Vec<String>>,
+    workspace_ids: Option<Vec<String>>,
+}
+
+impl EventFilter {
+    fn from_params(params: &Value) -> Self {
+        Self {
+            kinds: parse_optional_string_array(params, "kinds"),
+            workspace_ids: parse_optional_string_array(params, "workspaceIds"),
+        }
+    }
+
+    fn matches(&self, event: &DaemonEvent) -> bool {
+        let kind_ok = self
+            .kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.iter().any(|kind| kind == event.kind()));
+        let workspace_ok = self
+            .workspace_ids
+            .as_ref()
+            .is_none_or(|ids| ids.iter().any(|id| id == event.workspace_id()));
+        kind_ok && workspace_ok
+    }
+}
+
+type SharedEventFilter = Arc<std::sync::RwLock<EventFilter>>;
+
 impl EventSink for DaemonEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
         let _ = self.tx.send(DaemonEvent::AppServer(event));
@@ -131,6 +222,44 @@ struct DaemonConfig {
     listen: SocketAddr,
     token: Option<String>,
     data_dir: PathBuf,
+    log_level: LevelFilter,
+    log_format: LogFormat,
+    rate_limit: RateLimitConfig,
+    max_connections: usize,
+    /// Closes a connection that sends nothing for this long. `None` keeps idle
+    /// clients (e.g. ones only listening for events) connected.
+    idle_timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Pretty,
+    Json,
+}
+
+impl LogFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            other => Err(format!("unknown log format: {other}")),
+        }
+    }
+}
+
+/// Installs the global `tracing` subscriber on stderr. `RUST_LOG` directives,
+/// when set, refine the level chosen with `--log-level`.
+fn init_tracing(config: &DaemonConfig) {
+    let filter = EnvFilter::builder()
+        .with_default_directive(config.log_level.into())
+        .from_env_lossy();
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match config.log_format {
+        LogFormat::Pretty => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
 }
 
 struct DaemonState {
@@ -142,6 +271,11 @@ struct DaemonState {
     app_settings: Mutex<AppSettings>,
     event_sink: DaemonEventSink,
     codex_login_cancels: Mutex<HashMap<String, CodexLoginCancelState>>,
+    git_diff_streams: git_ui_core::GitDiffStreams,
+    default_branch_cache: git_ui_core::DefaultBranchCache,
+    git_undo_journal: Mutex<git_undo_core::GitUndoJournal>,
+    rate_limiter: Mutex<RateLimiter>,
+    started_at: Instant,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -155,7 +289,9 @@ impl DaemonState {
         let storage_path = config.data_dir.join("workspaces.json");
         let settings_path = config.data_dir.join("settings.json");
         let workspaces = read_workspaces(&storage_path).unwrap_or_default();
+        process_core::sync_workspace_envs(workspaces.values());
         let app_settings = read_settings(&settings_path).unwrap_or_default();
+        git_core::git_op_limiter().set_limit(app_settings.max_concurrent_git_ops as usize);
         Self {
             data_dir: config.data_dir.clone(),
             workspaces: Mutex::new(workspaces),
@@ -165,6 +301,11 @@ impl DaemonState {
             app_settings: Mutex::new(app_settings),
             event_sink,
             codex_login_cancels: Mutex::new(HashMap::new()),
+            git_diff_streams: Mutex::new(HashMap::new()),
+            default_branch_cache: Mutex::new(HashMap::new()),
+            git_undo_journal: Mutex::new(HashMap::new()),
+            rate_limiter: Mutex::new(RateLimiter::default()),
+            started_at: Instant::now(),
         }
     }
 
@@ -172,6 +313,26 @@ impl DaemonState {
         workspaces_core::list_workspaces_core(&self.workspaces, &self.sessions).await
     }
 
+    /// Full workspace entries for thin clients, in sidebar order.
+    async fn workspace_entries(&self) -> Vec<WorkspaceEntry> {
+        let mut entries: Vec<WorkspaceEntry> = self
+            .workspaces
+            .lock()
+            .await
+            .values()
+            .cloned()
+            .map(public_workspace_entry)
+            .collect();
+        entries.sort_by(|a, b| {
+            a.settings
+                .sort_order
+                .unwrap_or(u32::MAX)
+                .cmp(&b.settings.sort_order.unwrap_or(u32::MAX))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        entries
+    }
+
     async fn is_workspace_path_dir(&self, path: String) -> bool {
         workspaces_core::is_workspace_path_dir_core(&path)
     }
@@ -671,6 +832,10 @@ impl DaemonState {
         codex_core::auth_store_set_file_core(&self.app_settings).await
     }
 
+    async fn codex_auth_store_migrate(&self, target: String) -> Result<Value, String> {
+        codex_core::auth_store_migrate_core(&self.app_settings, target).await
+    }
+
     async fn codex_auth_profile_snapshot(&self, profile_id: String) -> Result<Value, String> {
         codex_core::auth_profile_snapshot_core(&self.app_settings, profile_id).await
     }
@@ -860,7 +1025,7 @@ fn usage() -> String {
     format!(
         "\
 USAGE:\n  codex-monitor-daemon [--listen <addr>] [--data-dir <path>] [--token <token> | --insecure-no-auth]\n\n\
-OPTIONS:\n  --listen <addr>        Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>      Data dir holding workspaces.json/settings.json\n  --token <token>        Shared token required by clients\n  --insecure-no-auth      Disable auth (dev only)\n  -h, --help             Show this help\n"
+OPTIONS:\n  --listen <addr>        Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>      Data dir holding workspaces.json/settings.json\n  --token <token>        Shared token required by clients\n  --insecure-no-auth      Disable auth (dev only)\n  --log-level <level>    error, warn, info, debug, or trace (default: info)\n  --log-format <format>  pretty or json (default: pretty)\n  --rate-limit <n>       Requests per second per token, 0 disables (default: 50)\n  --rate-burst <n>       Burst size for --rate-limit (default: 100)\n  --max-connections <n>  Concurrent client limit (default: {DEFAULT_MAX_CONNECTIONS})\n  --idle-timeout <secs>  Close clients idle this long, 0 disables (default: 0)\n  -h, --help             Show this help\n"
     )
 }
 
@@ -874,6 +1039,14 @@ fn parse_args() -> Result<DaemonConfig, String> {
         .filter(|value| !value.is_empty());
     let mut insecure_no_auth = false;
     let mut data_dir: Option<PathBuf> = None;
+    let mut log_level = LevelFilter::INFO;
+    let mut log_format = LogFormat::Pretty;
+    let mut max_connections = DEFAULT_MAX_CONNECTIONS;
+    let mut idle_timeout: Option<Duration> = None;
+    let mut rate_limit = RateLimitConfig {
+        per_second: DEFAULT_RATE_LIMIT_PER_SECOND,
+        burst: DEFAULT_RATE_LIMIT_BURST,
+    };
 
     let mut args = env::args().skip(1);
     while let Some(arg) = args.next() {
@@ -906,6 +1079,39 @@ fn parse_args() -> Result<DaemonConfig, String> {
                 insecure_no_auth = true;
                 token = None;
             }
+            "--log-level" => {
+                let value = args.next().ok_or("--log-level requires a value")?;
+                log_level = value
+                    .trim()
+                    .parse::<LevelFilter>()
+                    .map_err(|_| format!("unknown log level: {}", value.trim()))?;
+            }
+            "--log-format" => {
+                let value = args.next().ok_or("--log-format requires a value")?;
+                log_format = LogFormat::parse(&value)?;
+            }
+            "--rate-limit" => {
+                let value = args.next().ok_or("--rate-limit requires a value")?;
+                rate_limit.per_second = parse_non_negative(&value, "--rate-limit")?;
+            }
+            "--rate-burst" => {
+                let value = args.next().ok_or("--rate-burst requires a value")?;
+                rate_limit.burst = parse_non_negative(&value, "--rate-burst")?;
+            }
+            "--max-connections" => {
+                let value = args.next().ok_or("--max-connections requires a value")?;
+                max_connections = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|value| *value > 0)
+                    .ok_or("--max-connections requires a positive integer")?;
+            }
+            "--idle-timeout" => {
+                let value = args.next().ok_or("--idle-timeout requires a value")?;
+                let secs = parse_non_negative(&value, "--idle-timeout")?;
+                idle_timeout = (secs > 0.0).then(|| Duration::from_secs_f64(secs));
+            }
             _ => return Err(format!("Unknown argument: {arg}")),
         }
     }
@@ -921,15 +1127,51 @@ fn parse_args() -> Result<DaemonConfig, String> {
         listen,
         token,
         data_dir: data_dir.unwrap_or_else(default_data_dir),
+        log_level,
+        log_format,
+        rate_limit,
+        max_connections,
+        idle_timeout,
     })
 }
 
-fn build_error_response(id: Option<u64>, message: &str) -> Option<String> {
+fn parse_non_negative(value: &str, flag: &str) -> Result<f64, String> {
+    let parsed = value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("{flag} requires a number"))?;
+    if !parsed.is_finite() || parsed < 0.0 {
+        return Err(format!("{flag} must be zero or greater"));
+    }
+    Ok(parsed)
+}
+
+fn build_error_response(
+    id: Option<u64>,
+    message: &str,
+    correlation_id: Option<&str>,
+) -> Option<String> {
+    build_error_response_with_data(id, message, correlation_id, None)
+}
+
+fn build_error_response_with_data(
+    id: Option<u64>,
+    message: &str,
+    correlation_id: Option<&str>,
+    data: Option<Value>,
+) -> Option<String> {
     let id = id?;
+    let mut error = json!({ "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    if let Some(correlation_id) = correlation_id {
+        error["correlationId"] = Value::String(correlation_id.to_string());
+    }
     Some(
         serde_json::to_string(&json!({
             "id": id,
-            "error": { "message": message }
+            "error": error
         }))
         .unwrap_or_else(|_| "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()),
     )
@@ -943,17 +1185,18 @@ fn build_result_response(id: Option<u64>, result: Value) -> Option<String> {
 }
 
 fn build_event_notification(event: DaemonEvent) -> Option<String> {
+    let method = event.kind();
     let payload = match event {
         DaemonEvent::AppServer(payload) => json!({
-            "method": "app-server-event",
+            "method": method,
             "params": payload,
         }),
         DaemonEvent::TerminalOutput(payload) => json!({
-            "method": "terminal-output",
+            "method": method,
             "params": payload,
         }),
         DaemonEvent::TerminalExit(payload) => json!({
-            "method": "terminal-exit",
+            "method": method,
             "params": payload,
         }),
     };
@@ -971,6 +1214,49 @@ fn parse_auth_token(params: &Value) -> Option<String> {
     }
 }
 
+fn negotiate_protocol_version(params: &Value) -> Result<u32, String> {
+    let requested = match params {
+        Value::Object(map) => match map.get("protocolVersion") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(
+                value
+                    .as_u64()
+                    .ok_or("invalid `protocolVersion`")?,
+            ),
+        },
+        _ => None,
+    };
+    let Some(requested) = requested else {
+        return Ok(MIN_SUPPORTED_PROTOCOL_VERSION);
+    };
+    if requested < MIN_SUPPORTED_PROTOCOL_VERSION as u64
+        || requested > DAEMON_PROTOCOL_VERSION as u64
+    {
+        return Err(format!(
+            "version mismatch: client speaks protocol {requested}, daemon supports {MIN_SUPPORTED_PROTOCOL_VERSION}-{DAEMON_PROTOCOL_VERSION}"
+        ));
+    }
+    Ok(requested as u32)
+}
+
+fn negotiate_compression(params: &Value) -> Option<&'static str> {
+    let supported = parse_optional_string_array(params, "compression")?;
+    supported
+        .iter()
+        .any(|value| value == COMPRESSION_GZIP)
+        .then_some(COMPRESSION_GZIP)
+}
+
+fn build_auth_result(protocol_version: u32, compression: Option<&str>) -> Value {
+    json!({
+        "ok": true,
+        "protocolVersion": protocol_version,
+        "daemonVersion": env!("CARGO_PKG_VERSION"),
+        "capabilities": DAEMON_CAPABILITIES,
+        "compression": compression,
+    })
+}
+
 fn parse_string(value: &Value, key: &str) -> Result<String, String> {
     match value {
         Value::Object(map) => map
@@ -1005,6 +1291,26 @@ fn parse_optional_u32(value: &Value, key: &str) -> Option<u32> {
     }
 }
 
+fn parse_u64(value: &Value, key: &str) -> Result<u64, String> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .and_then(|value| value.as_u64())
+            .ok_or_else(|| format!("missing or invalid `{key}`")),
+        _ => Err(format!("missing `{key}`")),
+    }
+}
+
+fn parse_i64(value: &Value, key: &str) -> Result<i64, String> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .and_then(|value| value.as_i64())
+            .ok_or_else(|| format!("missing or invalid `{key}`")),
+        _ => Err(format!("missing `{key}`")),
+    }
+}
+
 fn parse_optional_bool(value: &Value, key: &str) -> Option<bool> {
     match value {
         Value::Object(map) => map.get(key).and_then(|value| value.as_bool()),
@@ -1052,6 +1358,28 @@ struct FileWriteRequest {
     content: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileReadRangeRequest {
+    scope: file_policy::FileScope,
+    kind: file_policy::FileKind,
+    workspace_id: Option<String>,
+    #[serde(default)]
+    offset: u64,
+    length: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileTailRequest {
+    scope: file_policy::FileScope,
+    kind: Option<file_policy::FileKind>,
+    workspace_id: Option<String>,
+    path: Option<String>,
+    #[serde(default)]
+    from_offset: u64,
+}
+
 fn parse_file_read_request(params: &Value) -> Result<FileReadRequest, String> {
     serde_json::from_value(params.clone()).map_err(|err| err.to_string())
 }
@@ -1065,13 +1393,22 @@ async fn handle_rpc_request(
     method: &str,
     params: Value,
     client_version: String,
+    protocol_version: u32,
 ) -> Result<Value, String> {
+    if rpc::find_method(method).is_none() {
+        return Err(format!("unknown method: {method}"));
+    }
     match method {
-        "ping" => Ok(json!({ "ok": true })),
+        "ping" => Ok(build_ping_result(state)),
+        "rpc.list" => Ok(rpc::rpc_list_response(protocol_version)),
         "list_workspaces" => {
             let workspaces = state.list_workspaces().await;
             serde_json::to_value(workspaces).map_err(|err| err.to_string())
         }
+        "workspaces.list" => {
+            let entries = state.workspace_entries().await;
+            serde_json::to_value(entries).map_err(|err| err.to_string())
+        }
         "is_workspace_path_dir" => {
             let path = parse_string(&params, "path")?;
             let is_dir = state.is_workspace_path_dir(path).await;
@@ -1146,12 +1483,92 @@ async fn handle_rpc_request(
                 .await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
+        "reorder_workspaces" => {
+            let ordered_ids = parse_optional_string_array(&params, "orderedIds")
+                .ok_or("missing `orderedIds`")?;
+            let workspaces = workspaces_core::reorder_workspaces_core(
+                ordered_ids,
+                &state.workspaces,
+                &state.sessions,
+                &state.storage_path,
+            )
+            .await?;
+            serde_json::to_value(workspaces).map_err(|err| err.to_string())
+        }
+        "create_workspace_group" => {
+            let name = parse_string(&params, "name")?;
+            let color = parse_optional_string(&params, "color");
+            let group = workspaces_core::create_group_core(
+                name,
+                color,
+                &state.app_settings,
+                &state.settings_path,
+            )
+            .await?;
+            serde_json::to_value(group).map_err(|err| err.to_string())
+        }
+        "rename_workspace_group" => {
+            let group_id = parse_string(&params, "groupId")?;
+            let name = parse_string(&params, "name")?;
+            let group = workspaces_core::rename_group_core(
+                group_id,
+                name,
+                &state.app_settings,
+                &state.settings_path,
+            )
+            .await?;
+            serde_json::to_value(group).map_err(|err| err.to_string())
+        }
+        "set_workspace_group_color" => {
+            let group_id = parse_string(&params, "groupId")?;
+            let color = parse_optional_string(&params, "color");
+            let group = workspaces_core::set_group_color_core(
+                group_id,
+                color,
+                &state.app_settings,
+                &state.settings_path,
+            )
+            .await?;
+            serde_json::to_value(group).map_err(|err| err.to_string())
+        }
+        "set_workspace_group_collapsed" => {
+            let group_id = parse_string(&params, "groupId")?;
+            let collapsed = parse_optional_bool(&params, "collapsed")
+                .ok_or("missing or invalid `collapsed`")?;
+            let group = workspaces_core::set_group_collapsed_core(
+                group_id,
+                collapsed,
+                &state.app_settings,
+                &state.settings_path,
+            )
+            .await?;
+            serde_json::to_value(group).map_err(|err| err.to_string())
+        }
+        "delete_workspace_group" => {
+            let group_id = parse_string(&params, "groupId")?;
+            workspaces_core::delete_group_core(
+                group_id,
+                &state.workspaces,
+                &state.app_settings,
+                &state.storage_path,
+                &state.settings_path,
+            )
+            .await?;
+            Ok(json!({ "ok": true }))
+        }
         "update_workspace_codex_bin" => {
             let id = parse_string(&params, "id")?;
             let codex_bin = parse_optional_string(&params, "codex_bin");
             let workspace = state.update_workspace_codex_bin(id, codex_bin).await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
+        "discover_workspaces" => {
+            let root = parse_string(&params, "root")?;
+            let depth = parse_optional_u32(&params, "depth").map(|value| value as usize);
+            let stubs =
+                workspaces_core::discover_workspaces_core(&state.workspaces, root, depth).await?;
+            serde_json::to_value(stubs).map_err(|err| err.to_string())
+        }
         "list_workspace_files" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             let files = state.list_workspace_files(workspace_id).await?;
@@ -1182,6 +1599,45 @@ async fn handle_rpc_request(
                 .await?;
             serde_json::to_value(json!({ "ok": true })).map_err(|err| err.to_string())
         }
+        "file_read_range" => {
+            let request: FileReadRangeRequest =
+                serde_json::from_value(params.clone()).map_err(|err| err.to_string())?;
+            let response = files_core::file_read_range_core(
+                &state.workspaces,
+                &state.app_settings,
+                request.scope,
+                request.kind,
+                request.workspace_id,
+                request.offset,
+                request.length,
+            )
+            .await?;
+            serde_json::to_value(response).map_err(|err| err.to_string())
+        }
+        "file_tail" => {
+            let request: FileTailRequest =
+                serde_json::from_value(params.clone()).map_err(|err| err.to_string())?;
+            let response = files_core::tail_file_core(
+                &state.workspaces,
+                &state.app_settings,
+                request.scope,
+                request.kind,
+                request.workspace_id,
+                request.path,
+                request.from_offset,
+            )
+            .await?;
+            serde_json::to_value(response).map_err(|err| err.to_string())
+        }
+        "file_write_transaction" => {
+            let edits: Vec<files_core::FileEdit> = serde_json::from_value(
+                parse_optional_value(&params, "edits").ok_or("missing `edits`")?,
+            )
+            .map_err(|err| err.to_string())?;
+            files_core::write_files_transaction_core(&state.workspaces, &state.app_settings, edits)
+                .await?;
+            Ok(json!({ "ok": true }))
+        }
         "get_app_settings" => {
             let settings = state.get_app_settings().await;
             serde_json::to_value(settings).map_err(|err| err.to_string())
@@ -1197,10 +1653,786 @@ async fn handle_rpc_request(
             let updated = state.update_app_settings(settings, client_version).await?;
             serde_json::to_value(updated).map_err(|err| err.to_string())
         }
+        "reload_codex_config" => {
+            let reload = settings_core::reload_codex_config_core(&state.app_settings).await?;
+            serde_json::to_value(reload).map_err(|err| err.to_string())
+        }
+        "export_settings_profile" => {
+            let include_secrets = parse_optional_bool(&params, "includeSecrets").unwrap_or(false);
+            let export =
+                settings_core::export_settings_profile_core(&state.app_settings, include_secrets)
+                    .await?;
+            serde_json::to_value(export).map_err(|err| err.to_string())
+        }
+        "import_settings_profile" => {
+            let profile = parse_optional_value(&params, "profile").ok_or("missing `profile`")?;
+            let merge = parse_optional_bool(&params, "merge").unwrap_or(true);
+            let settings =
+                settings_core::settings_from_profile_core(&state.app_settings, &profile, merge)
+                    .await?;
+            let updated = state.update_app_settings(settings, client_version).await?;
+            serde_json::to_value(updated).map_err(|err| err.to_string())
+        }
         "get_codex_config_path" => {
-            let path = settings_core::get_codex_config_path_core(&state.app_settings).await?;
+            let workspace_id = parse_optional_string(&params, "workspaceId");
+            let path = settings_core::get_codex_config_path_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+            )
+            .await?;
             Ok(Value::String(path))
         }
+        "get_effective_config" => {
+            let workspace_id = parse_optional_string(&params, "workspaceId");
+            settings_core::get_effective_config_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+            )
+            .await
+        }
+        "diff_codex_config" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            settings_core::diff_config_core(&state.workspaces, &state.app_settings, workspace_id)
+                .await
+        }
+        "get_project_trust" => {
+            let path = parse_string(&params, "path")?;
+            let trusted = settings_core::read_project_trust_core(&state.app_settings, path).await?;
+            Ok(json!(trusted))
+        }
+        "set_project_trust" => {
+            let path = parse_string(&params, "path")?;
+            let trusted = parse_optional_bool(&params, "trusted")
+                .ok_or("missing or invalid `trusted`")?;
+            settings_core::write_project_trust_core(&state.app_settings, path, trusted).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "list_feature_flags" => {
+            let flags = settings_core::list_feature_flags_core(&state.app_settings).await?;
+            serde_json::to_value(flags).map_err(|err| err.to_string())
+        }
+        "get_feature_flag" => {
+            let key = parse_string(&params, "key")?;
+            let enabled = settings_core::read_feature_flag_core(&state.app_settings, key).await?;
+            Ok(json!(enabled))
+        }
+        "set_feature_flag" => {
+            let key = parse_string(&params, "key")?;
+            let enabled = parse_optional_bool(&params, "enabled")
+                .ok_or("missing or invalid `enabled`")?;
+            settings_core::write_feature_flag_core(&state.app_settings, key, enabled).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "get_git_status" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let untracked_mode = parse_optional_value(&params, "untrackedMode")
+                .and_then(|value| serde_json::from_value(value).ok());
+            git_ui_core::get_git_status_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                untracked_mode,
+            )
+            .await
+        }
+        "is_working_tree_clean" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let count_changes = parse_optional_bool(&params, "countChanges").unwrap_or(false);
+            let result = git_ui_core::is_working_tree_clean_core(
+                &state.workspaces,
+                workspace_id,
+                count_changes,
+            )
+            .await?;
+            serde_json::to_value(result).map_err(|err| err.to_string())
+        }
+        "fetch_all_open_prs_as_branches" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let remote = parse_optional_string(&params, "remote");
+            let refspec = parse_optional_string(&params, "refspec");
+            let fetched = git_ui_core::fetch_all_open_prs_as_branches_core(
+                &state.workspaces,
+                workspace_id,
+                remote,
+                refspec,
+            )
+            .await?;
+            serde_json::to_value(fetched).map_err(|err| err.to_string())
+        }
+        "resolve_ref" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let ref_name = parse_string(&params, "refName")?;
+            let resolved =
+                git_ui_core::resolve_ref_core(&state.workspaces, workspace_id, ref_name).await?;
+            serde_json::to_value(resolved).map_err(|err| err.to_string())
+        }
+        "init_repo" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let initial_branch = parse_optional_string(&params, "initialBranch");
+            let repo_root =
+                git_ui_core::init_repo_core(&state.workspaces, workspace_id, initial_branch)
+                    .await?;
+            Ok(Value::String(repo_root))
+        }
+        "set_upstream_and_push" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let remote_name = parse_string(&params, "remoteName")?;
+            let url = parse_string(&params, "url")?;
+            let branch = parse_optional_string(&params, "branch");
+            let upstream = git_ui_core::set_upstream_and_push_core(
+                &state.workspaces,
+                workspace_id,
+                remote_name,
+                url,
+                branch,
+            )
+            .await?;
+            Ok(Value::String(upstream))
+        }
+        "get_git_status_batch" => {
+            let workspace_ids = parse_string_array(&params, "workspaceIds")?;
+            let summary_only = parse_optional_bool(&params, "summaryOnly").unwrap_or(false);
+            git_ui_core::get_git_status_batch_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_ids,
+                summary_only,
+            )
+            .await
+        }
+        "validate_workspace_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::validate_workspace_git_core(&state.workspaces, workspace_id).await
+        }
+        "get_conflict_versions" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            let versions =
+                git_ui_core::get_conflict_versions_core(&state.workspaces, workspace_id, path)
+                    .await?;
+            serde_json::to_value(versions).map_err(|err| err.to_string())
+        }
+        "resolve_conflict_with_content" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            let content = parse_string(&params, "content")?;
+            git_ui_core::resolve_conflict_with_content_core(
+                &state.workspaces,
+                workspace_id,
+                path,
+                content,
+            )
+            .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "stash_apply_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let index = parse_optional_u32(&params, "index").ok_or("missing `index`")?;
+            git_ui_core::stash_apply_git_core(&state.workspaces, workspace_id, index as usize)
+                .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "list_changed_paths_against_ref" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let base_ref = parse_string(&params, "baseRef")?;
+            let changed = git_ui_core::list_changed_paths_against_ref_core(
+                &state.workspaces,
+                workspace_id,
+                base_ref,
+            )
+            .await?;
+            serde_json::to_value(changed).map_err(|err| err.to_string())
+        }
+        "get_ahead_behind_against" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let other_ref = parse_string(&params, "otherRef")?;
+            let limit = parse_optional_u32(&params, "limit").map(|limit| limit as usize);
+            let result = git_ui_core::get_ahead_behind_against_core(
+                &state.workspaces,
+                workspace_id,
+                other_ref,
+                limit,
+            )
+            .await?;
+            serde_json::to_value(result).map_err(|err| err.to_string())
+        }
+        "list_recent_branches" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let limit = parse_optional_u32(&params, "limit").map(|limit| limit as usize);
+            let branches =
+                git_ui_core::list_recent_branches_core(&state.workspaces, workspace_id, limit)
+                    .await?;
+            serde_json::to_value(branches).map_err(|err| err.to_string())
+        }
+        "checkout_commit" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let sha = parse_string(&params, "sha")?;
+            let force = parse_optional_bool(&params, "force").unwrap_or(false);
+            let result = git_ui_core::checkout_commit_core(
+                &state.workspaces,
+                &state.git_undo_journal,
+                workspace_id,
+                sha,
+                force,
+            )
+            .await?;
+            serde_json::to_value(result).map_err(|err| err.to_string())
+        }
+        "create_branch_at_head" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let name = parse_string(&params, "name")?;
+            let branch =
+                git_ui_core::create_branch_at_head_core(&state.workspaces, workspace_id, name)
+                    .await?;
+            Ok(Value::String(branch))
+        }
+        "cancel_git_diff_stream" => {
+            let stream_id = parse_string(&params, "streamId")?;
+            git_ui_core::cancel_git_diff_stream_core(&state.git_diff_streams, &stream_id).await;
+            Ok(json!({ "ok": true }))
+        }
+        "get_default_branch" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let refresh = parse_optional_bool(&params, "refresh").unwrap_or(false);
+            let branch = git_ui_core::get_default_branch_core(
+                &state.workspaces,
+                &state.default_branch_cache,
+                workspace_id,
+                refresh,
+            )
+            .await?;
+            serde_json::to_value(branch).map_err(|err| err.to_string())
+        }
+        "show_stash_diff" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let index = parse_optional_u32(&params, "index").ok_or("missing `index`")? as usize;
+            let diffs = git_ui_core::show_stash_diff_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                index,
+            )
+            .await?;
+            serde_json::to_value(diffs).map_err(|err| err.to_string())
+        }
+        "get_git_undo_stack" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let entries =
+                git_undo_core::get_git_undo_stack_core(&state.git_undo_journal, &workspace_id)
+                    .await;
+            serde_json::to_value(entries).map_err(|err| err.to_string())
+        }
+        "undo_last_git_action" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let confirm = parse_optional_bool(&params, "confirm").unwrap_or(false);
+            let override_protection = parse_optional_bool(&params, "overrideProtection");
+            let entry = git_undo_core::undo_last_git_action_core(
+                &state.workspaces,
+                &state.git_undo_journal,
+                workspace_id,
+                confirm,
+                override_protection,
+            )
+            .await?;
+            serde_json::to_value(entry).map_err(|err| err.to_string())
+        }
+        "get_github_pull_request" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pr_number = parse_u64(&params, "prNumber")?;
+            let pull_request = github_core::get_github_pull_request_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                pr_number,
+            )
+            .await?;
+            serde_json::to_value(pull_request).map_err(|err| err.to_string())
+        }
+        "get_github_pull_request_diff" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pr_number = parse_u64(&params, "prNumber")?;
+            let diffs = github_core::get_github_pull_request_diff_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                pr_number,
+            )
+            .await?;
+            serde_json::to_value(diffs).map_err(|err| err.to_string())
+        }
+        "get_github_pull_request_raw_diff" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pr_number = parse_u64(&params, "prNumber")?;
+            let diff = github_core::get_github_pull_request_raw_diff_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                pr_number,
+            )
+            .await?;
+            Ok(Value::String(diff))
+        }
+        "stage_git_file" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            git_ui_core::stage_git_file_core(&state.workspaces, workspace_id, path).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "stage_git_all" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::stage_git_all_core(&state.workspaces, workspace_id).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "unstage_git_file" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            git_ui_core::unstage_git_file_core(&state.workspaces, workspace_id, path).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "revert_git_file" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            git_ui_core::revert_git_file_core(&state.workspaces, workspace_id, path).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "revert_git_all" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let override_protection = parse_optional_bool(&params, "overrideProtection");
+            git_ui_core::revert_git_all_core(
+                &state.workspaces,
+                &state.git_undo_journal,
+                workspace_id,
+                override_protection,
+            )
+            .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "push_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let no_verify = parse_optional_bool(&params, "noVerify");
+            git_ui_core::push_git_core(&state.workspaces, workspace_id, no_verify).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "pull_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::pull_git_core(&state.workspaces, &state.git_undo_journal, workspace_id)
+                .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "fetch_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let fetch_depth = parse_optional_u32(&params, "fetchDepth");
+            git_ui_core::fetch_git_core(&state.workspaces, workspace_id, fetch_depth).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "fetch_git_unshallow" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::fetch_git_unshallow_core(&state.workspaces, workspace_id).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "sync_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::sync_git_core(&state.workspaces, workspace_id).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "get_git_log" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let limit = parse_optional_u32(&params, "limit").map(|limit| limit as usize);
+            let pathspec = parse_optional_string_array(&params, "pathspec");
+            let detail = parse_optional_bool(&params, "detail");
+            let verify = parse_optional_bool(&params, "verify");
+            let log = git_ui_core::get_git_log_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                limit,
+                pathspec,
+                detail,
+                verify,
+            )
+            .await?;
+            serde_json::to_value(log).map_err(|err| err.to_string())
+        }
+        "get_git_commit_diff" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let sha = parse_string(&params, "sha")?;
+            let full_file = parse_optional_bool(&params, "fullFile");
+            let diffs = git_ui_core::get_git_commit_diff_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                sha,
+                full_file,
+            )
+            .await?;
+            serde_json::to_value(diffs).map_err(|err| err.to_string())
+        }
+        "get_git_remote" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let remote = git_ui_core::get_git_remote_core(&state.workspaces, workspace_id).await?;
+            serde_json::to_value(remote).map_err(|err| err.to_string())
+        }
+        "list_git_branches" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::list_git_branches_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+            )
+            .await
+        }
+        "checkout_git_branch" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let name = parse_string(&params, "name")?;
+            git_ui_core::checkout_git_branch_core(
+                &state.workspaces,
+                &state.git_undo_journal,
+                workspace_id,
+                name,
+            )
+            .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "create_git_branch" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let name = parse_string(&params, "name")?;
+            git_ui_core::create_git_branch_core(&state.workspaces, workspace_id, name).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "get_github_issues" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let issues = github_core::get_github_issues_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+            )
+            .await?;
+            serde_json::to_value(issues).map_err(|err| err.to_string())
+        }
+        "get_github_pull_requests" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pull_requests = github_core::get_github_pull_requests_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+            )
+            .await?;
+            serde_json::to_value(pull_requests).map_err(|err| err.to_string())
+        }
+        "get_github_pull_request_comments" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pr_number = parse_u64(&params, "prNumber")?;
+            let comments = github_core::get_github_pull_request_comments_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                pr_number,
+            )
+            .await?;
+            serde_json::to_value(comments).map_err(|err| err.to_string())
+        }
+        "get_git_change_summary" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let summary =
+                git_ui_core::get_git_change_summary_core(&state.workspaces, workspace_id).await?;
+            serde_json::to_value(summary).map_err(|err| err.to_string())
+        }
+        "reword_head_commit" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let message = parse_string(&params, "message")?;
+            let allow_merge = parse_optional_bool(&params, "allowMerge");
+            let override_protection = parse_optional_bool(&params, "overrideProtection");
+            let sha = git_ui_core::reword_head_commit_core(
+                &state.workspaces,
+                &state.git_undo_journal,
+                workspace_id,
+                message,
+                allow_merge,
+                override_protection,
+            )
+            .await?;
+            Ok(Value::String(sha))
+        }
+        "reset_head_author" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let name = parse_string(&params, "name")?;
+            let email = parse_string(&params, "email")?;
+            let override_protection = parse_optional_bool(&params, "overrideProtection");
+            let sha = git_ui_core::reset_head_author_core(
+                &state.workspaces,
+                workspace_id,
+                name,
+                email,
+                override_protection,
+            )
+            .await?;
+            Ok(Value::String(sha))
+        }
+        "reset_head_date" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let timestamp = parse_i64(&params, "timestamp")?;
+            let override_protection = parse_optional_bool(&params, "overrideProtection");
+            let sha = git_ui_core::reset_head_date_core(
+                &state.workspaces,
+                workspace_id,
+                timestamp,
+                override_protection,
+            )
+            .await?;
+            Ok(Value::String(sha))
+        }
+        "bisect_start" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let bad = parse_string(&params, "bad")?;
+            let good = parse_string(&params, "good")?;
+            git_ui_core::bisect_start_core(&state.workspaces, workspace_id, bad, good).await
+        }
+        "bisect_mark" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let result = serde_json::from_value(
+                parse_optional_value(&params, "result").ok_or("missing `result`")?,
+            )
+            .map_err(|err| err.to_string())?;
+            git_ui_core::bisect_mark_core(&state.workspaces, workspace_id, result).await
+        }
+        "bisect_reset" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::bisect_reset_core(&state.workspaces, workspace_id).await
+        }
+        "get_repo_operation_state" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let operation =
+                git_ui_core::get_repo_operation_state_core(&state.workspaces, workspace_id).await?;
+            serde_json::to_value(operation).map_err(|err| err.to_string())
+        }
+        "operation_continue" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let operation = git_ui_core::run_operation_action_core(
+                &state.workspaces,
+                workspace_id,
+                git_ui_core::OperationAction::Continue,
+            )
+            .await?;
+            serde_json::to_value(operation).map_err(|err| err.to_string())
+        }
+        "operation_skip" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let operation = git_ui_core::run_operation_action_core(
+                &state.workspaces,
+                workspace_id,
+                git_ui_core::OperationAction::Skip,
+            )
+            .await?;
+            serde_json::to_value(operation).map_err(|err| err.to_string())
+        }
+        "operation_abort" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let operation = git_ui_core::run_operation_action_core(
+                &state.workspaces,
+                workspace_id,
+                git_ui_core::OperationAction::Abort,
+            )
+            .await?;
+            serde_json::to_value(operation).map_err(|err| err.to_string())
+        }
+        "read_git_config" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let keys = parse_string_array(&params, "keys")?;
+            git_ui_core::read_git_config_core(&state.workspaces, workspace_id, keys).await
+        }
+        "write_git_config" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let key = parse_string(&params, "key")?;
+            let value = parse_string(&params, "value")?;
+            let scope = serde_json::from_value(
+                parse_optional_value(&params, "scope").ok_or("missing `scope`")?,
+            )
+            .map_err(|err| err.to_string())?;
+            git_ui_core::write_git_config_core(&state.workspaces, workspace_id, key, value, scope)
+                .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "stage_git_pattern" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pathspec = parse_string_array(&params, "pathspec")?;
+            git_ui_core::stage_git_pattern_core(&state.workspaces, workspace_id, pathspec).await?;
+            Ok(json!({ "ok": true }))
+        }
+        "unstage_git_pattern" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pathspec = parse_string_array(&params, "pathspec")?;
+            git_ui_core::unstage_git_pattern_core(&state.workspaces, workspace_id, pathspec)
+                .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "move_changes_to_new_branch" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let name = parse_string(&params, "name")?;
+            let start_point = parse_optional_string(&params, "startPoint");
+            let stash = parse_optional_bool(&params, "stash");
+            let override_protection = parse_optional_bool(&params, "overrideProtection");
+            let name = git_ui_core::move_changes_to_new_branch_core(
+                &state.workspaces,
+                &state.git_undo_journal,
+                workspace_id,
+                name,
+                start_point,
+                stash,
+                override_protection,
+            )
+            .await?;
+            Ok(Value::String(name))
+        }
+        "restore_file_from_rev" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            let rev = parse_string(&params, "rev")?;
+            let force = parse_optional_bool(&params, "force");
+            git_ui_core::restore_file_from_rev_core(
+                &state.workspaces,
+                workspace_id,
+                path,
+                rev,
+                force,
+            )
+            .await?;
+            Ok(json!({ "ok": true }))
+        }
+        "get_ref_comparison_diff" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let base = parse_string(&params, "base")?;
+            let head = parse_string(&params, "head")?;
+            git_ui_core::get_ref_comparison_diff_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                base,
+                head,
+            )
+            .await
+        }
+        "preview_sync_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::preview_sync_git_core(&state.workspaces, workspace_id).await
+        }
+        "fetch_all_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let results = git_ui_core::fetch_all_git_core(&state.workspaces, workspace_id).await?;
+            serde_json::to_value(results).map_err(|err| err.to_string())
+        }
+        "list_remote_branches" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            git_ui_core::list_remote_branches_core(&state.workspaces, workspace_id).await
+        }
+        "prune_remote_branches" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let remote = parse_string(&params, "remote")?;
+            let pruned =
+                git_ui_core::prune_remote_branches_core(&state.workspaces, workspace_id, remote)
+                    .await?;
+            serde_json::to_value(pruned).map_err(|err| err.to_string())
+        }
+        "explain_ignore" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            let explanation =
+                git_ui_core::explain_ignore_core(&state.workspaces, workspace_id, path).await?;
+            serde_json::to_value(explanation).map_err(|err| err.to_string())
+        }
+        "get_commit_notes" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let sha = parse_string(&params, "sha")?;
+            let notes_ref = parse_optional_string(&params, "notesRef");
+            let notes =
+                git_ui_core::get_commit_notes_core(&state.workspaces, workspace_id, sha, notes_ref)
+                    .await?;
+            serde_json::to_value(notes).map_err(|err| err.to_string())
+        }
+        "get_repo_stats" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let stats = git_ui_core::get_repo_stats_core(&state.workspaces, workspace_id).await?;
+            serde_json::to_value(stats).map_err(|err| err.to_string())
+        }
+        "repair_repo" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let report = git_ui_core::repair_repo_core(&state.workspaces, workspace_id).await?;
+            serde_json::to_value(report).map_err(|err| err.to_string())
+        }
+        "gc_repo" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let aggressive = parse_optional_bool(&params, "aggressive").unwrap_or(false);
+            // Progress lines only reach local callers (over a Tauri channel);
+            // RPC clients get the final report.
+            let report =
+                git_ui_core::gc_repo_core(&state.workspaces, workspace_id, aggressive, |_| {})
+                    .await?;
+            serde_json::to_value(report).map_err(|err| err.to_string())
+        }
+        "build_permalink" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            let line = parse_optional_u32(&params, "line").ok_or("missing `line`")?;
+            let rev = parse_optional_string(&params, "rev");
+            let url =
+                git_ui_core::build_permalink_core(&state.workspaces, workspace_id, path, line, rev)
+                    .await?;
+            Ok(Value::String(url))
+        }
+        "get_git_diffs" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let whitespace_check = parse_optional_bool(&params, "whitespaceCheck").unwrap_or(false);
+            let full_file = parse_optional_bool(&params, "fullFile").unwrap_or(false);
+            let diffs = git_ui_core::get_git_diffs_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                whitespace_check,
+                full_file,
+            )
+            .await?;
+            serde_json::to_value(diffs).map_err(|err| err.to_string())
+        }
+        "commit_with_body" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let subject = parse_string(&params, "subject")?;
+            let body = parse_optional_string(&params, "body");
+            let result = git_ui_core::commit_with_body_core(
+                &state.workspaces,
+                &state.app_settings,
+                workspace_id,
+                subject,
+                body,
+            )
+            .await?;
+            serde_json::to_value(result).map_err(|err| err.to_string())
+        }
+        "commit_and_push" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let message = parse_string(&params, "message")?;
+            let stage_all = parse_optional_bool(&params, "stageAll").unwrap_or(false);
+            let co_authors = parse_optional_string_array(&params, "coAuthors").unwrap_or_default();
+            let override_protection =
+                parse_optional_bool(&params, "overrideProtection").unwrap_or(false);
+            let result = git_ui_core::commit_and_push_core(
+                &state.workspaces,
+                workspace_id,
+                message,
+                stage_all,
+                co_authors,
+                override_protection,
+            )
+            .await?;
+            serde_json::to_value(result).map_err(|err| err.to_string())
+        }
+        "commit_git" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let message = parse_string(&params, "message")?;
+            let paths = parse_optional_string_array(&params, "paths");
+            git_ui_core::commit_git_core(&state.workspaces, workspace_id, message, paths).await?;
+            Ok(json!({ "ok": true }))
+        }
         "get_config_model" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             state.get_config_model(workspace_id).await
@@ -1304,6 +2536,10 @@ async fn handle_rpc_request(
         }
         "codex_auth_store_read" => state.codex_auth_store_read().await,
         "codex_auth_store_set_file" => state.codex_auth_store_set_file().await,
+        "codex_auth_store_migrate" => {
+            let target = parse_string(&params, "target")?;
+            state.codex_auth_store_migrate(target).await
+        }
         "codex_auth_profile_snapshot" => {
             let profile_id = parse_string(&params, "profileId")?;
             state.codex_auth_profile_snapshot(profile_id).await
@@ -1353,9 +2589,43 @@ async fn handle_rpc_request(
     }
 }
 
+/// Runs `stream_git_diffs`, sending each diff to `out_tx` as a
+/// `git-diff-stream` notification. A closed connection stops the stream.
+async fn stream_git_diffs_to_client(
+    state: &DaemonState,
+    params: &Value,
+    out_tx: mpsc::UnboundedSender<String>,
+) -> Result<bool, String> {
+    let workspace_id = parse_string(params, "workspaceId")?;
+    let stream_id = parse_string(params, "streamId")?;
+    let whitespace_check = parse_optional_bool(params, "whitespaceCheck").unwrap_or(false);
+    let event_stream_id = stream_id.clone();
+    git_ui_core::stream_git_diffs_core(
+        &state.workspaces,
+        &state.app_settings,
+        &state.git_diff_streams,
+        workspace_id,
+        stream_id,
+        whitespace_check,
+        move |diff| {
+            let event = GitDiffStreamEvent {
+                stream_id: event_stream_id.clone(),
+                diff,
+            };
+            let notification = json!({ "method": "git-diff-stream", "params": event });
+            match serde_json::to_string(&notification) {
+                Ok(payload) => out_tx.send(payload).is_ok(),
+                Err(_) => true,
+            }
+        },
+    )
+    .await
+}
+
 async fn forward_events(
     mut rx: broadcast::Receiver<DaemonEvent>,
     out_tx_events: mpsc::UnboundedSender<String>,
+    filter: SharedEventFilter,
 ) {
     loop {
         let event = match rx.recv().await {
@@ -1364,6 +2634,14 @@ async fn forward_events(
             Err(broadcast::error::RecvError::Closed) => break,
         };
 
+        let wanted = filter
+            .read()
+            .map(|filter| filter.matches(&event))
+            .unwrap_or(true);
+        if !wanted {
+            continue;
+        }
+
         let Some(payload) = build_event_notification(event) else {
             continue;
         };
@@ -1374,18 +2652,36 @@ async fn forward_events(
     }
 }
 
+fn build_ping_result(state: &DaemonState) -> Value {
+    json!({
+        "ok": true,
+        "pong": true,
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_secs": state.started_at.elapsed().as_secs(),
+    })
+}
+
 async fn handle_client(
     socket: TcpStream,
+    connection_id: String,
     config: Arc<DaemonConfig>,
     state: Arc<DaemonState>,
     events: broadcast::Sender<DaemonEvent>,
 ) {
+    let mut request_seq = 0u64;
     let (reader, mut writer) = socket.into_split();
     let mut lines = BufReader::new(reader).lines();
 
     let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
+    let compress_frames = Arc::new(AtomicBool::new(false));
+    let compress_frames_for_writer = Arc::clone(&compress_frames);
     let write_task = tokio::spawn(async move {
         while let Some(message) = out_rx.recv().await {
+            let message = if compress_frames_for_writer.load(Ordering::Relaxed) {
+                encode_frame(message)
+            } else {
+                message
+            };
             if writer.write_all(message.as_bytes()).await.is_err() {
                 break;
             }
@@ -1396,15 +2692,37 @@ async fn handle_client(
     });
 
     let mut authenticated = config.token.is_none();
+    // Negotiated during `auth`; connections without a token may skip the handshake.
+    let mut protocol_version = DAEMON_PROTOCOL_VERSION;
     let mut events_task: Option<tokio::task::JoinHandle<()>> = None;
+    let event_filter: SharedEventFilter = Arc::default();
 
     if authenticated {
         let rx = events.subscribe();
         let out_tx_events = out_tx.clone();
-        events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+        events_task = Some(tokio::spawn(forward_events(
+            rx,
+            out_tx_events,
+            Arc::clone(&event_filter),
+        )));
     }
 
-    while let Ok(Some(line)) = lines.next_line().await {
+    loop {
+        let next_line = match config.idle_timeout {
+            Some(idle_timeout) => {
+                match tokio::time::timeout(idle_timeout, lines.next_line()).await {
+                    Ok(next_line) => next_line,
+                    Err(_) => {
+                        tracing::info!("closing idle connection");
+                        break;
+                    }
+                }
+            }
+            None => lines.next_line().await,
+        };
+        let Ok(Some(line)) = next_line else {
+            break;
+        };
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -1422,41 +2740,216 @@ async fn handle_client(
             .unwrap_or("")
             .to_string();
         let params = message.get("params").cloned().unwrap_or(Value::Null);
+        request_seq += 1;
+        let correlation_id = format!("{connection_id}-{request_seq}");
 
-        if !authenticated {
-            if method != "auth" {
-                if let Some(response) = build_error_response(id, "unauthorized") {
-                    let _ = out_tx.send(response);
-                }
-                continue;
+        // Liveness probes skip auth and rate limiting so they work without
+        // credentials and never drain a client's token bucket.
+        if method == "ping" {
+            if let Some(response) = build_result_response(id, build_ping_result(&state)) {
+                let _ = out_tx.send(response);
             }
+            continue;
+        }
+
+        let (limit_key, limit_config) = match (authenticated, config.token.as_ref()) {
+            (true, Some(token)) => (format!("token:{token}"), config.rate_limit),
+            (true, None) => (format!("conn:{connection_id}"), config.rate_limit),
+            (false, _) => (format!("unauth:{connection_id}"), UNAUTHENTICATED_RATE_LIMIT),
+        };
+        let limited = state
+            .rate_limiter
+            .lock()
+            .await
+            .check(&limit_key, limit_config, Instant::now());
+        if let Err(retry_after) = limited {
+            let retry_after_ms = retry_after.as_millis().max(1) as u64;
+            tracing::warn!(
+                correlation_id = %correlation_id,
+                method = %method,
+                retry_after_ms,
+                "rate limited"
+            );
+            if let Some(response) = build_error_response_with_data(
+                id,
+                "rate_limited",
+                Some(&correlation_id),
+                Some(json!({ "retryAfterMs": retry_after_ms })),
+            ) {
+                let _ = out_tx.send(response);
+            }
+            continue;
+        }
 
-            let expected = config.token.clone().unwrap_or_default();
-            let provided = parse_auth_token(&params).unwrap_or_default();
-            if expected != provided {
-                if let Some(response) = build_error_response(id, "invalid token") {
-                    let _ = out_tx.send(response);
+        if !authenticated && method != "auth" {
+            tracing::warn!(
+                correlation_id = %correlation_id,
+                method = %method,
+                "rejected unauthenticated request"
+            );
+            if let Some(response) = build_error_response(id, "unauthorized", Some(&correlation_id)) {
+                let _ = out_tx.send(response);
+            }
+            continue;
+        }
+
+        if method == "auth" {
+            if let Some(expected) = config.token.as_ref() {
+                let provided = parse_auth_token(&params).unwrap_or_default();
+                if *expected != provided {
+                    tracing::warn!(
+                        correlation_id = %correlation_id,
+                        reason = "invalid token",
+                        "auth failed"
+                    );
+                    if let Some(response) =
+                        build_error_response(id, "invalid token", Some(&correlation_id))
+                    {
+                        let _ = out_tx.send(response);
+                    }
+                    continue;
                 }
-                continue;
             }
 
-            authenticated = true;
-            if let Some(response) = build_result_response(id, json!({ "ok": true })) {
+            protocol_version = match negotiate_protocol_version(&params) {
+                Ok(version) => version,
+                Err(message) => {
+                    tracing::warn!(
+                        correlation_id = %correlation_id,
+                        reason = %message,
+                        "auth failed"
+                    );
+                    if let Some(response) =
+                        build_error_response(id, &message, Some(&correlation_id))
+                    {
+                        let _ = out_tx.send(response);
+                    }
+                    continue;
+                }
+            };
+            tracing::info!(protocol_version, "client authenticated");
+
+            let compression = negotiate_compression(&params);
+            if let Some(response) =
+                build_result_response(id, build_auth_result(protocol_version, compression))
+            {
                 let _ = out_tx.send(response);
             }
+            // Enabled after queuing the auth response; it stays below the compression
+            // threshold, so the client never sees a compressed frame before opting in.
+            compress_frames.store(compression.is_some(), Ordering::Relaxed);
 
-            let rx = events.subscribe();
-            let out_tx_events = out_tx.clone();
-            events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+            if let Some(filter) = parse_optional_value(&params, "events") {
+                if let Ok(mut current) = event_filter.write() {
+                    *current = EventFilter::from_params(&filter);
+                }
+            }
+
+            if !authenticated {
+                authenticated = true;
+                let rx = events.subscribe();
+                let out_tx_events = out_tx.clone();
+                events_task = Some(tokio::spawn(forward_events(
+                    rx,
+                    out_tx_events,
+                    Arc::clone(&event_filter),
+                )));
+            }
 
             continue;
         }
 
+        // Connection-scoped, so handled here rather than in `handle_rpc_request`.
+        if method == "subscribe_events" {
+            let filter = EventFilter::from_params(&params);
+            if let Ok(mut current) = event_filter.write() {
+                *current = filter.clone();
+            }
+            let result = json!({
+                "ok": true,
+                "kinds": filter.kinds,
+                "workspaceIds": filter.workspace_ids,
+            });
+            if let Some(response) = build_result_response(id, result) {
+                let _ = out_tx.send(response);
+            }
+            continue;
+        }
+
+        // Also connection-scoped, and run in the background so that
+        // `cancel_git_diff_stream` can arrive while the stream is running.
+        if method == "stream_git_diffs" {
+            let state = Arc::clone(&state);
+            let out_tx = out_tx.clone();
+            tokio::spawn(
+                async move {
+                    let started_at = Instant::now();
+                    let result =
+                        stream_git_diffs_to_client(&state, &params, out_tx.clone()).await;
+                    let duration_ms = started_at.elapsed().as_millis() as u64;
+                    let response = match result {
+                        Ok(completed) => {
+                            tracing::info!(
+                                correlation_id = %correlation_id,
+                                method = "stream_git_diffs",
+                                id,
+                                duration_ms,
+                                outcome = "ok",
+                                "rpc request"
+                            );
+                            build_result_response(id, json!(completed))
+                        }
+                        Err(message) => {
+                            tracing::warn!(
+                                correlation_id = %correlation_id,
+                                method = "stream_git_diffs",
+                                id,
+                                duration_ms,
+                                outcome = "error",
+                                error = %message,
+                                "rpc request"
+                            );
+                            build_error_response(id, &message, Some(&correlation_id))
+                        }
+                    };
+                    if let Some(response) = response {
+                        let _ = out_tx.send(response);
+                    }
+                }
+                .in_current_span(),
+            );
+            continue;
+        }
+
         let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
-        let result = handle_rpc_request(&state, &method, params, client_version).await;
+        let started_at = Instant::now();
+        let result =
+            handle_rpc_request(&state, &method, params, client_version, protocol_version).await;
+        let duration_ms = started_at.elapsed().as_millis() as u64;
         let response = match result {
-            Ok(result) => build_result_response(id, result),
-            Err(message) => build_error_response(id, &message),
+            Ok(result) => {
+                tracing::info!(
+                    correlation_id = %correlation_id,
+                    method = %method,
+                    id,
+                    duration_ms,
+                    outcome = "ok",
+                    "rpc request"
+                );
+                build_result_response(id, result)
+            }
+            Err(message) => {
+                tracing::warn!(
+                    correlation_id = %correlation_id,
+                    method = %method,
+                    id,
+                    duration_ms,
+                    outcome = "error",
+                    error = %message,
+                    "rpc request"
+                );
+                build_error_response(id, &message, Some(&correlation_id))
+            }
         };
         if let Some(response) = response {
             let _ = out_tx.send(response);
@@ -1468,6 +2961,62 @@ async fn handle_client(
         task.abort();
     }
     write_task.abort();
+    {
+        let mut rate_limiter = state.rate_limiter.lock().await;
+        rate_limiter.forget(&format!("conn:{connection_id}"));
+        rate_limiter.forget(&format!("unauth:{connection_id}"));
+    }
+    tracing::info!(requests = request_seq, "client disconnected");
+}
+
+async fn reject_connection(mut socket: TcpStream) {
+    let payload = json!({ "error": { "message": TOO_MANY_CONNECTIONS_MESSAGE } }).to_string();
+    let _ = socket.write_all(payload.as_bytes()).await;
+    let _ = socket.write_all(b"\n").await;
+    let _ = socket.shutdown().await;
+}
+
+async fn serve(
+    listener: TcpListener,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+    events_tx: broadcast::Sender<DaemonEvent>,
+) {
+    let connection_slots = Arc::new(Semaphore::new(config.max_connections));
+    loop {
+        match listener.accept().await {
+            Ok((socket, addr)) => {
+                let Ok(permit) = Arc::clone(&connection_slots).try_acquire_owned() else {
+                    tracing::warn!(
+                        peer = %addr,
+                        reason = TOO_MANY_CONNECTIONS_MESSAGE,
+                        "connection refused"
+                    );
+                    tokio::spawn(reject_connection(socket));
+                    continue;
+                };
+                let connection_id = format!(
+                    "conn-{}",
+                    NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed)
+                );
+                let span = tracing::info_span!("connection", connection_id = %connection_id);
+                span.in_scope(|| tracing::info!(peer = %addr, "client connected"));
+                let config = Arc::clone(&config);
+                let state = Arc::clone(&state);
+                let events = events_tx.clone();
+                tokio::spawn(async move {
+                    handle_client(socket, connection_id, config, state, events)
+                        .instrument(span)
+                        .await;
+                    drop(permit);
+                });
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "accept failed");
+                continue;
+            }
+        }
+    }
 }
 
 fn main() {
@@ -1478,6 +3027,7 @@ fn main() {
             std::process::exit(2);
         }
     };
+    init_tracing(&config);
 
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -1495,28 +3045,197 @@ fn main() {
         let listener = TcpListener::bind(config.listen)
             .await
             .unwrap_or_else(|err| panic!("failed to bind {}: {err}", config.listen));
-        eprintln!(
-            "codex-monitor-daemon listening on {} (data dir: {})",
-            config.listen,
-            state
+        tracing::info!(
+            listen = %config.listen,
+            data_dir = %state
                 .storage_path
                 .parent()
                 .unwrap_or(&state.storage_path)
-                .display()
+                .display(),
+            "codex-monitor-daemon listening"
         );
 
-        loop {
-            match listener.accept().await {
-                Ok((socket, _addr)) => {
-                    let config = Arc::clone(&config);
-                    let state = Arc::clone(&state);
-                    let events = events_tx.clone();
-                    tokio::spawn(async move {
-                        handle_client(socket, config, state, events).await;
-                    });
-                }
-                Err(_) => continue,
-            }
-        }
+        serve(listener, config, state, events_tx).await;
     });
 }
+
+/// A workspace entry as served to remote clients. Scripts and codex args can
+/// embed credentials, so they stay on the daemon, and env values are masked.
+fn public_workspace_entry(mut entry: WorkspaceEntry) -> WorkspaceEntry {
+    entry.settings = entry.settings.redacted();
+    entry.settings.codex_args = None;
+    entry.settings.launch_script = None;
+    entry.settings.launch_scripts = None;
+    entry.settings.worktree_setup_script = None;
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(max_connections: usize) -> DaemonConfig {
+        DaemonConfig {
+            listen: "127.0.0.1:0".parse().expect("listen addr"),
+            token: None,
+            data_dir: env::temp_dir().join(format!(
+                "codex-monitor-daemon-test-{}",
+                uuid::Uuid::new_v4()
+            )),
+            log_level: LevelFilter::ERROR,
+            log_format: LogFormat::Pretty,
+            rate_limit: RateLimitConfig {
+                per_second: DEFAULT_RATE_LIMIT_PER_SECOND,
+                burst: DEFAULT_RATE_LIMIT_BURST,
+            },
+            max_connections,
+            idle_timeout: None,
+        }
+    }
+
+    fn run_async<F: std::future::Future<Output = ()>>(future: F) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("create runtime")
+            .block_on(future);
+    }
+
+    async fn start_daemon(config: DaemonConfig) -> SocketAddr {
+        let (events_tx, _events_rx) = broadcast::channel::<DaemonEvent>(16);
+        let event_sink = DaemonEventSink {
+            tx: events_tx.clone(),
+        };
+        let state = Arc::new(DaemonState::load(&config, event_sink));
+        let listener = TcpListener::bind(config.listen).await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(serve(listener, Arc::new(config), state, events_tx));
+        addr
+    }
+
+    async fn request(stream: &mut TcpStream, payload: &str) -> String {
+        let (reader, mut writer) = stream.split();
+        writer.write_all(payload.as_bytes()).await.expect("write");
+        writer.write_all(b"\n").await.expect("write newline");
+        let mut line = String::new();
+        BufReader::new(reader)
+            .read_line(&mut line)
+            .await
+            .expect("read line");
+        line
+    }
+
+    #[test]
+    fn event_filter_matches_kind_and_workspace() {
+        let output = DaemonEvent::TerminalOutput(TerminalOutput {
+            workspace_id: "ws-1".to_string(),
+            terminal_id: "term".to_string(),
+            data: "hi".to_string(),
+        });
+        assert!(EventFilter::default().matches(&output));
+
+        let filter = EventFilter::from_params(&json!({ "workspaceIds": ["ws-1"] }));
+        assert!(filter.matches(&output));
+        let filter = EventFilter::from_params(&json!({ "workspaceIds": ["ws-2"] }));
+        assert!(!filter.matches(&output));
+
+        let filter = EventFilter::from_params(&json!({
+            "kinds": ["app-server-event"],
+            "workspaceIds": ["ws-1"],
+        }));
+        assert!(!filter.matches(&output));
+        let filter = EventFilter::from_params(&json!({ "kinds": ["terminal-output"] }));
+        assert!(filter.matches(&output));
+    }
+
+    #[test]
+    fn public_workspace_entry_drops_scripts_and_args() {
+        let entry = WorkspaceEntry {
+            id: "ws-1".to_string(),
+            name: "app".to_string(),
+            path: "/repo/app".to_string(),
+            codex_bin: None,
+            kind: Default::default(),
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings {
+                group_id: Some("group".to_string()),
+                codex_args: Some("-c api_key=secret".to_string()),
+                launch_script: Some("TOKEN=secret npm start".to_string()),
+                worktree_setup_script: Some("cp ~/.env .".to_string()),
+                env: HashMap::from([("GH_TOKEN".to_string(), "secret".to_string())]),
+                ..WorkspaceSettings::default()
+            },
+        };
+        let public = public_workspace_entry(entry);
+        assert_eq!(public.settings.env["GH_TOKEN"], types::REDACTED_ENV_VALUE);
+        assert_eq!(public.path, "/repo/app");
+        assert_eq!(public.settings.group_id.as_deref(), Some("group"));
+        assert!(public.settings.codex_args.is_none());
+        assert!(public.settings.launch_script.is_none());
+        assert!(public.settings.worktree_setup_script.is_none());
+    }
+
+    #[test]
+    fn refuses_connections_beyond_max() {
+        run_async(async {
+            let max_connections = 2;
+            let addr = start_daemon(test_config(max_connections)).await;
+
+            let mut accepted = Vec::new();
+            for _ in 0..max_connections {
+                let mut stream = TcpStream::connect(addr).await.expect("connect");
+                let response = request(&mut stream, r#"{"id":1,"method":"ping"}"#).await;
+                assert!(response.contains("\"ok\":true"), "unexpected: {response}");
+                accepted.push(stream);
+            }
+
+            let refused = TcpStream::connect(addr).await.expect("connect");
+            let mut lines = BufReader::new(refused).lines();
+            let line = lines
+                .next_line()
+                .await
+                .expect("read refusal")
+                .expect("refusal line");
+            assert!(line.contains(TOO_MANY_CONNECTIONS_MESSAGE), "unexpected: {line}");
+            assert!(lines.next_line().await.expect("read eof").is_none());
+            drop(accepted);
+        });
+    }
+
+    #[test]
+    fn ping_skips_auth_and_rate_limit() {
+        run_async(async {
+            let mut config = test_config(4);
+            config.token = Some("secret".to_string());
+            let addr = start_daemon(config).await;
+
+            let mut stream = TcpStream::connect(addr).await.expect("connect");
+            for id in 0..10 {
+                let payload = format!(r#"{{"id":{id},"method":"ping"}}"#);
+                let response = request(&mut stream, &payload).await;
+                assert!(response.contains("\"pong\":true"), "unexpected: {response}");
+                assert!(response.contains("\"uptime_secs\""), "unexpected: {response}");
+            }
+
+            let response = request(&mut stream, r#"{"id":99,"method":"list_workspaces"}"#).await;
+            assert!(response.contains("unauthorized"), "unexpected: {response}");
+        });
+    }
+
+    #[test]
+    fn closes_idle_connections() {
+        run_async(async {
+            let mut config = test_config(4);
+            config.idle_timeout = Some(Duration::from_millis(50));
+            let addr = start_daemon(config).await;
+
+            let stream = TcpStream::connect(addr).await.expect("connect");
+            let mut lines = BufReader::new(stream).lines();
+            let next = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+                .await
+                .expect("connection should close before the test timeout");
+            assert!(matches!(next, Ok(None) | Err(_)));
+        });
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RateLimitConfig {
+    /// Sustained requests per second. Zero disables limiting.
+    pub(crate) per_second: f64,
+    pub(crate) burst: f64,
+}
+
+impl RateLimitConfig {
+    pub(crate) fn is_disabled(&self) -> bool {
+        self.per_second <= 0.0
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(config: RateLimitConfig, now: Instant) -> Self {
+        Self {
+            tokens: config.burst.max(1.0),
+            updated_at: now,
+        }
+    }
+
+    fn try_take(&mut self, config: RateLimitConfig, now: Instant) -> Result<(), Duration> {
+        let capacity = config.burst.max(1.0);
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.per_second).min(capacity);
+        self.updated_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        let missing = 1.0 - self.tokens;
+        Err(Duration::from_secs_f64(missing / config.per_second))
+    }
+}
+
+/// Token-bucket limiter keyed by auth token (or connection id when the client
+/// has not authenticated). Returns the suggested retry delay when exhausted.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl RateLimiter {
+    pub(crate) fn check(
+        &mut self,
+        key: &str,
+        config: RateLimitConfig,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        if config.is_disabled() {
+            return Ok(());
+        }
+        self.buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::new(config, now))
+            .try_take(config, now)
+    }
+
+    pub(crate) fn forget(&mut self, key: &str) {
+        self.buckets.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: RateLimitConfig = RateLimitConfig {
+        per_second: 2.0,
+        burst: 3.0,
+    };
+
+    #[test]
+    fn allows_burst_then_limits() {
+        let mut limiter = RateLimiter::default();
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check("token", CONFIG, now).is_ok());
+        }
+        let retry_after = limiter
+            .check("token", CONFIG, now)
+            .expect_err("bucket should be exhausted");
+        assert_eq!(retry_after, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn refills_over_time_per_key() {
+        let mut limiter = RateLimiter::default();
+        let now = Instant::now();
+        for _ in 0..3 {
+            limiter.check("a", CONFIG, now).expect("within burst");
+        }
+        assert!(limiter.check("a", CONFIG, now).is_err());
+        assert!(limiter.check("b", CONFIG, now).is_ok());
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.check("a", CONFIG, later).is_ok());
+    }
+
+    #[test]
+    fn zero_rate_disables_limiting() {
+        let mut limiter = RateLimiter::default();
+        let config = RateLimitConfig {
+            per_second: 0.0,
+            burst: 0.0,
+        };
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(limiter.check("token", config, now).is_ok());
+        }
+    }
+}
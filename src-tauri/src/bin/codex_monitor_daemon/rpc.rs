@@ -0,0 +1,1030 @@
+use serde_json::{json, Value};
+
+/// Bumped whenever a method is added, removed, or changes its params so clients
+/// can detect protocol additions without probing individual methods.
+pub(crate) const RPC_REGISTRY_VERSION: u32 = 72;
+
+pub(crate) struct RpcParamSpec {
+    pub(crate) name: &'static str,
+    pub(crate) kind: &'static str,
+    pub(crate) required: bool,
+}
+
+pub(crate) struct RpcMethodSpec {
+    pub(crate) name: &'static str,
+    pub(crate) description: &'static str,
+    pub(crate) params: &'static [RpcParamSpec],
+}
+
+const fn required(name: &'static str, kind: &'static str) -> RpcParamSpec {
+    RpcParamSpec {
+        name,
+        kind,
+        required: true,
+    }
+}
+
+const fn optional(name: &'static str, kind: &'static str) -> RpcParamSpec {
+    RpcParamSpec {
+        name,
+        kind,
+        required: false,
+    }
+}
+
+const WORKSPACE_ID: &[RpcParamSpec] = &[required("workspaceId", "string")];
+const WORKSPACE_THREAD: &[RpcParamSpec] = &[
+    required("workspaceId", "string"),
+    required("threadId", "string"),
+];
+const WORKSPACE_PAGE: &[RpcParamSpec] = &[
+    required("workspaceId", "string"),
+    optional("cursor", "string"),
+    optional("limit", "number"),
+];
+const ID: &[RpcParamSpec] = &[required("id", "string")];
+const PROFILE_ID: &[RpcParamSpec] = &[required("profileId", "string")];
+const NONE: &[RpcParamSpec] = &[];
+
+pub(crate) const RPC_METHODS: &[RpcMethodSpec] = &[
+    RpcMethodSpec {
+        name: "auth",
+        description: "Authenticate the connection and negotiate the protocol version.",
+        params: &[
+            required("token", "string"),
+            optional("protocolVersion", "number"),
+            optional("clientVersion", "string"),
+            optional("compression", "string[]"),
+            optional("events", "object"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "subscribe_events",
+        description: "Limit this connection's events by kind and/or workspace; omit both for all events.",
+        params: &[optional("kinds", "string[]"), optional("workspaceIds", "string[]")],
+    },
+    RpcMethodSpec {
+        name: "ping",
+        description: "Liveness probe; allowed before auth and not rate limited.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "rpc.list",
+        description: "List supported methods with descriptions and param schemas.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "list_workspaces",
+        description: "List registered workspaces and their connection state.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "workspaces.list",
+        description: "List the daemon's workspace entries with settings; scripts and codex args are withheld.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "is_workspace_path_dir",
+        description: "Check whether a path is an existing directory.",
+        params: &[required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "add_workspace",
+        description: "Register a workspace folder and spawn its session.",
+        params: &[required("path", "string"), optional("codex_bin", "string")],
+    },
+    RpcMethodSpec {
+        name: "add_worktree",
+        description: "Create a git worktree workspace under a parent workspace.",
+        params: &[
+            required("parentId", "string"),
+            required("branch", "string"),
+            optional("name", "string"),
+            optional("copyAgentsMd", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "worktree_setup_status",
+        description: "Report whether the worktree setup script still needs to run.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "worktree_setup_mark_ran",
+        description: "Mark the worktree setup script as completed.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "connect_workspace",
+        description: "Spawn or reconnect the session for a workspace.",
+        params: ID,
+    },
+    RpcMethodSpec {
+        name: "remove_workspace",
+        description: "Remove a workspace and its child worktrees.",
+        params: ID,
+    },
+    RpcMethodSpec {
+        name: "remove_worktree",
+        description: "Remove a worktree workspace and its checkout.",
+        params: ID,
+    },
+    RpcMethodSpec {
+        name: "rename_worktree",
+        description: "Rename the branch of a worktree workspace.",
+        params: &[required("id", "string"), required("branch", "string")],
+    },
+    RpcMethodSpec {
+        name: "rename_worktree_upstream",
+        description: "Rename the upstream branch of a worktree workspace.",
+        params: &[
+            required("id", "string"),
+            required("oldBranch", "string"),
+            required("newBranch", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "update_workspace_settings",
+        description: "Replace the settings of a workspace.",
+        params: &[required("id", "string"), required("settings", "object")],
+    },
+    RpcMethodSpec {
+        name: "reorder_workspaces",
+        description: "Set the sort order of every workspace in one write.",
+        params: &[required("orderedIds", "string[]")],
+    },
+    RpcMethodSpec {
+        name: "create_workspace_group",
+        description: "Create a workspace group.",
+        params: &[required("name", "string"), optional("color", "string")],
+    },
+    RpcMethodSpec {
+        name: "rename_workspace_group",
+        description: "Rename a workspace group.",
+        params: &[required("groupId", "string"), required("name", "string")],
+    },
+    RpcMethodSpec {
+        name: "set_workspace_group_color",
+        description: "Set or clear a workspace group's color.",
+        params: &[required("groupId", "string"), optional("color", "string")],
+    },
+    RpcMethodSpec {
+        name: "set_workspace_group_collapsed",
+        description: "Collapse or expand a workspace group in the sidebar.",
+        params: &[required("groupId", "string"), required("collapsed", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "delete_workspace_group",
+        description: "Delete a workspace group and ungroup its members.",
+        params: &[required("groupId", "string")],
+    },
+    RpcMethodSpec {
+        name: "update_workspace_codex_bin",
+        description: "Override the codex binary used by a workspace.",
+        params: &[required("id", "string"), optional("codex_bin", "string")],
+    },
+    RpcMethodSpec {
+        name: "discover_workspaces",
+        description: "Find unregistered git repositories under a folder.",
+        params: &[required("root", "string"), optional("depth", "number")],
+    },
+    RpcMethodSpec {
+        name: "list_workspace_files",
+        description: "List files in a workspace, honoring ignore rules.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "read_workspace_file",
+        description: "Read a text file relative to a workspace root.",
+        params: &[required("workspaceId", "string"), required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "file_read_range",
+        description: "Read a byte range of a policy-managed file.",
+        params: &[
+            required("scope", "string"),
+            required("kind", "string"),
+            optional("workspaceId", "string"),
+            optional("offset", "number"),
+            required("length", "number"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "file_tail",
+        description: "Read bytes appended to a file since an offset.",
+        params: &[
+            required("scope", "string"),
+            optional("kind", "string"),
+            optional("workspaceId", "string"),
+            optional("path", "string"),
+            optional("fromOffset", "number"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "file_read",
+        description: "Read a policy-managed file (AGENTS.md or config.toml).",
+        params: &[
+            required("scope", "string"),
+            required("kind", "string"),
+            optional("workspaceId", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "file_write",
+        description: "Write a policy-managed file (AGENTS.md or config.toml).",
+        params: &[
+            required("scope", "string"),
+            required("kind", "string"),
+            optional("workspaceId", "string"),
+            required("content", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "file_write_transaction",
+        description: "Write several policy-managed files all-or-nothing.",
+        params: &[required("edits", "object[]")],
+    },
+    RpcMethodSpec {
+        name: "get_app_settings",
+        description: "Read the daemon app settings.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "update_app_settings",
+        description: "Replace the daemon app settings.",
+        params: &[required("settings", "object")],
+    },
+    RpcMethodSpec {
+        name: "reload_codex_config",
+        description: "Re-read feature flags, personality and auth store from config.toml; the daemon does not watch it.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "export_settings_profile",
+        description: "Export app settings as a shareable profile; secrets are omitted unless requested.",
+        params: &[optional("includeSecrets", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "import_settings_profile",
+        description: "Apply a settings profile, merging into current settings by default.",
+        params: &[required("profile", "object"), optional("merge", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "get_codex_config_path",
+        description: "Resolve config.toml, honoring a workspace CODEX_HOME override.",
+        params: &[optional("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_effective_config",
+        description: "Read config.toml merged with the active profile, with key sources.",
+        params: &[optional("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "diff_codex_config",
+        description: "Compare the global config.toml with a workspace's config.toml.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "get_project_trust",
+        description: "Read the Codex trust level recorded for a project path.",
+        params: &[required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "set_project_trust",
+        description: "Mark a project path as trusted or untrusted in config.toml.",
+        params: &[required("path", "string"), required("trusted", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "list_feature_flags",
+        description: "List every boolean flag in the config.toml [features] table.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "get_feature_flag",
+        description: "Read any [features] flag from config.toml; null when unset.",
+        params: &[required("key", "string")],
+    },
+    RpcMethodSpec {
+        name: "set_feature_flag",
+        description: "Set any [features] flag in config.toml.",
+        params: &[required("key", "string"), required("enabled", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "get_git_status",
+        description: "Report git status for a workspace's repository.",
+        params: &[required("workspaceId", "string"), optional("untrackedMode", "string")],
+    },
+    RpcMethodSpec {
+        name: "is_working_tree_clean",
+        description: "Fast clean/dirty check; stops at the first change unless countChanges is set.",
+        params: &[required("workspaceId", "string"), optional("countChanges", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "fetch_all_open_prs_as_branches",
+        description: "Fetch every pull request head as <remote>/pr/<n>; refspec overrides the GitHub layout.",
+        params: &[
+            required("workspaceId", "string"),
+            optional("remote", "string"),
+            optional("refspec", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "resolve_ref",
+        description: "Resolve a branch, tag, remote ref or revision to its commit.",
+        params: &[required("workspaceId", "string"), required("refName", "string")],
+    },
+    RpcMethodSpec {
+        name: "init_repo",
+        description: "Run git init at the workspace path; refuses inside an existing repository.",
+        params: &[required("workspaceId", "string"), optional("initialBranch", "string")],
+    },
+    RpcMethodSpec {
+        name: "set_upstream_and_push",
+        description: "Add a remote if absent, push a branch with -u and return the new upstream.",
+        params: &[
+            required("workspaceId", "string"),
+            required("remoteName", "string"),
+            required("url", "string"),
+            optional("branch", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_git_status_batch",
+        description: "Report git status (or change counts) for several workspaces; errors are per workspace.",
+        params: &[required("workspaceIds", "string[]"), optional("summaryOnly", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "validate_workspace_git",
+        description: "Check that a workspace still resolves to an openable git repository.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "get_conflict_versions",
+        description: "Read the base/ours/theirs versions of a conflicted file.",
+        params: &[required("workspaceId", "string"), required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "resolve_conflict_with_content",
+        description: "Write merged content over a conflicted file and stage it.",
+        params: &[
+            required("workspaceId", "string"),
+            required("path", "string"),
+            required("content", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "stash_apply_git",
+        description: "Apply stash@{index} without dropping it from the stash list.",
+        params: &[required("workspaceId", "string"), required("index", "number")],
+    },
+    RpcMethodSpec {
+        name: "list_changed_paths_against_ref",
+        description: "List files changed between a ref and the working tree, with line counts only.",
+        params: &[required("workspaceId", "string"), required("baseRef", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_ahead_behind_against",
+        description: "Ahead/behind counts and entries of HEAD against any ref.",
+        params: &[
+            required("workspaceId", "string"),
+            required("otherRef", "string"),
+            optional("limit", "number"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "list_recent_branches",
+        description: "Local branches in the order they were last checked out.",
+        params: &[required("workspaceId", "string"), optional("limit", "number")],
+    },
+    RpcMethodSpec {
+        name: "checkout_commit",
+        description: "Detach HEAD at a commit; force discards tracked changes. Journaled for undo.",
+        params: &[
+            required("workspaceId", "string"),
+            required("sha", "string"),
+            optional("force", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "create_branch_at_head",
+        description: "Name the current (e.g. detached) HEAD commit as a branch and attach HEAD.",
+        params: &[required("workspaceId", "string"), required("name", "string")],
+    },
+    RpcMethodSpec {
+        name: "stream_git_diffs",
+        description: "Send working tree diffs as git-diff-stream events; false when cancelled.",
+        params: &[
+            required("workspaceId", "string"),
+            required("streamId", "string"),
+            optional("whitespaceCheck", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "cancel_git_diff_stream",
+        description: "Stop a running stream_git_diffs started with the same stream id.",
+        params: &[required("streamId", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_default_branch",
+        description: "Default branch from origin/HEAD, else inferred from main/master; cached.",
+        params: &[required("workspaceId", "string"), optional("refresh", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "show_stash_diff",
+        description: "Diff of stash@{index}, including the untracked files stashed with -u.",
+        params: &[required("workspaceId", "string"), required("index", "number")],
+    },
+    RpcMethodSpec {
+        name: "get_git_undo_stack",
+        description: "Journaled git actions that can be undone, most recent first.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "undo_last_git_action",
+        description: "Undo the most recent journaled git action; requires confirm.",
+        params: &[
+            required("workspaceId", "string"),
+            required("confirm", "boolean"),
+            optional("overrideProtection", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_github_pull_request",
+        description: "One PR by number via gh, with mergeability, review decision and checks.",
+        params: &[required("workspaceId", "string"), required("prNumber", "number")],
+    },
+    RpcMethodSpec {
+        name: "get_github_pull_request_diff",
+        description: "A PR's diff from `gh pr diff`, split into per-file entries.",
+        params: &[required("workspaceId", "string"), required("prNumber", "number")],
+    },
+    RpcMethodSpec {
+        name: "get_github_pull_request_raw_diff",
+        description: "A PR's unified diff exactly as `gh pr diff` prints it.",
+        params: &[required("workspaceId", "string"), required("prNumber", "number")],
+    },
+    RpcMethodSpec {
+        name: "stage_git_file",
+        description: "Stage one file, including both sides of a detected rename.",
+        params: &[required("workspaceId", "string"), required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "stage_git_all",
+        description: "Stage every change in the repository (`git add -A`).",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "unstage_git_file",
+        description: "Unstage one file, including both sides of a detected rename.",
+        params: &[required("workspaceId", "string"), required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "revert_git_file",
+        description: "Discard staged and unstaged changes to one file, deleting it if untracked.",
+        params: &[required("workspaceId", "string"), required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "revert_git_all",
+        description: "Discard all changes after stashing them so the revert can be undone.",
+        params: &[
+            required("workspaceId", "string"),
+            optional("overrideProtection", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "push_git",
+        description: "Push HEAD to its upstream, optionally skipping the pre-push hook.",
+        params: &[required("workspaceId", "string"), optional("noVerify", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "pull_git",
+        description: "Pull with the configured strategy, autostashing local changes.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "fetch_git",
+        description: "Fetch and prune the upstream remote, optionally to a given depth.",
+        params: &[required("workspaceId", "string"), optional("fetchDepth", "number")],
+    },
+    RpcMethodSpec {
+        name: "fetch_git_unshallow",
+        description: "Fetch the full history of a shallow clone.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "sync_git",
+        description: "Pull then push the current branch.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_git_log",
+        description: "Commits on HEAD plus ahead/behind upstream; optional detail and signatures.",
+        params: &[
+            required("workspaceId", "string"),
+            optional("limit", "number"),
+            optional("pathspec", "string[]"),
+            optional("detail", "boolean"),
+            optional("verify", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_git_commit_diff",
+        description: "Per-file diffs of a commit against its first parent.",
+        params: &[
+            required("workspaceId", "string"),
+            required("sha", "string"),
+            optional("fullFile", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_git_remote",
+        description: "URL of `origin`, or of the first remote when there is no `origin`.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "list_git_branches",
+        description: "Local branches, most recently committed first.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "checkout_git_branch",
+        description: "Check out a local branch; the previous HEAD is journaled for undo.",
+        params: &[required("workspaceId", "string"), required("name", "string")],
+    },
+    RpcMethodSpec {
+        name: "create_git_branch",
+        description: "Create a branch at HEAD and check it out.",
+        params: &[required("workspaceId", "string"), required("name", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_github_issues",
+        description: "Up to 50 open issues via gh, with the total open count.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_github_pull_requests",
+        description: "Up to 50 open PRs via gh, with the total open count.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_github_pull_request_comments",
+        description: "The first 30 conversation comments on a PR.",
+        params: &[required("workspaceId", "string"), required("prNumber", "number")],
+    },
+    RpcMethodSpec {
+        name: "get_git_change_summary",
+        description: "Changed, staged, unstaged and conflicted file counts.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "reword_head_commit",
+        description: "Replace HEAD's message, keeping its tree; journaled for undo.",
+        params: &[
+            required("workspaceId", "string"),
+            required("message", "string"),
+            optional("allowMerge", "boolean"),
+            optional("overrideProtection", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "reset_head_author",
+        description: "Amend HEAD with a new author identity and a refreshed author date.",
+        params: &[
+            required("workspaceId", "string"),
+            required("name", "string"),
+            required("email", "string"),
+            optional("overrideProtection", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "reset_head_date",
+        description: "Amend HEAD's author date to a Unix timestamp, keeping its offset.",
+        params: &[
+            required("workspaceId", "string"),
+            required("timestamp", "number"),
+            optional("overrideProtection", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "bisect_start",
+        description: "Start bisecting between a bad and a good revision.",
+        params: &[
+            required("workspaceId", "string"),
+            required("bad", "string"),
+            required("good", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "bisect_mark",
+        description: "Mark the commit under test as good, bad or skip.",
+        params: &[required("workspaceId", "string"), required("result", "string")],
+    },
+    RpcMethodSpec {
+        name: "bisect_reset",
+        description: "End the bisect and return to the branch it started from.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_repo_operation_state",
+        description: "The merge, rebase, cherry-pick, revert or bisect in progress, if any.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "operation_continue",
+        description: "Continue the merge, rebase, cherry-pick or revert in progress.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "operation_skip",
+        description: "Skip the current step of the rebase, cherry-pick, revert or bisect.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "operation_abort",
+        description: "Abort the operation in progress, restoring the state before it began.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "read_git_config",
+        description: "Effective values of git config keys, with the scope each came from.",
+        params: &[required("workspaceId", "string"), required("keys", "string[]")],
+    },
+    RpcMethodSpec {
+        name: "write_git_config",
+        description: "Set a git config key in the repo's local config or the global config.",
+        params: &[
+            required("workspaceId", "string"),
+            required("key", "string"),
+            required("value", "string"),
+            required("scope", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "stage_git_pattern",
+        description: "Stage every path matching repo-relative pathspecs (magic allowed).",
+        params: &[required("workspaceId", "string"), required("pathspec", "string[]")],
+    },
+    RpcMethodSpec {
+        name: "unstage_git_pattern",
+        description: "Unstage every path matching repo-relative pathspecs (magic allowed).",
+        params: &[required("workspaceId", "string"), required("pathspec", "string[]")],
+    },
+    RpcMethodSpec {
+        name: "move_changes_to_new_branch",
+        description: "Create a branch and switch to it, carrying uncommitted changes along.",
+        params: &[
+            required("workspaceId", "string"),
+            required("name", "string"),
+            optional("startPoint", "string"),
+            optional("stash", "boolean"),
+            optional("overrideProtection", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "restore_file_from_rev",
+        description: "Overwrite a worktree file with its content at a revision.",
+        params: &[
+            required("workspaceId", "string"),
+            required("path", "string"),
+            required("rev", "string"),
+            optional("force", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_ref_comparison_diff",
+        description: "Commits in `head` but not `base`, and the diff between the two refs.",
+        params: &[
+            required("workspaceId", "string"),
+            required("base", "string"),
+            required("head", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "preview_sync_git",
+        description: "Fetch, then report ahead/behind and would-be conflicts against upstream.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "fetch_all_git",
+        description: "Fetch and prune every remote, reporting each remote's outcome.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "list_remote_branches",
+        description: "Remote-tracking branches of the workspace repository.",
+        params: &[required("workspaceId", "string")],
+    },
+    RpcMethodSpec {
+        name: "prune_remote_branches",
+        description: "Run `git remote prune` and list the remote-tracking branches it removed.",
+        params: &[required("workspaceId", "string"), required("remote", "string")],
+    },
+    RpcMethodSpec {
+        name: "explain_ignore",
+        description: "Report which ignore rule, if any, matches a path (git check-ignore -v).",
+        params: &[required("workspaceId", "string"), required("path", "string")],
+    },
+    RpcMethodSpec {
+        name: "get_commit_notes",
+        description: "Read a commit's git note from the default or a named notes ref.",
+        params: &[
+            required("workspaceId", "string"),
+            required("sha", "string"),
+            optional("notesRef", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_repo_stats",
+        description: "Summarize commit, branch, tag and file counts plus on-disk sizes.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "repair_repo",
+        description: "Remove a stale index.lock and run a connectivity-only fsck.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "gc_repo",
+        description: "Run git gc (optionally aggressive) and report .git size before and after. Progress is not streamed over RPC.",
+        params: &[required("workspaceId", "string"), optional("aggressive", "boolean")],
+    },
+    RpcMethodSpec {
+        name: "build_permalink",
+        description: "Build a forge web URL for a file line, pinned to a commit sha.",
+        params: &[
+            required("workspaceId", "string"),
+            required("path", "string"),
+            required("line", "number"),
+            optional("rev", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_git_diffs",
+        description: "Diff a workspace's working tree against HEAD; fullFile shows whole files.",
+        params: &[
+            required("workspaceId", "string"),
+            optional("whitespaceCheck", "boolean"),
+            optional("fullFile", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "commit_git",
+        description: "Commit staged changes, optionally limited to selected paths.",
+        params: &[
+            required("workspaceId", "string"),
+            required("message", "string"),
+            optional("paths", "string[]"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "commit_with_body",
+        description: "Commit staged changes with a subject and body; overlong subjects produce warnings.",
+        params: &[
+            required("workspaceId", "string"),
+            required("subject", "string"),
+            optional("body", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "commit_and_push",
+        description: "Optionally stage everything, then commit and push; a failed step is reported in the result.",
+        params: &[
+            required("workspaceId", "string"),
+            required("message", "string"),
+            optional("stageAll", "boolean"),
+            optional("coAuthors", "string[]"),
+            optional("overrideProtection", "boolean"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "get_config_model",
+        description: "Read the model configured for a workspace.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "start_thread",
+        description: "Start a new thread in a workspace.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "resume_thread",
+        description: "Resume an existing thread.",
+        params: WORKSPACE_THREAD,
+    },
+    RpcMethodSpec {
+        name: "fork_thread",
+        description: "Fork an existing thread.",
+        params: WORKSPACE_THREAD,
+    },
+    RpcMethodSpec {
+        name: "list_threads",
+        description: "List threads for a workspace.",
+        params: WORKSPACE_PAGE,
+    },
+    RpcMethodSpec {
+        name: "list_mcp_server_status",
+        description: "List MCP server status for a workspace.",
+        params: WORKSPACE_PAGE,
+    },
+    RpcMethodSpec {
+        name: "archive_thread",
+        description: "Archive a thread.",
+        params: WORKSPACE_THREAD,
+    },
+    RpcMethodSpec {
+        name: "compact_thread",
+        description: "Compact the context of a thread.",
+        params: WORKSPACE_THREAD,
+    },
+    RpcMethodSpec {
+        name: "set_thread_name",
+        description: "Rename a thread.",
+        params: &[
+            required("workspaceId", "string"),
+            required("threadId", "string"),
+            required("name", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "send_user_message",
+        description: "Send a user message and start a turn.",
+        params: &[
+            required("workspaceId", "string"),
+            required("threadId", "string"),
+            required("text", "string"),
+            optional("model", "string"),
+            optional("effort", "string"),
+            optional("accessMode", "string"),
+            optional("images", "string[]"),
+            optional("collaborationMode", "object"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "turn_interrupt",
+        description: "Interrupt a running turn.",
+        params: &[
+            required("workspaceId", "string"),
+            required("threadId", "string"),
+            required("turnId", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "start_review",
+        description: "Start a review turn for a target.",
+        params: &[
+            required("workspaceId", "string"),
+            required("threadId", "string"),
+            required("target", "object"),
+            optional("delivery", "string"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "model_list",
+        description: "List models available to a workspace.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "collaboration_mode_list",
+        description: "List collaboration modes available to a workspace.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "account_rate_limits",
+        description: "Read account rate limits.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "account_read",
+        description: "Read account details.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "codex_auth_store_read",
+        description: "Read the configured codex auth credentials store.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "codex_auth_store_set_file",
+        description: "Switch the codex auth credentials store to file.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "codex_auth_store_migrate",
+        description: "Switch the codex auth credentials store (file, keyring, auto).",
+        params: &[required("target", "string")],
+    },
+    RpcMethodSpec {
+        name: "codex_auth_profile_snapshot",
+        description: "Snapshot the current auth into a profile.",
+        params: PROFILE_ID,
+    },
+    RpcMethodSpec {
+        name: "codex_auth_profile_apply",
+        description: "Apply a saved auth profile.",
+        params: PROFILE_ID,
+    },
+    RpcMethodSpec {
+        name: "codex_login",
+        description: "Start a codex login flow.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "codex_login_cancel",
+        description: "Cancel a pending codex login flow.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "respawn_sessions",
+        description: "Restart all connected workspace sessions.",
+        params: NONE,
+    },
+    RpcMethodSpec {
+        name: "skills_list",
+        description: "List skills available to a workspace.",
+        params: WORKSPACE_ID,
+    },
+    RpcMethodSpec {
+        name: "apps_list",
+        description: "List apps available to a workspace.",
+        params: WORKSPACE_PAGE,
+    },
+    RpcMethodSpec {
+        name: "respond_to_server_request",
+        description: "Answer a pending app-server request.",
+        params: &[
+            required("workspaceId", "string"),
+            required("requestId", "string|number"),
+            required("result", "any"),
+        ],
+    },
+    RpcMethodSpec {
+        name: "remember_approval_rule",
+        description: "Persist an approval rule for a command prefix.",
+        params: &[
+            required("workspaceId", "string"),
+            required("command", "string[]"),
+        ],
+    },
+];
+
+pub(crate) fn find_method(name: &str) -> Option<&'static RpcMethodSpec> {
+    RPC_METHODS.iter().find(|spec| spec.name == name)
+}
+
+pub(crate) fn rpc_list_response(protocol_version: u32) -> Value {
+    let methods = RPC_METHODS
+        .iter()
+        .map(|spec| {
+            let params = spec
+                .params
+                .iter()
+                .map(|param| {
+                    json!({
+                        "name": param.name,
+                        "type": param.kind,
+                        "required": param.required,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "name": spec.name,
+                "description": spec.description,
+                "params": params,
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "version": RPC_REGISTRY_VERSION,
+        "protocolVersion": protocol_version,
+        "methods": methods,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn registry_method_names_are_unique() {
+        let mut seen = HashSet::new();
+        for spec in RPC_METHODS {
+            assert!(seen.insert(spec.name), "duplicate method {}", spec.name);
+        }
+    }
+
+    #[test]
+    fn registry_version_is_bumped_with_new_methods() {
+        // Tripwire: a new method must bump RPC_REGISTRY_VERSION as well as
+        // this count.
+        assert_eq!(RPC_METHODS.len(), 149);
+        assert_eq!(RPC_REGISTRY_VERSION, 72);
+    }
+
+    #[test]
+    fn rpc_list_response_includes_version_and_self() {
+        let response = rpc_list_response(1);
+        assert_eq!(response["version"], json!(RPC_REGISTRY_VERSION));
+        let methods = response["methods"].as_array().expect("methods");
+        assert!(methods.iter().any(|method| method["name"] == "rpc.list"));
+    }
+}
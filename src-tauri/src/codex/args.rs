@@ -47,6 +47,21 @@ pub(crate) fn resolve_workspace_codex_args(
     None
 }
 
+/// Extracts the `--profile`/`-p` value Codex would receive from these args.
+pub(crate) fn profile_from_codex_args(value: Option<&str>) -> Option<String> {
+    let args = parse_codex_args(value).ok()?;
+    let mut iter = args.iter();
+    let mut profile = None;
+    while let Some(arg) = iter.next() {
+        if arg == "--profile" || arg == "-p" {
+            profile = iter.next().cloned();
+        } else if let Some(value) = arg.strip_prefix("--profile=") {
+            profile = Some(value.to_string());
+        }
+    }
+    profile.filter(|value| !value.trim().is_empty())
+}
+
 fn normalize_codex_args(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -58,7 +73,7 @@ fn normalize_codex_args(value: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_codex_args, resolve_workspace_codex_args};
+    use super::{parse_codex_args, profile_from_codex_args, resolve_workspace_codex_args};
     use crate::types::{AppSettings, WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
 
     #[test]
@@ -67,6 +82,19 @@ mod tests {
         assert!(parse_codex_args(Some("   ")).expect("parse blanks").is_empty());
     }
 
+    #[test]
+    fn profile_from_codex_args_reads_last_profile_flag() {
+        assert_eq!(profile_from_codex_args(None), None);
+        assert_eq!(
+            profile_from_codex_args(Some("--profile work --flag")),
+            Some("work".to_string())
+        );
+        assert_eq!(
+            profile_from_codex_args(Some("-p a --profile=b")),
+            Some("b".to_string())
+        );
+    }
+
     #[test]
     fn parses_simple_args() {
         let args =
@@ -1,15 +1,48 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use serde_json::{json, Map, Value};
 use toml::Value as TomlValue;
 
-use crate::codex::home::resolve_default_codex_home_with_settings;
+use crate::codex::home::{
+    resolve_default_codex_home_with_settings, resolve_workspace_codex_home_with_settings,
+};
 use crate::files::ops::{read_with_policy, write_with_policy};
 use crate::files::policy::{policy_for, FileKind, FilePolicy, FileScope};
-use crate::types::AppSettings;
+use crate::types::{AppSettings, FeatureFlag, WorkspaceEntry};
 
 const FEATURES_TABLE: &str = "[features]";
 const AUTH_STORE_KEY: &str = "cli_auth_credentials_store";
+const TRUST_LEVEL_KEY: &str = "trust_level";
+
+/// Values Codex accepts for `cli_auth_credentials_store`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuthStore {
+    File,
+    Keyring,
+    Auto,
+}
+
+impl AuthStore {
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "file" => Ok(Self::File),
+            "keyring" => Ok(Self::Keyring),
+            "auto" => Ok(Self::Auto),
+            other => Err(format!(
+                "Unknown auth store `{other}` (expected file, keyring, or auto)"
+            )),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Keyring => "keyring",
+            Self::Auto => "auto",
+        }
+    }
+}
 
 pub(crate) fn read_steer_enabled_with_settings(
     settings: Option<&AppSettings>,
@@ -106,7 +139,19 @@ pub(crate) fn write_personality_with_settings(
 pub(crate) fn write_auth_store_file_with_settings(
     settings: Option<&AppSettings>,
 ) -> Result<(), String> {
-    write_auth_store_with_settings("file", settings)
+    write_auth_store_with_settings(AuthStore::File.as_str(), settings)
+}
+
+pub(crate) fn write_auth_store_keyring_with_settings(
+    settings: Option<&AppSettings>,
+) -> Result<(), String> {
+    write_auth_store_with_settings(AuthStore::Keyring.as_str(), settings)
+}
+
+pub(crate) fn write_auth_store_auto_with_settings(
+    settings: Option<&AppSettings>,
+) -> Result<(), String> {
+    write_auth_store_with_settings(AuthStore::Auto.as_str(), settings)
 }
 
 fn write_auth_store_with_settings(
@@ -124,7 +169,35 @@ fn write_auth_store_with_settings(
     fs::write(&path, updated).map_err(|err| err.to_string())
 }
 
-fn read_feature_flag_with_settings(
+/// Keys are written unquoted into `[features]`, so only bare TOML keys are
+/// accepted.
+pub(crate) fn validate_feature_key(key: &str) -> Result<&str, String> {
+    let key = key.trim();
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if valid {
+        Ok(key)
+    } else {
+        Err(format!("Invalid feature flag key `{key}`"))
+    }
+}
+
+/// Every boolean entry in `[features]`, in file order.
+pub(crate) fn list_feature_flags_with_settings(
+    settings: Option<&AppSettings>,
+) -> Result<Vec<FeatureFlag>, String> {
+    let path = config_toml_path_with_settings(settings)
+        .ok_or("Unable to resolve CODEX_HOME".to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    Ok(parse_feature_flags(&contents))
+}
+
+pub(crate) fn read_feature_flag_with_settings(
     key: &str,
     settings: Option<&AppSettings>,
 ) -> Result<Option<bool>, String> {
@@ -137,7 +210,7 @@ fn read_feature_flag_with_settings(
     Ok(find_feature_flag(&contents, key))
 }
 
-fn write_feature_flag_with_settings(
+pub(crate) fn write_feature_flag_with_settings(
     key: &str,
     enabled: bool,
     settings: Option<&AppSettings>,
@@ -153,12 +226,215 @@ fn write_feature_flag_with_settings(
     fs::write(&path, updated).map_err(|err| err.to_string())
 }
 
+/// Reads `trust_level` from `[projects."<path>"]`. `None` means Codex has no
+/// recorded decision for the project.
+pub(crate) fn read_project_trust_with_settings(
+    project_path: &str,
+    settings: Option<&AppSettings>,
+) -> Result<Option<bool>, String> {
+    let key = normalize_project_key(project_path)?;
+    let path = config_toml_path_with_settings(settings)
+        .ok_or("Unable to resolve CODEX_HOME".to_string())?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    Ok(find_project_trust(&contents, &key))
+}
+
+pub(crate) fn write_project_trust_with_settings(
+    project_path: &str,
+    trusted: bool,
+    settings: Option<&AppSettings>,
+) -> Result<(), String> {
+    let key = normalize_project_key(project_path)?;
+    let path = config_toml_path_with_settings(settings)
+        .ok_or("Unable to resolve CODEX_HOME".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let contents = fs::read_to_string(&path).unwrap_or_default();
+    let updated = upsert_project_trust(&contents, &key, trusted)?;
+    fs::write(&path, updated).map_err(|err| err.to_string())
+}
+
 pub(crate) fn config_toml_path_with_settings(
     settings: Option<&AppSettings>,
 ) -> Option<PathBuf> {
     resolve_default_codex_home_with_settings(settings).map(|home| home.join("config.toml"))
 }
 
+/// Workspace-scoped variant of `config_toml_path_with_settings`: prefers the
+/// workspace (or parent worktree) `codexHome` override before the global home.
+pub(crate) fn config_toml_path_for_workspace_with_settings(
+    entry: &WorkspaceEntry,
+    parent_entry: Option<&WorkspaceEntry>,
+    settings: Option<&AppSettings>,
+) -> Option<PathBuf> {
+    resolve_workspace_codex_home_with_settings(entry, parent_entry, settings)
+        .map(|home| home.join("config.toml"))
+        .or_else(|| config_toml_path_with_settings(settings))
+}
+
+/// Merges `[profiles.<name>]` over the top-level keys of config.toml the way Codex
+/// does, recording for each top-level key whether it came from the base config
+/// or the profile. `profile_override` (from `--profile`) beats `profile = ...`.
+pub(crate) fn read_effective_config(
+    path: &Path,
+    profile_override: Option<&str>,
+) -> Result<Value, String> {
+    let contents = if path.exists() {
+        fs::read_to_string(path).map_err(|err| err.to_string())?
+    } else {
+        String::new()
+    };
+    let mut effective = build_effective_config(&contents, profile_override)?;
+    effective["path"] = Value::String(path.to_string_lossy().to_string());
+    effective["exists"] = Value::Bool(path.exists());
+    Ok(effective)
+}
+
+fn build_effective_config(contents: &str, profile_override: Option<&str>) -> Result<Value, String> {
+    let parsed: TomlValue =
+        toml::from_str(contents).map_err(|err| format!("Invalid config.toml: {err}"))?;
+    let base = match parsed {
+        TomlValue::Table(table) => table,
+        _ => toml::map::Map::new(),
+    };
+
+    let (profile, profile_source) = match profile_override {
+        Some(name) => (Some(name.to_string()), Some("args")),
+        None => match base.get("profile").and_then(|value| value.as_str()) {
+            Some(name) => (Some(name.to_string()), Some("config")),
+            None => (None, None),
+        },
+    };
+    let profile_table = profile.as_deref().and_then(|name| {
+        base.get("profiles")
+            .and_then(|profiles| profiles.get(name))
+            .and_then(|value| value.as_table())
+            .cloned()
+    });
+
+    let mut merged = Map::new();
+    let mut sources = Map::new();
+    for (key, value) in base.iter() {
+        if key == "profiles" || key == "profile" {
+            continue;
+        }
+        merged.insert(key.clone(), toml_to_json(value)?);
+        sources.insert(key.clone(), json!("base"));
+    }
+    if let Some(table) = profile_table.as_ref() {
+        for (key, value) in table.iter() {
+            merged.insert(key.clone(), toml_to_json(value)?);
+            sources.insert(key.clone(), json!("profile"));
+        }
+    }
+
+    Ok(json!({
+        "profile": profile,
+        "profileSource": profile_source,
+        "profileFound": profile_table.is_some(),
+        "config": Value::Object(merged),
+        "sources": Value::Object(sources),
+    }))
+}
+
+/// Key-by-key comparison of two config.toml files. Nested tables are flattened
+/// to dotted keys so `[features] steer` shows up as `features.steer`.
+pub(crate) fn diff_config_files(base_path: &Path, other_path: &Path) -> Result<Value, String> {
+    let base = read_flattened_config(base_path)?;
+    let other = read_flattened_config(other_path)?;
+    let mut diff = diff_flattened_configs(&base, &other);
+    diff["basePath"] = Value::String(base_path.to_string_lossy().to_string());
+    diff["otherPath"] = Value::String(other_path.to_string_lossy().to_string());
+    diff["samePath"] = Value::Bool(base_path == other_path);
+    Ok(diff)
+}
+
+fn read_flattened_config(path: &Path) -> Result<Map<String, Value>, String> {
+    let contents = if path.exists() {
+        fs::read_to_string(path).map_err(|err| err.to_string())?
+    } else {
+        String::new()
+    };
+    let parsed: TomlValue = toml::from_str(&contents)
+        .map_err(|err| format!("Invalid config.toml at {}: {err}", path.display()))?;
+    let mut flattened = Map::new();
+    if let TomlValue::Table(table) = parsed {
+        flatten_toml_table("", &table, &mut flattened)?;
+    }
+    Ok(flattened)
+}
+
+fn flatten_toml_table(
+    prefix: &str,
+    table: &toml::map::Map<String, TomlValue>,
+    out: &mut Map<String, Value>,
+) -> Result<(), String> {
+    for (key, value) in table.iter() {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            TomlValue::Table(nested) if !nested.is_empty() => {
+                flatten_toml_table(&path, nested, out)?
+            }
+            other => {
+                out.insert(path, toml_to_json(other)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn diff_flattened_configs(base: &Map<String, Value>, other: &Map<String, Value>) -> Value {
+    let mut added = Map::new();
+    let mut removed = Map::new();
+    let mut changed = Map::new();
+    for (key, value) in other.iter() {
+        match base.get(key) {
+            None => {
+                added.insert(key.clone(), value.clone());
+            }
+            Some(previous) if previous != value => {
+                changed.insert(key.clone(), json!({ "from": previous, "to": value }));
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, value) in base.iter() {
+        if !other.contains_key(key) {
+            removed.insert(key.clone(), value.clone());
+        }
+    }
+    json!({
+        "added": Value::Object(added),
+        "removed": Value::Object(removed),
+        "changed": Value::Object(changed),
+    })
+}
+
+fn toml_to_json(value: &TomlValue) -> Result<Value, String> {
+    match value {
+        TomlValue::Datetime(datetime) => Ok(Value::String(datetime.to_string())),
+        TomlValue::Array(items) => items
+            .iter()
+            .map(toml_to_json)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        TomlValue::Table(table) => table
+            .iter()
+            .map(|(key, value)| toml_to_json(value).map(|value| (key.clone(), value)))
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Value::Object),
+        other => serde_json::to_value(other).map_err(|err| err.to_string()),
+    }
+}
+
 pub(crate) fn read_config_model(codex_home: Option<PathBuf>) -> Result<Option<String>, String> {
     let path = codex_home
         .or_else(crate::codex::home::resolve_default_codex_home)
@@ -327,6 +603,34 @@ fn find_feature_flag(contents: &str, key: &str) -> Option<bool> {
     None
 }
 
+fn parse_feature_flags(contents: &str) -> Vec<FeatureFlag> {
+    let mut flags: Vec<FeatureFlag> = Vec::new();
+    let mut in_features = false;
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_features = trimmed == FEATURES_TABLE;
+            continue;
+        }
+        if !in_features || trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        let enabled = match value.split('#').next().unwrap_or("").trim() {
+            "true" => true,
+            "false" => false,
+            _ => continue,
+        };
+        let key = key.trim().to_string();
+        if !flags.iter().any(|flag| flag.key == key) {
+            flags.push(FeatureFlag { key, enabled });
+        }
+    }
+    flags
+}
+
 fn upsert_feature_flag(contents: &str, key: &str, enabled: bool) -> String {
     let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
     let mut in_features = false;
@@ -383,6 +687,127 @@ fn upsert_feature_flag(contents: &str, key: &str, enabled: bool) -> String {
     updated
 }
 
+/// Codex keys projects by the canonical absolute path of the project root.
+fn normalize_project_key(project_path: &str) -> Result<String, String> {
+    let trimmed = project_path.trim();
+    if trimmed.is_empty() {
+        return Err("Project path is required".to_string());
+    }
+    let path = Path::new(trimmed);
+    if !path.is_absolute() {
+        return Err(format!("Project path must be absolute: {trimmed}"));
+    }
+    if !path.is_dir() {
+        return Err(format!("Project path is not a directory: {trimmed}"));
+    }
+    let canonical = path.canonicalize().map_err(|err| err.to_string())?;
+    Ok(canonical.to_string_lossy().to_string())
+}
+
+fn project_table_header(key: &str) -> String {
+    let escaped = key.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[projects.\"{escaped}\"]")
+}
+
+fn find_project_trust(contents: &str, key: &str) -> Option<bool> {
+    let parsed: TomlValue = toml::from_str(contents).ok()?;
+    let level = parsed
+        .get("projects")?
+        .get(key)?
+        .get(TRUST_LEVEL_KEY)?
+        .as_str()?;
+    match level.trim() {
+        "trusted" => Some(true),
+        "untrusted" => Some(false),
+        _ => None,
+    }
+}
+
+/// Whether a `[...]` header line opens the `projects.<key>` table, however
+/// the key is quoted or spaced.
+fn is_project_table_header(line: &str, key: &str) -> bool {
+    let Ok(parsed) = toml::from_str::<TomlValue>(line) else {
+        return false;
+    };
+    parsed
+        .get("projects")
+        .and_then(TomlValue::as_table)
+        .filter(|projects| projects.len() == 1)
+        .and_then(|projects| projects.get(key))
+        .and_then(TomlValue::as_table)
+        .is_some_and(|table| table.is_empty())
+}
+
+/// Sets `trust_level` for a project with a line-based edit that keeps
+/// comments and formatting. The result is parsed before it is returned, so
+/// an edit that would leave config.toml invalid (e.g. a duplicate table when
+/// the project is defined in a form this editor doesn't recognize) fails
+/// instead of being written.
+fn upsert_project_trust(contents: &str, key: &str, trusted: bool) -> Result<String, String> {
+    let header = project_table_header(key);
+    let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+    let mut table_start: Option<usize> = None;
+    let mut table_end: Option<usize> = None;
+    let mut key_index: Option<usize> = None;
+
+    for (idx, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            if table_start.is_some() {
+                table_end = Some(idx);
+                break;
+            }
+            if is_project_table_header(trimmed, key) {
+                table_start = Some(idx);
+            }
+            continue;
+        }
+        if table_start.is_some() && is_key_value_for(line, TRUST_LEVEL_KEY) {
+            key_index = Some(idx);
+            break;
+        }
+    }
+
+    let trust_line = format!(
+        "{TRUST_LEVEL_KEY} = \"{}\"",
+        if trusted { "trusted" } else { "untrusted" }
+    );
+
+    if let Some(index) = key_index {
+        lines[index] = trust_line;
+    } else if let Some(start) = table_start {
+        let end = table_end.unwrap_or(lines.len());
+        // Keep blank lines separating the next table below the new key.
+        let insert_at = (start + 1..end)
+            .rev()
+            .find(|idx| !lines[*idx].trim().is_empty())
+            .map(|idx| idx + 1)
+            .unwrap_or(start + 1);
+        lines.insert(insert_at, trust_line);
+    } else {
+        if !lines.is_empty() && !lines.last().unwrap().trim().is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(trust_line);
+    }
+
+    let mut updated = lines.join("\n");
+    if contents.ends_with('\n') || updated.is_empty() {
+        updated.push('\n');
+    }
+    toml::from_str::<TomlValue>(&updated)
+        .map_err(|err| format!("Could not update project trust in config.toml: {err}"))?;
+    if find_project_trust(&updated, key) != Some(trusted) {
+        return Err(
+            "Could not update project trust in config.toml: the project is defined in a form \
+             that can't be edited safely."
+                .to_string(),
+        );
+    }
+    Ok(updated)
+}
+
 fn remove_top_level_key(contents: &str, key: &str) -> String {
     let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
     let table_start = first_table_start_index(&lines).unwrap_or(lines.len());
@@ -461,8 +886,40 @@ impl<T> RetainWithIndex<T> for Vec<T> {
 #[cfg(test)]
 mod tests {
     use super::{
-        parse_personality_from_toml, remove_top_level_key, upsert_top_level_string_key,
+        build_effective_config, diff_flattened_configs, find_project_trust, flatten_toml_table,
+        upsert_project_trust, AuthStore, parse_personality_from_toml, remove_top_level_key,
+        upsert_top_level_string_key,
     };
+    use serde_json::{json, Map};
+    use toml::Value as TomlValue;
+
+    fn flatten(contents: &str) -> Map<String, serde_json::Value> {
+        let parsed: TomlValue = toml::from_str(contents).expect("parse toml");
+        let mut out = Map::new();
+        flatten_toml_table("", parsed.as_table().expect("table"), &mut out).expect("flatten");
+        out
+    }
+
+    #[test]
+    fn diff_configs_reports_added_removed_and_changed_keys() {
+        let base = flatten("model = \"gpt-5\"\napproval_policy = \"never\"\n[features]\nsteer = true\n");
+        let other = flatten("model = \"o3\"\nsandbox_mode = \"read-only\"\n[features]\nsteer = true\n");
+        let diff = diff_flattened_configs(&base, &other);
+        assert_eq!(diff["added"], json!({ "sandbox_mode": "read-only" }));
+        assert_eq!(diff["removed"], json!({ "approval_policy": "never" }));
+        assert_eq!(
+            diff["changed"],
+            json!({ "model": { "from": "gpt-5", "to": "o3" } })
+        );
+    }
+
+    #[test]
+    fn auth_store_parse_rejects_unknown_values() {
+        assert_eq!(AuthStore::parse("file"), Ok(AuthStore::File));
+        assert_eq!(AuthStore::parse(" keyring "), Ok(AuthStore::Keyring));
+        assert_eq!(AuthStore::parse("auto"), Ok(AuthStore::Auto));
+        assert!(AuthStore::parse("vault").is_err());
+    }
 
     #[test]
     fn parse_personality_reads_supported_values() {
@@ -484,6 +941,29 @@ mod tests {
         assert_eq!(updated, "personality = \"friendly\"\n[features]\nsteer = true\n");
     }
 
+    #[test]
+    fn parse_feature_flags_lists_booleans_in_features_table() {
+        let input = "steer = false\n[features]\n# comment\nsteer = true\nnew_thing = false # soon\nmodel = \"x\"\n[other]\nflag = true\n";
+        let flags = parse_feature_flags(input);
+        assert_eq!(
+            flags
+                .iter()
+                .map(|flag| (flag.key.as_str(), flag.enabled))
+                .collect::<Vec<_>>(),
+            vec![("steer", true), ("new_thing", false)]
+        );
+        let updated = upsert_feature_flag(input, "new_thing", true);
+        assert_eq!(find_feature_flag(&updated, "new_thing"), Some(true));
+    }
+
+    #[test]
+    fn validate_feature_key_rejects_non_bare_keys() {
+        assert_eq!(validate_feature_key(" web_search-v2 "), Ok("web_search-v2"));
+        assert!(validate_feature_key("").is_err());
+        assert!(validate_feature_key("a.b").is_err());
+        assert!(validate_feature_key("x = true\n[y]").is_err());
+    }
+
     #[test]
     fn upsert_replaces_existing_top_level_personality() {
         let input = "personality = \"friendly\"\n[features]\nsteer = true\n";
@@ -497,4 +977,56 @@ mod tests {
         let updated = remove_top_level_key(input, "personality");
         assert_eq!(updated, "model = \"gpt-5\"\n[features]\nsteer = true\n");
     }
+
+    #[test]
+    fn upsert_project_trust_creates_and_updates_table() {
+        let input = "# keep me\nmodel = \"gpt-5\"\n";
+        let created = upsert_project_trust(input, "/work/app", true).expect("create table");
+        assert_eq!(
+            created,
+            "# keep me\nmodel = \"gpt-5\"\n\n[projects.\"/work/app\"]\ntrust_level = \"trusted\"\n"
+        );
+        assert_eq!(find_project_trust(&created, "/work/app"), Some(true));
+
+        let input = "[projects.\"/work/app\"]\n# note\n\n[features]\nsteer = true\n";
+        let inserted = upsert_project_trust(input, "/work/app", false).expect("insert key");
+        assert_eq!(
+            inserted,
+            "[projects.\"/work/app\"]\n# note\ntrust_level = \"untrusted\"\n\n[features]\nsteer = true\n"
+        );
+        let flipped = upsert_project_trust(&inserted, "/work/app", true).expect("flip key");
+        assert_eq!(find_project_trust(&flipped, "/work/app"), Some(true));
+        assert_eq!(find_project_trust(&flipped, "/work/other"), None);
+
+        let single_quoted = "[projects.'/work/app']\ntrust_level = \"untrusted\"\n";
+        let updated =
+            upsert_project_trust(single_quoted, "/work/app", true).expect("single-quoted header");
+        assert_eq!(updated, "[projects.'/work/app']\ntrust_level = \"trusted\"\n");
+
+        // Appending a table header here would duplicate the inline definition.
+        let inline = "[projects]\n\"/work/app\" = { trust_level = \"untrusted\" }\n";
+        assert!(upsert_project_trust(inline, "/work/app", true).is_err());
+    }
+
+    #[test]
+    fn effective_config_applies_selected_profile() {
+        let input = "model = \"gpt-5\"\napproval_policy = \"never\"\nprofile = \"work\"\n\n[profiles.work]\nmodel = \"o3\"\n";
+        let effective = build_effective_config(input, None).expect("effective config");
+        assert_eq!(effective["profile"], "work");
+        assert_eq!(effective["profileSource"], "config");
+        assert_eq!(effective["config"]["model"], "o3");
+        assert_eq!(effective["config"]["approval_policy"], "never");
+        assert_eq!(effective["sources"]["model"], "profile");
+        assert_eq!(effective["sources"]["approval_policy"], "base");
+        assert!(effective["config"].get("profiles").is_none());
+    }
+
+    #[test]
+    fn effective_config_prefers_args_profile() {
+        let input = "profile = \"work\"\n[profiles.work]\nmodel = \"o3\"\n[profiles.home]\nmodel = \"gpt-5\"\n";
+        let effective = build_effective_config(input, Some("home")).expect("effective config");
+        assert_eq!(effective["profile"], "home");
+        assert_eq!(effective["profileSource"], "args");
+        assert_eq!(effective["config"]["model"], "gpt-5");
+    }
 }
@@ -1,4 +1,5 @@
 use std::env;
+use std::fs;
 use std::path::PathBuf;
 
 use crate::types::{AppSettings, WorkspaceEntry};
@@ -69,6 +70,33 @@ pub(crate) fn normalize_codex_home(value: &str) -> Option<PathBuf> {
     Some(PathBuf::from(trimmed))
 }
 
+/// Resolves a workspace `codexHome` override against the workspace path and makes
+/// sure it is usable, creating the directory when it does not exist yet.
+pub(crate) fn validate_workspace_codex_home(
+    value: &str,
+    workspace_path: &str,
+) -> Result<Option<PathBuf>, String> {
+    let Some(path) = normalize_codex_home_with_base(value, &PathBuf::from(workspace_path)) else {
+        return Ok(None);
+    };
+    if path.exists() {
+        if !path.is_dir() {
+            return Err(format!(
+                "CODEX_HOME override is not a directory: {}",
+                path.display()
+            ));
+        }
+        return Ok(Some(path));
+    }
+    fs::create_dir_all(&path).map_err(|err| {
+        format!(
+            "Unable to create CODEX_HOME override {}: {err}",
+            path.display()
+        )
+    })?;
+    Ok(Some(path))
+}
+
 fn normalize_codex_home_with_base(value: &str, base: &PathBuf) -> Option<PathBuf> {
     let path = normalize_codex_home(value)?;
     if path.is_absolute() {
@@ -239,6 +267,26 @@ mod tests {
         settings
     }
 
+    #[test]
+    fn validate_workspace_codex_home_creates_missing_dir() {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-test-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let root_str = root.to_string_lossy().to_string();
+
+        let resolved = validate_workspace_codex_home(".codex", &root_str).expect("validate");
+
+        assert_eq!(resolved, Some(root.join(".codex")));
+        assert!(root.join(".codex").is_dir());
+
+        std::fs::write(root.join("not-a-dir"), "x").expect("write file");
+        assert!(validate_workspace_codex_home("not-a-dir", &root_str).is_err());
+        assert_eq!(validate_workspace_codex_home("  ", &root_str), Ok(None));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn worktree_inherits_parent_codex_home_override() {
         let parent = workspace_entry(WorkspaceKind::Main, "/repo", Some("/tmp/codex-parent"));
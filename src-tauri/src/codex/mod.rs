@@ -516,6 +516,25 @@ pub(crate) async fn codex_auth_store_set_file(
     codex_core::auth_store_set_file_core(&state.app_settings).await
 }
 
+#[tauri::command]
+pub(crate) async fn codex_auth_store_migrate(
+    target: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "codex_auth_store_migrate",
+            json!({ "target": target }),
+        )
+        .await;
+    }
+
+    codex_core::auth_store_migrate_core(&state.app_settings, target).await
+}
+
 #[tauri::command]
 pub(crate) async fn codex_auth_profile_snapshot(
     profile_id: String,
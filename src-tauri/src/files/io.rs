@@ -1,6 +1,6 @@
 use std::fs::File;
-use std::io::Read;
-use std::path::{Path, PathBuf};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +11,35 @@ pub(crate) struct TextFileResponse {
     pub truncated: bool,
 }
 
+/// Bytes appended to a file since a caller-held offset. `reset` means the
+/// file shrank (rotated or truncated) and reading restarted from the top;
+/// `truncated` means more data remains past `offset`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TailFileResponse {
+    pub exists: bool,
+    pub content: String,
+    pub offset: u64,
+    pub truncated: bool,
+    pub reset: bool,
+}
+
+/// One page of a file read with `read_text_file_range_within`. `content`
+/// covers `offset..nextOffset`; `nextOffset` can fall short of the requested
+/// end when a multi-byte character straddles it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TextFileRangeResponse {
+    pub exists: bool,
+    pub content: String,
+    pub offset: u64,
+    pub next_offset: u64,
+    pub total_size: u64,
+    pub has_more: bool,
+}
+
+/// Upper bound for one tail or range read.
+pub(crate) const MAX_CHUNK_BYTES: u64 = 1024 * 1024;
+
 fn missing_response() -> TextFileResponse {
     TextFileResponse {
         exists: false,
@@ -93,6 +122,176 @@ pub(crate) fn read_text_file_within(
     })
 }
 
+/// Resolves `relative_path` under `root` to an existing file, applying the
+/// containment and symlink rules of `read_text_file_within`. `None` when the
+/// root (if allowed to be missing) or the file does not exist.
+fn resolve_existing_file_within(
+    root: &Path,
+    relative_path: &str,
+    root_may_be_missing: bool,
+    root_context: &str,
+    allow_external_symlink_target: bool,
+) -> Result<Option<PathBuf>, String> {
+    let file_context = relative_path.trim();
+    let relative = Path::new(file_context);
+    if relative.as_os_str().is_empty()
+        || !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(format!("Invalid {file_context} path"));
+    }
+    let Some(canonical_root) = resolve_root(root, root_context, root_may_be_missing)? else {
+        return Ok(None);
+    };
+
+    let candidate = canonical_root.join(relative);
+    if !candidate.exists() {
+        return Ok(None);
+    }
+    let candidate_is_symlink = std::fs::symlink_metadata(&candidate)
+        .map_err(|err| format!("Failed to open {file_context}: {err}"))?
+        .file_type()
+        .is_symlink();
+    let canonical_path = candidate
+        .canonicalize()
+        .map_err(|err| format!("Failed to open {file_context}: {err}"))?;
+    if !canonical_path.starts_with(&canonical_root)
+        && !(allow_external_symlink_target && candidate_is_symlink)
+    {
+        return Err(format!("Invalid {file_context} path"));
+    }
+    if !canonical_path.is_file() {
+        return Err(format!("{file_context} is not a file"));
+    }
+    Ok(Some(canonical_path))
+}
+
+/// Reads up to `max_bytes` from `start` and returns the text plus the number
+/// of bytes it covers. A multi-byte character split at the end of the chunk
+/// is left out (and so left for the next read) rather than decoded lossily.
+fn read_text_chunk(
+    path: &Path,
+    start: u64,
+    max_bytes: u64,
+    file_context: &str,
+) -> Result<(String, u64), String> {
+    let mut file =
+        File::open(path).map_err(|err| format!("Failed to open {file_context}: {err}"))?;
+    file.seek(SeekFrom::Start(start))
+        .map_err(|err| format!("Failed to read {file_context}: {err}"))?;
+    let mut buffer = Vec::new();
+    file.take(max_bytes)
+        .read_to_end(&mut buffer)
+        .map_err(|err| format!("Failed to read {file_context}: {err}"))?;
+    Ok(match std::str::from_utf8(&buffer) {
+        Ok(text) => (text.to_string(), buffer.len() as u64),
+        Err(err) if err.error_len().is_none() => {
+            let valid = err.valid_up_to();
+            (
+                String::from_utf8_lossy(&buffer[..valid]).into_owned(),
+                valid as u64,
+            )
+        }
+        Err(_) => (
+            String::from_utf8_lossy(&buffer).into_owned(),
+            buffer.len() as u64,
+        ),
+    })
+}
+
+fn file_len(path: &Path, file_context: &str) -> Result<u64, String> {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .map_err(|err| format!("Failed to read {file_context}: {err}"))
+}
+
+/// Reads `relative_path` under `root` from `from_offset` to EOF (capped at
+/// `MAX_CHUNK_BYTES`), with the same containment rules as
+/// `read_text_file_within`.
+pub(crate) fn tail_text_file_within(
+    root: &Path,
+    relative_path: &str,
+    from_offset: u64,
+    root_may_be_missing: bool,
+    root_context: &str,
+    allow_external_symlink_target: bool,
+) -> Result<TailFileResponse, String> {
+    let Some(path) = resolve_existing_file_within(
+        root,
+        relative_path,
+        root_may_be_missing,
+        root_context,
+        allow_external_symlink_target,
+    )?
+    else {
+        return Ok(TailFileResponse {
+            exists: false,
+            content: String::new(),
+            offset: 0,
+            truncated: false,
+            reset: from_offset > 0,
+        });
+    };
+    let file_context = relative_path.trim();
+    let len = file_len(&path, file_context)?;
+    let reset = from_offset > len;
+    let start = if reset { 0 } else { from_offset };
+    let (content, consumed) = read_text_chunk(&path, start, MAX_CHUNK_BYTES, file_context)?;
+    let offset = start + consumed;
+    Ok(TailFileResponse {
+        exists: true,
+        content,
+        offset,
+        truncated: offset < len,
+        reset,
+    })
+}
+
+/// Reads `length` bytes (capped at `MAX_CHUNK_BYTES`) of `relative_path`
+/// under `root` starting at `offset`, for paging through large files.
+pub(crate) fn read_text_file_range_within(
+    root: &Path,
+    relative_path: &str,
+    offset: u64,
+    length: u64,
+    root_may_be_missing: bool,
+    root_context: &str,
+    allow_external_symlink_target: bool,
+) -> Result<TextFileRangeResponse, String> {
+    let Some(path) = resolve_existing_file_within(
+        root,
+        relative_path,
+        root_may_be_missing,
+        root_context,
+        allow_external_symlink_target,
+    )?
+    else {
+        return Ok(TextFileRangeResponse {
+            exists: false,
+            content: String::new(),
+            offset,
+            next_offset: offset,
+            total_size: 0,
+            has_more: false,
+        });
+    };
+    let file_context = relative_path.trim();
+    let total_size = file_len(&path, file_context)?;
+    let start = offset.min(total_size);
+    let (content, consumed) =
+        read_text_chunk(&path, start, length.min(MAX_CHUNK_BYTES), file_context)?;
+    let next_offset = start + consumed;
+    Ok(TextFileRangeResponse {
+        exists: true,
+        content,
+        offset: start,
+        next_offset,
+        total_size,
+        has_more: next_offset < total_size,
+    })
+}
+
 pub(crate) fn write_text_file_within(
     root: &Path,
     filename: &str,
@@ -102,6 +301,28 @@ pub(crate) fn write_text_file_within(
     file_context: &str,
     allow_external_symlink_target: bool,
 ) -> Result<(), String> {
+    let target_path = resolve_write_target_within(
+        root,
+        filename,
+        create_root,
+        root_context,
+        file_context,
+        allow_external_symlink_target,
+    )?;
+    std::fs::write(&target_path, content)
+        .map_err(|err| format!("Failed to write {file_context}: {err}"))
+}
+
+/// Resolves where a write of `filename` under `root` would land, applying the
+/// same containment and symlink rules as `write_text_file_within`.
+pub(crate) fn resolve_write_target_within(
+    root: &Path,
+    filename: &str,
+    create_root: bool,
+    root_context: &str,
+    file_context: &str,
+    allow_external_symlink_target: bool,
+) -> Result<PathBuf, String> {
     let canonical_root = if create_root {
         resolve_or_create_root(root, root_context)?
     } else {
@@ -114,7 +335,7 @@ pub(crate) fn write_text_file_within(
         return Err(format!("Invalid {file_context} path"));
     }
 
-    let target_path = if candidate.exists() {
+    if candidate.exists() {
         let candidate_is_symlink = std::fs::symlink_metadata(&candidate)
             .map_err(|err| format!("Failed to resolve {file_context}: {err}"))?
             .file_type()
@@ -127,13 +348,10 @@ pub(crate) fn write_text_file_within(
         {
             return Err(format!("Invalid {file_context} path"));
         }
-        canonical_path
+        Ok(canonical_path)
     } else {
-        candidate
-    };
-
-    std::fs::write(&target_path, content)
-        .map_err(|err| format!("Failed to write {file_context}: {err}"))
+        Ok(candidate)
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +374,78 @@ mod tests {
         assert!(response.content.is_empty());
     }
 
+    #[test]
+    fn tail_reads_appended_bytes_and_resets_on_truncation() {
+        let root = temp_dir();
+        std::fs::create_dir_all(root.join("logs")).expect("create root");
+        let log = root.join("logs/run.log");
+        std::fs::write(&log, "one\n").expect("write log");
+
+        let first = tail_text_file_within(&root, "logs/run.log", 0, false, "workspace root", false)
+            .expect("tail");
+        assert!(first.exists);
+        assert_eq!(first.content, "one\n");
+        assert_eq!(first.offset, 4);
+        assert!(!first.reset);
+
+        std::fs::write(&log, "one\ntwo\n\u{e9}").expect("append log");
+        let bytes = std::fs::read(&log).expect("read log");
+        std::fs::write(&log, &bytes[..bytes.len() - 1]).expect("split char");
+        let second =
+            tail_text_file_within(&root, "logs/run.log", first.offset, false, "workspace root", false)
+                .expect("tail");
+        assert_eq!(second.content, "two\n");
+        assert_eq!(second.offset, 8);
+
+        std::fs::write(&log, "x\n").expect("truncate log");
+        let third =
+            tail_text_file_within(&root, "logs/run.log", second.offset, false, "workspace root", false)
+                .expect("tail");
+        assert!(third.reset);
+        assert_eq!(third.content, "x\n");
+
+        assert!(tail_text_file_within(&root, "../run.log", 0, false, "workspace root", false)
+            .is_err());
+        assert!(tail_text_file_within(&root, "/etc/hosts", 0, false, "workspace root", false)
+            .is_err());
+    }
+
+    #[test]
+    fn range_read_pages_through_file() {
+        let root = temp_dir();
+        std::fs::create_dir_all(&root).expect("create root");
+        std::fs::write(root.join("config.toml"), "abcdefghij").expect("write file");
+
+        let first = read_text_file_range_within(&root, "config.toml", 0, 4, false, "CODEX_HOME", false)
+            .expect("first page");
+        assert_eq!(first.content, "abcd");
+        assert_eq!(first.next_offset, 4);
+        assert_eq!(first.total_size, 10);
+        assert!(first.has_more);
+
+        let last = read_text_file_range_within(
+            &root,
+            "config.toml",
+            first.next_offset,
+            100,
+            false,
+            "CODEX_HOME",
+            false,
+        )
+        .expect("last page");
+        assert_eq!(last.content, "efghij");
+        assert!(!last.has_more);
+
+        let past_end = read_text_file_range_within(&root, "config.toml", 50, 4, false, "CODEX_HOME", false)
+            .expect("past end");
+        assert_eq!(past_end.offset, 10);
+        assert!(past_end.content.is_empty());
+
+        let missing = read_text_file_range_within(&root, "AGENTS.md", 0, 4, false, "CODEX_HOME", false)
+            .expect("missing file");
+        assert!(!missing.exists);
+    }
+
     #[test]
     fn write_creates_root_and_round_trips() {
         let root = temp_dir();
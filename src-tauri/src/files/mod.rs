@@ -1,10 +1,19 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
 use serde_json::json;
+use tauri::ipc::Channel;
 use tauri::{AppHandle, State};
 
 use crate::remote_backend;
-use crate::shared::files_core::{file_read_core, file_write_core};
+use crate::shared::files_core::{
+    file_read_core, file_read_range_core, file_write_core, tail_file_core,
+    write_files_transaction_core, FileEdit,
+};
 use crate::state::AppState;
-use self::io::TextFileResponse;
+use self::io::{TailFileResponse, TextFileRangeResponse, TextFileResponse};
 use self::policy::{FileKind, FileScope};
 
 pub(crate) mod io;
@@ -32,6 +41,55 @@ async fn file_read_impl(
     file_read_core(&state.workspaces, &state.app_settings, scope, kind, workspace_id).await
 }
 
+const FILE_TAIL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Complete lines appended since the previous event of a file tail stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileTailLines {
+    pub(crate) lines: Vec<String>,
+    pub(crate) offset: u64,
+    pub(crate) reset: bool,
+}
+
+async fn file_tail_impl(
+    scope: FileScope,
+    kind: Option<FileKind>,
+    workspace_id: Option<String>,
+    path: Option<String>,
+    from_offset: u64,
+    state: &AppState,
+    app: &AppHandle,
+) -> Result<TailFileResponse, String> {
+    if remote_backend::is_remote_mode(state).await {
+        let response = remote_backend::call_remote(
+            state,
+            app.clone(),
+            "file_tail",
+            json!({
+                "scope": scope,
+                "kind": kind,
+                "workspaceId": workspace_id,
+                "path": path,
+                "fromOffset": from_offset,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    tail_file_core(
+        &state.workspaces,
+        &state.app_settings,
+        scope,
+        kind,
+        workspace_id,
+        path,
+        from_offset,
+    )
+    .await
+}
+
 async fn file_write_impl(
     scope: FileScope,
     kind: FileKind,
@@ -78,6 +136,46 @@ pub(crate) async fn file_read(
     file_read_impl(scope, kind, workspace_id, &*state, &app).await
 }
 
+#[tauri::command]
+pub(crate) async fn file_read_range(
+    scope: FileScope,
+    kind: FileKind,
+    workspace_id: Option<String>,
+    offset: Option<u64>,
+    length: u64,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<TextFileRangeResponse, String> {
+    let offset = offset.unwrap_or(0);
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "file_read_range",
+            json!({
+                "scope": scope,
+                "kind": kind,
+                "workspaceId": workspace_id,
+                "offset": offset,
+                "length": length,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    file_read_range_core(
+        &state.workspaces,
+        &state.app_settings,
+        scope,
+        kind,
+        workspace_id,
+        offset,
+        length,
+    )
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn file_write(
     scope: FileScope,
@@ -89,3 +187,140 @@ pub(crate) async fn file_write(
 ) -> Result<(), String> {
     file_write_impl(scope, kind, workspace_id, content, &*state, &app).await
 }
+
+#[tauri::command]
+pub(crate) async fn file_tail(
+    scope: FileScope,
+    kind: Option<FileKind>,
+    workspace_id: Option<String>,
+    path: Option<String>,
+    from_offset: Option<u64>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<TailFileResponse, String> {
+    file_tail_impl(
+        scope,
+        kind,
+        workspace_id,
+        path,
+        from_offset.unwrap_or(0),
+        &*state,
+        &app,
+    )
+    .await
+}
+
+/// Polls a file for appended data and sends complete lines over `on_lines`
+/// until cancelled via `cancel_file_tail_stream` or the channel closes. A
+/// trailing partial line is held back until its newline arrives. Resolves to
+/// the offset read up to when the stream ended.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn stream_file_tail(
+    scope: FileScope,
+    kind: Option<FileKind>,
+    workspace_id: Option<String>,
+    path: Option<String>,
+    from_offset: Option<u64>,
+    stream_id: String,
+    on_lines: Channel<FileTailLines>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<u64, String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut streams = state.file_tail_streams.lock().await;
+        if let Some(previous) = streams.insert(stream_id.clone(), cancel.clone()) {
+            previous.store(true, Ordering::SeqCst);
+        }
+    }
+    let mut offset = from_offset.unwrap_or(0);
+    let mut pending = String::new();
+    let result = loop {
+        if cancel.load(Ordering::SeqCst) {
+            break Ok(offset);
+        }
+        let chunk = match file_tail_impl(
+            scope,
+            kind,
+            workspace_id.clone(),
+            path.clone(),
+            offset,
+            &*state,
+            &app,
+        )
+        .await
+        {
+            Ok(chunk) => chunk,
+            Err(err) => break Err(err),
+        };
+        if chunk.reset {
+            pending.clear();
+        }
+        offset = chunk.offset;
+        pending.push_str(&chunk.content);
+        let lines = match pending.rfind('\n') {
+            Some(index) => {
+                let complete: String = pending.drain(..=index).collect();
+                complete.lines().map(str::to_string).collect()
+            }
+            None => Vec::new(),
+        };
+        if (!lines.is_empty() || chunk.reset)
+            && on_lines
+                .send(FileTailLines {
+                    lines,
+                    offset,
+                    reset: chunk.reset,
+                })
+                .is_err()
+        {
+            break Ok(offset);
+        }
+        if !chunk.truncated {
+            tokio::time::sleep(FILE_TAIL_POLL_INTERVAL).await;
+        }
+    };
+    {
+        let mut streams = state.file_tail_streams.lock().await;
+        if streams
+            .get(&stream_id)
+            .is_some_and(|current| Arc::ptr_eq(current, &cancel))
+        {
+            streams.remove(&stream_id);
+        }
+    }
+    result
+}
+
+#[tauri::command]
+pub(crate) async fn cancel_file_tail_stream(
+    stream_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let streams = state.file_tail_streams.lock().await;
+    if let Some(cancel) = streams.get(&stream_id) {
+        cancel.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn file_write_transaction(
+    edits: Vec<FileEdit>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "file_write_transaction",
+            json!({ "edits": edits }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    write_files_transaction_core(&state.workspaces, &state.app_settings, edits).await
+}
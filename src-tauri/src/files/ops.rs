@@ -1,6 +1,12 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use crate::files::io::{read_text_file_within, write_text_file_within, TextFileResponse};
+use uuid::Uuid;
+
+use crate::files::io::{
+    read_text_file_within, resolve_write_target_within, write_text_file_within, TextFileResponse,
+};
 use crate::files::policy::FilePolicy;
 
 pub(crate) fn read_with_policy(root: &PathBuf, policy: FilePolicy) -> Result<TextFileResponse, String> {
@@ -30,6 +36,87 @@ pub(crate) fn write_with_policy(
     )
 }
 
+pub(crate) fn resolve_write_target_with_policy(
+    root: &PathBuf,
+    policy: FilePolicy,
+) -> Result<PathBuf, String> {
+    resolve_write_target_within(
+        root,
+        policy.filename,
+        policy.create_root,
+        policy.root_context,
+        policy.filename,
+        policy.allow_external_symlink_target,
+    )
+}
+
+fn staging_path(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{name}.codex-monitor-{}.tmp", Uuid::new_v4()))
+}
+
+fn restore_committed(committed: &[(PathBuf, Option<Vec<u8>>)]) {
+    for (target, original) in committed.iter().rev() {
+        let _ = match original {
+            Some(bytes) => fs::write(target, bytes),
+            None => fs::remove_file(target),
+        };
+    }
+}
+
+/// Writes every `(target, content)` pair or none of them. Contents are staged
+/// in temp files beside their targets and only renamed into place once all of
+/// them were written; if a rename fails, targets already replaced get their
+/// previous contents back (or are removed if they did not exist).
+pub(crate) fn write_files_atomically(writes: &[(PathBuf, String)]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for (target, _) in writes {
+        if !seen.insert(target) {
+            return Err(format!("{} is edited more than once", target.display()));
+        }
+    }
+
+    let mut staged: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (target, content) in writes {
+        let temp = staging_path(target);
+        if let Err(err) = fs::write(&temp, content) {
+            let _ = fs::remove_file(&temp);
+            for (_, temp) in &staged {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(format!("Failed to write {}: {err}", target.display()));
+        }
+        staged.push((target.clone(), temp));
+    }
+
+    let mut committed: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+    for (index, (target, temp)) in staged.iter().enumerate() {
+        let original = if target.exists() {
+            fs::read(target).map(Some)
+        } else {
+            Ok(None)
+        };
+        let result = original.and_then(|original| {
+            fs::rename(temp, target)?;
+            Ok(original)
+        });
+        match result {
+            Ok(original) => committed.push((target.clone(), original)),
+            Err(err) => {
+                restore_committed(&committed);
+                for (_, temp) in &staged[index..] {
+                    let _ = fs::remove_file(temp);
+                }
+                return Err(format!("Failed to write {}: {err}", target.display()));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -38,7 +125,7 @@ mod tests {
 
     use crate::files::policy::{policy_for, FileKind, FileScope};
 
-    use super::{read_with_policy, write_with_policy};
+    use super::{read_with_policy, write_files_atomically, write_with_policy};
 
     fn temp_dir(prefix: &str) -> std::path::PathBuf {
         let dir = std::env::temp_dir().join(format!("codex-monitor-{prefix}-{}", Uuid::new_v4()));
@@ -91,6 +178,45 @@ mod tests {
         let _ = fs::remove_dir_all(&root);
     }
 
+    #[test]
+    fn write_files_atomically_rolls_back_on_failure() {
+        let root = temp_dir("file-transaction");
+        fs::create_dir_all(&root).expect("create root");
+        let agents = root.join("AGENTS.md");
+        let config = root.join("config.toml");
+        fs::write(&agents, "old agents").expect("seed agents");
+
+        write_files_atomically(&[
+            (agents.clone(), "new agents".to_string()),
+            (config.clone(), "model = \"o3\"\n".to_string()),
+        ])
+        .expect("commit both");
+        assert_eq!(fs::read_to_string(&agents).unwrap(), "new agents");
+        assert_eq!(fs::read_to_string(&config).unwrap(), "model = \"o3\"\n");
+
+        // A directory cannot be replaced by a file, so the second rename fails.
+        let blocked = root.join("blocked");
+        fs::create_dir_all(blocked.join("child")).expect("create blocker");
+        fs::remove_file(&config).expect("remove config");
+        let result = write_files_atomically(&[
+            (agents.clone(), "partial".to_string()),
+            (config.clone(), "new config".to_string()),
+            (blocked.clone(), "never".to_string()),
+        ]);
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&agents).unwrap(), "new agents");
+        assert!(!config.exists());
+        assert!(blocked.is_dir());
+        let leftovers: Vec<_> = fs::read_dir(&root)
+            .expect("read root")
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn global_config_write_creates_root() {
         let root = temp_dir("global-config");
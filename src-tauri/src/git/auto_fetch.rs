@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::git_utils::resolve_git_root;
+use crate::remote_backend;
+use crate::shared::git_ui_core::fetch_with_default_remote;
+use crate::state::AppState;
+
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+const MIN_FETCH_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+pub(crate) const GIT_REMOTE_UPDATED_EVENT: &str = "git-remote-updated";
+
+/// Delay before the next fetch: the configured interval, doubled for each
+/// consecutive failure (e.g. while offline) up to an hour.
+pub(super) fn next_fetch_delay(interval_secs: u64, consecutive_failures: u32) -> Duration {
+    let interval = Duration::from_secs(interval_secs).max(MIN_FETCH_INTERVAL);
+    if consecutive_failures == 0 {
+        return interval;
+    }
+    let factor = 1u32 << consecutive_failures.min(6);
+    interval
+        .checked_mul(factor)
+        .unwrap_or(MAX_BACKOFF)
+        .min(MAX_BACKOFF.max(interval))
+}
+
+struct FetchSchedule {
+    interval_secs: u64,
+    next_due: Instant,
+    failures: u32,
+}
+
+/// Fetches each workspace whose `autoFetchIntervalSecs` is set on that cadence
+/// and emits `git-remote-updated` afterwards. Settings are re-read every tick,
+/// so adding a workspace or changing its interval takes effect without a
+/// restart; clearing the interval stops fetching.
+pub(crate) fn spawn_git_auto_fetch(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut schedules: HashMap<String, FetchSchedule> = HashMap::new();
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            if remote_backend::is_remote_mode(&*state).await {
+                schedules.clear();
+                continue;
+            }
+            let targets: Vec<(String, PathBuf, u64)> = {
+                let workspaces = state.workspaces.lock().await;
+                workspaces
+                    .values()
+                    .filter_map(|entry| {
+                        let interval = entry.settings.auto_fetch_interval_secs?;
+                        let root = resolve_git_root(entry).ok()?;
+                        Some((entry.id.clone(), root, interval))
+                    })
+                    .collect()
+            };
+            schedules.retain(|id, _| targets.iter().any(|(target_id, _, _)| target_id == id));
+
+            let now = Instant::now();
+            for (workspace_id, root, interval_secs) in targets {
+                let schedule = schedules
+                    .entry(workspace_id.clone())
+                    .or_insert_with(|| FetchSchedule {
+                        interval_secs,
+                        next_due: now + next_fetch_delay(interval_secs, 0),
+                        failures: 0,
+                    });
+                if schedule.interval_secs != interval_secs {
+                    schedule.interval_secs = interval_secs;
+                    schedule.failures = 0;
+                    schedule.next_due = now + next_fetch_delay(interval_secs, 0);
+                }
+                if now < schedule.next_due {
+                    continue;
+                }
+                let result = fetch_with_default_remote(&root, None).await;
+                schedule.failures = match result {
+                    Ok(()) => 0,
+                    Err(_) => schedule.failures.saturating_add(1),
+                };
+                schedule.next_due =
+                    Instant::now() + next_fetch_delay(interval_secs, schedule.failures);
+                let _ = app.emit(
+                    GIT_REMOTE_UPDATED_EVENT,
+                    json!({
+                        "workspaceId": workspace_id,
+                        "ok": result.is_ok(),
+                        "error": result.err(),
+                    }),
+                );
+            }
+        }
+    });
+}
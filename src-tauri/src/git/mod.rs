@@ -1,91 +1,71 @@
-use std::fs;
 use std::path::{Path, PathBuf};
 
-use base64::{engine::general_purpose::STANDARD, Engine as _};
-use git2::{BranchType, DiffOptions, Repository, Sort, Status, StatusOptions};
+use git2::{DiffOptions, Repository, Status};
 use serde_json::json;
-use tauri::State;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Listener, State};
 
-use crate::shared::process_core::tokio_command;
+use crate::shared::git_core::{
+    build_diff_exclude_matcher, diagnose_git_core, diagnose_github_core,
+    git_op_limiter, spawn_git_blocking,
+};
+use crate::shared::git_ui_core::{
+    build_permalink_core, cancel_git_diff_stream_core,
+    checkout_commit_core, collect_git_file_diffs, create_branch_at_head_core,
+    get_ahead_behind_against_core, list_recent_branches_core,
+    commit_and_push_core, commit_git_core, commit_with_body_core,
+    explain_ignore_core, gc_repo_core, get_commit_notes_core, get_default_branch_core,
+    get_conflict_versions_core, get_git_diffs_core, get_git_status_batch_core,
+    get_git_status_core, get_repo_stats_core, init_repo_core, is_working_tree_clean_core,
+    fetch_all_open_prs_as_branches_core, resolve_ref_core, set_upstream_and_push_core,
+    list_changed_paths_against_ref_core, repair_repo_core,
+    resolve_conflict_with_content_core, show_stash_diff_core, stash_apply_git_core,
+    stream_git_diffs_core, validate_workspace_git_core, get_git_change_summary_core,
+    revert_git_all_core, revert_git_file_core, stage_git_all_core, stage_git_file_core,
+    unstage_git_file_core, fetch_git_core, fetch_git_unshallow_core,
+    pull_git_core, push_git_core, sync_git_core, checkout_git_branch_core, create_git_branch_core,
+    get_git_commit_diff_core, get_git_log_core, get_git_remote_core, list_git_branches_core,
+    reword_head_commit_core, reset_head_author_core, reset_head_date_core, bisect_mark_core,
+    bisect_reset_core, bisect_start_core, get_repo_operation_state_core, run_operation_action_core,
+    OperationAction, read_git_config_core, write_git_config_core, stage_git_pattern_core,
+    unstage_git_pattern_core, move_changes_to_new_branch_core, restore_file_from_rev_core,
+    get_ref_comparison_diff_core, preview_sync_git_core, fetch_all_git_core,
+    list_remote_branches_core, prune_remote_branches_core,
+};
+use crate::shared::git_undo_core;
+use crate::shared::github_core::{
+    get_github_issues_core, get_github_pull_request_comments_core, get_github_pull_request_core,
+    get_github_pull_request_diff_core, get_github_pull_request_raw_diff_core,
+    get_github_pull_requests_core,
+};
+use crate::shared::process_core::{tokio_command, workspace_env_for};
 use crate::git_utils::{
-    checkout_branch, commit_to_entry, diff_patch_to_string, diff_stats_for_path, image_mime_type,
-    list_git_roots as scan_git_roots, parse_github_repo, resolve_git_root,
+    diff_patch_to_string,
+    list_git_roots as scan_git_roots, resolve_git_root,
 };
+use crate::remote_backend;
 use crate::state::AppState;
 use crate::types::{
-    BranchInfo, GitCommitDiff, GitFileDiff, GitFileStatus, GitHubIssue, GitHubIssuesResponse,
+    DefaultBranch, GitCommitDiff, GitConflictVersions, GitDiffStreamEvent, GitFileDiff,
+    GitHubIssuesResponse,
     GitHubPullRequest, GitHubPullRequestComment, GitHubPullRequestDiff,
-    GitHubPullRequestsResponse, GitLogResponse,
+    BisectResult, ConfigScope, GitHubPullRequestsResponse, GitLogResponse, GitUntrackedMode,
+    GitUndoEntry, RemoteFetchResult, RepoOperation,
+    CommitAndPushResult, CommitResult, GcReport, IgnoreExplanation, PullRequestRefsFetch, RecentBranch, RepoRepairReport, RepoStats,
+    CheckoutCommitResult, GitAheadBehind, GitChangeSummary, ResolvedRef, WorkingTreeCleanliness,
 };
 use crate::utils::{git_env_path, normalize_git_path, resolve_git_binary};
 
-const INDEX_SKIP_WORKTREE_FLAG: u16 = 0x4000;
-const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
-const MAX_TEXT_DIFF_BYTES: usize = 2 * 1024 * 1024;
-
-fn encode_image_base64(data: &[u8]) -> Option<String> {
-    if data.len() > MAX_IMAGE_BYTES {
-        return None;
-    }
-    Some(STANDARD.encode(data))
-}
-
-fn blob_to_base64(blob: git2::Blob) -> Option<String> {
-    if blob.size() > MAX_IMAGE_BYTES {
-        return None;
-    }
-    encode_image_base64(blob.content())
-}
-
-fn read_image_base64(path: &Path) -> Option<String> {
-    let metadata = fs::metadata(path).ok()?;
-    if metadata.len() > MAX_IMAGE_BYTES as u64 {
-        return None;
-    }
-    let data = fs::read(path).ok()?;
-    encode_image_base64(&data)
-}
-
-fn bytes_look_binary(bytes: &[u8]) -> bool {
-    bytes.iter().take(8192).any(|byte| *byte == 0)
-}
-
-fn split_lines_preserving_newlines(content: &str) -> Vec<String> {
-    if content.is_empty() {
-        return Vec::new();
-    }
-    content
-        .split_inclusive('\n')
-        .map(ToString::to_string)
-        .collect()
-}
-
-fn blob_to_lines(blob: git2::Blob<'_>) -> Option<Vec<String>> {
-    if blob.size() > MAX_TEXT_DIFF_BYTES || blob.is_binary() {
-        return None;
-    }
-    let content = String::from_utf8_lossy(blob.content());
-    Some(split_lines_preserving_newlines(content.as_ref()))
-}
-
-fn read_text_lines(path: &Path) -> Option<Vec<String>> {
-    let metadata = fs::metadata(path).ok()?;
-    if metadata.len() > MAX_TEXT_DIFF_BYTES as u64 {
-        return None;
-    }
-    let data = fs::read(path).ok()?;
-    if bytes_look_binary(&data) {
-        return None;
-    }
-    let content = String::from_utf8_lossy(&data);
-    Some(split_lines_preserving_newlines(content.as_ref()))
-}
+pub(crate) mod auto_fetch;
+pub(crate) mod watcher;
 
 async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String> {
     let git_bin = resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
+    let _permit = git_op_limiter().acquire().await;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_root)
+        .envs(workspace_env_for(repo_root))
         .env("PATH", git_env_path())
         .output()
         .await
@@ -108,215 +88,6 @@ async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String>
     Err(detail.to_string())
 }
 
-fn action_paths_for_file(repo_root: &Path, path: &str) -> Vec<String> {
-    let target = normalize_git_path(path).trim().to_string();
-    if target.is_empty() {
-        return Vec::new();
-    }
-
-    let repo = match Repository::open(repo_root) {
-        Ok(repo) => repo,
-        Err(_) => return vec![target],
-    };
-
-    let mut status_options = StatusOptions::new();
-    status_options
-        .include_untracked(true)
-        .recurse_untracked_dirs(true)
-        .renames_head_to_index(true)
-        .renames_index_to_workdir(true)
-        .include_ignored(false);
-
-    let statuses = match repo.statuses(Some(&mut status_options)) {
-        Ok(statuses) => statuses,
-        Err(_) => return vec![target],
-    };
-
-    for entry in statuses.iter() {
-        let status = entry.status();
-        if !(status.contains(Status::WT_RENAMED) || status.contains(Status::INDEX_RENAMED)) {
-            continue;
-        }
-        let delta = entry.index_to_workdir().or_else(|| entry.head_to_index());
-        let Some(delta) = delta else {
-            continue;
-        };
-        let (Some(old_path), Some(new_path)) =
-            (delta.old_file().path(), delta.new_file().path())
-        else {
-            continue;
-        };
-        let old_path = normalize_git_path(old_path.to_string_lossy().as_ref());
-        let new_path = normalize_git_path(new_path.to_string_lossy().as_ref());
-        if old_path != target && new_path != target {
-            continue;
-        }
-        if old_path == new_path || new_path.is_empty() {
-            return vec![target];
-        }
-        let mut result = Vec::new();
-        if !old_path.is_empty() {
-            result.push(old_path);
-        }
-        if !new_path.is_empty() && !result.contains(&new_path) {
-            result.push(new_path);
-        }
-        return if result.is_empty() { vec![target] } else { result };
-    }
-
-    vec![target]
-}
-
-fn parse_upstream_ref(name: &str) -> Option<(String, String)> {
-    let trimmed = name.strip_prefix("refs/remotes/").unwrap_or(name);
-    let mut parts = trimmed.splitn(2, '/');
-    let remote = parts.next()?;
-    let branch = parts.next()?;
-    if remote.is_empty() || branch.is_empty() {
-        return None;
-    }
-    Some((remote.to_string(), branch.to_string()))
-}
-
-fn upstream_remote_and_branch(repo_root: &Path) -> Result<Option<(String, String)>, String> {
-    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
-    let head = match repo.head() {
-        Ok(head) => head,
-        Err(_) => return Ok(None),
-    };
-    if !head.is_branch() {
-        return Ok(None);
-    }
-    let branch_name = match head.shorthand() {
-        Some(name) => name,
-        None => return Ok(None),
-    };
-    let branch = repo
-        .find_branch(branch_name, BranchType::Local)
-        .map_err(|e| e.to_string())?;
-    let upstream_branch = match branch.upstream() {
-        Ok(upstream) => upstream,
-        Err(_) => return Ok(None),
-    };
-    let upstream_ref = upstream_branch.get();
-    let upstream_name = upstream_ref
-        .name()
-        .or_else(|| upstream_ref.shorthand());
-    Ok(upstream_name.and_then(parse_upstream_ref))
-}
-
-async fn push_with_upstream(repo_root: &Path) -> Result<(), String> {
-    let upstream = upstream_remote_and_branch(repo_root)?;
-    if let Some((remote, branch)) = upstream {
-        // Refresh remote-tracking refs before push so ahead/behind state is current
-        // and we can surface pull/sync requirements before attempting the push.
-        // This is best-effort because some setups intentionally allow push but not fetch.
-        let _ = run_git_command(repo_root, &["fetch", "--prune", remote.as_str()]).await;
-        let refspec = format!("HEAD:{branch}");
-        return run_git_command(
-            repo_root,
-            &["push", remote.as_str(), refspec.as_str()],
-        )
-        .await;
-    }
-    run_git_command(repo_root, &["push"]).await
-}
-
-async fn fetch_with_default_remote(repo_root: &Path) -> Result<(), String> {
-    let upstream = upstream_remote_and_branch(repo_root)?;
-    if let Some((remote, _)) = upstream {
-        return run_git_command(repo_root, &["fetch", "--prune", remote.as_str()]).await;
-    }
-    run_git_command(repo_root, &["fetch", "--prune"]).await
-}
-
-async fn pull_with_default_strategy(repo_root: &Path) -> Result<(), String> {
-    fn autostash_unsupported(lower: &str) -> bool {
-        lower.contains("unknown option") && lower.contains("autostash")
-    }
-
-    fn needs_reconcile_strategy(lower: &str) -> bool {
-        lower.contains("need to specify how to reconcile divergent branches")
-            || lower.contains("you have divergent branches")
-    }
-
-    // Respect user/repo git config first, while still allowing dirty worktrees.
-    match run_git_command(repo_root, &["pull", "--autostash"]).await {
-        Ok(()) => Ok(()),
-        Err(err) => {
-            let lower = err.to_lowercase();
-            if autostash_unsupported(&lower) {
-                match run_git_command(repo_root, &["pull"]).await {
-                    Ok(()) => Ok(()),
-                    Err(no_autostash_err) => {
-                        let no_autostash_lower = no_autostash_err.to_lowercase();
-                        if needs_reconcile_strategy(&no_autostash_lower) {
-                            return run_git_command(repo_root, &["pull", "--no-rebase"]).await;
-                        }
-                        Err(no_autostash_err)
-                    }
-                }
-            } else if needs_reconcile_strategy(&lower) {
-                match run_git_command(repo_root, &["pull", "--no-rebase", "--autostash"]).await {
-                    Ok(()) => Ok(()),
-                    Err(merge_err) => {
-                        let merge_lower = merge_err.to_lowercase();
-                        if autostash_unsupported(&merge_lower) {
-                            return run_git_command(repo_root, &["pull", "--no-rebase"]).await;
-                        }
-                        Err(merge_err)
-                    }
-                }
-            } else {
-                Err(err)
-            }
-        }
-    }
-}
-
-fn status_for_index(status: Status) -> Option<&'static str> {
-    if status.contains(Status::INDEX_NEW) {
-        Some("A")
-    } else if status.contains(Status::INDEX_MODIFIED) {
-        Some("M")
-    } else if status.contains(Status::INDEX_DELETED) {
-        Some("D")
-    } else if status.contains(Status::INDEX_RENAMED) {
-        Some("R")
-    } else if status.contains(Status::INDEX_TYPECHANGE) {
-        Some("T")
-    } else {
-        None
-    }
-}
-
-fn status_for_workdir(status: Status) -> Option<&'static str> {
-    if status.contains(Status::WT_NEW) {
-        Some("A")
-    } else if status.contains(Status::WT_MODIFIED) {
-        Some("M")
-    } else if status.contains(Status::WT_DELETED) {
-        Some("D")
-    } else if status.contains(Status::WT_RENAMED) {
-        Some("R")
-    } else if status.contains(Status::WT_TYPECHANGE) {
-        Some("T")
-    } else {
-        None
-    }
-}
-
-fn status_for_delta(status: git2::Delta) -> &'static str {
-    match status {
-        git2::Delta::Added => "A",
-        git2::Delta::Modified => "M",
-        git2::Delta::Deleted => "D",
-        git2::Delta::Renamed => "R",
-        git2::Delta::Typechange => "T",
-        _ => "M",
-    }
-}
-
 fn build_combined_diff(diff: &git2::Diff) -> String {
     let mut combined_diff = String::new();
     for (index, delta) in diff.deltas().enumerate() {
@@ -388,249 +159,163 @@ fn collect_workspace_diff(repo_root: &Path) -> Result<String, String> {
     Ok(build_combined_diff(&diff))
 }
 
-fn github_repo_from_path(path: &Path) -> Result<String, String> {
-    let repo = Repository::open(path).map_err(|e| e.to_string())?;
-    let remotes = repo.remotes().map_err(|e| e.to_string())?;
-    let name = if remotes.iter().any(|remote| remote == Some("origin")) {
-        "origin".to_string()
-    } else {
-        remotes
-            .iter()
-            .flatten()
-            .next()
-            .unwrap_or("")
-            .to_string()
-    };
-    if name.is_empty() {
-        return Err("No git remote configured.".to_string());
-    }
-    let remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
-    let remote_url = remote
-        .url()
-        .ok_or("Remote has no URL configured.")?;
-    parse_github_repo(remote_url).ok_or("Remote is not a GitHub repository.".to_string())
-}
-
-fn parse_pr_diff(diff: &str) -> Vec<GitHubPullRequestDiff> {
-    let mut entries = Vec::new();
-    let mut current_lines: Vec<&str> = Vec::new();
-    let mut current_old_path: Option<String> = None;
-    let mut current_new_path: Option<String> = None;
-    let mut current_status: Option<String> = None;
-
-    let finalize = |lines: &Vec<&str>,
-                        old_path: &Option<String>,
-                        new_path: &Option<String>,
-                        status: &Option<String>,
-                        results: &mut Vec<GitHubPullRequestDiff>| {
-        if lines.is_empty() {
-            return;
-        }
-        let diff_text = lines.join("\n");
-        if diff_text.trim().is_empty() {
-            return;
-        }
-        let status_value = status.clone().unwrap_or_else(|| "M".to_string());
-        let path = if status_value == "D" {
-            old_path.clone().unwrap_or_default()
-        } else {
-            new_path.clone().or_else(|| old_path.clone()).unwrap_or_default()
-        };
-        if path.is_empty() {
-            return;
-        }
-        results.push(GitHubPullRequestDiff {
-            path: normalize_git_path(&path),
-            status: status_value,
-            diff: diff_text,
-        });
-    };
-
-    for line in diff.lines() {
-        if line.starts_with("diff --git ") {
-            finalize(
-                &current_lines,
-                &current_old_path,
-                &current_new_path,
-                &current_status,
-                &mut entries,
-            );
-            current_lines = vec![line];
-            current_old_path = None;
-            current_new_path = None;
-            current_status = None;
-
-            let rest = line.trim_start_matches("diff --git ").trim();
-            let mut parts = rest.split_whitespace();
-            let old_part = parts.next().unwrap_or("").trim_start_matches("a/");
-            let new_part = parts.next().unwrap_or("").trim_start_matches("b/");
-            if !old_part.is_empty() {
-                current_old_path = Some(old_part.to_string());
-            }
-            if !new_part.is_empty() {
-                current_new_path = Some(new_part.to_string());
-            }
-            continue;
-        }
-        if line.starts_with("new file mode ") {
-            current_status = Some("A".to_string());
-        } else if line.starts_with("deleted file mode ") {
-            current_status = Some("D".to_string());
-        } else if line.starts_with("rename from ") {
-            current_status = Some("R".to_string());
-            let path = line.trim_start_matches("rename from ").trim();
-            if !path.is_empty() {
-                current_old_path = Some(path.to_string());
-            }
-        } else if line.starts_with("rename to ") {
-            current_status = Some("R".to_string());
-            let path = line.trim_start_matches("rename to ").trim();
-            if !path.is_empty() {
-                current_new_path = Some(path.to_string());
-            }
-        }
-        current_lines.push(line);
-    }
-
-    finalize(
-        &current_lines,
-        &current_old_path,
-        &current_new_path,
-        &current_status,
-        &mut entries,
-    );
-
-    entries
-}
 #[tauri::command]
 pub(crate) async fn get_git_status(
     workspace_id: String,
+    untracked_mode: Option<GitUntrackedMode>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<serde_json::Value, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    drop(workspaces);
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_status",
+            json!({ "workspaceId": workspace_id, "untrackedMode": untracked_mode }),
+        )
+        .await;
+    }
+    get_git_status_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        untracked_mode,
+    )
+    .await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+/// Fast clean/dirty check for flows like branch switching; see
+/// `working_tree_cleanliness`.
+#[tauri::command]
+pub(crate) async fn is_working_tree_clean(
+    workspace_id: String,
+    count_changes: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkingTreeCleanliness, String> {
+    let count_changes = count_changes.unwrap_or(false);
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "is_working_tree_clean",
+            json!({ "workspaceId": workspace_id, "countChanges": count_changes }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    is_working_tree_clean_core(&state.workspaces, workspace_id, count_changes).await
+}
 
-    let branch_name = repo
-        .head()
-        .ok()
-        .and_then(|head| head.shorthand().map(|s| s.to_string()))
-        .unwrap_or_else(|| "unknown".to_string());
+#[tauri::command]
+pub(crate) async fn init_repo(
+    workspace_id: String,
+    initial_branch: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "init_repo",
+            json!({ "workspaceId": workspace_id, "initialBranch": initial_branch }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    init_repo_core(&state.workspaces, workspace_id, initial_branch).await
+}
 
-    let mut status_options = StatusOptions::new();
-    status_options
-        .include_untracked(true)
-        .recurse_untracked_dirs(true)
-        .renames_head_to_index(true)
-        .renames_index_to_workdir(true)
-        .include_ignored(false);
-
-    let statuses = repo
-        .statuses(Some(&mut status_options))
-        .map_err(|e| e.to_string())?;
-
-    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
-    let index = repo.index().ok();
-
-    let mut files = Vec::new();
-    let mut staged_files = Vec::new();
-    let mut unstaged_files = Vec::new();
-    let mut total_additions = 0i64;
-    let mut total_deletions = 0i64;
-    for entry in statuses.iter() {
-        let path = entry.path().unwrap_or("");
-        if path.is_empty() {
-            continue;
-        }
-        if let Some(index) = index.as_ref() {
-            if let Some(entry) = index.get_path(Path::new(path), 0) {
-                if entry.flags_extended & INDEX_SKIP_WORKTREE_FLAG != 0 {
-                    continue;
-                }
-            }
-        }
-        let status = entry.status();
-        let normalized_path = normalize_git_path(path);
-        let include_index = status.intersects(
-            Status::INDEX_NEW
-                | Status::INDEX_MODIFIED
-                | Status::INDEX_DELETED
-                | Status::INDEX_RENAMED
-                | Status::INDEX_TYPECHANGE,
-        );
-        let include_workdir = status.intersects(
-            Status::WT_NEW
-                | Status::WT_MODIFIED
-                | Status::WT_DELETED
-                | Status::WT_RENAMED
-                | Status::WT_TYPECHANGE,
-        );
-        let mut combined_additions = 0i64;
-        let mut combined_deletions = 0i64;
-
-        if include_index {
-            let (additions, deletions) =
-                diff_stats_for_path(&repo, head_tree.as_ref(), path, true, false)
-                    .unwrap_or((0, 0));
-            if let Some(status_str) = status_for_index(status) {
-                staged_files.push(GitFileStatus {
-                    path: normalized_path.clone(),
-                    status: status_str.to_string(),
-                    additions,
-                    deletions,
-                });
-            }
-            combined_additions += additions;
-            combined_deletions += deletions;
-            total_additions += additions;
-            total_deletions += deletions;
-        }
+#[tauri::command]
+pub(crate) async fn set_upstream_and_push(
+    workspace_id: String,
+    remote_name: String,
+    url: String,
+    branch: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "set_upstream_and_push",
+            json!({
+                "workspaceId": workspace_id,
+                "remoteName": remote_name,
+                "url": url,
+                "branch": branch,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    set_upstream_and_push_core(&state.workspaces, workspace_id, remote_name, url, branch).await
+}
 
-        if include_workdir {
-            let (additions, deletions) =
-                diff_stats_for_path(&repo, head_tree.as_ref(), path, false, true)
-                    .unwrap_or((0, 0));
-            if let Some(status_str) = status_for_workdir(status) {
-                unstaged_files.push(GitFileStatus {
-                    path: normalized_path.clone(),
-                    status: status_str.to_string(),
-                    additions,
-                    deletions,
-                });
-            }
-            combined_additions += additions;
-            combined_deletions += deletions;
-            total_additions += additions;
-            total_deletions += deletions;
-        }
+/// Status for many workspaces in one call; see `get_git_status_batch_core`.
+#[tauri::command]
+pub(crate) async fn get_git_status_batch(
+    workspace_ids: Vec<String>,
+    summary_only: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_status_batch",
+            json!({ "workspaceIds": workspace_ids, "summaryOnly": summary_only }),
+        )
+        .await;
+    }
+    get_git_status_batch_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_ids,
+        summary_only.unwrap_or(false),
+    )
+    .await
+}
 
-        if include_index || include_workdir {
-            let status_str = status_for_workdir(status)
-                .or_else(|| status_for_index(status))
-                .unwrap_or("--");
-            files.push(GitFileStatus {
-                path: normalized_path,
-                status: status_str.to_string(),
-                additions: combined_additions,
-                deletions: combined_deletions,
-            });
-        }
+#[tauri::command]
+pub(crate) async fn validate_workspace_git(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "validate_workspace_git",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await;
     }
+    validate_workspace_git_core(&state.workspaces, workspace_id).await
+}
 
-    Ok(json!({
-        "branchName": branch_name,
-        "files": files,
-        "stagedFiles": staged_files,
-        "unstagedFiles": unstaged_files,
-        "totalAdditions": total_additions,
-        "totalDeletions": total_deletions,
-    }))
+/// Cheap variant of `get_git_status` for badges: counts only, no per-file stats.
+#[tauri::command]
+pub(crate) async fn get_git_change_summary(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitChangeSummary, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_change_summary",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_git_change_summary_core(&state.workspaces, workspace_id).await
 }
 
 #[tauri::command]
@@ -638,39 +323,78 @@ pub(crate) async fn stage_git_file(
     workspace_id: String,
     path: String,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let entry = {
-        let workspaces = state.workspaces.lock().await;
-        workspaces
-            .get(&workspace_id)
-            .cloned()
-            .ok_or("workspace not found")?
-    };
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "stage_git_file",
+            json!({ "workspaceId": workspace_id, "path": path }),
+        )
+        .await?;
+        return Ok(());
+    }
+    stage_git_file_core(&state.workspaces, workspace_id, path).await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    // If libgit2 reports a rename, we want a single UI action to stage both the
-    // old + new paths so the change actually moves to the staged section.
-    for path in action_paths_for_file(&repo_root, &path) {
-        run_git_command(&repo_root, &["add", "-A", "--", &path]).await?;
+#[tauri::command]
+pub(crate) async fn stage_git_pattern(
+    workspace_id: String,
+    pathspec: Vec<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "stage_git_pattern",
+            json!({ "workspaceId": workspace_id, "pathspec": pathspec }),
+        )
+        .await?;
+        return Ok(());
     }
-    Ok(())
+    stage_git_pattern_core(&state.workspaces, workspace_id, pathspec).await
 }
 
 #[tauri::command]
-pub(crate) async fn stage_git_all(
+pub(crate) async fn unstage_git_pattern(
     workspace_id: String,
+    pathspec: Vec<String>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let entry = {
-        let workspaces = state.workspaces.lock().await;
-        workspaces
-            .get(&workspace_id)
-            .cloned()
-            .ok_or("workspace not found")?
-    };
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "unstage_git_pattern",
+            json!({ "workspaceId": workspace_id, "pathspec": pathspec }),
+        )
+        .await?;
+        return Ok(());
+    }
+    unstage_git_pattern_core(&state.workspaces, workspace_id, pathspec).await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    run_git_command(&repo_root, &["add", "-A"]).await
+#[tauri::command]
+pub(crate) async fn stage_git_all(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "stage_git_all",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+    stage_git_all_core(&state.workspaces, workspace_id).await
 }
 
 #[tauri::command]
@@ -678,20 +402,19 @@ pub(crate) async fn unstage_git_file(
     workspace_id: String,
     path: String,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let entry = {
-        let workspaces = state.workspaces.lock().await;
-        workspaces
-            .get(&workspace_id)
-            .cloned()
-            .ok_or("workspace not found")?
-    };
-
-    let repo_root = resolve_git_root(&entry)?;
-    for path in action_paths_for_file(&repo_root, &path) {
-        run_git_command(&repo_root, &["restore", "--staged", "--", &path]).await?;
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "unstage_git_file",
+            json!({ "workspaceId": workspace_id, "path": path }),
+        )
+        .await?;
+        return Ok(());
     }
-    Ok(())
+    unstage_git_file_core(&state.workspaces, workspace_id, path).await
 }
 
 #[tauri::command]
@@ -699,121 +422,398 @@ pub(crate) async fn revert_git_file(
     workspace_id: String,
     path: String,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let entry = {
-        let workspaces = state.workspaces.lock().await;
-        workspaces
-            .get(&workspace_id)
-            .cloned()
-            .ok_or("workspace not found")?
-    };
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "revert_git_file",
+            json!({ "workspaceId": workspace_id, "path": path }),
+        )
+        .await?;
+        return Ok(());
+    }
+    revert_git_file_core(&state.workspaces, workspace_id, path).await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    for path in action_paths_for_file(&repo_root, &path) {
-        if run_git_command(
-            &repo_root,
-            &["restore", "--staged", "--worktree", "--", &path],
+#[tauri::command]
+pub(crate) async fn restore_file_from_rev(
+    workspace_id: String,
+    path: String,
+    rev: String,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "restore_file_from_rev",
+            json!({ "workspaceId": workspace_id, "path": path, "rev": rev, "force": force }),
         )
-        .await
-        .is_ok()
-        {
-            continue;
-        }
-        run_git_command(&repo_root, &["clean", "-f", "--", &path]).await?;
+        .await?;
+        return Ok(());
     }
-    Ok(())
+    restore_file_from_rev_core(&state.workspaces, workspace_id, path, rev, force).await
 }
 
 #[tauri::command]
 pub(crate) async fn revert_git_all(
     workspace_id: String,
+    override_protection: Option<bool>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?;
-    let repo_root = resolve_git_root(entry)?;
-    run_git_command(&repo_root, &["restore", "--staged", "--worktree", "--", "."]).await?;
-    run_git_command(&repo_root, &["clean", "-f", "-d"]).await
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "revert_git_all",
+            json!({ "workspaceId": workspace_id, "overrideProtection": override_protection }),
+        )
+        .await?;
+        return Ok(());
+    }
+    revert_git_all_core(
+        &state.workspaces,
+        &state.git_undo_journal,
+        workspace_id,
+        override_protection,
+    )
+    .await
 }
 
 #[tauri::command]
 pub(crate) async fn commit_git(
     workspace_id: String,
     message: String,
+    paths: Option<Vec<String>>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-
-    let repo_root = resolve_git_root(&entry)?;
-    run_git_command(&repo_root, &["commit", "-m", &message]).await
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "commit_git",
+            json!({ "workspaceId": workspace_id, "message": message, "paths": paths }),
+        )
+        .await?;
+        return Ok(());
+    }
+    commit_git_core(&state.workspaces, workspace_id, message, paths).await
 }
 
 #[tauri::command]
-pub(crate) async fn push_git(
+pub(crate) async fn commit_with_body(
     workspace_id: String,
+    subject: String,
+    body: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-
-    let repo_root = resolve_git_root(&entry)?;
-    push_with_upstream(&repo_root).await
+    app: AppHandle,
+) -> Result<CommitResult, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "commit_with_body",
+            json!({ "workspaceId": workspace_id, "subject": subject, "body": body }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    commit_with_body_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        subject,
+        body,
+    )
+    .await
 }
 
 #[tauri::command]
-pub(crate) async fn pull_git(
+pub(crate) async fn commit_and_push(
+    workspace_id: String,
+    message: String,
+    stage_all: Option<bool>,
+    co_authors: Option<Vec<String>>,
+    override_protection: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<CommitAndPushResult, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "commit_and_push",
+            json!({
+                "workspaceId": workspace_id,
+                "message": message,
+                "stageAll": stage_all,
+                "coAuthors": co_authors,
+                "overrideProtection": override_protection,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    commit_and_push_core(
+        &state.workspaces,
+        workspace_id,
+        message,
+        stage_all.unwrap_or(false),
+        co_authors.unwrap_or_default(),
+        override_protection.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn reword_head_commit(
+    workspace_id: String,
+    message: String,
+    allow_merge: Option<bool>,
+    override_protection: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "reword_head_commit",
+            json!({
+                "workspaceId": workspace_id,
+                "message": message,
+                "allowMerge": allow_merge,
+                "overrideProtection": override_protection,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    reword_head_commit_core(
+        &state.workspaces,
+        &state.git_undo_journal,
+        workspace_id,
+        message,
+        allow_merge,
+        override_protection,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn reset_head_author(
+    workspace_id: String,
+    name: String,
+    email: String,
+    override_protection: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "reset_head_author",
+            json!({
+                "workspaceId": workspace_id,
+                "name": name,
+                "email": email,
+                "overrideProtection": override_protection,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    reset_head_author_core(&state.workspaces, workspace_id, name, email, override_protection)
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn reset_head_date(
+    workspace_id: String,
+    timestamp: i64,
+    override_protection: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "reset_head_date",
+            json!({
+                "workspaceId": workspace_id,
+                "timestamp": timestamp,
+                "overrideProtection": override_protection,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    reset_head_date_core(&state.workspaces, workspace_id, timestamp, override_protection).await
+}
+
+#[tauri::command]
+pub(crate) async fn push_git(
     workspace_id: String,
+    no_verify: Option<bool>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "push_git",
+            json!({ "workspaceId": workspace_id, "noVerify": no_verify }),
+        )
+        .await?;
+        return Ok(());
+    }
+    push_git_core(&state.workspaces, workspace_id, no_verify).await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    pull_with_default_strategy(&repo_root).await
+#[tauri::command]
+pub(crate) async fn pull_git(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "pull_git",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+    pull_git_core(&state.workspaces, &state.git_undo_journal, workspace_id).await
 }
 
 #[tauri::command]
 pub(crate) async fn fetch_git(
     workspace_id: String,
+    fetch_depth: Option<u32>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "fetch_git",
+            json!({ "workspaceId": workspace_id, "fetchDepth": fetch_depth }),
+        )
+        .await?;
+        return Ok(());
+    }
+    fetch_git_core(&state.workspaces, workspace_id, fetch_depth).await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    fetch_with_default_remote(&repo_root).await
+#[tauri::command]
+pub(crate) async fn fetch_all_git(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<RemoteFetchResult>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "fetch_all_git",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    fetch_all_git_core(&state.workspaces, workspace_id).await
+}
+
+/// Fetches all pull request heads as `<remote>/pr/<n>` tracking refs.
+#[tauri::command]
+pub(crate) async fn fetch_all_open_prs_as_branches(
+    workspace_id: String,
+    remote: Option<String>,
+    refspec: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<PullRequestRefsFetch, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "fetch_all_open_prs_as_branches",
+            json!({ "workspaceId": workspace_id, "remote": remote, "refspec": refspec }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    fetch_all_open_prs_as_branches_core(&state.workspaces, workspace_id, remote, refspec).await
+}
+
+#[tauri::command]
+pub(crate) async fn fetch_git_unshallow(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "fetch_git_unshallow",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+    fetch_git_unshallow_core(&state.workspaces, workspace_id).await
 }
 
 #[tauri::command]
 pub(crate) async fn sync_git(
     workspace_id: String,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "sync_git",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+    sync_git_core(&state.workspaces, workspace_id).await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    // Pull first, then push (like VSCode sync)
-    pull_with_default_strategy(&repo_root).await?;
-    push_with_upstream(&repo_root).await
+#[tauri::command]
+pub(crate) async fn preview_sync_git(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "preview_sync_git",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    preview_sync_git_core(&state.workspaces, workspace_id).await
 }
 
 #[tauri::command]
@@ -852,813 +852,2995 @@ pub(crate) async fn get_workspace_diff(
 #[tauri::command]
 pub(crate) async fn get_git_diffs(
     workspace_id: String,
+    whitespace_check: Option<bool>,
+    full_file: Option<bool>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<Vec<GitFileDiff>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    drop(workspaces);
-
-    let repo_root = resolve_git_root(&entry)?;
-    let ignore_whitespace_changes = {
-        let settings = state.app_settings.lock().await;
-        settings.git_diff_ignore_whitespace_changes
-    };
-    tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-        let head_tree = repo
-            .head()
-            .ok()
-            .and_then(|head| head.peel_to_tree().ok());
-
-        let mut options = DiffOptions::new();
-        options
-            .include_untracked(true)
-            .recurse_untracked_dirs(true)
-            .show_untracked_content(true);
-        options.ignore_whitespace_change(ignore_whitespace_changes);
-
-        let diff = match head_tree.as_ref() {
-            Some(tree) => repo
-                .diff_tree_to_workdir_with_index(Some(tree), Some(&mut options))
-                .map_err(|e| e.to_string())?,
-            None => repo
-                .diff_tree_to_workdir_with_index(None, Some(&mut options))
-                .map_err(|e| e.to_string())?,
-        };
-
-        let mut results = Vec::new();
-        for (index, delta) in diff.deltas().enumerate() {
-            let old_path = delta.old_file().path();
-            let new_path = delta.new_file().path();
-            let display_path = new_path.or(old_path);
-            let Some(display_path) = display_path else {
-                continue;
-            };
-            let old_path_str = old_path.map(|path| path.to_string_lossy());
-            let new_path_str = new_path.map(|path| path.to_string_lossy());
-            let display_path_str = display_path.to_string_lossy();
-            let normalized_path = normalize_git_path(&display_path_str);
-            let old_image_mime = old_path_str.as_deref().and_then(image_mime_type);
-            let new_image_mime = new_path_str.as_deref().and_then(image_mime_type);
-            let is_image = old_image_mime.is_some() || new_image_mime.is_some();
-            let is_deleted = delta.status() == git2::Delta::Deleted;
-            let is_added = delta.status() == git2::Delta::Added;
-
-            let old_lines = if !is_added {
-                head_tree
-                    .as_ref()
-                    .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
-                    .and_then(|entry| repo.find_blob(entry.id()).ok())
-                    .and_then(blob_to_lines)
-            } else {
-                None
-            };
-
-            let new_lines = if !is_deleted {
-                match new_path {
-                    Some(path) => {
-                        let full_path = repo_root.join(path);
-                        read_text_lines(&full_path)
-                    }
-                    None => None,
-                }
-            } else {
-                None
-            };
-
-            if is_image {
-                let old_image_data = if !is_added && old_image_mime.is_some() {
-                    head_tree
-                        .as_ref()
-                        .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
-                        .and_then(|entry| repo.find_blob(entry.id()).ok())
-                        .and_then(blob_to_base64)
-                } else {
-                    None
-                };
-
-                let new_image_data = if !is_deleted && new_image_mime.is_some() {
-                    match new_path {
-                        Some(path) => {
-                            let full_path = repo_root.join(path);
-                            read_image_base64(&full_path)
-                        }
-                        None => None,
-                    }
-                } else {
-                    None
-                };
-
-                results.push(GitFileDiff {
-                    path: normalized_path,
-                    diff: String::new(),
-                    old_lines: None,
-                    new_lines: None,
-                    is_binary: true,
-                    is_image: true,
-                    old_image_data,
-                    new_image_data,
-                    old_image_mime: old_image_mime.map(str::to_string),
-                    new_image_mime: new_image_mime.map(str::to_string),
-                });
-                continue;
-            }
-
-            let patch = match git2::Patch::from_diff(&diff, index) {
-                Ok(patch) => patch,
-                Err(_) => continue,
-            };
-            let Some(mut patch) = patch else {
-                continue;
-            };
-            let content = match diff_patch_to_string(&mut patch) {
-                Ok(content) => content,
-                Err(_) => continue,
-            };
-            if content.trim().is_empty() {
-                continue;
-            }
-            results.push(GitFileDiff {
-                path: normalized_path,
-                diff: content,
-                old_lines,
-                new_lines,
-                is_binary: false,
-                is_image: false,
-                old_image_data: None,
-                new_image_data: None,
-                old_image_mime: None,
-                new_image_mime: None,
-            });
-        }
-
-        Ok(results)
-    })
+    let whitespace_check = whitespace_check.unwrap_or(false);
+    let full_file = full_file.unwrap_or(false);
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_diffs",
+            json!({
+                "workspaceId": workspace_id,
+                "whitespaceCheck": whitespace_check,
+                "fullFile": full_file,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_git_diffs_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        whitespace_check,
+        full_file,
+    )
     .await
-    .map_err(|e| e.to_string())?
 }
 
+/// Streams working tree diffs over `on_diff` as they are produced, for
+/// changesets too large to build in one response. Resolves to false when the
+/// stream was cancelled via `cancel_git_diff_stream`.
 #[tauri::command]
-pub(crate) async fn get_git_log(
+pub(crate) async fn stream_git_diffs(
     workspace_id: String,
-    limit: Option<usize>,
+    stream_id: String,
+    whitespace_check: Option<bool>,
+    on_diff: Channel<GitFileDiff>,
     state: State<'_, AppState>,
-) -> Result<GitLogResponse, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    drop(workspaces);
-
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let max_items = limit.unwrap_or(40);
-    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-    revwalk.push_head().map_err(|e| e.to_string())?;
-    revwalk
-        .set_sorting(Sort::TIME)
-        .map_err(|e| e.to_string())?;
-
-    let mut total = 0usize;
-    for oid_result in revwalk {
-        oid_result.map_err(|e| e.to_string())?;
-        total += 1;
-    }
-
-    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-    revwalk.push_head().map_err(|e| e.to_string())?;
-    revwalk
-        .set_sorting(Sort::TIME)
-        .map_err(|e| e.to_string())?;
-
-    let mut entries = Vec::new();
-    for oid_result in revwalk.take(max_items) {
-        let oid = oid_result.map_err(|e| e.to_string())?;
-        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
-        entries.push(commit_to_entry(commit));
-    }
-
-    let mut ahead = 0usize;
-    let mut behind = 0usize;
-    let mut ahead_entries = Vec::new();
-    let mut behind_entries = Vec::new();
-    let mut upstream = None;
-
-    if let Ok(head) = repo.head() {
-        if head.is_branch() {
-            if let Some(branch_name) = head.shorthand() {
-                if let Ok(branch) = repo.find_branch(branch_name, BranchType::Local) {
-                    if let Ok(upstream_branch) = branch.upstream() {
-                        let upstream_ref = upstream_branch.get();
-                        upstream = upstream_ref
-                            .shorthand()
-                            .map(|name| name.to_string())
-                            .or_else(|| upstream_ref.name().map(|name| name.to_string()));
-                        if let (Some(head_oid), Some(upstream_oid)) =
-                            (head.target(), upstream_ref.target())
-                        {
-                            let (ahead_count, behind_count) = repo
-                                .graph_ahead_behind(head_oid, upstream_oid)
-                                .map_err(|e| e.to_string())?;
-                            ahead = ahead_count;
-                            behind = behind_count;
-
-                            let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-                            revwalk.push(head_oid).map_err(|e| e.to_string())?;
-                            revwalk.hide(upstream_oid).map_err(|e| e.to_string())?;
-                            revwalk
-                                .set_sorting(Sort::TIME)
-                                .map_err(|e| e.to_string())?;
-                            for oid_result in revwalk.take(max_items) {
-                                let oid = oid_result.map_err(|e| e.to_string())?;
-                                let commit =
-                                    repo.find_commit(oid).map_err(|e| e.to_string())?;
-                                ahead_entries.push(commit_to_entry(commit));
-                            }
-
-                            let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-                            revwalk.push(upstream_oid).map_err(|e| e.to_string())?;
-                            revwalk.hide(head_oid).map_err(|e| e.to_string())?;
-                            revwalk
-                                .set_sorting(Sort::TIME)
-                                .map_err(|e| e.to_string())?;
-                            for oid_result in revwalk.take(max_items) {
-                                let oid = oid_result.map_err(|e| e.to_string())?;
-                                let commit =
-                                    repo.find_commit(oid).map_err(|e| e.to_string())?;
-                                behind_entries.push(commit_to_entry(commit));
-                            }
-                        }
-                    }
+    app: AppHandle,
+) -> Result<bool, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        // The daemon sends each diff as a `git-diff-stream` notification to the
+        // connection that started the stream.
+        let forwarded_id = stream_id.clone();
+        let listener = app.listen("git-diff-stream", move |event| {
+            if let Ok(event) = serde_json::from_str::<GitDiffStreamEvent>(event.payload()) {
+                if event.stream_id == forwarded_id {
+                    let _ = on_diff.send(event.diff);
                 }
             }
-        }
+        });
+        let response = remote_backend::call_remote(
+            &*state,
+            app.clone(),
+            "stream_git_diffs",
+            json!({
+                "workspaceId": workspace_id,
+                "streamId": stream_id,
+                "whitespaceCheck": whitespace_check,
+            }),
+        )
+        .await;
+        app.unlisten(listener);
+        return serde_json::from_value(response?).map_err(|err| err.to_string());
     }
+    stream_git_diffs_core(
+        &state.workspaces,
+        &state.app_settings,
+        &state.git_diff_streams,
+        workspace_id,
+        stream_id,
+        whitespace_check.unwrap_or(false),
+        move |diff| on_diff.send(diff).is_ok(),
+    )
+    .await
+}
 
-    Ok(GitLogResponse {
-        total,
-        entries,
-        ahead,
-        behind,
-        ahead_entries,
-        behind_entries,
-        upstream,
-    })
+#[tauri::command]
+pub(crate) async fn cancel_git_diff_stream(
+    stream_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "cancel_git_diff_stream",
+            json!({ "streamId": stream_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+    cancel_git_diff_stream_core(&state.git_diff_streams, &stream_id).await;
+    Ok(())
 }
 
 #[tauri::command]
-pub(crate) async fn get_git_commit_diff(
+pub(crate) async fn get_conflict_versions(
     workspace_id: String,
-    sha: String,
+    path: String,
     state: State<'_, AppState>,
-) -> Result<Vec<GitCommitDiff>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    drop(workspaces);
-
-    let ignore_whitespace_changes = {
-        let settings = state.app_settings.lock().await;
-        settings.git_diff_ignore_whitespace_changes
-    };
-
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let oid = git2::Oid::from_str(&sha).map_err(|e| e.to_string())?;
-    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
-    let commit_tree = commit.tree().map_err(|e| e.to_string())?;
-    let parent_tree = commit
-        .parent(0)
-        .ok()
-        .and_then(|parent| parent.tree().ok());
-
-    let mut options = DiffOptions::new();
-    options.ignore_whitespace_change(ignore_whitespace_changes);
-    let diff = repo
-        .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), Some(&mut options))
-        .map_err(|e| e.to_string())?;
+    app: AppHandle,
+) -> Result<GitConflictVersions, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_conflict_versions",
+            json!({ "workspaceId": workspace_id, "path": path }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_conflict_versions_core(&state.workspaces, workspace_id, path).await
+}
 
-    let mut results = Vec::new();
-    for (index, delta) in diff.deltas().enumerate() {
-        let old_path = delta.old_file().path();
-        let new_path = delta.new_file().path();
-        let display_path = new_path.or(old_path);
-        let Some(display_path) = display_path else {
-            continue;
-        };
-        let old_path_str = old_path.map(|path| path.to_string_lossy());
-        let new_path_str = new_path.map(|path| path.to_string_lossy());
-        let display_path_str = display_path.to_string_lossy();
-        let normalized_path = normalize_git_path(&display_path_str);
-        let old_image_mime = old_path_str.as_deref().and_then(image_mime_type);
-        let new_image_mime = new_path_str.as_deref().and_then(image_mime_type);
-        let is_image = old_image_mime.is_some() || new_image_mime.is_some();
-        let is_deleted = delta.status() == git2::Delta::Deleted;
-        let is_added = delta.status() == git2::Delta::Added;
-
-        let old_lines = if !is_added {
-            parent_tree
-                .as_ref()
-                .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
-                .and_then(|entry| repo.find_blob(entry.id()).ok())
-                .and_then(blob_to_lines)
-        } else {
-            None
-        };
+#[tauri::command]
+pub(crate) async fn resolve_conflict_with_content(
+    workspace_id: String,
+    path: String,
+    content: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "resolve_conflict_with_content",
+            json!({ "workspaceId": workspace_id, "path": path, "content": content }),
+        )
+        .await?;
+        return Ok(());
+    }
+    resolve_conflict_with_content_core(&state.workspaces, workspace_id, path, content).await
+}
 
-        let new_lines = if !is_deleted {
-            new_path
-                .and_then(|path| commit_tree.get_path(path).ok())
-                .and_then(|entry| repo.find_blob(entry.id()).ok())
-                .and_then(blob_to_lines)
-        } else {
-            None
-        };
+#[tauri::command]
+pub(crate) async fn explain_ignore(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<IgnoreExplanation, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "explain_ignore",
+            json!({ "workspaceId": workspace_id, "path": path }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    explain_ignore_core(&state.workspaces, workspace_id, path).await
+}
 
-        if is_image {
-            let old_image_data = if !is_added && old_image_mime.is_some() {
-                parent_tree
-                    .as_ref()
-                    .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
-                    .and_then(|entry| repo.find_blob(entry.id()).ok())
-                    .and_then(blob_to_base64)
-            } else {
-                None
-            };
-
-            let new_image_data = if !is_deleted && new_image_mime.is_some() {
-                new_path
-                    .and_then(|path| commit_tree.get_path(path).ok())
-                    .and_then(|entry| repo.find_blob(entry.id()).ok())
-                    .and_then(blob_to_base64)
-            } else {
-                None
-            };
-
-            results.push(GitCommitDiff {
-                path: normalized_path,
-                status: status_for_delta(delta.status()).to_string(),
-                diff: String::new(),
-                old_lines: None,
-                new_lines: None,
-                is_binary: true,
-                is_image: true,
-                old_image_data,
-                new_image_data,
-                old_image_mime: old_image_mime.map(str::to_string),
-                new_image_mime: new_image_mime.map(str::to_string),
-            });
-            continue;
-        }
+#[tauri::command]
+pub(crate) async fn get_commit_notes(
+    workspace_id: String,
+    sha: String,
+    notes_ref: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Option<String>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_commit_notes",
+            json!({ "workspaceId": workspace_id, "sha": sha, "notesRef": notes_ref }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_commit_notes_core(&state.workspaces, workspace_id, sha, notes_ref).await
+}
 
-        let patch = match git2::Patch::from_diff(&diff, index) {
-            Ok(patch) => patch,
-            Err(_) => continue,
-        };
-        let Some(mut patch) = patch else {
-            continue;
-        };
-        let content = match diff_patch_to_string(&mut patch) {
-            Ok(content) => content,
-            Err(_) => continue,
-        };
-        if content.trim().is_empty() {
-            continue;
-        }
-        results.push(GitCommitDiff {
-            path: normalized_path,
-            status: status_for_delta(delta.status()).to_string(),
-            diff: content,
-            old_lines,
-            new_lines,
-            is_binary: false,
-            is_image: false,
-            old_image_data: None,
-            new_image_data: None,
-            old_image_mime: None,
-            new_image_mime: None,
-        });
+#[tauri::command]
+pub(crate) async fn get_repo_stats(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RepoStats, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_repo_stats",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
+    get_repo_stats_core(&state.workspaces, workspace_id).await
+}
 
-    Ok(results)
+#[tauri::command]
+pub(crate) async fn repair_repo(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RepoRepairReport, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "repair_repo",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    repair_repo_core(&state.workspaces, workspace_id).await
 }
 
+/// Compacts the repository, sending `git gc` progress lines over
+/// `on_progress` (local mode only).
 #[tauri::command]
-pub(crate) async fn get_git_remote(
+pub(crate) async fn gc_repo(
     workspace_id: String,
+    aggressive: Option<bool>,
+    on_progress: Channel<String>,
     state: State<'_, AppState>,
-) -> Result<Option<String>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+    app: AppHandle,
+) -> Result<GcReport, String> {
+    let aggressive = aggressive.unwrap_or(false);
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "gc_repo",
+            json!({ "workspaceId": workspace_id, "aggressive": aggressive }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    gc_repo_core(&state.workspaces, workspace_id, aggressive, |line| {
+        let _ = on_progress.send(line.to_string());
+    })
+    .await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let remotes = repo.remotes().map_err(|e| e.to_string())?;
-    let name = if remotes.iter().any(|remote| remote == Some("origin")) {
-        "origin".to_string()
-    } else {
-        remotes
-            .iter()
-            .flatten()
-            .next()
-            .unwrap_or("")
-            .to_string()
-    };
-    if name.is_empty() {
-        return Ok(None);
+#[tauri::command]
+pub(crate) async fn build_permalink(
+    workspace_id: String,
+    path: String,
+    line: u32,
+    rev: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "build_permalink",
+            json!({ "workspaceId": workspace_id, "path": path, "line": line, "rev": rev }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
-    let remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
-    Ok(remote.url().map(|url| url.to_string()))
+    build_permalink_core(&state.workspaces, workspace_id, path, line, rev).await
 }
 
 #[tauri::command]
-pub(crate) async fn get_github_issues(
+pub(crate) async fn get_git_file_diff(
     workspace_id: String,
+    path: String,
+    whitespace_check: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<GitHubIssuesResponse, String> {
+) -> Result<Option<GitFileDiff>, String> {
     let workspaces = state.workspaces.lock().await;
     let entry = workspaces
         .get(&workspace_id)
         .ok_or("workspace not found")?
         .clone();
+    drop(workspaces);
 
     let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let output = tokio_command("gh")
-        .args([
-            "issue",
-            "list",
-            "--repo",
-            &repo_name,
-            "--limit",
-            "50",
-            "--json",
-            "number,title,url,updatedAt",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
+    let exclude_matcher =
+        build_diff_exclude_matcher(&repo_root, &entry.settings.diff_exclude_globs)?;
+    let (whitespace, context_lines, normalize_line_endings) = {
+        let settings = state.app_settings.lock().await;
+        (
+            settings.git_diff_whitespace,
+            settings.diff_context_lines,
+            settings.git_diff_normalize_line_endings,
+        )
+    };
+    let path = normalize_git_path(path.trim());
+    if path.is_empty() {
+        return Err("path is required".to_string());
     }
+    spawn_git_blocking(move || {
+        let diffs = collect_git_file_diffs(
+            &repo_root,
+            exclude_matcher.as_ref(),
+            whitespace,
+            context_lines,
+            normalize_line_endings,
+            whitespace_check.unwrap_or(false),
+            Some(&path),
+        )?;
+        Ok(diffs.into_iter().find(|diff| diff.path == path))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    let issues: Vec<GitHubIssue> =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+#[tauri::command]
+pub(crate) async fn get_git_log(
+    workspace_id: String,
+    limit: Option<usize>,
+    pathspec: Option<Vec<String>>,
+    detail: Option<bool>,
+    verify: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitLogResponse, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_log",
+            json!({
+                "workspaceId": workspace_id,
+                "limit": limit,
+                "pathspec": pathspec,
+                "detail": detail,
+                "verify": verify,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_git_log_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        limit,
+        pathspec,
+        detail,
+        verify,
+    )
+    .await
+}
 
-    let search_query = format!("repo:{repo_name} is:issue is:open");
-    let search_query = search_query.replace(' ', "+");
-    let total = match tokio_command("gh")
-        .args([
-            "api",
-            &format!("/search/issues?q={search_query}"),
-            "--jq",
-            ".total_count",
-        ])
-        .current_dir(&repo_root)
-        .output()
+#[tauri::command]
+pub(crate) async fn get_ref_comparison_diff(
+    workspace_id: String,
+    base: String,
+    head: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_ref_comparison_diff",
+            json!({ "workspaceId": workspace_id, "base": base, "head": head }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_ref_comparison_diff_core(&state.workspaces, &state.app_settings, workspace_id, base, head)
         .await
-    {
-        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .parse::<usize>()
-            .unwrap_or(issues.len()),
-        _ => issues.len(),
-    };
-
-    Ok(GitHubIssuesResponse { total, issues })
 }
 
+/// Ahead/behind counts of HEAD against an arbitrary ref, independent of the
+/// branch's tracking configuration.
 #[tauri::command]
-pub(crate) async fn get_github_pull_requests(
+pub(crate) async fn get_ahead_behind_against(
     workspace_id: String,
+    other_ref: String,
+    limit: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<GitHubPullRequestsResponse, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+    app: AppHandle,
+) -> Result<GitAheadBehind, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_ahead_behind_against",
+            json!({ "workspaceId": workspace_id, "otherRef": other_ref, "limit": limit }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_ahead_behind_against_core(&state.workspaces, workspace_id, other_ref, limit).await
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let output = tokio_command("gh")
-        .args([
-            "pr",
-            "list",
-            "--repo",
-            &repo_name,
-            "--state",
-            "open",
-            "--limit",
-            "50",
-            "--json",
-            "number,title,url,updatedAt,createdAt,body,headRefName,baseRefName,isDraft,author",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
+/// Commit metadata for a branch, tag, remote ref or revision expression.
+#[tauri::command]
+pub(crate) async fn resolve_ref(
+    workspace_id: String,
+    ref_name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ResolvedRef, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "resolve_ref",
+            json!({ "workspaceId": workspace_id, "refName": ref_name }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
+    resolve_ref_core(&state.workspaces, workspace_id, ref_name).await
+}
 
-    let pull_requests: Vec<GitHubPullRequest> =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+/// Changed files between `base_ref` and the working tree, with line counts
+/// but no patch text.
+#[tauri::command]
+pub(crate) async fn list_changed_paths_against_ref(
+    workspace_id: String,
+    base_ref: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<GitFileStatus>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "list_changed_paths_against_ref",
+            json!({ "workspaceId": workspace_id, "baseRef": base_ref }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    list_changed_paths_against_ref_core(&state.workspaces, workspace_id, base_ref).await
+}
 
-    let search_query = format!("repo:{repo_name} is:pr is:open");
-    let search_query = search_query.replace(' ', "+");
-    let total = match tokio_command("gh")
-        .args([
-            "api",
-            &format!("/search/issues?q={search_query}"),
-            "--jq",
-            ".total_count",
-        ])
-        .current_dir(&repo_root)
-        .output()
+#[tauri::command]
+pub(crate) async fn get_git_commit_diff(
+    workspace_id: String,
+    sha: String,
+    full_file: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<GitCommitDiff>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_commit_diff",
+            json!({ "workspaceId": workspace_id, "sha": sha, "fullFile": full_file }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_git_commit_diff_core(&state.workspaces, &state.app_settings, workspace_id, sha, full_file)
         .await
-    {
-        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .parse::<usize>()
-            .unwrap_or(pull_requests.len()),
-        _ => pull_requests.len(),
-    };
-
-    Ok(GitHubPullRequestsResponse {
-        total,
-        pull_requests,
-    })
 }
 
 #[tauri::command]
-pub(crate) async fn get_github_pull_request_diff(
+pub(crate) async fn show_stash_diff(
     workspace_id: String,
-    pr_number: u64,
+    index: usize,
     state: State<'_, AppState>,
-) -> Result<Vec<GitHubPullRequestDiff>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-
-    let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let output = tokio_command("gh")
-        .args([
-            "pr",
-            "diff",
-            &pr_number.to_string(),
-            "--repo",
-            &repo_name,
-            "--color",
-            "never",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
+    app: AppHandle,
+) -> Result<Vec<GitCommitDiff>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "show_stash_diff",
+            json!({ "workspaceId": workspace_id, "index": index }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
-
-    let diff_text = String::from_utf8_lossy(&output.stdout);
-    Ok(parse_pr_diff(&diff_text))
+    show_stash_diff_core(&state.workspaces, &state.app_settings, workspace_id, index).await
 }
 
 #[tauri::command]
-pub(crate) async fn get_github_pull_request_comments(
+pub(crate) async fn stash_apply_git(
     workspace_id: String,
-    pr_number: u64,
+    index: usize,
     state: State<'_, AppState>,
-) -> Result<Vec<GitHubPullRequestComment>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-
-    let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let comments_endpoint =
-        format!("/repos/{repo_name}/issues/{pr_number}/comments?per_page=30");
-    let jq_filter = r#"[.[] | {id, body, createdAt: .created_at, url: .html_url, author: (if .user then {login: .user.login} else null end)}]"#;
-
-    let output = tokio_command("gh")
-        .args(["api", &comments_endpoint, "--jq", jq_filter])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "stash_apply_git",
+            json!({ "workspaceId": workspace_id, "index": index }),
+        )
+        .await?;
+        return Ok(());
     }
+    stash_apply_git_core(&state.workspaces, workspace_id, index).await
+}
 
-    let comments: Vec<GitHubPullRequestComment> =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+#[tauri::command]
+pub(crate) async fn diagnose_git() -> Result<serde_json::Value, String> {
+    Ok(diagnose_git_core().await)
+}
 
-    Ok(comments)
+#[tauri::command]
+pub(crate) async fn diagnose_github() -> Result<serde_json::Value, String> {
+    Ok(diagnose_github_core().await)
 }
 
 #[tauri::command]
-pub(crate) async fn list_git_branches(
+pub(crate) async fn read_git_config(
     workspace_id: String,
+    keys: Vec<String>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<serde_json::Value, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let mut branches = Vec::new();
-    let refs = repo
-        .branches(Some(BranchType::Local))
-        .map_err(|e| e.to_string())?;
-    for branch_result in refs {
-        let (branch, _) = branch_result.map_err(|e| e.to_string())?;
-        let name = branch.name().ok().flatten().unwrap_or("").to_string();
-        if name.is_empty() {
-            continue;
-        }
-        let last_commit = branch
-            .get()
-            .target()
-            .and_then(|oid| repo.find_commit(oid).ok())
-            .map(|commit| commit.time().seconds())
-            .unwrap_or(0);
-        branches.push(BranchInfo { name, last_commit });
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "read_git_config",
+            json!({ "workspaceId": workspace_id, "keys": keys }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
-    branches.sort_by(|a, b| b.last_commit.cmp(&a.last_commit));
-    Ok(json!({ "branches": branches }))
+    read_git_config_core(&state.workspaces, workspace_id, keys).await
 }
 
 #[tauri::command]
-pub(crate) async fn checkout_git_branch(
+pub(crate) async fn write_git_config(
     workspace_id: String,
-    name: String,
+    key: String,
+    value: String,
+    scope: ConfigScope,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    checkout_branch(&repo, &name).map_err(|e| e.to_string())
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "write_git_config",
+            json!({ "workspaceId": workspace_id, "key": key, "value": value, "scope": scope }),
+        )
+        .await?;
+        return Ok(());
+    }
+    write_git_config_core(&state.workspaces, workspace_id, key, value, scope).await
 }
 
 #[tauri::command]
-pub(crate) async fn create_git_branch(
+pub(crate) async fn bisect_start(
     workspace_id: String,
-    name: String,
+    bad: String,
+    good: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let head = repo.head().map_err(|e| e.to_string())?;
-    let target = head.peel_to_commit().map_err(|e| e.to_string())?;
-    repo.branch(&name, &target, false)
-        .map_err(|e| e.to_string())?;
-    checkout_branch(&repo, &name).map_err(|e| e.to_string())
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "bisect_start",
+            json!({ "workspaceId": workspace_id, "bad": bad, "good": good }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    bisect_start_core(&state.workspaces, workspace_id, bad, good).await
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::path::Path;
-
-    fn create_temp_repo() -> (PathBuf, Repository) {
-        let root = std::env::temp_dir().join(format!(
-            "codex-monitor-test-{}",
-            uuid::Uuid::new_v4()
-        ));
-        fs::create_dir_all(&root).expect("create temp repo root");
-        let repo = Repository::init(&root).expect("init repo");
-        (root, repo)
+#[tauri::command]
+pub(crate) async fn bisect_mark(
+    workspace_id: String,
+    result: BisectResult,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "bisect_mark",
+            json!({ "workspaceId": workspace_id, "result": result }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
+    bisect_mark_core(&state.workspaces, workspace_id, result).await
+}
 
-    #[test]
-    fn collect_workspace_diff_prefers_staged_changes() {
-        let (root, repo) = create_temp_repo();
-        let file_path = root.join("staged.txt");
-        fs::write(&file_path, "staged\n").expect("write staged file");
-        let mut index = repo.index().expect("index");
-        index.add_path(Path::new("staged.txt")).expect("add path");
-        index.write().expect("write index");
+#[tauri::command]
+pub(crate) async fn bisect_reset(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "bisect_reset",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    bisect_reset_core(&state.workspaces, workspace_id).await
+}
 
-        let diff = collect_workspace_diff(&root).expect("collect diff");
-        assert!(diff.contains("staged.txt"));
-        assert!(diff.contains("staged"));
+#[tauri::command]
+pub(crate) async fn get_repo_operation_state(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RepoOperation, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_repo_operation_state",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
+    get_repo_operation_state_core(&state.workspaces, workspace_id).await
+}
 
-    #[test]
-    fn collect_workspace_diff_falls_back_to_workdir() {
-        let (root, _repo) = create_temp_repo();
-        let file_path = root.join("unstaged.txt");
-        fs::write(&file_path, "unstaged\n").expect("write unstaged file");
+#[tauri::command]
+pub(crate) async fn operation_continue(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RepoOperation, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "operation_continue",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    run_operation_action_core(&state.workspaces, workspace_id, OperationAction::Continue).await
+}
 
-        let diff = collect_workspace_diff(&root).expect("collect diff");
-        assert!(diff.contains("unstaged.txt"));
-        assert!(diff.contains("unstaged"));
+#[tauri::command]
+pub(crate) async fn operation_skip(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RepoOperation, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "operation_skip",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
     }
+    run_operation_action_core(&state.workspaces, workspace_id, OperationAction::Skip).await
+}
 
-    #[test]
-    fn action_paths_for_file_expands_renames() {
-        let (root, repo) = create_temp_repo();
-        fs::write(root.join("a.txt"), "hello\n").expect("write file");
+#[tauri::command]
+pub(crate) async fn operation_abort(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RepoOperation, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "operation_abort",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    run_operation_action_core(&state.workspaces, workspace_id, OperationAction::Abort).await
+}
 
-        let mut index = repo.index().expect("repo index");
-        index
-            .add_path(Path::new("a.txt"))
-            .expect("add path");
-        let tree_id = index.write_tree().expect("write tree");
-        let tree = repo.find_tree(tree_id).expect("find tree");
-        let sig =
-            git2::Signature::now("Test", "test@example.com").expect("signature");
-        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
-            .expect("commit");
+#[tauri::command]
+pub(crate) async fn get_git_remote(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Option<String>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_remote",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_git_remote_core(&state.workspaces, workspace_id).await
+}
 
-        fs::rename(root.join("a.txt"), root.join("b.txt")).expect("rename file");
+#[tauri::command]
+pub(crate) async fn get_github_issues(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitHubIssuesResponse, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_github_issues",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_github_issues_core(&state.workspaces, &state.app_settings, workspace_id).await
+}
 
-        // Stage the rename so libgit2 reports it as an INDEX_RENAMED entry.
-        let mut index = repo.index().expect("repo index");
-        index
-            .remove_path(Path::new("a.txt"))
-            .expect("remove old path");
-        index
-            .add_path(Path::new("b.txt"))
-            .expect("add new path");
-        index.write().expect("write index");
+#[tauri::command]
+pub(crate) async fn get_github_pull_requests(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitHubPullRequestsResponse, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_github_pull_requests",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_github_pull_requests_core(&state.workspaces, &state.app_settings, workspace_id).await
+}
+
+/// Full metadata for one PR by number, including mergeability, review
+/// decision and CI checks, regardless of whether it falls in the list window.
+#[tauri::command]
+pub(crate) async fn get_github_pull_request(
+    workspace_id: String,
+    pr_number: u64,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitHubPullRequest, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_github_pull_request",
+            json!({ "workspaceId": workspace_id, "prNumber": pr_number }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_github_pull_request_core(&state.workspaces, &state.app_settings, workspace_id, pr_number)
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn get_github_pull_request_diff(
+    workspace_id: String,
+    pr_number: u64,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<GitHubPullRequestDiff>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_github_pull_request_diff",
+            json!({ "workspaceId": workspace_id, "prNumber": pr_number }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_github_pull_request_diff_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        pr_number,
+    )
+    .await
+}
+
+/// Unparsed variant of `get_github_pull_request_diff`, for external viewers
+/// or applying the patch locally.
+#[tauri::command]
+pub(crate) async fn get_github_pull_request_raw_diff(
+    workspace_id: String,
+    pr_number: u64,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_github_pull_request_raw_diff",
+            json!({ "workspaceId": workspace_id, "prNumber": pr_number }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_github_pull_request_raw_diff_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        pr_number,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn get_github_pull_request_comments(
+    workspace_id: String,
+    pr_number: u64,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<GitHubPullRequestComment>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_github_pull_request_comments",
+            json!({ "workspaceId": workspace_id, "prNumber": pr_number }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_github_pull_request_comments_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        pr_number,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn list_git_branches(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "list_git_branches",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    list_git_branches_core(&state.workspaces, &state.app_settings, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn list_recent_branches(
+    workspace_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<RecentBranch>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "list_recent_branches",
+            json!({ "workspaceId": workspace_id, "limit": limit }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    list_recent_branches_core(&state.workspaces, workspace_id, limit).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_default_branch(
+    workspace_id: String,
+    refresh: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<DefaultBranch, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_default_branch",
+            json!({ "workspaceId": workspace_id, "refresh": refresh }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    get_default_branch_core(
+        &state.workspaces,
+        &state.default_branch_cache,
+        workspace_id,
+        refresh.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn list_remote_branches(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "list_remote_branches",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    list_remote_branches_core(&state.workspaces, workspace_id).await
+}
+
+/// Runs `git remote prune <remote>` and reports the remote-tracking branches
+/// it removed.
+#[tauri::command]
+pub(crate) async fn prune_remote_branches(
+    workspace_id: String,
+    remote: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<String>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "prune_remote_branches",
+            json!({ "workspaceId": workspace_id, "remote": remote }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    prune_remote_branches_core(&state.workspaces, workspace_id, remote).await
+}
+
+#[tauri::command]
+pub(crate) async fn checkout_git_branch(
+    workspace_id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "checkout_git_branch",
+            json!({ "workspaceId": workspace_id, "name": name }),
+        )
+        .await?;
+        return Ok(());
+    }
+    checkout_git_branch_core(&state.workspaces, &state.git_undo_journal, workspace_id, name).await
+}
+
+/// Inspects a specific commit in detached-HEAD state. The result reports the
+/// detached state and the branch that was left, so the UI can point the way
+/// back.
+#[tauri::command]
+pub(crate) async fn checkout_commit(
+    workspace_id: String,
+    sha: String,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<CheckoutCommitResult, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "checkout_commit",
+            json!({ "workspaceId": workspace_id, "sha": sha, "force": force }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    checkout_commit_core(
+        &state.workspaces,
+        &state.git_undo_journal,
+        workspace_id,
+        sha,
+        force.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_undo_stack(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<GitUndoEntry>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "get_git_undo_stack",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    Ok(git_undo_core::get_git_undo_stack_core(&state.git_undo_journal, &workspace_id).await)
+}
+
+/// Restores the state recorded before the most recent journaled action.
+/// Undo can itself discard work (`reset --hard`), so callers must confirm.
+#[tauri::command]
+pub(crate) async fn undo_last_git_action(
+    workspace_id: String,
+    confirm: bool,
+    override_protection: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitUndoEntry, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "undo_last_git_action",
+            json!({
+                "workspaceId": workspace_id,
+                "confirm": confirm,
+                "overrideProtection": override_protection,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    git_undo_core::undo_last_git_action_core(
+        &state.workspaces,
+        &state.git_undo_journal,
+        workspace_id,
+        confirm,
+        override_protection,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_branch_at_head(
+    workspace_id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "create_branch_at_head",
+            json!({ "workspaceId": workspace_id, "name": name }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    create_branch_at_head_core(&state.workspaces, workspace_id, name).await
+}
+
+#[tauri::command]
+pub(crate) async fn move_changes_to_new_branch(
+    workspace_id: String,
+    name: String,
+    start_point: Option<String>,
+    stash: Option<bool>,
+    override_protection: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "move_changes_to_new_branch",
+            json!({
+                "workspaceId": workspace_id,
+                "name": name,
+                "startPoint": start_point,
+                "stash": stash,
+                "overrideProtection": override_protection,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    move_changes_to_new_branch_core(
+        &state.workspaces,
+        &state.git_undo_journal,
+        workspace_id,
+        name,
+        start_point,
+        stash,
+        override_protection,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_git_branch(
+    workspace_id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "create_git_branch",
+            json!({ "workspaceId": workspace_id, "name": name }),
+        )
+        .await?;
+        return Ok(());
+    }
+    create_git_branch_core(&state.workspaces, workspace_id, name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::git_ui_core::{
+        action_paths_for_file, ahead_behind_against_in_repo, any_path_has_staged_changes,
+        append_co_author_trailers, branches_in_repo, changed_paths_against_ref,
+        checkout_commit_in_repo, classify_push_error, classify_remote_push_error,
+        clear_stale_index_lock, commit_notes_in_repo, commit_selected_paths_in_repo,
+        commit_touches_pathspec, compare_refs_in_repo, compose_commit_message,
+        conflict_versions_in_repo, create_branch_at_head_in_repo, default_branch_in_repo,
+        drain_progress_lines, git_status_in_repo, log_entry, mark_entries_with_notes,
+        merge_conflict_paths, move_changes_to_new_branch_in_repo, operation_action_args,
+        parse_bisect_output, parse_check_ignore_verbose, parse_signature_records,
+        read_git_config_values, recent_branches_in_repo, refspec_destination_prefix,
+        remote_branch_names, repo_operation_state, repo_stats_in_repo, reset_head_author_in_repo,
+        reset_head_date_in_repo, resolve_bisect_bounds, resolve_conflict_with_content_in_repo,
+        resolve_ref_in_repo, restore_file_from_rev_in_repo, reword_head_commit_in_repo,
+        signature_status_from_code, stash_diff_in_repo, subject_length_warning,
+        submodule_commits_between, summarize_changes, validate_git_config_key, validate_git_root,
+        validate_pathspecs, visit_git_file_diffs, walk_log_entries, working_tree_cleanliness,
+        write_git_config_value, WhitespaceRules, PRE_PUSH_HOOK_FAILED_PREFIX,
+        REMOTE_NOT_FOUND_PREFIX, REMOTE_PERMISSION_DENIED_PREFIX,
+    };
+    use crate::types::{
+        AppSettings, DiffWhitespace, DiffWhitespaceError, GitSignatureStatus, GitUndoKind, RefType,
+        WorkspaceEntry, WorkspaceKind, WorkspaceSettings,
+    };
+    use crate::git_utils::{checkout_branch, commit_to_entry};
+    use crate::shared::github_core::{
+        forget_gh_authenticated_host, gh_error_is_auth_failure, gh_error_is_rate_limited,
+        gh_error_is_transient, gh_rate_limit_wait, gh_retry_delay, github_repo_from_path,
+        parse_pr_diff, GH_AUTHENTICATED_HOSTS, GH_RETRY_MAX_DELAY,
+    };
+    use git2::BranchType;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::Mutex;
+    use std::time::Duration;
+
+    fn create_temp_repo() -> (PathBuf, Repository) {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-test-{}",
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&root).expect("create temp repo root");
+        let repo = Repository::init(&root).expect("init repo");
+        (root, repo)
+    }
+
+    /// Writes `content` to `path`, stages it and commits it on HEAD.
+    fn commit_file(
+        repo: &Repository,
+        path: &str,
+        content: impl AsRef<[u8]>,
+        message: &str,
+    ) -> git2::Oid {
+        let full = repo.workdir().expect("workdir").join(path);
+        if let Some(parent) = full.parent() {
+            fs::create_dir_all(parent).expect("create dir");
+        }
+        fs::write(&full, content).expect("write file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new(path)).expect("add path");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("write tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .expect("commit")
+    }
+
+    /// Points HEAD at `branch` (created at `start` when given) and resets the
+    /// index and working tree to it.
+    fn switch_branch(repo: &Repository, branch: &str, start: Option<git2::Oid>) {
+        if let Some(start) = start {
+            let commit = repo.find_commit(start).expect("start commit");
+            repo.branch(branch, &commit, false).expect("create branch");
+        }
+        repo.set_head(&format!("refs/heads/{branch}")).expect("set head");
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .expect("checkout head");
+    }
+
+    fn workspace_entry(id: &str, path: &Path) -> WorkspaceEntry {
+        WorkspaceEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string_lossy().to_string(),
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        }
+    }
+
+    /// A workspace map holding a single `w1` workspace at `root`.
+    fn workspaces_with(root: &Path) -> Mutex<HashMap<String, WorkspaceEntry>> {
+        Mutex::new(HashMap::from([(
+            "w1".to_string(),
+            workspace_entry("w1", root),
+        )]))
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("create runtime")
+            .block_on(future)
+    }
+
+    #[test]
+    fn ahead_behind_against_counts_both_sides() {
+        let (_root, repo) = create_temp_repo();
+        let base = commit_file(&repo, "base.txt", "base", "base.txt");
+        switch_branch(&repo, "other", Some(base));
+        commit_file(&repo, "other.txt", "other", "other.txt");
+        switch_branch(&repo, "mine", Some(base));
+        commit_file(&repo, "mine-1.txt", "mine", "mine-1.txt");
+        commit_file(&repo, "mine-2.txt", "mine", "mine-2.txt");
+
+        let result = ahead_behind_against_in_repo(&repo, "other", 40).expect("ahead/behind");
+        assert_eq!(result.ahead, 2);
+        assert_eq!(result.behind, 1);
+        assert_eq!(result.ahead_entries.len(), 2);
+        assert_eq!(result.behind_entries[0].summary, "other.txt");
+
+        let limited = ahead_behind_against_in_repo(&repo, "other", 1).expect("limited");
+        assert_eq!(limited.ahead, 2);
+        assert_eq!(limited.ahead_entries.len(), 1);
+        assert!(ahead_behind_against_in_repo(&repo, "missing", 40).is_err());
+    }
+
+    #[test]
+    fn classify_push_error_flags_hook_failures_only() {
+        let hook_output = "lint failed\nerror: failed to push some refs to 'origin'".to_string();
+        assert!(classify_push_error(hook_output.clone(), true)
+            .starts_with(PRE_PUSH_HOOK_FAILED_PREFIX));
+        assert_eq!(classify_push_error(hook_output.clone(), false), hook_output);
+
+        let rejected = " ! [rejected] main -> main (non-fast-forward)\n\
+            error: failed to push some refs to 'origin'"
+            .to_string();
+        assert_eq!(classify_push_error(rejected.clone(), true), rejected);
+    }
+
+    #[test]
+    fn pre_push_hook_installed_checks_hooks_dir() {
+        let (root, repo) = create_temp_repo();
+        assert!(!pre_push_hook_installed(&root));
+        let hooks_dir = repo.path().join("hooks");
+        fs::create_dir_all(&hooks_dir).expect("create hooks dir");
+        fs::write(hooks_dir.join("pre-push"), "#!/bin/sh\nexit 1\n").expect("write hook");
+        assert!(pre_push_hook_installed(&root));
+    }
+
+    #[test]
+    fn changed_paths_against_ref_counts_lines_without_patches() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&repo, "kept.txt", "a\nb\n", "add kept");
+        commit_file(&repo, "gone.txt", "x\n", "add gone");
+
+        fs::write(root.join("kept.txt"), "a\nB\nc\n").expect("modify kept");
+        fs::remove_file(root.join("gone.txt")).expect("remove gone");
+        fs::write(root.join("new.txt"), "1\n2\n").expect("write new");
+
+        let mut changed = changed_paths_against_ref(&root, "HEAD", None).expect("changed paths");
+        changed.sort_by(|a, b| a.path.cmp(&b.path));
+        let summary: Vec<(&str, &str, i64, i64)> = changed
+            .iter()
+            .map(|file| (file.path.as_str(), file.status.as_str(), file.additions, file.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("gone.txt", "D", 0, 1),
+                ("kept.txt", "M", 2, 1),
+                ("new.txt", "A", 2, 0),
+            ]
+        );
+        assert!(changed_paths_against_ref(&root, "no-such-ref", None).is_err());
+    }
+
+    #[test]
+    fn submodule_commits_between_lists_new_commits_newest_first() {
+        let (root, repo) = create_temp_repo();
+        let sub_root = root.join("vendor/lib");
+        fs::create_dir_all(&sub_root).expect("create submodule dir");
+        let sub = Repository::init(&sub_root).expect("init submodule");
+        let shas: Vec<git2::Oid> = ["one", "two", "three"]
+            .into_iter()
+            .map(|message| commit_file(&sub, "lib.txt", message, message))
+            .collect();
+
+        let commits =
+            submodule_commits_between(&repo, Path::new("vendor/lib"), shas[0], shas[2])
+                .expect("submodule commits");
+        let summaries: Vec<&str> = commits.iter().map(|entry| entry.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["three", "two"]);
+
+        assert!(submodule_commits_between(
+            &repo,
+            Path::new("missing"),
+            shas[0],
+            shas[2]
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn parse_check_ignore_verbose_reports_rule_and_negation() {
+        let explanation = parse_check_ignore_verbose(".gitignore\u{0}3\u{0}*.log\u{0}debug.log\u{0}");
+        assert!(explanation.ignored);
+        assert_eq!(explanation.source.as_deref(), Some(".gitignore"));
+        assert_eq!(explanation.line, Some(3));
+        assert_eq!(explanation.pattern.as_deref(), Some("*.log"));
+
+        let negated =
+            parse_check_ignore_verbose("logs/.gitignore\u{0}1\u{0}!keep.log\u{0}logs/keep.log\u{0}");
+        assert!(!negated.ignored);
+        assert_eq!(negated.pattern.as_deref(), Some("!keep.log"));
+
+        let none = parse_check_ignore_verbose("");
+        assert!(!none.ignored);
+        assert!(none.source.is_none());
+    }
+
+    #[test]
+    fn repo_stats_counts_history_refs_and_tracked_files() {
+        let (root, repo) = create_temp_repo();
+        let empty = repo_stats_in_repo(&root).expect("stats on unborn repo");
+        assert_eq!(empty.commit_count, 0);
+        assert_eq!(empty.tracked_files, 0);
+
+        commit_file(&repo, "a.txt", "alpha\n", "a.txt");
+        let head = commit_file(&repo, "b.txt", "beta!\n", "b.txt");
+        let head = repo.find_commit(head).expect("head commit");
+        repo.branch("feature", &head, false).expect("branch");
+        repo.tag_lightweight("v1", head.as_object(), false).expect("tag");
+        fs::write(root.join("untracked.txt"), "ignored by stats").expect("write untracked");
+
+        let stats = repo_stats_in_repo(&root).expect("stats");
+        assert_eq!(stats.commit_count, 2);
+        assert_eq!(stats.branch_count, 2);
+        assert_eq!(stats.remote_branch_count, 0);
+        assert_eq!(stats.tag_count, 1);
+        assert_eq!(stats.tracked_files, 2);
+        assert_eq!(stats.worktree_size, 12);
+        assert!(stats.git_dir_size > 0);
+    }
+
+    #[test]
+    fn stash_diff_includes_tracked_and_untracked_changes() {
+        let (root, mut repo) = create_temp_repo();
+        commit_file(&repo, "tracked.txt", "one\n", "init");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+
+        fs::write(root.join("tracked.txt"), "one\ntwo\n").expect("modify tracked");
+        fs::write(root.join("new.txt"), "fresh\n").expect("write untracked");
+        repo.stash_save(&sig, "wip", Some(git2::StashFlags::INCLUDE_UNTRACKED))
+            .expect("stash");
+        assert!(!root.join("new.txt").exists());
+
+        let diffs = stash_diff_in_repo(&repo, 0, DiffWhitespace::None, 3).expect("stash diff");
+        let paths: Vec<(&str, &str)> = diffs
+            .iter()
+            .map(|diff| (diff.path.as_str(), diff.status.as_str()))
+            .collect();
+        assert_eq!(paths, vec![("tracked.txt", "M"), ("new.txt", "A")]);
+        assert!(diffs[0].diff.contains("+two"));
+
+        assert!(stash_diff_in_repo(&repo, 1, DiffWhitespace::None, 3).is_err());
+    }
+
+    #[test]
+    fn clear_stale_index_lock_only_removes_old_unowned_locks() {
+        let (_root, repo) = create_temp_repo();
+        let git_dir = repo.path().to_path_buf();
+        let lock = git_dir.join("index.lock");
+
+        let report = clear_stale_index_lock(&git_dir, Some(false));
+        assert!(!report.found);
+
+        fs::write(&lock, "").expect("write lock");
+        let report = clear_stale_index_lock(&git_dir, Some(false));
+        assert!(report.found && !report.removed);
+        assert!(lock.exists());
+
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(600);
+        fs::File::options()
+            .write(true)
+            .open(&lock)
+            .and_then(|file| file.set_modified(old))
+            .expect("age lock");
+        for git_running in [Some(true), None] {
+            let report = clear_stale_index_lock(&git_dir, git_running);
+            assert!(report.found && !report.removed);
+            assert!(report.note.is_some());
+            assert!(lock.exists());
+        }
+
+        let report = clear_stale_index_lock(&git_dir, Some(false));
+        assert!(report.removed);
+        assert!(report.age_secs.unwrap_or_default() >= 600);
+        assert!(!lock.exists());
+    }
+
+    #[test]
+    fn collect_workspace_diff_prefers_staged_changes() {
+        let (root, repo) = create_temp_repo();
+        let file_path = root.join("staged.txt");
+        fs::write(&file_path, "staged\n").expect("write staged file");
+        let mut index = repo.index().expect("index");
+        index.add_path(Path::new("staged.txt")).expect("add path");
+        index.write().expect("write index");
+
+        let diff = collect_workspace_diff(&root).expect("collect diff");
+        assert!(diff.contains("staged.txt"));
+        assert!(diff.contains("staged"));
+    }
+
+    #[test]
+    fn collect_workspace_diff_falls_back_to_workdir() {
+        let (root, _repo) = create_temp_repo();
+        let file_path = root.join("unstaged.txt");
+        fs::write(&file_path, "unstaged\n").expect("write unstaged file");
+
+        let diff = collect_workspace_diff(&root).expect("collect diff");
+        assert!(diff.contains("unstaged.txt"));
+        assert!(diff.contains("unstaged"));
+    }
+
+    #[test]
+    fn action_paths_for_file_expands_renames() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&repo, "a.txt", "hello\n", "init");
+
+        fs::rename(root.join("a.txt"), root.join("b.txt")).expect("rename file");
+
+        // Stage the rename so libgit2 reports it as an INDEX_RENAMED entry.
+        let mut index = repo.index().expect("repo index");
+        index
+            .remove_path(Path::new("a.txt"))
+            .expect("remove old path");
+        index
+            .add_path(Path::new("b.txt"))
+            .expect("add new path");
+        index.write().expect("write index");
 
         let paths = action_paths_for_file(&root, "b.txt");
         assert_eq!(paths, vec!["a.txt".to_string(), "b.txt".to_string()]);
     }
+
+    #[test]
+    fn reword_head_commit_keeps_tree_and_updates_message() {
+        let (root, repo) = create_temp_repo();
+        let initial = commit_file(&repo, "a.txt", "hello\n", "inital");
+        let tree_id = repo.find_commit(initial).expect("commit").tree_id();
+
+        // A staged change must not leak into the reworded commit.
+        fs::write(root.join("b.txt"), "staged\n").expect("write staged file");
+        let mut index = repo.index().expect("repo index");
+        index
+            .add_path(Path::new("b.txt"))
+            .expect("add path");
+        index.write().expect("write index");
+
+        let sha = reword_head_commit_in_repo(&repo, "initial", false).expect("reword");
+        let head = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .expect("head commit");
+        assert_eq!(head.id().to_string(), sha);
+        assert_eq!(head.message(), Some("initial"));
+        assert_eq!(head.tree_id(), tree_id);
+        assert_eq!(head.parent_count(), 0);
+    }
+
+    #[test]
+    fn move_changes_to_new_branch_reports_conflicts_and_carries_changes_with_stash() {
+        let (root, mut repo) = create_temp_repo();
+        let base = commit_file(&repo, "a.txt", "1\n2\n3\n4\n5\n", "base");
+        let other = commit_file(&repo, "a.txt", "1\n2\n3\n4\n5-other\n", "other");
+        let base_sha = base.to_string();
+
+        let head_name = move_changes_to_new_branch_in_repo(&mut repo, "here", None, false)
+            .expect("branch at head");
+        assert_eq!(head_name, "here");
+        assert_eq!(repo.head().expect("head").target(), Some(other));
+
+        fs::write(root.join("a.txt"), "1-mine\n2\n3\n4\n5-other\n").expect("edit file");
+        fs::write(root.join("notes.txt"), "todo\n").expect("write untracked");
+        let err = move_changes_to_new_branch_in_repo(&mut repo, "fix", Some(&base_sha), false)
+            .expect_err("conflict without stash");
+        assert!(err.contains("Stash"));
+        assert!(repo.find_branch("fix", BranchType::Local).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).expect("read file"),
+            "1-mine\n2\n3\n4\n5-other\n"
+        );
+
+        fs::write(root.join("staged.txt"), "staged\n").expect("write staged file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("staged.txt")).expect("add path");
+        index.write().expect("write index");
+        let name = move_changes_to_new_branch_in_repo(&mut repo, "fix", Some(&base_sha), true)
+            .expect("move with stash");
+        assert_eq!(name, "fix");
+        let head = repo.head().expect("head");
+        assert_eq!(head.shorthand(), Some("fix"));
+        assert_eq!(head.target(), Some(base));
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).expect("read file"),
+            "1-mine\n2\n3\n4\n5\n"
+        );
+        assert!(root.join("notes.txt").exists());
+        let staged = repo.status_file(Path::new("staged.txt")).expect("staged status");
+        assert!(staged.contains(Status::INDEX_NEW));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn git_status_flags_large_files_only_when_threshold_set() {
+        let (root, repo) = create_temp_repo();
+        fs::write(root.join("big.bin"), vec![0u8; 2048]).expect("write big file");
+        fs::write(root.join("small.txt"), "hi\n").expect("write small file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("big.bin")).expect("add path");
+        index.write().expect("write index");
+
+        let status = git_status_in_repo(&root, None, GitUntrackedMode::All, None).expect("status");
+        assert!(status["files"]
+            .as_array()
+            .expect("files")
+            .iter()
+            .all(|file| file.get("isLarge").is_none()));
+
+        let status =
+            git_status_in_repo(&root, None, GitUntrackedMode::All, Some(1024)).expect("status");
+        let staged = status["stagedFiles"].as_array().expect("staged");
+        assert_eq!(staged[0]["path"], "big.bin");
+        assert_eq!(staged[0]["isLarge"], true);
+        assert_eq!(staged[0]["size"], 2048);
+        let small = status["files"]
+            .as_array()
+            .expect("files")
+            .iter()
+            .find(|file| file["path"] == "small.txt")
+            .expect("small file listed");
+        assert!(small.get("isLarge").is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn git_status_folders_mode_collapses_untracked_dirs() {
+        let (root, _repo) = create_temp_repo();
+        fs::create_dir_all(root.join("build/out")).expect("create dirs");
+        fs::write(root.join("build/out/a.o"), "x\n").expect("write a");
+        fs::write(root.join("build/out/b.o"), "x\n").expect("write b");
+
+        let mode: GitUntrackedMode = serde_json::from_value(json!("folders")).expect("mode");
+        assert_eq!(mode, GitUntrackedMode::Folders);
+        let status = git_status_in_repo(&root, None, mode, None).expect("status");
+        let files = status["files"].as_array().expect("files");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0]["path"], "build");
+        assert_eq!(files[0]["isDir"], true);
+
+        let status = git_status_in_repo(&root, None, GitUntrackedMode::All, None).expect("status");
+        assert_eq!(status["files"].as_array().expect("files").len(), 2);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn git_status_skips_diff_excluded_paths() {
+        let (root, _repo) = create_temp_repo();
+        fs::create_dir_all(root.join("web/node_modules/pkg")).expect("create dirs");
+        fs::write(root.join("web/node_modules/pkg/index.js"), "x\n").expect("write dep");
+        fs::write(root.join("web/app.generated.ts"), "x\n").expect("write generated");
+        fs::write(root.join("web/app.ts"), "x\n").expect("write source");
+        let globs = vec!["node_modules".to_string(), "*.generated.ts".to_string()];
+        let matcher = build_diff_exclude_matcher(&root, &globs).expect("matcher");
+
+        let status = git_status_in_repo(&root, matcher.as_ref(), GitUntrackedMode::All, None)
+            .expect("status");
+        let paths: Vec<&str> = status["files"]
+            .as_array()
+            .expect("files")
+            .iter()
+            .filter_map(|file| file["path"].as_str())
+            .collect();
+        assert_eq!(paths, vec!["web/app.ts"]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_pr_diff_keeps_binary_files_and_one_sided_renames() {
+        let diff = "diff --git a/docs/old name.md b/docs/new name.md\n\
+similarity index 100%\n\
+rename from docs/old name.md\n\
+diff --git a/logo.png b/logo.png\n\
+new file mode 100644\n\
+index 0000000..1234567\n\
+Binary files /dev/null and b/logo.png differ\n\
+diff --git a/src/main.rs b/src/main.rs\n\
+--- a/src/main.rs\n\
++++ b/src/main.rs\n\
+@@ -1 +1 @@\n\
+-a\n\
++b\n";
+        let entries = parse_pr_diff(diff);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, "docs/new name.md");
+        assert_eq!(entries[0].status, "R");
+        assert_eq!(entries[1].path, "logo.png");
+        assert_eq!(entries[1].status, "A");
+        assert!(entries[1].is_binary);
+        assert_eq!(entries[2].path, "src/main.rs");
+        assert!(!entries[2].is_binary);
+    }
+
+    #[test]
+    fn parse_signature_records_maps_status_codes() {
+        let output = "aaa\x1fG\x1fAlice <alice@example.com>\x1e\nbbb\x1fN\x1f\x1e\nccc\x1fE\x1f\x1e\nddd\x1fB\x1fMallory\x1e\n";
+        let records = parse_signature_records(output);
+        assert_eq!(
+            records["aaa"],
+            (GitSignatureStatus::Good, Some("Alice <alice@example.com>".to_string()))
+        );
+        assert_eq!(records["bbb"], (GitSignatureStatus::None, None));
+        assert_eq!(records["ccc"], (GitSignatureStatus::Unknown, None));
+        assert_eq!(records["ddd"].0, GitSignatureStatus::Bad);
+        assert_eq!(signature_status_from_code("U"), GitSignatureStatus::Untrusted);
+        assert_eq!(signature_status_from_code("Y"), GitSignatureStatus::Unknown);
+    }
+
+    #[test]
+    fn log_entry_detail_reports_body_and_stats_against_first_parent() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&repo, "a.txt", "one\ntwo\n", "init");
+        let oid = commit_file(
+            &repo,
+            "a.txt",
+            "one\nthree\nfour\n",
+            "Edit a\n\nLonger explanation.\n",
+        );
+        let commit = repo.find_commit(oid).expect("find commit");
+
+        let plain = log_entry(&repo, commit.clone(), false).expect("plain entry");
+        assert!(plain.body.is_none());
+        assert!(plain.files_changed.is_none());
+
+        let entry = log_entry(&repo, commit, true).expect("detail entry");
+        assert_eq!(entry.summary, "Edit a");
+        assert_eq!(entry.body.as_deref(), Some("Longer explanation."));
+        assert_eq!(entry.files_changed, Some(vec!["a.txt".to_string()]));
+        assert_eq!(entry.additions, Some(2));
+        assert_eq!(entry.deletions, Some(1));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn commit_touches_pathspec_filters_by_directory_and_glob() {
+        let (_root, repo) = create_temp_repo();
+        let commit_paths = |path: &str, message: &str| {
+            let oid = commit_file(&repo, path, message, message);
+            repo.find_commit(oid).expect("find commit")
+        };
+        let api = commit_paths("src/api/routes.rs", "api");
+        let docs = commit_paths("docs/readme.md", "docs");
+
+        let dir = vec!["src/api".to_string()];
+        assert!(commit_touches_pathspec(&repo, &api, &dir).expect("api"));
+        assert!(!commit_touches_pathspec(&repo, &docs, &dir).expect("docs"));
+        let glob = vec!["*.md".to_string()];
+        assert!(commit_touches_pathspec(&repo, &docs, &glob).expect("glob"));
+        assert!(!commit_touches_pathspec(&repo, &api, &glob).expect("glob"));
+    }
+
+    #[test]
+    fn walk_log_entries_leaves_pathspec_total_unknown() {
+        let (_root, repo) = create_temp_repo();
+        commit_file(&repo, "src/a.rs", "one", "a1");
+        commit_file(&repo, "docs/x.md", "one", "docs");
+        commit_file(&repo, "src/a.rs", "two", "a2");
+        commit_file(&repo, "src/a.rs", "three", "a3");
+
+        let (entries, total, has_more) = walk_log_entries(&repo, 2, &[], false).expect("walk");
+        assert_eq!(entries.len(), 2);
+        assert!(total.is_some_and(|total| total >= 4));
+        assert!(has_more);
+
+        let src = vec!["src".to_string()];
+        let (entries, total, has_more) = walk_log_entries(&repo, 2, &src, false).expect("walk");
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.summary.starts_with('a')));
+        assert_eq!(total, None);
+        assert!(has_more);
+
+        let (entries, _, has_more) = walk_log_entries(&repo, 3, &src, false).expect("walk");
+        assert_eq!(entries.len(), 3);
+        assert!(!has_more);
+    }
+
+    #[test]
+    fn repo_operation_state_reads_marker_files() {
+        let (_root, repo) = create_temp_repo();
+        assert_eq!(repo_operation_state(&repo), RepoOperation::None);
+
+        let git_dir = repo.path().to_path_buf();
+        fs::write(git_dir.join("MERGE_HEAD"), "deadbeef\n").expect("write merge head");
+        assert_eq!(repo_operation_state(&repo), RepoOperation::Merge);
+
+        let rebase_dir = git_dir.join("rebase-merge");
+        fs::create_dir_all(&rebase_dir).expect("create rebase dir");
+        fs::write(rebase_dir.join("msgnum"), "2\n").expect("write msgnum");
+        fs::write(rebase_dir.join("end"), "5\n").expect("write end");
+        assert_eq!(
+            repo_operation_state(&repo),
+            RepoOperation::Rebase {
+                step: Some(2),
+                total: Some(5),
+            }
+        );
+    }
+
+    #[test]
+    fn operation_action_args_dispatch_by_operation() {
+        let rebase = RepoOperation::Rebase {
+            step: None,
+            total: None,
+        };
+        assert_eq!(
+            operation_action_args(rebase, OperationAction::Skip).expect("rebase skip"),
+            vec!["-c", "core.editor=true", "rebase", "--skip"]
+        );
+        assert_eq!(
+            operation_action_args(RepoOperation::CherryPick, OperationAction::Continue)
+                .expect("cherry-pick continue"),
+            vec!["-c", "core.editor=true", "cherry-pick", "--continue"]
+        );
+        assert_eq!(
+            operation_action_args(RepoOperation::Bisect, OperationAction::Abort)
+                .expect("bisect abort"),
+            vec!["bisect", "reset"]
+        );
+        assert!(operation_action_args(RepoOperation::Merge, OperationAction::Skip).is_err());
+        assert!(operation_action_args(RepoOperation::None, OperationAction::Abort).is_err());
+    }
+
+    #[test]
+    fn undo_journal_restores_stash_and_head() {
+        let (root, mut repo) = create_temp_repo();
+        commit_file(&repo, "a.txt", "one\n", "initial");
+
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        fs::write(root.join("new.txt"), "new\n").expect("write untracked");
+        let mut entry = git_undo_core::capture_head(&repo, "revert all", GitUndoKind::StashPop)
+            .expect("capture");
+        entry.stash = git_undo_core::stash_worktree(&mut repo, "test").expect("stash");
+        assert!(entry.stash.is_some());
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "one\n");
+        assert!(!root.join("new.txt").exists());
+        assert_eq!(git_undo_core::stash_worktree(&mut repo, "empty").expect("stash"), None);
+
+        git_undo_core::undo_entry_in_repo(&mut repo, &entry).expect("pop stash");
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "two\n");
+        assert!(root.join("new.txt").exists());
+
+        let mut before =
+            git_undo_core::capture_head(&repo, "reword commit", GitUndoKind::ResetHead)
+                .expect("capture");
+        reword_head_commit_in_repo(&repo, "reworded", false).expect("reword");
+        git_undo_core::record_after(&mut before, &repo).expect("record after");
+        let reworded = before.after.clone().expect("after");
+        // a.txt still carries the popped edit, which a hard reset would lose.
+        let dirty = git_undo_core::undo_entry_in_repo(&mut repo, &before).expect_err("dirty tree");
+        assert!(dirty.starts_with("Commit or stash"));
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .expect("discard edit");
+        commit_file(&repo, "later.txt", "later\n", "later");
+        let moved = git_undo_core::undo_entry_in_repo(&mut repo, &before).expect_err("moved head");
+        assert!(moved.starts_with("HEAD has moved"));
+        {
+            let reworded = repo
+                .find_object(git2::Oid::from_str(&reworded).expect("oid"), None)
+                .expect("reworded commit");
+            repo.reset(&reworded, git2::ResetType::Hard, None)
+                .expect("back to reworded");
+        }
+        git_undo_core::undo_entry_in_repo(&mut repo, &before).expect("reset");
+        let head = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .expect("head commit");
+        assert_eq!(head.id().to_string(), before.head);
+
+        let mut journal = git_undo_core::GitUndoJournal::new();
+        for _ in 0..25 {
+            git_undo_core::push_undo_entry(&mut journal, "ws", before.clone());
+        }
+        assert_eq!(journal["ws"].len(), 20);
+    }
+
+    #[test]
+    fn compose_commit_message_separates_subject_and_body() {
+        assert_eq!(
+            compose_commit_message("  Fix parser  ", Some("\nFirst paragraph.\n\nSecond.\n\n"))
+                .expect("message"),
+            "Fix parser\n\nFirst paragraph.\n\nSecond."
+        );
+        assert_eq!(
+            compose_commit_message("Fix parser", Some("  ")).expect("subject only"),
+            "Fix parser"
+        );
+        assert!(compose_commit_message(" ", None).is_err());
+        assert!(compose_commit_message("Fix\nparser", None).is_err());
+
+        assert_eq!(subject_length_warning("Short", 72), None);
+        assert!(subject_length_warning(&"x".repeat(73), 72).is_some());
+        assert_eq!(subject_length_warning(&"x".repeat(200), 0), None);
+    }
+
+    #[test]
+    fn drain_progress_lines_splits_on_carriage_returns() {
+        let mut pending =
+            "Counting objects:  50% (1/2)\rCounting objects: 100% (2/2), done.\nCompress".to_string();
+        assert_eq!(
+            drain_progress_lines(&mut pending),
+            vec![
+                "Counting objects:  50% (1/2)".to_string(),
+                "Counting objects: 100% (2/2), done.".to_string(),
+            ]
+        );
+        assert_eq!(pending, "Compress");
+        pending.push_str("ing objects: 100%\r\n");
+        assert_eq!(
+            drain_progress_lines(&mut pending),
+            vec!["Compressing objects: 100%".to_string()]
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn commit_notes_are_read_from_default_and_named_refs() {
+        let (_root, repo) = create_temp_repo();
+        let oid = commit_file(&repo, "a.txt", "a\n", "init");
+        let sha = oid.to_string();
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+
+        assert_eq!(commit_notes_in_repo(&repo, &sha, None).expect("no notes"), None);
+        let mut entries = vec![commit_to_entry(repo.find_commit(oid).expect("commit"))];
+        mark_entries_with_notes(&repo, &mut entries);
+        assert_eq!(entries[0].has_notes, None);
+
+        repo.note(&sig, &sig, None, oid, "build: green", false)
+            .expect("default note");
+        repo.note(&sig, &sig, Some("refs/notes/ci"), oid, "run 42", false)
+            .expect("ci note");
+        assert_eq!(
+            commit_notes_in_repo(&repo, &sha, None).expect("default"),
+            Some("build: green".to_string())
+        );
+        assert_eq!(
+            commit_notes_in_repo(&repo, "HEAD", Some("ci")).expect("named"),
+            Some("run 42".to_string())
+        );
+        assert_eq!(
+            commit_notes_in_repo(&repo, &sha, Some("refs/notes/missing")).expect("missing ref"),
+            None
+        );
+        mark_entries_with_notes(&repo, &mut entries);
+        assert_eq!(entries[0].has_notes, Some(true));
+    }
+
+    #[test]
+    fn working_tree_cleanliness_counts_or_stops_early() {
+        let (root, repo) = create_temp_repo();
+        let clean = working_tree_cleanliness(&repo, true).expect("clean check");
+        assert!(clean.clean);
+
+        fs::write(root.join("staged.txt"), "staged\n").expect("write staged");
+        let mut index = repo.index().expect("index");
+        index.add_path(Path::new("staged.txt")).expect("add path");
+        index.write().expect("write index");
+        fs::create_dir_all(root.join("dir")).expect("create dir");
+        fs::write(root.join("dir/a.txt"), "a\n").expect("write a");
+        fs::write(root.join("dir/b.txt"), "b\n").expect("write b");
+
+        let counted = working_tree_cleanliness(&repo, true).expect("counted");
+        assert_eq!(
+            counted,
+            WorkingTreeCleanliness {
+                clean: false,
+                staged: 1,
+                unstaged: 0,
+                untracked: 2,
+                conflicted: 0,
+            }
+        );
+        let quick = working_tree_cleanliness(&repo, false).expect("quick");
+        assert!(!quick.clean);
+        assert_eq!(quick.staged + quick.unstaged + quick.untracked + quick.conflicted, 1);
+    }
+
+    #[test]
+    fn init_repo_creates_repo_and_refuses_nested() {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-init-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let nested = root.join("nested");
+        fs::create_dir_all(&nested).expect("create nested dir");
+        let workspaces = Mutex::new(HashMap::from([
+            ("root".to_string(), workspace_entry("root", &root)),
+            ("nested".to_string(), workspace_entry("nested", &nested)),
+        ]));
+
+        let repo_root = block_on(init_repo_core(
+            &workspaces,
+            "root".to_string(),
+            Some("trunk".to_string()),
+        ))
+        .expect("init repo");
+        assert_eq!(
+            Path::new(&repo_root).canonicalize().expect("canonical root"),
+            root.canonicalize().expect("canonical temp")
+        );
+        let repo = Repository::open(&root).expect("open repo");
+        assert_eq!(
+            repo.find_reference("HEAD")
+                .expect("head ref")
+                .symbolic_target(),
+            Some("refs/heads/trunk")
+        );
+
+        let nested_error = block_on(init_repo_core(&workspaces, "nested".to_string(), None))
+            .expect_err("nested init refused");
+        assert!(nested_error.starts_with("Already inside a git repository"));
+    }
+
+    #[test]
+    fn resolve_ref_peels_annotated_tags_and_reports_ref_type() {
+        let (_root, repo) = create_temp_repo();
+        let oid = commit_file(&repo, "a.txt", "a\n", "release");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        let commit = repo.find_commit(oid).expect("find commit");
+        repo.tag("v1.0", commit.as_object(), &sig, "v1.0", false)
+            .expect("annotated tag");
+        repo.branch("topic", &commit, false).expect("branch");
+
+        let tag = resolve_ref_in_repo(&repo, "v1.0").expect("resolve tag");
+        assert_eq!(tag.sha, oid.to_string());
+        assert_eq!(tag.ref_type, RefType::Tag);
+        assert_eq!(tag.commit.summary, "release");
+        assert!(oid.to_string().starts_with(&tag.short_sha));
+
+        assert_eq!(
+            resolve_ref_in_repo(&repo, "topic").expect("branch").ref_type,
+            RefType::Branch
+        );
+        assert_eq!(
+            resolve_ref_in_repo(&repo, &oid.to_string()).expect("sha").ref_type,
+            RefType::Commit
+        );
+        let error = resolve_ref_in_repo(&repo, "nope").expect_err("unresolvable");
+        assert!(error.starts_with("Failed to resolve 'nope'"));
+    }
+
+    #[test]
+    fn co_author_trailers_are_appended_once() {
+        let co_authors = vec![
+            "Ada <ada@example.com>".to_string(),
+            "  ".to_string(),
+            "Bob <bob@example.com>".to_string(),
+        ];
+        assert_eq!(
+            append_co_author_trailers("Fix parser\n", &co_authors),
+            "Fix parser\n\nCo-authored-by: Ada <ada@example.com>\nCo-authored-by: Bob <bob@example.com>"
+        );
+        let existing = "Fix parser\n\nCo-authored-by: Ada <ada@example.com>";
+        assert_eq!(
+            append_co_author_trailers(existing, &co_authors[..1]),
+            existing
+        );
+        assert_eq!(append_co_author_trailers("Fix parser", &[]), "Fix parser");
+    }
+
+    #[test]
+    fn commit_and_push_keeps_commit_when_push_fails() {
+        let (root, repo) = create_temp_repo();
+        {
+            let mut config = repo.config().expect("config");
+            config.set_str("user.name", "Test").expect("user.name");
+            config.set_str("user.email", "test@example.com").expect("user.email");
+        }
+        fs::write(root.join("fix.txt"), "fix\n").expect("write file");
+        let workspaces = workspaces_with(&root);
+
+        let result = block_on(commit_and_push_core(
+            &workspaces,
+            "w1".to_string(),
+            "Quick fix".to_string(),
+            true,
+            Vec::new(),
+            false,
+        ))
+        .expect("commit and push");
+        assert!(result.staged);
+        assert!(result.committed);
+        assert!(!result.pushed);
+        assert_eq!(result.failed_step.as_deref(), Some("push"));
+        assert!(result.error.is_some());
+        let head = repo.head().expect("head").peel_to_commit().expect("head commit");
+        assert_eq!(result.commit_sha, Some(head.id().to_string()));
+        assert_eq!(head.summary(), Some("Quick fix"));
+
+        let co_authors = vec!["Ada <ada@example.com>".to_string()];
+        let error = block_on(commit_and_push_core(
+            &workspaces,
+            "w1".to_string(),
+            "  \n".to_string(),
+            false,
+            co_authors,
+            false,
+        ))
+        .expect_err("trailers alone are not a message");
+        assert_eq!(error, "Commit message cannot be empty.");
+    }
+
+    #[test]
+    fn refspec_destination_prefix_requires_wildcards() {
+        assert_eq!(
+            refspec_destination_prefix("+refs/pull/*/head:refs/remotes/origin/pr/*", "origin"),
+            Ok("refs/remotes/origin/pr/".to_string())
+        );
+        assert!(refspec_destination_prefix(
+            "refs/pull/1/head:refs/remotes/origin/pr/1",
+            "origin"
+        )
+        .is_err());
+        assert!(refspec_destination_prefix("refs/pull/*/head", "origin").is_err());
+        assert!(refspec_destination_prefix("refs/pull/*/head:pr/*", "origin").is_err());
+        assert!(refspec_destination_prefix("+refs/heads/*:refs/heads/*", "origin").is_err());
+        assert!(refspec_destination_prefix("+refs/tags/*:refs/tags/*", "origin").is_err());
+        assert!(refspec_destination_prefix(
+            "+refs/pull/*/head:refs/remotes/upstream/pr/*",
+            "origin"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn fetch_all_open_prs_reports_created_refs_and_unsupported_remotes() {
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        let forge_root = std::env::temp_dir().join(format!(
+            "codex-monitor-forge-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let forge = Repository::init_bare(&forge_root).expect("init forge");
+        let tree_id = forge
+            .treebuilder(None)
+            .expect("treebuilder")
+            .write()
+            .expect("write tree");
+        let tree = forge.find_tree(tree_id).expect("find tree");
+        let base = forge
+            .commit(Some("refs/heads/main"), &sig, &sig, "base", &tree, &[])
+            .expect("base commit");
+        let base = forge.find_commit(base).expect("find base");
+        for number in [2, 10] {
+            forge
+                .commit(
+                    Some(&format!("refs/pull/{number}/head")),
+                    &sig,
+                    &sig,
+                    &format!("pr {number}"),
+                    &tree,
+                    &[&base],
+                )
+                .expect("pr commit");
+        }
+        let plain_root = std::env::temp_dir().join(format!(
+            "codex-monitor-plain-{}",
+            uuid::Uuid::new_v4()
+        ));
+        Repository::init_bare(&plain_root).expect("init plain remote");
+
+        let (root, repo) = create_temp_repo();
+        repo.remote("origin", &forge_root.to_string_lossy())
+            .expect("add origin");
+        repo.remote("plain", &plain_root.to_string_lossy())
+            .expect("add plain");
+        let workspaces = workspaces_with(&root);
+        let fetch = |remote: &str| {
+            block_on(fetch_all_open_prs_as_branches_core(
+                &workspaces,
+                "w1".to_string(),
+                Some(remote.to_string()),
+                None,
+            ))
+            .expect("fetch pr refs")
+        };
+
+        let first = fetch("origin");
+        assert!(first.supported);
+        assert_eq!(first.created, vec!["origin/pr/2", "origin/pr/10"]);
+        assert_eq!(first.total, 2);
+        assert!(repo.find_reference("refs/remotes/origin/pr/10").is_ok());
+        let second = fetch("origin");
+        assert!(second.created.is_empty());
+        assert!(second.updated.is_empty());
+
+        let plain = fetch("plain");
+        assert!(!plain.supported);
+        assert_eq!(plain.total, 0);
+    }
+
+    #[test]
+    fn classify_remote_push_error_detects_access_failures() {
+        assert!(classify_remote_push_error(
+            "remote: Repository not found.\nfatal: repository 'x' not found".to_string()
+        )
+        .starts_with(REMOTE_NOT_FOUND_PREFIX));
+        assert!(classify_remote_push_error(
+            "git@github.com: Permission denied (publickey).".to_string()
+        )
+        .starts_with(REMOTE_PERMISSION_DENIED_PREFIX));
+        assert!(classify_remote_push_error(
+            "fatal: unable to access 'https://x/': The requested URL returned error: 403"
+                .to_string()
+        )
+        .starts_with(REMOTE_PERMISSION_DENIED_PREFIX));
+        assert_eq!(
+            classify_remote_push_error("rejected: non-fast-forward".to_string()),
+            "rejected: non-fast-forward"
+        );
+        let hook = "pre-receive hook declined: ticket 4031 missing".to_string();
+        assert_eq!(classify_remote_push_error(hook.clone()), hook);
+    }
+
+    #[test]
+    fn set_upstream_and_push_adds_remote_and_tracks_branch() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&repo, "a.txt", "a\n", "initial");
+        let branch = repo.head().expect("head").shorthand().expect("branch").to_string();
+        let remote_root = std::env::temp_dir().join(format!(
+            "codex-monitor-remote-{}",
+            uuid::Uuid::new_v4()
+        ));
+        Repository::init_bare(&remote_root).expect("init bare remote");
+        let workspaces = workspaces_with(&root);
+        let url = remote_root.to_string_lossy().to_string();
+
+        let upstream = block_on(set_upstream_and_push_core(
+            &workspaces,
+            "w1".to_string(),
+            "origin".to_string(),
+            url.clone(),
+            None,
+        ))
+        .expect("push");
+        assert_eq!(upstream, format!("origin/{branch}"));
+        assert_eq!(
+            repo.find_remote("origin").expect("remote").url(),
+            Some(url.as_str())
+        );
+
+        let missing = remote_root.join("missing.git").to_string_lossy().to_string();
+        block_on(set_upstream_and_push_core(
+            &workspaces,
+            "w1".to_string(),
+            "backup".to_string(),
+            missing,
+            None,
+        ))
+        .expect_err("push to a missing remote fails");
+        assert!(repo.find_remote("backup").is_err());
+    }
+
+    #[test]
+    fn git_status_batch_reports_errors_per_workspace() {
+        let (root, _repo) = create_temp_repo();
+        fs::write(root.join("new.txt"), "new\n").expect("write file");
+        let plain = root.join("plain");
+        fs::create_dir_all(&plain).expect("create plain dir");
+        let mut bad = workspace_entry("bad", &root);
+        bad.settings.git_root = Some("plain".to_string());
+        let workspaces = Mutex::new(HashMap::from([
+            ("ok".to_string(), workspace_entry("ok", &root)),
+            ("bad".to_string(), bad),
+        ]));
+        let settings = Mutex::new(AppSettings::default());
+
+        let statuses = block_on(get_git_status_batch_core(
+            &workspaces,
+            &settings,
+            vec!["ok".to_string(), "bad".to_string(), "missing".to_string()],
+            true,
+        ))
+        .expect("batch status");
+        assert_eq!(statuses["ok"]["status"]["changedFiles"], 1);
+        assert!(statuses["bad"]["error"].is_string());
+        assert_eq!(statuses["missing"]["error"], "workspace not found");
+    }
+
+    #[test]
+    fn validate_git_root_reports_missing_repo_and_bad_override() {
+        let (root, _repo) = create_temp_repo();
+        let mut entry = workspace_entry("w1", &root);
+
+        let report = validate_git_root(&entry);
+        assert_eq!(report["ok"], true);
+        assert_eq!(report["isBare"], false);
+        assert_eq!(report["headState"], "unborn");
+        assert!(report["gitRootOverride"].is_null());
+
+        fs::create_dir_all(root.join("plain")).expect("create plain dir");
+        entry.settings.git_root = Some("plain".to_string());
+        let report = validate_git_root(&entry);
+        assert_eq!(report["ok"], false);
+        assert_eq!(report["gitRootOverride"]["valid"], false);
+        assert!(report["error"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with("Not a git repository"));
+
+        entry.settings.git_root = None;
+        fs::remove_dir_all(root.join(".git")).expect("remove .git");
+        let report = validate_git_root(&entry);
+        assert_eq!(report["ok"], false);
+        assert!(report["repoRoot"].is_null());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn reset_head_author_rewrites_identity_and_validates_input() {
+        let (_root, repo) = create_temp_repo();
+        let initial = commit_file(&repo, "a.txt", "hello\n", "initial");
+        let tree_id = repo.find_commit(initial).expect("commit").tree_id();
+
+        assert!(reset_head_author_in_repo(&repo, "Bad <Name>", "a@b.c").is_err());
+        assert!(reset_head_author_in_repo(&repo, "Name", "not-an-email").is_err());
+
+        let sha =
+            reset_head_author_in_repo(&repo, "Right", "right@example.com").expect("reset");
+        let head = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .expect("head commit");
+        assert_eq!(head.id().to_string(), sha);
+        assert_eq!(head.author().name(), Some("Right"));
+        assert_eq!(head.author().email(), Some("right@example.com"));
+        assert_eq!(head.message(), Some("initial"));
+        assert_eq!(head.tree_id(), tree_id);
+
+        reset_head_date_in_repo(&repo, 1_700_000_000).expect("reset date");
+        let head = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .expect("head commit");
+        assert_eq!(head.author().when().seconds(), 1_700_000_000);
+        assert_eq!(head.author().name(), Some("Right"));
+    }
+
+    #[test]
+    fn summarize_changes_counts_staged_and_unstaged() {
+        let (root, repo) = create_temp_repo();
+        fs::write(root.join("staged.txt"), "staged\n").expect("write staged file");
+        fs::write(root.join("untracked.txt"), "untracked\n").expect("write untracked file");
+        let mut index = repo.index().expect("repo index");
+        index
+            .add_path(Path::new("staged.txt"))
+            .expect("add path");
+        index.write().expect("write index");
+        fs::write(root.join("staged.txt"), "staged and edited\n").expect("edit staged file");
+
+        let summary = summarize_changes(&repo, None).expect("summary");
+        assert_eq!(
+            summary,
+            GitChangeSummary {
+                changed_files: 2,
+                staged: 1,
+                unstaged: 2,
+                conflicted: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn git_config_keys_are_validated_before_writing() {
+        assert!(validate_git_config_key("user.email").is_ok());
+        assert!(validate_git_config_key("branch.feature/x.remote").is_ok());
+        assert!(validate_git_config_key("core").is_err());
+        assert!(validate_git_config_key(".email").is_err());
+        assert!(validate_git_config_key("user.1email").is_err());
+
+        let (_root, repo) = create_temp_repo();
+        assert!(write_git_config_value(&repo, "user", "x", ConfigScope::Local).is_err());
+        write_git_config_value(&repo, "pull.rebase", "true", ConfigScope::Local)
+            .expect("write local config");
+        let values = read_git_config_values(&repo, &["pull.rebase".to_string()])
+            .expect("read config");
+        assert_eq!(values["pull.rebase"]["value"], "true");
+        assert_eq!(values["pull.rebase"]["scope"], "local");
+    }
+
+    #[test]
+    fn parse_bisect_output_reads_progress_and_result() {
+        let progress = parse_bisect_output(
+            "Bisecting: 6 revisions left to test after this (roughly 3 steps)\n[abc123] Fix thing",
+        );
+        assert_eq!(progress.remaining_revisions, Some(6));
+        assert_eq!(progress.remaining_steps, Some(3));
+        assert_eq!(progress.first_bad, None);
+
+        let done = parse_bisect_output("abc123def is the first bad commit\ncommit abc123def");
+        assert_eq!(done.first_bad.as_deref(), Some("abc123def"));
+        assert_eq!(done.remaining_steps, Some(0));
+    }
+
+    #[test]
+    fn resolve_bisect_bounds_rejects_options() {
+        let (root, repo) = create_temp_repo();
+        let good = commit_file(&repo, "a.txt", "1\n", "good");
+        let bad = commit_file(&repo, "a.txt", "2\n", "bad");
+
+        assert_eq!(
+            resolve_bisect_bounds(&repo, " HEAD ", "HEAD~1"),
+            Ok((bad.to_string(), good.to_string()))
+        );
+        assert!(resolve_bisect_bounds(&repo, "--no-checkout", "HEAD~1").is_err());
+        assert!(resolve_bisect_bounds(&repo, "HEAD", "-c").is_err());
+        assert!(resolve_bisect_bounds(&repo, "HEAD", "missing").is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn gh_retry_only_on_transient_errors() {
+        assert!(gh_error_is_transient("HTTP 502: Bad Gateway (https://api.github.com/graphql)"));
+        assert!(!gh_error_is_transient("API rate limit exceeded for user ID 1."));
+        assert!(!gh_error_is_transient("You have exceeded a secondary rate limit."));
+        assert!(gh_error_is_transient("read tcp: connection reset by peer"));
+        assert!(!gh_error_is_transient("GraphQL: Could not resolve to a Repository"));
+        assert!(!gh_error_is_transient("HTTP 401: Bad credentials"));
+
+        assert!(gh_error_is_auth_failure("HTTP 401: Bad credentials"));
+        assert!(gh_error_is_auth_failure("please run:  gh auth login"));
+        assert!(!gh_error_is_auth_failure("HTTP 502: Bad Gateway"));
+
+        assert_eq!(gh_retry_delay(1), Duration::from_millis(500));
+        assert_eq!(gh_retry_delay(2), Duration::from_secs(1));
+        assert_eq!(gh_retry_delay(10), GH_RETRY_MAX_DELAY);
+
+        assert!(gh_error_is_rate_limited("API rate limit exceeded for user ID 1."));
+        assert!(!gh_error_is_rate_limited("HTTP 502: Bad Gateway"));
+        let limits = |remaining: i64, reset: i64| {
+            json!({ "resources": { "core": { "remaining": remaining, "reset": reset } } })
+                .to_string()
+        };
+        let now = 1_700_000_000;
+        assert_eq!(
+            gh_rate_limit_wait(&limits(0, now + 3), now),
+            Ok(Some(Duration::from_secs(3)))
+        );
+        assert_eq!(gh_rate_limit_wait(&limits(0, now - 5), now), Ok(Some(Duration::ZERO)));
+        let error = gh_rate_limit_wait(&limits(0, now + 600), now).expect_err("beyond cap");
+        assert!(error.contains("2023-11-14T22:23:20+00:00"), "{error}");
+        // A secondary limit leaves the core quota intact: no retry.
+        assert_eq!(gh_rate_limit_wait(&limits(4000, now + 600), now), Ok(None));
+        assert_eq!(gh_rate_limit_wait("not json", now), Ok(None));
+    }
+
+    #[test]
+    fn gh_auth_cache_forgets_hosts_and_rejects_other_forges() {
+        let hosts = GH_AUTHENTICATED_HOSTS.get_or_init(Default::default);
+        hosts.lock().unwrap().insert("ghe.forget.test".to_string());
+        forget_gh_authenticated_host("ghe.forget.test");
+        assert!(!hosts.lock().unwrap().contains("ghe.forget.test"));
+
+        let (root, repo) = create_temp_repo();
+        repo.remote("origin", "git@gitlab.com:group/repo.git")
+            .expect("add remote");
+        let error = github_repo_from_path(&root).err().expect("gitlab remote");
+        assert!(error.contains("not a GitHub repository"));
+        repo.remote_set_url("origin", "https://ghe.example.com/org/repo.git")
+            .expect("set url");
+        assert_eq!(github_repo_from_path(&root).expect("ghe remote").host, "ghe.example.com");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn checkout_commit_detaches_and_respects_dirty_tree() {
+        let (root, repo) = create_temp_repo();
+        let first = commit_file(&repo, "a.txt", "one\n", "first");
+        commit_file(&repo, "a.txt", "two\n", "second");
+
+        fs::write(root.join("a.txt"), "dirty\n").expect("dirty file");
+        assert!(checkout_commit_in_repo(&repo, &first.to_string(), false).is_err());
+        assert!(!repo.head_detached().expect("head state"));
+
+        let result = checkout_commit_in_repo(&repo, &first.to_string(), true).expect("forced");
+        assert!(result.detached_head);
+        assert_eq!(result.head_sha, first.to_string());
+        assert_eq!(result.summary, "first");
+        assert!(result.previous_branch.is_some());
+        assert!(repo.head_detached().expect("head state"));
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).expect("read file"),
+            "one\n"
+        );
+    }
+
+    #[test]
+    fn create_branch_at_head_attaches_detached_head() {
+        let (_root, repo) = create_temp_repo();
+        let commit_id = commit_file(&repo, "a.txt", "hello\n", "init");
+        repo.set_head_detached(commit_id).expect("detach head");
+        assert!(repo.head_detached().expect("head state"));
+
+        let name = create_branch_at_head_in_repo(&repo, "rescue").expect("create branch");
+        assert_eq!(name, "rescue");
+        assert!(!repo.head_detached().expect("head state"));
+        let head = repo.head().expect("head");
+        assert_eq!(head.shorthand(), Some("rescue"));
+        assert_eq!(head.target(), Some(commit_id));
+    }
+
+    #[test]
+    fn auto_fetch_backs_off_on_failures() {
+        assert_eq!(auto_fetch::next_fetch_delay(300, 0), Duration::from_secs(300));
+        assert_eq!(auto_fetch::next_fetch_delay(300, 2), Duration::from_secs(1200));
+        assert_eq!(auto_fetch::next_fetch_delay(300, 20), Duration::from_secs(3600));
+        // Intervals below the floor are clamped so a typo can't hammer a remote.
+        assert_eq!(auto_fetch::next_fetch_delay(1, 0), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn validate_pathspecs_rejects_empty_and_escaping_specs() {
+        let specs = validate_pathspecs(&[
+            " *.rs ".to_string(),
+            ":!Cargo.lock".to_string(),
+            "".to_string(),
+        ])
+        .expect("valid specs");
+        assert_eq!(specs, vec!["*.rs".to_string(), ":!Cargo.lock".to_string()]);
+        assert!(validate_pathspecs(&["  ".to_string()]).is_err());
+        assert!(validate_pathspecs(&["../outside".to_string()]).is_err());
+        assert!(validate_pathspecs(&["/etc/passwd".to_string()]).is_err());
+    }
+
+    #[test]
+    fn status_fingerprint_changes_with_worktree() {
+        let (root, _repo) = create_temp_repo();
+        let before = watcher::repo_status_fingerprint(&root).expect("fingerprint");
+        assert_eq!(watcher::repo_status_fingerprint(&root), Some(before));
+        fs::write(root.join("new.txt"), "new\n").expect("write file");
+        let after = watcher::repo_status_fingerprint(&root).expect("fingerprint");
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn collect_git_file_diffs_limits_to_pathspec() {
+        let (root, _repo) = create_temp_repo();
+        fs::write(root.join("one.txt"), "one\n").expect("write file");
+        fs::write(root.join("two.txt"), "two\n").expect("write file");
+
+        let all = collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, false, false, None).expect("all diffs");
+        assert_eq!(all.len(), 2);
+        let single =
+            collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, false, false, Some("two.txt")).expect("single diff");
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].path, "two.txt");
+    }
+
+    #[test]
+    fn compare_refs_lists_range_commits_and_diffs() {
+        let (_root, repo) = create_temp_repo();
+        let base = commit_file(&repo, "a.txt", "base", "base");
+        commit_file(&repo, "b.txt", "feature one", "feature one");
+        let head = commit_file(&repo, "c.txt", "feature two", "feature two");
+
+        let result = compare_refs_in_repo(
+            &repo,
+            &base.to_string(),
+            &head.to_string(),
+            DiffWhitespace::None,
+        3,
+        )
+        .expect("compare refs");
+        assert_eq!(result["commits"].as_array().map(Vec::len), Some(2));
+        let paths: Vec<&str> = result["diffs"]
+            .as_array()
+            .expect("diffs")
+            .iter()
+            .filter_map(|diff| diff["path"].as_str())
+            .collect();
+        assert_eq!(paths, vec!["b.txt", "c.txt"]);
+
+        let error = compare_refs_in_repo(&repo, "missing-ref", "HEAD", DiffWhitespace::None, 3)
+            .expect_err("missing base");
+        assert!(error.contains("base"));
+    }
+
+    #[test]
+    fn merge_conflict_paths_detects_diverged_edits() {
+        let (_root, repo) = create_temp_repo();
+        commit_file(&repo, "shared.txt", "base\n", "base shared");
+        let base = commit_file(&repo, "other.txt", "x\n", "base other");
+        let commit_on_branch = |branch: &str, path: &str, content: &str| {
+            switch_branch(&repo, branch, Some(base));
+            commit_file(&repo, path, content, branch)
+        };
+        let ours = commit_on_branch("ours", "shared.txt", "ours\n");
+        let theirs = commit_on_branch("theirs", "shared.txt", "theirs\n");
+        let clean = commit_on_branch("clean", "other.txt", "y\n");
+
+        assert_eq!(
+            merge_conflict_paths(&repo, ours, theirs).expect("merge preview"),
+            vec!["shared.txt".to_string()]
+        );
+        assert!(merge_conflict_paths(&repo, ours, clean)
+            .expect("merge preview")
+            .is_empty());
+    }
+
+    /// Leaves `shared.txt` conflicted in the index and working tree by
+    /// merging diverged edits, plus a clean `other.txt`.
+    fn create_conflicted_repo() -> (PathBuf, Repository) {
+        let (root, repo) = create_temp_repo();
+        commit_file(&repo, "other.txt", "x\n", "other");
+        let base = commit_file(&repo, "shared.txt", "base\n", "base");
+        let ours_branch = repo.head().expect("head").shorthand().expect("branch").to_string();
+        switch_branch(&repo, "theirs", Some(base));
+        let theirs = commit_file(&repo, "shared.txt", "theirs\n", "theirs");
+        switch_branch(&repo, &ours_branch, None);
+        commit_file(&repo, "shared.txt", "ours\n", "ours");
+        let theirs = repo.find_annotated_commit(theirs).expect("annotated theirs");
+        repo.merge(&[&theirs], None, None).expect("merge");
+        assert!(repo.index().expect("index").has_conflicts());
+        (root, repo)
+    }
+
+    #[test]
+    fn conflict_versions_reads_all_three_stages() {
+        let (root, _repo) = create_conflicted_repo();
+
+        let versions = conflict_versions_in_repo(&root, "shared.txt").expect("versions");
+        assert_eq!(versions.path, "shared.txt");
+        assert_eq!(versions.base.lines, Some(vec!["base\n".to_string()]));
+        assert_eq!(versions.ours.lines, Some(vec!["ours\n".to_string()]));
+        assert_eq!(versions.theirs.lines, Some(vec!["theirs\n".to_string()]));
+        assert!(!versions.ours.is_binary);
+
+        let err = conflict_versions_in_repo(&root, "other.txt").expect_err("not conflicted");
+        assert!(err.contains("not conflicted"));
+        assert!(conflict_versions_in_repo(&root, "../shared.txt").is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn resolve_conflict_with_content_writes_and_stages_merge() {
+        let (root, repo) = create_conflicted_repo();
+
+        assert!(resolve_conflict_with_content_in_repo(&root, "other.txt", "nope\n").is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("other.txt")).expect("read other"),
+            "x\n"
+        );
+
+        resolve_conflict_with_content_in_repo(&root, "shared.txt", "merged\n")
+            .expect("resolve");
+        assert_eq!(
+            fs::read_to_string(root.join("shared.txt")).expect("read merged"),
+            "merged\n"
+        );
+        let mut index = repo.index().expect("index");
+        index.read(true).expect("reload index");
+        assert!(!index.has_conflicts());
+        let staged = index.get_path(Path::new("shared.txt"), 0).expect("staged entry");
+        let blob = repo.find_blob(staged.id).expect("blob");
+        assert_eq!(blob.content(), b"merged\n");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn collect_git_file_diffs_summarizes_binary_changes() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&repo, "blob.bin", [0u8, 1, 2, 3], "initial");
+
+        fs::write(root.join("blob.bin"), [0u8, 0xff, 0x10, 0x20, 0x30, 0x40])
+            .expect("rewrite binary");
+        let diffs = collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, false, false, None)
+            .expect("diffs");
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs[0].is_binary);
+        let summary = diffs[0].binary_summary.as_ref().expect("binary summary");
+        assert_eq!(summary.change, "modified");
+        assert_eq!(summary.old_size, Some(4));
+        assert_eq!(summary.new_size, Some(6));
+        assert_eq!(summary.hex_preview.as_deref(), Some("00 ff 10 20 30 40"));
+    }
+
+    #[test]
+    fn collect_git_file_diffs_honors_context_lines() {
+        let (root, repo) = create_temp_repo();
+        let original: String = (1..=20).map(|n| format!("line{n}\n")).collect();
+        commit_file(&repo, "long.txt", &original, "initial");
+        fs::write(root.join("long.txt"), original.replace("line10\n", "changed\n"))
+            .expect("edit file");
+
+        let diff_with = |context_lines: u32| {
+            collect_git_file_diffs(&root, None, DiffWhitespace::None, context_lines, false, false, None)
+                .expect("diffs")
+                .remove(0)
+                .diff
+        };
+        let tight = diff_with(0);
+        assert!(!tight.contains(" line9\n"));
+        let default = diff_with(3);
+        assert!(default.contains(" line7\n"));
+        assert!(!default.contains(" line6\n"));
+        let full = diff_with(diff_context_lines(3, true));
+        assert!(full.contains(" line1\n"));
+        assert!(full.contains(" line20\n"));
+    }
+
+    #[test]
+    fn collect_git_file_diffs_reports_encoding_and_line_endings() {
+        let (root, _repo) = create_temp_repo();
+        fs::write(root.join("crlf.txt"), "one\r\ntwo\r\n").expect("write crlf");
+        fs::write(root.join("legacy.txt"), b"caf\xe9\n").expect("write latin-1");
+
+        let diffs = collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, true, false, None)
+            .expect("collect diffs");
+        let crlf = diffs.iter().find(|diff| diff.path == "crlf.txt").expect("crlf diff");
+        assert_eq!(crlf.line_ending.as_deref(), Some("crlf"));
+        assert_eq!(crlf.encoding.as_deref(), Some("utf-8"));
+        assert_eq!(
+            crlf.new_lines.as_deref(),
+            Some(&["one\n".to_string(), "two\n".to_string()][..])
+        );
+        assert!(!crlf.diff.contains('\r'));
+
+        let legacy = diffs.iter().find(|diff| diff.path == "legacy.txt").expect("legacy diff");
+        assert_eq!(legacy.encoding.as_deref(), Some("latin-1"));
+        assert_eq!(legacy.new_lines.as_deref(), Some(&["café\n".to_string()][..]));
+        assert!(legacy.diff.contains("+café\n"));
+
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("héllo\n".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        fs::write(root.join("wide.txt"), utf16).expect("write utf-16");
+        let wide = collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, true, false, Some("wide.txt"))
+            .expect("collect diffs");
+        assert_eq!(wide[0].encoding.as_deref(), Some("utf-16le"));
+        assert!(!wide[0].is_binary);
+        assert!(wide[0].diff.contains("+héllo\n"));
+
+        let raw = collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, false, false, Some("crlf.txt"))
+            .expect("collect diffs");
+        assert_eq!(raw[0].new_lines.as_deref(), Some(&["one\r\n".to_string(), "two\r\n".to_string()][..]));
+    }
+
+    #[test]
+    fn whitespace_check_flags_added_lines_by_config() {
+        let rules = WhitespaceRules::from_config(None);
+        assert_eq!(rules.line_errors("fine"), Vec::<&str>::new());
+        assert_eq!(rules.line_errors("trailing  "), vec!["blank-at-eol"]);
+        assert_eq!(rules.line_errors(" \tmixed"), vec!["space-before-tab"]);
+        assert_eq!(rules.line_errors("crlf\r"), vec!["blank-at-eol"]);
+
+        let rules = WhitespaceRules::from_config(Some("-blank-at-eol,tab-in-indent,cr-at-eol"));
+        assert_eq!(rules.line_errors("trailing  "), Vec::<&str>::new());
+        assert_eq!(rules.line_errors("\tindented"), vec!["tab-in-indent"]);
+        assert_eq!(rules.line_errors("crlf\r"), Vec::<&str>::new());
+        let rules = WhitespaceRules::from_config(Some("indent-with-non-tab,tabwidth=4"));
+        assert_eq!(rules.line_errors("    four"), vec!["indent-with-non-tab"]);
+
+        let (root, _repo) = create_temp_repo();
+        fs::write(root.join("a.txt"), "clean\ndirty \n").expect("write file");
+        let unchecked = collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, false, false, None)
+            .expect("collect diffs");
+        assert!(unchecked[0].whitespace_errors.is_none());
+        let checked = collect_git_file_diffs(&root, None, DiffWhitespace::None, 3, false, true, None)
+            .expect("collect diffs");
+        assert_eq!(
+            checked[0].whitespace_errors,
+            Some(vec![DiffWhitespaceError {
+                line: 2,
+                kinds: vec!["blank-at-eol".to_string()],
+            }])
+        );
+    }
+
+    #[test]
+    fn recent_branches_follow_checkout_order() {
+        let (_root, repo) = create_temp_repo();
+        let oid = commit_file(&repo, "a.txt", "a\n", "init");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        let commit = repo.find_commit(oid).expect("find commit");
+        let main = repo
+            .head()
+            .expect("head")
+            .shorthand()
+            .expect("branch name")
+            .to_string();
+        for name in ["alpha", "beta", "gone"] {
+            repo.branch(name, &commit, false).expect("create branch");
+        }
+
+        let fallback = recent_branches_in_repo(&repo, 10).expect("fallback");
+        assert_eq!(fallback[0].name, main);
+        assert_eq!(fallback.len(), 4);
+
+        for name in ["alpha", "gone", "beta", "alpha"] {
+            checkout_branch(&repo, name).expect("checkout");
+            let message = format!("checkout: moving from x to {name}");
+            let mut reflog = repo.reflog("HEAD").expect("reflog");
+            reflog.append(oid, &sig, Some(&message)).expect("append");
+            reflog.write().expect("write reflog");
+        }
+        repo.find_branch("gone", BranchType::Local)
+            .expect("find branch")
+            .delete()
+            .expect("delete branch");
+
+        let names: Vec<String> = recent_branches_in_repo(&repo, 10)
+            .expect("recent")
+            .into_iter()
+            .map(|branch| branch.name)
+            .collect();
+        assert_eq!(names, vec!["alpha".to_string(), "beta".to_string()]);
+        assert_eq!(recent_branches_in_repo(&repo, 1).expect("limited").len(), 1);
+    }
+
+    #[test]
+    fn branches_in_repo_lists_remote_tracking_refs() {
+        let (_root, repo) = create_temp_repo();
+        let head = commit_file(&repo, "a.txt", "one\n", "initial");
+        repo.reference("refs/remotes/origin/main", head, false, "test")
+            .expect("origin/main");
+        repo.reference("refs/remotes/origin/stale", head, false, "test")
+            .expect("origin/stale");
+        repo.reference("refs/remotes/upstream/main", head, false, "test")
+            .expect("upstream/main");
+        repo.reference_symbolic("refs/remotes/origin/HEAD", "refs/remotes/origin/main", false, "test")
+            .expect("origin/HEAD");
+
+        let mut names: Vec<String> = branches_in_repo(&repo, BranchType::Remote)
+            .expect("remote branches")
+            .into_iter()
+            .map(|branch| branch.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["origin/main", "origin/stale", "upstream/main"]);
+
+        let mut origin = remote_branch_names(&repo, "origin").expect("origin branches");
+        origin.sort();
+        assert_eq!(origin, vec!["origin/main", "origin/stale"]);
+    }
+
+    #[test]
+    fn default_branch_prefers_origin_head_then_probes_main_and_master() {
+        let (root, repo) = create_temp_repo();
+        assert!(default_branch_in_repo(&repo).is_err());
+
+        let head = commit_file(&repo, "a.txt", "one\n", "initial");
+        // Keep the local branch out of the main/master probe.
+        let local = repo.head().expect("head").shorthand().expect("branch").to_string();
+        repo.find_branch(&local, BranchType::Local)
+            .expect("local branch")
+            .rename("work", false)
+            .expect("rename branch");
+        repo.reference("refs/remotes/origin/master", head, false, "test")
+            .expect("origin/master");
+        assert_eq!(
+            default_branch_in_repo(&repo).expect("inferred"),
+            DefaultBranch {
+                name: "master".to_string(),
+                inferred: true,
+            }
+        );
+
+        repo.reference("refs/remotes/origin/trunk", head, false, "test")
+            .expect("origin/trunk");
+        repo.reference_symbolic("refs/remotes/origin/HEAD", "refs/remotes/origin/trunk", false, "test")
+            .expect("origin/HEAD");
+        assert_eq!(
+            default_branch_in_repo(&repo).expect("authoritative"),
+            DefaultBranch {
+                name: "trunk".to_string(),
+                inferred: false,
+            }
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn visit_git_file_diffs_stops_when_cancelled() {
+        let (root, _repo) = create_temp_repo();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(root.join(name), "content\n").expect("write file");
+        }
+
+        let cancel = AtomicBool::new(false);
+        let mut seen = Vec::new();
+        let completed = visit_git_file_diffs(
+            &root,
+            None,
+            DiffWhitespace::None,
+            3,
+            false,
+            false,
+            None,
+            Some(&cancel),
+            |diff| {
+                seen.push(diff.path);
+                cancel.store(true, Ordering::SeqCst);
+                true
+            },
+        )
+        .expect("visit diffs");
+        assert!(!completed);
+        assert_eq!(seen.len(), 1);
+
+        let completed = visit_git_file_diffs(&root, None, DiffWhitespace::None, 3, false, false, None, None, |_| true)
+            .expect("visit diffs");
+        assert!(completed);
+    }
+
+    #[test]
+    fn restore_file_from_rev_recreates_deleted_binary() {
+        let (root, repo) = create_temp_repo();
+        let png = [0x89u8, b'P', b'N', b'G', 0x00, 0xff, 0x10];
+        commit_file(&repo, "assets/logo.png", png, "add logo");
+
+        fs::remove_dir_all(root.join("assets")).expect("delete image");
+        restore_file_from_rev_in_repo(&repo, &root, "assets/logo.png", "HEAD", false)
+            .expect("restore");
+        assert_eq!(fs::read(root.join("assets/logo.png")).expect("read"), png);
+
+        fs::write(root.join("assets/logo.png"), "edited").expect("edit image");
+        assert!(
+            restore_file_from_rev_in_repo(&repo, &root, "assets/logo.png", "HEAD", false)
+                .is_err()
+        );
+        restore_file_from_rev_in_repo(&repo, &root, "assets/logo.png", "HEAD", true)
+            .expect("forced restore");
+        assert!(restore_file_from_rev_in_repo(&repo, &root, "../x", "HEAD", true).is_err());
+    }
+
+    #[test]
+    fn commit_selected_paths_takes_staged_content_only() {
+        let (root, repo) = create_temp_repo();
+        {
+            let mut config = repo.config().expect("config");
+            config.set_str("user.name", "Test").expect("user.name");
+            config.set_str("user.email", "test@example.com").expect("user.email");
+        }
+        commit_file(&repo, "a.txt", "one\n", "init");
+        // a.txt is partially staged: "two" in the index, "three" on disk.
+        fs::write(root.join("a.txt"), "two\n").expect("stage edit");
+        fs::write(root.join("other.txt"), "other\n").expect("write other");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("a.txt")).expect("add a");
+        index.add_path(Path::new("other.txt")).expect("add other");
+        index.write().expect("write index");
+        fs::write(root.join("a.txt"), "three\n").expect("unstaged edit");
+
+        let sha = block_on(commit_selected_paths_in_repo(
+            &root,
+            "Update a",
+            &["a.txt".to_string()],
+        ))
+        .expect("commit selected");
+        let head = repo.head().expect("head").peel_to_commit().expect("head commit");
+        assert_eq!(head.id().to_string(), sha);
+        assert_eq!(head.message(), Some("Update a\n"));
+        let tree = head.tree().expect("tree");
+        let blob = repo
+            .find_blob(tree.get_path(Path::new("a.txt")).expect("a.txt").id())
+            .expect("blob");
+        assert_eq!(blob.content(), b"two\n");
+        assert!(tree.get_path(Path::new("other.txt")).is_err());
+        assert_eq!(fs::read_to_string(root.join("a.txt")).expect("read a"), "three\n");
+        assert!(any_path_has_staged_changes(&root, &["other.txt".to_string()])
+            .expect("other still staged"));
+        assert!(!any_path_has_staged_changes(&root, &["a.txt".to_string()])
+            .expect("a committed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commit_selected_paths_runs_commit_hooks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (root, repo) = create_temp_repo();
+        {
+            let mut config = repo.config().expect("config");
+            config.set_str("user.name", "Test").expect("user.name");
+            config.set_str("user.email", "test@example.com").expect("user.email");
+        }
+        commit_file(&repo, "a.txt", "one\n", "init");
+        let hook = repo.path().join("hooks").join("pre-commit");
+        fs::create_dir_all(hook.parent().expect("hooks dir")).expect("create hooks dir");
+        fs::write(&hook, "#!/bin/sh\nexit 1\n").expect("write hook");
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).expect("chmod hook");
+        fs::write(root.join("a.txt"), "two\n").expect("edit a");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("a.txt")).expect("add a");
+        index.write().expect("write index");
+
+        assert!(block_on(commit_selected_paths_in_repo(
+            &root,
+            "Update a",
+            &["a.txt".to_string()],
+        ))
+        .is_err());
+        let head = repo.head().expect("head").peel_to_commit().expect("head commit");
+        assert_eq!(head.message(), Some("init"));
+        let leftovers = fs::read_dir(repo.path())
+            .expect("read git dir")
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("codex-monitor-index-"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn any_path_has_staged_changes_ignores_unstaged_paths() {
+        let (root, repo) = create_temp_repo();
+        fs::write(root.join("staged.txt"), "staged\n").expect("write staged file");
+        fs::write(root.join("unstaged.txt"), "unstaged\n").expect("write unstaged file");
+        let mut index = repo.index().expect("repo index");
+        index
+            .add_path(Path::new("staged.txt"))
+            .expect("add path");
+        index.write().expect("write index");
+
+        assert!(any_path_has_staged_changes(&root, &["staged.txt".to_string()])
+            .expect("check staged"));
+        assert!(!any_path_has_staged_changes(&root, &["unstaged.txt".to_string()])
+            .expect("check unstaged"));
+    }
 }
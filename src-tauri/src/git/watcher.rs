@@ -0,0 +1,248 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use git2::{Repository, StatusOptions};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::git_utils::resolve_git_root;
+use crate::remote_backend;
+use crate::state::AppState;
+
+/// How often the set of watched workspaces is reconciled with the connected ones.
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(2);
+/// Quiet period after the last file event before status is re-checked.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+pub(crate) const GIT_STATUS_DIRTY_EVENT: &str = "git-status-dirty";
+
+fn hash_mtime(hasher: &mut DefaultHasher, path: &Path) {
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok());
+    modified.hash(hasher);
+}
+
+/// Cheap fingerprint of everything `get_git_status` reports: HEAD and index
+/// mtimes plus the path/flag pairs from libgit2's status walk (no diff stats).
+pub(super) fn repo_status_fingerprint(repo_root: &Path) -> Option<u64> {
+    let repo = Repository::open(repo_root).ok()?;
+    let mut hasher = DefaultHasher::new();
+    hash_mtime(&mut hasher, &repo.path().join("HEAD"));
+    hash_mtime(&mut hasher, &repo.path().join("index"));
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let statuses = repo.statuses(Some(&mut status_options)).ok()?;
+    for entry in statuses.iter() {
+        entry.path_bytes().hash(&mut hasher);
+        entry.status().bits().hash(&mut hasher);
+        if let Some(path) = entry.path() {
+            hash_mtime(&mut hasher, &repo_root.join(path));
+        }
+    }
+    Some(hasher.finish())
+}
+
+/// Events under these `.git` entries never change what `get_git_status`
+/// reports, and object writes during a fetch or gc would otherwise keep the
+/// debounce from settling.
+fn is_relevant_event_path(repo_root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(repo_root) else {
+        return true;
+    };
+    let mut components = relative.components();
+    if !components
+        .next()
+        .is_some_and(|part| part.as_os_str() == ".git")
+    {
+        return true;
+    }
+    let Some(entry) = components.next() else {
+        return true;
+    };
+    let entry = entry.as_os_str();
+    entry != "objects" && entry != "logs" && !entry.to_string_lossy().ends_with(".lock")
+}
+
+struct WatchedRepo {
+    root: PathBuf,
+    fingerprint: Option<u64>,
+    /// When the last relevant event arrived; `None` while the repo is quiet.
+    dirty_since: Option<Instant>,
+    _watcher: RecommendedWatcher,
+}
+
+fn watch_repo(
+    workspace_id: &str,
+    root: &Path,
+    events: &mpsc::UnboundedSender<String>,
+) -> notify::Result<RecommendedWatcher> {
+    let workspace_id = workspace_id.to_string();
+    let events = events.clone();
+    let event_root = root.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if event
+            .paths
+            .iter()
+            .any(|path| is_relevant_event_path(&event_root, path))
+        {
+            let _ = events.send(workspace_id.clone());
+        }
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+/// Watches connected workspaces with `notify` and emits `git-status-dirty`
+/// (with just the workspace id) once file events have been quiet for
+/// [`DEBOUNCE`] and the status fingerprint actually changed, so a large
+/// checkout produces a single event. Workspaces that disappear from the map
+/// or disconnect stop being watched. In remote mode nothing is watched; the
+/// UI polls instead.
+pub(crate) fn spawn_git_status_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel::<String>();
+        let mut watched: HashMap<String, WatchedRepo> = HashMap::new();
+        let mut next_reconcile = Instant::now();
+        loop {
+            let next_settle = watched
+                .values()
+                .filter_map(|repo| repo.dirty_since)
+                .min()
+                .map(|since| since + DEBOUNCE);
+            let wake_at = next_settle.map_or(next_reconcile, |at| at.min(next_reconcile));
+            tokio::select! {
+                Some(workspace_id) = events_rx.recv() => {
+                    if let Some(repo) = watched.get_mut(&workspace_id) {
+                        repo.dirty_since = Some(Instant::now());
+                    }
+                    continue;
+                }
+                _ = tokio::time::sleep_until(wake_at) => {}
+            }
+
+            let now = Instant::now();
+            if now >= next_reconcile {
+                next_reconcile = now + RECONCILE_INTERVAL;
+                let state = app.state::<AppState>();
+                if remote_backend::is_remote_mode(&*state).await {
+                    watched.clear();
+                    continue;
+                }
+                let connected: Vec<String> = state.sessions.lock().await.keys().cloned().collect();
+                let roots: Vec<(String, PathBuf)> = {
+                    let workspaces = state.workspaces.lock().await;
+                    connected
+                        .iter()
+                        .filter_map(|id| workspaces.get(id))
+                        .filter_map(|entry| {
+                            resolve_git_root(entry)
+                                .ok()
+                                .map(|root| (entry.id.clone(), root))
+                        })
+                        .collect()
+                };
+                watched.retain(|id, repo| {
+                    roots
+                        .iter()
+                        .any(|(root_id, root)| root_id == id && *root == repo.root)
+                });
+                for (workspace_id, root) in roots {
+                    if watched.contains_key(&workspace_id) {
+                        continue;
+                    }
+                    let Ok(watcher) = watch_repo(&workspace_id, &root, &events_tx) else {
+                        continue;
+                    };
+                    let fingerprint_root = root.clone();
+                    let fingerprint = tokio::task::spawn_blocking(move || {
+                        repo_status_fingerprint(&fingerprint_root)
+                    })
+                    .await
+                    .ok()
+                    .flatten();
+                    watched.insert(
+                        workspace_id,
+                        WatchedRepo {
+                            root,
+                            fingerprint,
+                            dirty_since: None,
+                            _watcher: watcher,
+                        },
+                    );
+                }
+            }
+
+            let settled: Vec<String> = watched
+                .iter()
+                .filter(|(_, repo)| {
+                    repo.dirty_since
+                        .is_some_and(|since| since + DEBOUNCE <= now)
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            for workspace_id in settled {
+                let Some(repo) = watched.get_mut(&workspace_id) else {
+                    continue;
+                };
+                repo.dirty_since = None;
+                let root = repo.root.clone();
+                let fingerprint =
+                    tokio::task::spawn_blocking(move || repo_status_fingerprint(&root))
+                        .await
+                        .ok()
+                        .flatten();
+                if fingerprint.is_none() || fingerprint == repo.fingerprint {
+                    continue;
+                }
+                repo.fingerprint = fingerprint;
+                let _ = app.emit(
+                    GIT_STATUS_DIRTY_EVENT,
+                    json!({ "workspaceId": workspace_id }),
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_relevant_event_path;
+    use std::path::Path;
+
+    #[test]
+    fn object_and_lock_churn_is_ignored() {
+        let root = Path::new("/repo");
+        assert!(is_relevant_event_path(root, Path::new("/repo/src/main.rs")));
+        assert!(is_relevant_event_path(root, Path::new("/repo/.git/index")));
+        assert!(is_relevant_event_path(
+            root,
+            Path::new("/repo/.git/refs/heads/main")
+        ));
+        assert!(!is_relevant_event_path(
+            root,
+            Path::new("/repo/.git/objects/ab/cdef")
+        ));
+        assert!(!is_relevant_event_path(
+            root,
+            Path::new("/repo/.git/index.lock")
+        ));
+        assert!(!is_relevant_event_path(
+            root,
+            Path::new("/repo/.git/logs/HEAD")
+        ));
+    }
+}
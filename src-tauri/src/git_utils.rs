@@ -27,12 +27,20 @@ pub(crate) fn image_mime_type(path: &str) -> Option<&'static str> {
 pub(crate) fn commit_to_entry(commit: git2::Commit) -> GitLogEntry {
     let summary = commit.summary().unwrap_or("").to_string();
     let author = commit.author().name().unwrap_or("").to_string();
-    let timestamp = commit.time().seconds();
+    let time = commit.time();
     GitLogEntry {
         sha: commit.id().to_string(),
         summary,
         author,
-        timestamp,
+        timestamp: time.seconds(),
+        timezone_offset: time.offset_minutes(),
+        body: None,
+        files_changed: None,
+        additions: None,
+        deletions: None,
+        signature_status: None,
+        signer: None,
+        has_notes: None,
     }
 }
 
@@ -82,17 +90,57 @@ pub(crate) fn diff_stats_for_path(
     Ok((additions, deletions))
 }
 
+fn bytes_look_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8192).any(|byte| *byte == 0)
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| {
+        if little_endian {
+            u16::from_le_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_be_bytes([pair[0], pair[1]])
+        }
+    });
+    char::decode_utf16(units)
+        .map(|unit| unit.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Honors a BOM when present. Bytes that are not valid UTF-8 are read as
+/// latin-1, which maps every byte to a character, so legacy files show their
+/// accented characters instead of U+FFFD.
+pub(crate) fn decode_text(bytes: &[u8]) -> Option<(String, &'static str)> {
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return Some((String::from_utf8_lossy(rest).into_owned(), "utf-8-bom"));
+    }
+    // UTF-16 is full of NUL bytes, so check its BOM before the binary sniff.
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return Some((decode_utf16(rest, true), "utf-16le"));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return Some((decode_utf16(rest, false), "utf-16be"));
+    }
+    if bytes_look_binary(bytes) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some((text.to_string(), "utf-8")),
+        Err(_) => Some((bytes.iter().map(|byte| *byte as char).collect(), "latin-1")),
+    }
+}
+
 pub(crate) fn diff_patch_to_string(patch: &mut git2::Patch) -> Result<String, git2::Error> {
     let buf = patch.to_buf()?;
-    Ok(buf
-        .as_str()
-        .map(|value| value.to_string())
-        .unwrap_or_else(|| String::from_utf8_lossy(&buf).to_string()))
+    // Decode with the same rules as file content so latin-1 lines match `new_lines`.
+    Ok(decode_text(&buf)
+        .map(|(text, _)| text)
+        .unwrap_or_else(|| String::from_utf8_lossy(&buf).into_owned()))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{checkout_branch, image_mime_type};
+    use super::{checkout_branch, forge_blob_url, image_mime_type, parse_remote_host_and_path};
     use git2::Repository;
     use std::fs;
     use std::path::Path;
@@ -106,6 +154,44 @@ mod tests {
         assert_eq!(image_mime_type("readme.txt"), None);
     }
 
+    #[test]
+    fn parse_remote_host_and_path_handles_enterprise_hosts() {
+        let parsed = |url: &str| parse_remote_host_and_path(url);
+        assert_eq!(
+            parsed("git@github.example.com:team/app.git"),
+            Some(("github.example.com".to_string(), "team/app".to_string()))
+        );
+        assert_eq!(
+            parsed("ssh://git@GHE.corp:2222/team/app.git"),
+            Some(("ghe.corp".to_string(), "team/app".to_string()))
+        );
+        assert_eq!(
+            parsed("https://user@ghe.corp/team/app/"),
+            Some(("ghe.corp".to_string(), "team/app".to_string()))
+        );
+        assert_eq!(parsed("/srv/git/app.git"), None);
+        assert_eq!(parsed("https://ghe.corp/app"), None);
+    }
+
+    #[test]
+    fn forge_blob_url_matches_each_forge() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(
+            forge_blob_url("git@github.com:owner/repo.git", sha, "src/my file.rs", 12).unwrap(),
+            format!("https://github.com/owner/repo/blob/{sha}/src/my%20file.rs#L12")
+        );
+        assert_eq!(
+            forge_blob_url("https://gitlab.example.com/group/sub/repo.git", sha, "a.rs", 3)
+                .unwrap(),
+            format!("https://gitlab.example.com/group/sub/repo/-/blob/{sha}/a.rs#L3")
+        );
+        assert_eq!(
+            forge_blob_url("git@bitbucket.org:team/repo.git", sha, "a.rs", 3).unwrap(),
+            format!("https://bitbucket.org/team/repo/src/{sha}/a.rs#lines-3")
+        );
+        assert!(forge_blob_url("git@example.com:team/repo.git", sha, "a.rs", 3).is_err());
+    }
+
     #[test]
     fn checkout_branch_missing_does_not_change_head() {
         let root = std::env::temp_dir().join(format!(
@@ -153,6 +239,72 @@ pub(crate) fn parse_github_repo(remote_url: &str) -> Option<String> {
     }
 }
 
+/// Splits a remote URL into `(host, "owner/repo")` for any forge host, so
+/// GitHub Enterprise remotes can be routed to the right `gh` host. Handles
+/// scp-style (`git@host:owner/repo`), `ssh://` and `http(s)://` remotes.
+pub(crate) fn parse_remote_host_and_path(remote_url: &str) -> Option<(String, String)> {
+    let trimmed = remote_url.trim();
+    let (authority, path) = if let Some((_, rest)) = trimmed.split_once("://") {
+        rest.split_once('/')?
+    } else {
+        let (authority, path) = trimmed.split_once(':')?;
+        if authority.contains('/') {
+            return None;
+        }
+        (authority, path)
+    };
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or("").to_ascii_lowercase();
+    let path = path
+        .trim_matches('/')
+        .trim_end_matches(".git")
+        .trim_end_matches('/');
+    if host.is_empty() || !path.contains('/') {
+        return None;
+    }
+    Some((host, path.to_string()))
+}
+
+/// Percent-encodes a repo-relative path for a URL, keeping `/` separators.
+fn encode_url_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Web URL of `path` at `sha`, anchored to `line`, on the forge hosting
+/// `remote_url`. The forge is inferred from the host name, so self-hosted
+/// GitHub Enterprise/GitLab instances work as long as the name says so.
+pub(crate) fn forge_blob_url(
+    remote_url: &str,
+    sha: &str,
+    path: &str,
+    line: u32,
+) -> Result<String, String> {
+    let (host, repo) = parse_remote_host_and_path(remote_url)
+        .ok_or_else(|| format!("Cannot build a web link for remote {remote_url}."))?;
+    let path = encode_url_path(path.trim_start_matches('/'));
+    let base = format!("https://{host}/{repo}");
+    if host.contains("github") {
+        Ok(format!("{base}/blob/{sha}/{path}#L{line}"))
+    } else if host.contains("gitlab") {
+        Ok(format!("{base}/-/blob/{sha}/{path}#L{line}"))
+    } else if host.contains("bitbucket") {
+        Ok(format!("{base}/src/{sha}/{path}#lines-{line}"))
+    } else if host.contains("codeberg") || host.contains("gitea") || host.contains("forgejo") {
+        Ok(format!("{base}/src/commit/{sha}/{path}#L{line}"))
+    } else {
+        Err(format!("Unsupported forge host: {host}."))
+    }
+}
+
 pub(crate) fn resolve_git_root(entry: &WorkspaceEntry) -> Result<PathBuf, String> {
     let base = PathBuf::from(&entry.path);
     let root = entry
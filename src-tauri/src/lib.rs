@@ -53,6 +53,9 @@ pub fn run() {
         .setup(|app| {
             let state = state::AppState::load(&app.handle());
             app.manage(state);
+            git::watcher::spawn_git_status_watcher(app.handle().clone());
+            git::auto_fetch::spawn_git_auto_fetch(app.handle().clone());
+            settings::config_watcher::spawn_codex_config_watcher(app.handle().clone());
             #[cfg(desktop)]
             {
                 app.handle()
@@ -73,9 +76,24 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             settings::get_app_settings,
             settings::update_app_settings,
+            settings::reload_codex_config,
+            settings::export_settings_profile,
+            settings::import_settings_profile,
             settings::get_codex_config_path,
+            settings::get_effective_config,
+            settings::get_project_trust,
+            settings::set_project_trust,
+            settings::list_feature_flags,
+            settings::get_feature_flag,
+            settings::set_feature_flag,
+            settings::diff_codex_config,
             files::file_read,
             files::file_write,
+            files::file_write_transaction,
+            files::file_read_range,
+            files::file_tail,
+            files::stream_file_tail,
+            files::cancel_file_tail_stream,
             codex::get_config_model,
             menu::menu_set_accelerators,
             codex::codex_doctor,
@@ -93,6 +111,12 @@ pub fn run() {
             workspaces::rename_worktree_upstream,
             workspaces::apply_worktree_changes,
             workspaces::update_workspace_settings,
+            workspaces::reorder_workspaces,
+            workspaces::create_workspace_group,
+            workspaces::rename_workspace_group,
+            workspaces::set_workspace_group_color,
+            workspaces::set_workspace_group_collapsed,
+            workspaces::delete_workspace_group,
             workspaces::update_workspace_codex_bin,
             codex::start_thread,
             codex::send_user_message,
@@ -113,37 +137,97 @@ pub fn run() {
             codex::collaboration_mode_list,
             workspaces::connect_workspace,
             git::get_git_status,
+            git::get_git_status_batch,
+            git::is_working_tree_clean,
+            git::init_repo,
+            git::set_upstream_and_push,
+            git::get_conflict_versions,
+            git::resolve_conflict_with_content,
+            git::validate_workspace_git,
+            git::build_permalink,
+            git::repair_repo,
+            git::gc_repo,
+            git::get_repo_stats,
+            git::explain_ignore,
+            git::get_commit_notes,
+            git::get_git_change_summary,
             git::list_git_roots,
             git::get_git_diffs,
+            git::get_git_file_diff,
+            git::get_ref_comparison_diff,
+            git::get_ahead_behind_against,
+            git::resolve_ref,
+            git::stream_git_diffs,
+            git::cancel_git_diff_stream,
             git::get_git_log,
             git::get_git_commit_diff,
+            git::show_stash_diff,
+            git::list_changed_paths_against_ref,
+            git::stash_apply_git,
             git::get_git_remote,
+            git::diagnose_git,
+            git::diagnose_github,
+            git::read_git_config,
+            git::write_git_config,
+            git::bisect_start,
+            git::bisect_mark,
+            git::bisect_reset,
+            git::get_repo_operation_state,
+            git::operation_continue,
+            git::operation_skip,
+            git::operation_abort,
             git::stage_git_file,
             git::stage_git_all,
+            git::stage_git_pattern,
+            git::unstage_git_pattern,
             git::unstage_git_file,
             git::revert_git_file,
             git::revert_git_all,
+            git::restore_file_from_rev,
             git::commit_git,
+            git::commit_and_push,
+            git::commit_with_body,
+            git::reword_head_commit,
+            git::reset_head_author,
+            git::reset_head_date,
             git::push_git,
             git::pull_git,
             git::fetch_git,
+            git::fetch_git_unshallow,
+            git::fetch_all_git,
+            git::fetch_all_open_prs_as_branches,
             git::sync_git,
+            git::preview_sync_git,
             git::get_github_issues,
             git::get_github_pull_requests,
+            git::get_github_pull_request,
             git::get_github_pull_request_diff,
+            git::get_github_pull_request_raw_diff,
             git::get_github_pull_request_comments,
             workspaces::list_workspace_files,
+            workspaces::discover_workspaces,
             workspaces::read_workspace_file,
             workspaces::open_workspace_in,
+            workspaces::open_path,
             workspaces::get_open_app_icon,
             git::list_git_branches,
             git::checkout_git_branch,
+            git::checkout_commit,
+            git::list_recent_branches,
+            git::list_remote_branches,
+            git::prune_remote_branches,
+            git::get_git_undo_stack,
+            git::undo_last_git_action,
             git::create_git_branch,
+            git::get_default_branch,
+            git::move_changes_to_new_branch,
+            git::create_branch_at_head,
             codex::model_list,
             codex::account_rate_limits,
             codex::account_read,
             codex::codex_auth_store_read,
             codex::codex_auth_store_set_file,
+            codex::codex_auth_store_migrate,
             codex::codex_auth_profile_snapshot,
             codex::codex_auth_profile_apply,
             codex::codex_login,
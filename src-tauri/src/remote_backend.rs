@@ -1,21 +1,34 @@
+mod transport;
+
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::TcpStream;
 use tokio::sync::{mpsc, oneshot, Mutex};
 
+use crate::shared::compression_core::{decode_frame, COMPRESSION_GZIP};
 use crate::state::AppState;
 use crate::types::BackendMode;
+use transport::{transport_for, RemoteTransportConfig, TransportReader, TransportWriter};
 
-const DEFAULT_REMOTE_HOST: &str = "127.0.0.1:4732";
 const DISCONNECTED_MESSAGE: &str = "remote backend disconnected";
+const REMOTE_PROTOCOL_VERSION: u32 = 1;
 
 type PendingMap = HashMap<u64, oneshot::Sender<Result<Value, String>>>;
 
+/// Application-level heartbeat: TCP alone can take a very long time to
+/// notice a peer that vanished without closing the connection.
+#[derive(Debug, Clone, Copy)]
+struct KeepaliveConfig {
+    interval: Duration,
+    timeout: Duration,
+    max_missed: u32,
+}
+
 pub(crate) fn normalize_path_for_remote(path: String) -> String {
     let trimmed = path.trim();
     if trimmed.is_empty() {
@@ -117,25 +130,56 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
         }
     }
 
-    let (host, token) = {
+    let (provider, address, token, keepalive) = {
         let settings = state.app_settings.lock().await;
+        let keepalive = (settings.remote_keepalive_interval_secs > 0).then(|| KeepaliveConfig {
+            interval: Duration::from_secs(settings.remote_keepalive_interval_secs),
+            timeout: Duration::from_secs(settings.remote_keepalive_timeout_secs.max(1)),
+            max_missed: settings.remote_keepalive_max_missed.max(1),
+        });
         (
+            settings.remote_backend_provider,
             settings.remote_backend_host.clone(),
             settings.remote_backend_token.clone(),
+            keepalive,
         )
     };
 
-    let resolved_host = if host.trim().is_empty() {
-        DEFAULT_REMOTE_HOST.to_string()
-    } else {
-        host
-    };
+    let config = RemoteTransportConfig::from_settings(provider, &address);
+    let (reader, writer) = transport_for(provider).connect(&config).await?;
+    let client = spawn_transport_io(app, reader, writer, keepalive);
+
+    if let Some(token) = token {
+        client
+            .call(
+                "auth",
+                json!({
+                    "token": token,
+                    "protocolVersion": REMOTE_PROTOCOL_VERSION,
+                    "clientVersion": env!("CARGO_PKG_VERSION"),
+                    "compression": [COMPRESSION_GZIP],
+                }),
+            )
+            .await
+            .map(|_| ())?;
+    }
 
-    let stream = TcpStream::connect(resolved_host.clone())
-        .await
-        .map_err(|err| format!("Failed to connect to remote backend at {resolved_host}: {err}"))?;
-    let (reader, mut writer) = stream.into_split();
+    {
+        let mut guard = state.remote_backend.lock().await;
+        *guard = Some(client.clone());
+    }
 
+    Ok(client)
+}
+
+/// Starts the writer and reader tasks for a freshly connected transport and
+/// returns the client that talks through them.
+fn spawn_transport_io(
+    app: AppHandle,
+    reader: TransportReader,
+    mut writer: TransportWriter,
+    keepalive: Option<KeepaliveConfig>,
+) -> RemoteBackend {
     let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
     let pending = Arc::new(Mutex::new(PendingMap::new()));
     let pending_for_writer = Arc::clone(&pending);
@@ -160,15 +204,8 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
         }
     });
 
-    let app_for_reader = app.clone();
     let read_task = tokio::spawn(async move {
-        read_loop(
-            app_for_reader,
-            reader,
-            pending_for_reader,
-            connected_for_reader,
-        )
-        .await;
+        read_loop(app, reader, pending_for_reader, connected_for_reader).await;
     });
 
     let client = RemoteBackend {
@@ -180,26 +217,54 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
         }),
     };
 
-    if let Some(token) = token {
-        client
-            .call("auth", json!({ "token": token }))
-            .await
-            .map(|_| ())?;
+    if let Some(keepalive) = keepalive {
+        let tasks = [write_task.abort_handle(), read_task.abort_handle()];
+        tokio::spawn(keepalive_loop(Arc::downgrade(&client.inner), keepalive, tasks));
     }
 
-    {
-        let mut guard = state.remote_backend.lock().await;
-        *guard = Some(client.clone());
-    }
-
-    drop((write_task, read_task));
+    client
+}
 
-    Ok(client)
+/// Pings the daemon every `interval`. After `max_missed` consecutive pings
+/// fail or time out, the connection is declared dead: I/O tasks are stopped
+/// and in-flight calls fail, so the next `call_remote` reconnects. Exits once
+/// every client handle is gone.
+async fn keepalive_loop(
+    inner: Weak<RemoteBackendInner>,
+    config: KeepaliveConfig,
+    tasks: [tokio::task::AbortHandle; 2],
+) {
+    let mut missed = 0u32;
+    loop {
+        tokio::time::sleep(config.interval).await;
+        let Some(inner) = inner.upgrade() else {
+            break;
+        };
+        if !inner.connected.load(Ordering::SeqCst) {
+            break;
+        }
+        let client = RemoteBackend { inner };
+        match tokio::time::timeout(config.timeout, client.call("ping", json!({}))).await {
+            Ok(Ok(_)) => missed = 0,
+            _ => missed += 1,
+        }
+        if missed >= config.max_missed {
+            client.inner.connected.store(false, Ordering::SeqCst);
+            let mut pending = client.inner.pending.lock().await;
+            for (_, sender) in pending.drain() {
+                let _ = sender.send(Err(DISCONNECTED_MESSAGE.to_string()));
+            }
+            break;
+        }
+    }
+    for task in tasks {
+        task.abort();
+    }
 }
 
 async fn read_loop(
     app: AppHandle,
-    reader: tokio::net::tcp::OwnedReadHalf,
+    reader: TransportReader,
     pending: Arc<Mutex<PendingMap>>,
     connected: Arc<AtomicBool>,
 ) {
@@ -211,7 +276,11 @@ async fn read_loop(
             continue;
         }
 
-        let message: Value = match serde_json::from_str(trimmed) {
+        let decoded = match decode_frame(trimmed) {
+            Ok(decoded) => decoded,
+            Err(_) => continue,
+        };
+        let message: Value = match serde_json::from_str(&decoded) {
             Ok(value) => value,
             Err(_) => continue,
         };
@@ -254,6 +323,9 @@ async fn read_loop(
             "terminal-exit" => {
                 let _ = app.emit("terminal-exit", params);
             }
+            "git-diff-stream" => {
+                let _ = app.emit("git-diff-stream", params);
+            }
             _ => {}
         }
     }
@@ -0,0 +1,169 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+
+use crate::types::RemoteBackendProvider;
+
+pub(crate) const DEFAULT_REMOTE_HOST: &str = "127.0.0.1:4732";
+
+pub(crate) type TransportReader = Box<dyn AsyncRead + Send + Unpin>;
+pub(crate) type TransportWriter = Box<dyn AsyncWrite + Send + Unpin>;
+type ConnectFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(TransportReader, TransportWriter), String>> + Send + 'a>>;
+
+/// Where to reach the remote backend, one variant per provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RemoteTransportConfig {
+    Tcp { host: String },
+    Unix { path: PathBuf },
+}
+
+impl RemoteTransportConfig {
+    /// Interprets the `remoteBackendHost` setting for `provider`: a
+    /// `host:port` for TCP, a socket path for Unix.
+    pub(crate) fn from_settings(provider: RemoteBackendProvider, address: &str) -> Self {
+        let address = address.trim();
+        match provider {
+            RemoteBackendProvider::Tcp => RemoteTransportConfig::Tcp {
+                host: if address.is_empty() {
+                    DEFAULT_REMOTE_HOST.to_string()
+                } else {
+                    address.to_string()
+                },
+            },
+            RemoteBackendProvider::Unix => RemoteTransportConfig::Unix {
+                path: PathBuf::from(address),
+            },
+        }
+    }
+}
+
+/// Opens the byte stream the line-delimited JSON-RPC protocol runs over.
+pub(crate) trait RemoteTransport: Send + Sync {
+    fn connect<'a>(&'a self, config: &'a RemoteTransportConfig) -> ConnectFuture<'a>;
+}
+
+/// The single place providers are registered.
+pub(crate) fn transport_for(provider: RemoteBackendProvider) -> Box<dyn RemoteTransport> {
+    match provider {
+        RemoteBackendProvider::Tcp => Box::new(TcpTransport),
+        RemoteBackendProvider::Unix => Box::new(UnixTransport),
+    }
+}
+
+fn mismatched_config(transport: &str, config: &RemoteTransportConfig) -> String {
+    format!("{transport} transport cannot connect using {config:?}")
+}
+
+struct TcpTransport;
+
+impl RemoteTransport for TcpTransport {
+    fn connect<'a>(&'a self, config: &'a RemoteTransportConfig) -> ConnectFuture<'a> {
+        Box::pin(async move {
+            let RemoteTransportConfig::Tcp { host } = config else {
+                return Err(mismatched_config("TCP", config));
+            };
+            let stream = TcpStream::connect(host.as_str()).await.map_err(|err| {
+                format!("Failed to connect to remote backend at {host}: {err}")
+            })?;
+            let (reader, writer) = stream.into_split();
+            Ok((Box::new(reader) as TransportReader, Box::new(writer) as TransportWriter))
+        })
+    }
+}
+
+/// For daemons reached through a forwarded Unix socket (e.g. `ssh -L`),
+/// which keeps the daemon off any TCP port.
+struct UnixTransport;
+
+impl RemoteTransport for UnixTransport {
+    #[cfg(unix)]
+    fn connect<'a>(&'a self, config: &'a RemoteTransportConfig) -> ConnectFuture<'a> {
+        Box::pin(async move {
+            let RemoteTransportConfig::Unix { path } = config else {
+                return Err(mismatched_config("Unix socket", config));
+            };
+            if path.as_os_str().is_empty() {
+                return Err("Remote backend socket path is empty.".to_string());
+            }
+            let stream = tokio::net::UnixStream::connect(path).await.map_err(|err| {
+                format!(
+                    "Failed to connect to remote backend at {}: {err}",
+                    path.display()
+                )
+            })?;
+            let (reader, writer) = stream.into_split();
+            Ok((Box::new(reader) as TransportReader, Box::new(writer) as TransportWriter))
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn connect<'a>(&'a self, _config: &'a RemoteTransportConfig) -> ConnectFuture<'a> {
+        Box::pin(async {
+            Err("Unix socket transport is not supported on this platform.".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_from_settings_defaults_tcp_host() {
+        assert_eq!(
+            RemoteTransportConfig::from_settings(RemoteBackendProvider::Tcp, "  "),
+            RemoteTransportConfig::Tcp {
+                host: DEFAULT_REMOTE_HOST.to_string()
+            }
+        );
+        assert_eq!(
+            RemoteTransportConfig::from_settings(RemoteBackendProvider::Unix, "/tmp/daemon.sock"),
+            RemoteTransportConfig::Unix {
+                path: PathBuf::from("/tmp/daemon.sock")
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_transport_round_trips_lines() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("create runtime")
+            .block_on(async {
+                let path = std::env::temp_dir()
+                    .join(format!("codex-monitor-transport-{}.sock", uuid::Uuid::new_v4()));
+                let listener = tokio::net::UnixListener::bind(&path).expect("bind socket");
+                let server = tokio::spawn(async move {
+                    let (stream, _) = listener.accept().await.expect("accept");
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    let line = lines.next_line().await.expect("read").expect("line");
+                    writer
+                        .write_all(format!("{line}\n").as_bytes())
+                        .await
+                        .expect("echo");
+                });
+
+                let config = RemoteTransportConfig::Unix { path: path.clone() };
+                let tcp = transport_for(RemoteBackendProvider::Tcp);
+                assert!(tcp.connect(&config).await.is_err());
+
+                let transport = transport_for(RemoteBackendProvider::Unix);
+                let (reader, mut writer) = transport.connect(&config).await.expect("connect");
+                writer.write_all(b"{\"id\":1}\n").await.expect("write");
+                let mut lines = BufReader::new(reader).lines();
+                let echoed = lines.next_line().await.expect("read").expect("line");
+                assert_eq!(echoed, "{\"id\":1}");
+                server.await.expect("server");
+                let _ = std::fs::remove_file(&path);
+            });
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::codex::config as codex_config;
+use crate::remote_backend;
+use crate::shared::settings_core::reload_codex_config_core;
+use crate::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+pub(crate) const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+type ConfigFingerprint = Option<(SystemTime, u64)>;
+
+fn config_fingerprint(path: &Path) -> ConfigFingerprint {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Records `fingerprint` and reports whether the file changed since the last
+/// poll. The first poll only establishes a baseline.
+fn config_file_changed(
+    last_seen: &mut Option<ConfigFingerprint>,
+    fingerprint: ConfigFingerprint,
+) -> bool {
+    let first_poll = last_seen.is_none();
+    last_seen.replace(fingerprint) != Some(fingerprint) && !first_poll
+}
+
+/// Polls the global config.toml and reloads the settings it backs when the
+/// file changes on disk, emitting `settings-changed` with the new settings
+/// only if something the app shows actually moved.
+///
+/// Only the app runs this watcher. The daemon does not watch config.toml;
+/// remote clients pick up outside edits by calling `reload_codex_config`.
+pub(crate) fn spawn_codex_config_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Outer `None` means nothing observed yet; inner `None` means no file.
+        let mut last_seen: Option<ConfigFingerprint> = None;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let state = app.state::<AppState>();
+            if remote_backend::is_remote_mode(&*state).await {
+                last_seen = None;
+                continue;
+            }
+            let path = {
+                let settings = state.app_settings.lock().await;
+                codex_config::config_toml_path_with_settings(Some(&settings))
+            };
+            let fingerprint = path.as_deref().and_then(config_fingerprint);
+            if !config_file_changed(&mut last_seen, fingerprint) {
+                continue;
+            }
+            if let Ok(reload) = reload_codex_config_core(&state.app_settings).await {
+                if reload.changed {
+                    let _ = app.emit(SETTINGS_CHANGED_EVENT, &reload.settings);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::config_file_changed;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn config_file_changed_skips_the_baseline_and_repeats() {
+        let at = |secs| Some((SystemTime::UNIX_EPOCH + Duration::from_secs(secs), 10));
+        let mut last_seen = None;
+        assert!(!config_file_changed(&mut last_seen, at(1)));
+        assert!(!config_file_changed(&mut last_seen, at(1)));
+        assert!(config_file_changed(&mut last_seen, at(2)));
+        assert!(config_file_changed(&mut last_seen, None));
+        assert!(!config_file_changed(&mut last_seen, None));
+        assert!(config_file_changed(&mut last_seen, at(3)));
+    }
+}
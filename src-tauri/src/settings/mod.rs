@@ -1,15 +1,21 @@
-use tauri::{Manager, State, Window};
+use tauri::{Emitter, Manager, State, Window};
 
 use crate::remote_backend;
 use crate::state::AppState;
 use crate::shared::settings_core::{
-    get_app_settings_core, get_codex_config_path_core, update_app_settings_core,
+    diff_config_core, export_settings_profile_core, get_app_settings_core,
+    get_codex_config_path_core, get_effective_config_core, list_feature_flags_core,
+    read_feature_flag_core, read_project_trust_core, reload_codex_config_core,
+    settings_from_profile_core, update_app_settings_core, write_feature_flag_core,
+    write_project_trust_core,
 };
 use crate::shared::workspaces_core;
-use crate::types::AppSettings;
+use crate::types::{AppSettings, CodexConfigReload, FeatureFlag, SettingsProfileExport};
 use crate::window;
 use crate::codex::spawn_workspace_session;
 
+pub(crate) mod config_watcher;
+
 #[tauri::command]
 pub(crate) async fn get_app_settings(
     state: State<'_, AppState>,
@@ -67,8 +73,81 @@ pub(crate) async fn update_app_settings(
     Ok(updated)
 }
 
+/// Re-reads config.toml into the in-memory settings and broadcasts them with
+/// `settings-changed`.
+#[tauri::command]
+pub(crate) async fn reload_codex_config(
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<CodexConfigReload, String> {
+    let reload = if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "reload_codex_config",
+            serde_json::Value::Null,
+        )
+        .await?;
+        serde_json::from_value(response).map_err(|err| err.to_string())?
+    } else {
+        reload_codex_config_core(&state.app_settings).await?
+    };
+    let _ = window
+        .app_handle()
+        .emit(config_watcher::SETTINGS_CHANGED_EVENT, &reload.settings);
+    Ok(reload)
+}
+
+#[tauri::command]
+pub(crate) async fn export_settings_profile(
+    include_secrets: Option<bool>,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<SettingsProfileExport, String> {
+    let include_secrets = include_secrets.unwrap_or(false);
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "export_settings_profile",
+            serde_json::json!({ "includeSecrets": include_secrets }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+    export_settings_profile_core(&state.app_settings, include_secrets).await
+}
+
+/// Applies an exported profile like a regular settings update, so config.toml
+/// writes and session respawns happen as usual.
+#[tauri::command]
+pub(crate) async fn import_settings_profile(
+    profile: serde_json::Value,
+    merge: Option<bool>,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<AppSettings, String> {
+    let merge = merge.unwrap_or(true);
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "import_settings_profile",
+            serde_json::json!({ "profile": profile, "merge": merge }),
+        )
+        .await?;
+        let updated: AppSettings =
+            serde_json::from_value(response).map_err(|err| err.to_string())?;
+        let _ = window::apply_window_appearance(&window, updated.theme.as_str());
+        return Ok(updated);
+    }
+    let settings = settings_from_profile_core(&state.app_settings, &profile, merge).await?;
+    update_app_settings(settings, state, window).await
+}
+
 #[tauri::command]
 pub(crate) async fn get_codex_config_path(
+    workspace_id: Option<String>,
     state: State<'_, AppState>,
     window: Window,
 ) -> Result<String, String> {
@@ -77,11 +156,152 @@ pub(crate) async fn get_codex_config_path(
             &*state,
             window.app_handle().clone(),
             "get_codex_config_path",
+            serde_json::json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    get_codex_config_path_core(&state.workspaces, &state.app_settings, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_effective_config(
+    workspace_id: Option<String>,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "get_effective_config",
+            serde_json::json!({ "workspaceId": workspace_id }),
+        )
+        .await;
+    }
+
+    get_effective_config_core(&state.workspaces, &state.app_settings, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn diff_codex_config(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "diff_codex_config",
+            serde_json::json!({ "workspaceId": workspace_id }),
+        )
+        .await;
+    }
+
+    diff_config_core(&state.workspaces, &state.app_settings, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_project_trust(
+    path: String,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<Option<bool>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "get_project_trust",
+            serde_json::json!({ "path": path }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    read_project_trust_core(&state.app_settings, path).await
+}
+
+/// All boolean flags in config.toml's `[features]`, including ones this app
+/// has no dedicated setting for.
+#[tauri::command]
+pub(crate) async fn list_feature_flags(
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<Vec<FeatureFlag>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "list_feature_flags",
             serde_json::Value::Null,
         )
         .await?;
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    get_codex_config_path_core(&state.app_settings).await
+    list_feature_flags_core(&state.app_settings).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_feature_flag(
+    key: String,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<Option<bool>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "get_feature_flag",
+            serde_json::json!({ "key": key }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    read_feature_flag_core(&state.app_settings, key).await
+}
+
+#[tauri::command]
+pub(crate) async fn set_feature_flag(
+    key: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "set_feature_flag",
+            serde_json::json!({ "key": key, "enabled": enabled }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    write_feature_flag_core(&state.app_settings, key, enabled).await
+}
+
+#[tauri::command]
+pub(crate) async fn set_project_trust(
+    path: String,
+    trusted: bool,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            window.app_handle().clone(),
+            "set_project_trust",
+            serde_json::json!({ "path": path, "trusted": trusted }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    write_project_trust_core(&state.app_settings, path, trusted).await
 }
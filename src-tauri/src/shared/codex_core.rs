@@ -13,7 +13,7 @@ use tokio::time::timeout;
 use tokio::time::Instant;
 
 use crate::backend::app_server::WorkspaceSession;
-use crate::codex::config as codex_config;
+use crate::codex::config::{self as codex_config, AuthStore};
 use crate::codex::home::{
     normalize_codex_home, resolve_default_codex_home_with_settings,
     resolve_workspace_codex_home_with_settings,
@@ -328,6 +328,59 @@ pub(crate) async fn auth_store_set_file_core(
     Ok(json!({ "ok": true }))
 }
 
+pub(crate) async fn auth_store_migrate_core(
+    app_settings: &Mutex<AppSettings>,
+    target: String,
+) -> Result<Value, String> {
+    let target = AuthStore::parse(&target)?;
+    let settings = app_settings.lock().await.clone();
+    // Codex treats a missing key as the file store.
+    let current = codex_config::read_auth_store_with_settings(Some(&settings))?
+        .map(|value| AuthStore::parse(&value))
+        .transpose()?
+        .unwrap_or(AuthStore::File);
+    if current == target {
+        return Ok(json!({
+            "ok": true,
+            "changed": false,
+            "from": current.as_str(),
+            "to": target.as_str(),
+            "warnings": [],
+        }));
+    }
+
+    let auth_file_exists = resolve_default_codex_home_with_settings(Some(&settings))
+        .map(|home| home.join("auth.json").is_file())
+        .unwrap_or(false);
+    let mut warnings = Vec::new();
+    match (current, target) {
+        (AuthStore::File, AuthStore::Keyring) if auth_file_exists => warnings.push(
+            "Existing auth.json credentials will be ignored by the keyring store. Run `codex login` to store them in the keyring."
+                .to_string(),
+        ),
+        (AuthStore::Keyring, AuthStore::File) if !auth_file_exists => warnings.push(
+            "Credentials saved in the keyring are not copied to auth.json. Run `codex login` to sign in with the file store."
+                .to_string(),
+        ),
+        _ => {}
+    }
+
+    match target {
+        AuthStore::File => codex_config::write_auth_store_file_with_settings(Some(&settings))?,
+        AuthStore::Keyring => {
+            codex_config::write_auth_store_keyring_with_settings(Some(&settings))?
+        }
+        AuthStore::Auto => codex_config::write_auth_store_auto_with_settings(Some(&settings))?,
+    }
+    Ok(json!({
+        "ok": true,
+        "changed": true,
+        "from": current.as_str(),
+        "to": target.as_str(),
+        "warnings": warnings,
+    }))
+}
+
 pub(crate) async fn auth_profile_snapshot_core(
     app_settings: &Mutex<AppSettings>,
     profile_id: String,
@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::io::{Read, Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+/// Marker for gzip-compressed, base64-encoded frames on the line-delimited
+/// daemon transport. Plain JSON frames never start with `~`.
+pub(crate) const COMPRESSED_FRAME_PREFIX: &str = "~gz:";
+pub(crate) const COMPRESSION_GZIP: &str = "gzip";
+/// Frames smaller than this are sent as-is; compressing them costs more than it saves.
+pub(crate) const COMPRESSION_THRESHOLD_BYTES: usize = 16 * 1024;
+
+pub(crate) fn encode_frame(payload: String) -> String {
+    if payload.len() < COMPRESSION_THRESHOLD_BYTES {
+        return payload;
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    if encoder.write_all(payload.as_bytes()).is_err() {
+        return payload;
+    }
+    let Ok(compressed) = encoder.finish() else {
+        return payload;
+    };
+    let encoded = STANDARD.encode(compressed);
+    if encoded.len() + COMPRESSED_FRAME_PREFIX.len() >= payload.len() {
+        return payload;
+    }
+    format!("{COMPRESSED_FRAME_PREFIX}{encoded}")
+}
+
+pub(crate) fn decode_frame(line: &str) -> Result<Cow<'_, str>, String> {
+    let Some(encoded) = line.strip_prefix(COMPRESSED_FRAME_PREFIX) else {
+        return Ok(Cow::Borrowed(line));
+    };
+    let compressed = STANDARD
+        .decode(encoded.trim())
+        .map_err(|err| format!("invalid compressed frame: {err}"))?;
+    let mut decoded = String::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut decoded)
+        .map_err(|err| format!("invalid compressed frame: {err}"))?;
+    Ok(Cow::Owned(decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_frames_are_left_uncompressed() {
+        let payload = "{\"method\":\"ping\"}".to_string();
+        assert_eq!(encode_frame(payload.clone()), payload);
+    }
+
+    #[test]
+    fn large_frames_round_trip() {
+        let payload = format!("{{\"diff\":\"{}\"}}", "+line\\n".repeat(10_000));
+        let encoded = encode_frame(payload.clone());
+        assert!(encoded.starts_with(COMPRESSED_FRAME_PREFIX));
+        assert!(encoded.len() < payload.len());
+        assert_eq!(decode_frame(&encoded).expect("decode"), payload);
+    }
+
+    #[test]
+    fn plain_frames_decode_as_is() {
+        let line = "{\"id\":1,\"result\":null}";
+        assert_eq!(decode_frame(line).expect("decode"), line);
+    }
+}
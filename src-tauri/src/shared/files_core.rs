@@ -1,14 +1,31 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
 use crate::codex::home as codex_home;
-use crate::files::io::TextFileResponse;
-use crate::files::ops::{read_with_policy, write_with_policy};
+use crate::files::io::{
+    read_text_file_range_within, tail_text_file_within, TailFileResponse, TextFileRangeResponse,
+    TextFileResponse,
+};
+use crate::files::ops::{
+    read_with_policy, resolve_write_target_with_policy, write_files_atomically, write_with_policy,
+};
 use crate::files::policy::{policy_for, FileKind, FileScope};
 use crate::types::{AppSettings, WorkspaceEntry};
 
+/// One file in a `write_files_transaction_core` batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileEdit {
+    pub(crate) scope: FileScope,
+    pub(crate) kind: FileKind,
+    #[serde(default)]
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) content: String,
+}
+
 async fn resolve_default_codex_home(
     app_settings: &Mutex<AppSettings>,
 ) -> Result<PathBuf, String> {
@@ -56,6 +73,34 @@ pub(crate) async fn file_read_core(
     read_with_policy(&root, policy)
 }
 
+/// Reads one page of a policy-managed file, so large files can be paged
+/// instead of loaded whole.
+pub(crate) async fn file_read_range_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    scope: FileScope,
+    kind: FileKind,
+    workspace_id: Option<String>,
+    offset: u64,
+    length: u64,
+) -> Result<TextFileRangeResponse, String> {
+    let policy = policy_for(scope, kind)?;
+    let root = resolve_root_core(workspaces, app_settings, scope, workspace_id.as_deref()).await?;
+    tokio::task::spawn_blocking(move || {
+        read_text_file_range_within(
+            &root,
+            policy.filename,
+            offset,
+            length,
+            policy.root_may_be_missing,
+            policy.root_context,
+            policy.allow_external_symlink_target,
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
 pub(crate) async fn file_write_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     app_settings: &Mutex<AppSettings>,
@@ -68,3 +113,70 @@ pub(crate) async fn file_write_core(
     let root = resolve_root_core(workspaces, app_settings, scope, workspace_id.as_deref()).await?;
     write_with_policy(&root, policy, &content)
 }
+
+/// Follows a growing file from `from_offset`. The file is either a
+/// policy-managed `kind` or, in workspace scope only, a `path` relative to the
+/// workspace root (e.g. a log); exactly one must be given. Global scope holds
+/// credentials such as auth.json, so it only serves policy-managed files.
+pub(crate) async fn tail_file_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    scope: FileScope,
+    kind: Option<FileKind>,
+    workspace_id: Option<String>,
+    path: Option<String>,
+    from_offset: u64,
+) -> Result<TailFileResponse, String> {
+    let (relative, root_may_be_missing, root_context, allow_external_symlink_target) =
+        match (kind, path) {
+            (Some(kind), None) => {
+                let policy = policy_for(scope, kind)?;
+                (
+                    policy.filename.to_string(),
+                    policy.root_may_be_missing,
+                    policy.root_context,
+                    policy.allow_external_symlink_target,
+                )
+            }
+            (None, Some(_)) if scope == FileScope::Global => {
+                return Err("Global files can only be tailed by `kind`".to_string())
+            }
+            (None, Some(path)) => (path, false, "workspace root", false),
+            _ => return Err("Specify exactly one of `kind` or `path`".to_string()),
+        };
+    let root = resolve_root_core(workspaces, app_settings, scope, workspace_id.as_deref()).await?;
+    tokio::task::spawn_blocking(move || {
+        tail_text_file_within(
+            &root,
+            &relative,
+            from_offset,
+            root_may_be_missing,
+            root_context,
+            allow_external_symlink_target,
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+/// Saves several policy-managed files all-or-nothing, e.g. config.toml and
+/// AGENTS.md edited together.
+pub(crate) async fn write_files_transaction_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    edits: Vec<FileEdit>,
+) -> Result<(), String> {
+    if edits.is_empty() {
+        return Ok(());
+    }
+    let mut writes = Vec::with_capacity(edits.len());
+    for edit in edits {
+        let policy = policy_for(edit.scope, edit.kind)?;
+        let root =
+            resolve_root_core(workspaces, app_settings, edit.scope, edit.workspace_id.as_deref())
+                .await?;
+        let target = resolve_write_target_with_policy(&root, policy)?;
+        writes.push((target, edit.content));
+    }
+    write_files_atomically(&writes)
+}
@@ -1,9 +1,83 @@
 #![allow(dead_code)]
 
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
 
-use crate::shared::process_core::tokio_command;
-use crate::utils::{git_env_path, resolve_git_binary};
+use globset::Glob;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde_json::{json, Value};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::shared::process_core::{tokio_command, workspace_env_for};
+use crate::utils::{git_env_path, is_executable, resolve_git_binary};
+
+pub(crate) const DEFAULT_MAX_CONCURRENT_GIT_OPS: usize = 8;
+
+/// Bounds how many git subprocesses and git2-heavy blocking jobs run at once
+/// across all workspaces. Process-wide because `run_git_command` is a free
+/// function shared by the app and the daemon.
+pub(crate) struct GitOpLimiter {
+    semaphore: Arc<Semaphore>,
+    limit: StdMutex<usize>,
+}
+
+impl GitOpLimiter {
+    fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limit: StdMutex::new(limit),
+        }
+    }
+
+    pub(crate) async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.semaphore.clone().acquire_owned().await.ok()
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        *self.limit.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Resizes the pool in place. Shrinking below the number of running
+    /// operations retires their permits as they finish instead of blocking.
+    pub(crate) fn set_limit(&self, limit: usize) {
+        let limit = limit.max(1);
+        let mut current = self.limit.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if limit > *current {
+            self.semaphore.add_permits(limit - *current);
+        } else if limit < *current {
+            let shrink = *current - limit;
+            let pending = shrink - self.semaphore.forget_permits(shrink);
+            if pending > 0 {
+                let semaphore = self.semaphore.clone();
+                if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                    handle.spawn(async move {
+                        if let Ok(permits) = semaphore.acquire_many_owned(pending as u32).await {
+                            permits.forget();
+                        }
+                    });
+                }
+            }
+        }
+        *current = limit;
+    }
+}
+
+pub(crate) fn git_op_limiter() -> &'static GitOpLimiter {
+    static LIMITER: OnceLock<GitOpLimiter> = OnceLock::new();
+    LIMITER.get_or_init(|| GitOpLimiter::new(DEFAULT_MAX_CONCURRENT_GIT_OPS))
+}
+
+/// `spawn_blocking` that first waits for a slot in the global git limiter.
+pub(crate) async fn spawn_git_blocking<F, T>(work: F) -> Result<T, tokio::task::JoinError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let _permit = git_op_limiter().acquire().await;
+    tokio::task::spawn_blocking(work).await
+}
 
 fn format_git_error(stdout: &[u8], stderr: &[u8]) -> String {
     let stderr = String::from_utf8_lossy(stderr);
@@ -20,12 +94,217 @@ fn format_git_error(stdout: &[u8], stderr: &[u8]) -> String {
     }
 }
 
+/// Reports how git was resolved so a broken git panel can point the user at
+/// the actual cause instead of a bare "Failed to run git".
+pub(crate) async fn diagnose_git_core() -> Value {
+    let env_path = git_env_path();
+    let resolved = resolve_git_binary();
+    let (path, resolve_error) = match &resolved {
+        Ok(path) => (Some(path.clone()), None),
+        Err(error) => (None, Some(error.clone())),
+    };
+    let executable = path.as_deref().map(is_executable).unwrap_or(false);
+    let on_path = path
+        .as_deref()
+        .and_then(|path| path.parent())
+        .map(|dir| std::env::split_paths(&env_path).any(|entry| entry == dir))
+        .unwrap_or(false);
+
+    let (version, version_error) = match path.as_ref() {
+        Some(path) => match tokio_command(path)
+            .arg("--version")
+            .env("PATH", &env_path)
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => (
+                Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+                None,
+            ),
+            Ok(output) => (
+                None,
+                Some(format_git_error(&output.stdout, &output.stderr)),
+            ),
+            Err(err) => (None, Some(err.to_string())),
+        },
+        None => (None, None),
+    };
+
+    let guidance = if path.is_none() {
+        Some("git not found on PATH; install it or add its directory to PATH.".to_string())
+    } else if !executable {
+        Some("git was found but is not executable; check its file permissions.".to_string())
+    } else if version.is_none() {
+        Some("git was found but `git --version` failed; reinstall git.".to_string())
+    } else if !on_path {
+        Some(
+            "git was found outside PATH; terminals and Codex may not see the same git."
+                .to_string(),
+        )
+    } else {
+        None
+    };
+
+    json!({
+        "ok": version.is_some(),
+        "path": path.map(|path| path.to_string_lossy().to_string()),
+        "resolveError": resolve_error,
+        "executable": executable,
+        "version": version,
+        "versionError": version_error,
+        "onPath": on_path,
+        "envPath": env_path,
+        "guidance": guidance,
+    })
+}
+
+pub(crate) const PROTECTED_BRANCH_ERROR: &str = "ProtectedBranch";
+
+/// Returns the first protected pattern matching `branch`. An invalid pattern
+/// is an error rather than a silent non-match, so a typo can't unprotect a
+/// branch.
+pub(crate) fn protected_branch_pattern<'a>(
+    patterns: &'a [String],
+    branch: &str,
+) -> Result<Option<&'a str>, String> {
+    for pattern in protected_patterns(patterns) {
+        if protected_glob(pattern)?.compile_matcher().is_match(branch) {
+            return Ok(Some(pattern));
+        }
+    }
+    Ok(None)
+}
+
+pub(crate) fn validate_protected_branches(patterns: &[String]) -> Result<(), String> {
+    protected_patterns(patterns).try_for_each(|pattern| protected_glob(pattern).map(|_| ()))
+}
+
+fn protected_patterns(patterns: &[String]) -> impl Iterator<Item = &str> {
+    patterns
+        .iter()
+        .map(|pattern| pattern.trim())
+        .filter(|pattern| !pattern.is_empty())
+}
+
+fn protected_glob(pattern: &str) -> Result<Glob, String> {
+    Glob::new(pattern).map_err(|err| format!("Invalid protected branch pattern `{pattern}`: {err}"))
+}
+
+/// Refuses destructive operations on protected branches unless the caller
+/// explicitly overrides the protection.
+pub(crate) fn ensure_branch_unprotected(
+    patterns: &[String],
+    branch: Option<&str>,
+    override_protection: bool,
+) -> Result<(), String> {
+    if override_protection {
+        return Ok(());
+    }
+    let Some(branch) = branch else {
+        return Ok(());
+    };
+    match protected_branch_pattern(patterns, branch)? {
+        Some(pattern) => Err(format!(
+            "{PROTECTED_BRANCH_ERROR}: `{branch}` matches protected pattern `{pattern}`. Pass overrideProtection to continue."
+        )),
+        None => Ok(()),
+    }
+}
+
+fn parse_gh_auth_login(output: &str) -> Option<String> {
+    // `gh auth status` prints "Logged in to github.com account <login>" (newer)
+    // or "Logged in to github.com as <login>" (older releases).
+    output.lines().find_map(|line| {
+        let (_, rest) = line.split_once("Logged in to ")?;
+        let rest = rest
+            .split_once(" account ")
+            .or_else(|| rest.split_once(" as "))
+            .map(|(_, login)| login)?;
+        let login = rest.split_whitespace().next()?;
+        let login = login.trim_matches(|ch: char| !ch.is_alphanumeric() && ch != '-');
+        (!login.is_empty()).then(|| login.to_string())
+    })
+}
+
+/// Checks that the GitHub CLI is installed and authenticated so the GitHub
+/// panels can show one actionable state instead of failing on every call.
+pub(crate) async fn diagnose_github_core() -> Value {
+    let version_output = match tokio_command("gh")
+        .arg("--version")
+        .env("PATH", git_env_path())
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(err) => {
+            return json!({
+                "installed": false,
+                "authenticated": false,
+                "login": Value::Null,
+                "version": Value::Null,
+                "error": format!(
+                    "GitHub CLI (gh) not found: {err}. Install it from https://cli.github.com."
+                ),
+            });
+        }
+    };
+    let version = String::from_utf8_lossy(&version_output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string());
+
+    let auth_output = tokio_command("gh")
+        .args(["auth", "status"])
+        .env("PATH", git_env_path())
+        .output()
+        .await;
+    let (authenticated, login, error) = match auth_output {
+        Ok(output) => {
+            let combined = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            if output.status.success() {
+                (true, parse_gh_auth_login(&combined), None)
+            } else {
+                (
+                    false,
+                    None,
+                    Some("GitHub CLI is not authenticated. Run `gh auth login`.".to_string()),
+                )
+            }
+        }
+        Err(err) => (false, None, Some(format!("Failed to run gh: {err}"))),
+    };
+
+    json!({
+        "installed": true,
+        "authenticated": authenticated,
+        "login": login,
+        "version": version,
+        "error": error,
+    })
+}
+
 pub(crate) async fn run_git_command(repo_path: &PathBuf, args: &[&str]) -> Result<String, String> {
+    run_git_command_with_env(repo_path, args, &[]).await
+}
+
+/// `run_git_command` with extra environment variables, e.g. `GIT_INDEX_FILE`.
+pub(crate) async fn run_git_command_with_env(
+    repo_path: &PathBuf,
+    args: &[&str],
+    extra_env: &[(&str, &OsStr)],
+) -> Result<String, String> {
     let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+    let _permit = git_op_limiter().acquire().await;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_path)
+        .envs(workspace_env_for(repo_path))
         .env("PATH", git_env_path())
+        .envs(extra_env.iter().copied())
         .output()
         .await
         .map_err(|err| format!("Failed to run git: {err}"))?;
@@ -51,9 +330,11 @@ pub(crate) async fn run_git_command_bytes(
     args: &[&str],
 ) -> Result<Vec<u8>, String> {
     let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+    let _permit = git_op_limiter().acquire().await;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_path)
+        .envs(workspace_env_for(repo_path))
         .env("PATH", git_env_path())
         .output()
         .await
@@ -66,9 +347,11 @@ pub(crate) async fn run_git_command_bytes(
 
 pub(crate) async fn run_git_diff(repo_path: &PathBuf, args: &[&str]) -> Result<Vec<u8>, String> {
     let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+    let _permit = git_op_limiter().acquire().await;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_path)
+        .envs(workspace_env_for(repo_path))
         .env("PATH", git_env_path())
         .output()
         .await
@@ -88,6 +371,7 @@ pub(crate) async fn git_branch_exists(repo_path: &PathBuf, branch: &str) -> Resu
     let status = tokio_command(git_bin)
         .args(["show-ref", "--verify", &format!("refs/heads/{branch}")])
         .current_dir(repo_path)
+        .envs(workspace_env_for(repo_path))
         .env("PATH", git_env_path())
         .status()
         .await
@@ -100,6 +384,7 @@ pub(crate) async fn git_remote_exists(repo_path: &PathBuf, remote: &str) -> Resu
     let status = tokio_command(git_bin)
         .args(["remote", "get-url", remote])
         .current_dir(repo_path)
+        .envs(workspace_env_for(repo_path))
         .env("PATH", git_env_path())
         .status()
         .await
@@ -113,6 +398,7 @@ pub(crate) async fn git_remote_branch_exists_live(
     branch: &str,
 ) -> Result<bool, String> {
     let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+    let _permit = git_op_limiter().acquire().await;
     let output = tokio_command(git_bin)
         .args([
             "ls-remote",
@@ -121,6 +407,7 @@ pub(crate) async fn git_remote_branch_exists_live(
             &format!("refs/heads/{branch}"),
         ])
         .current_dir(repo_path)
+        .envs(workspace_env_for(repo_path))
         .env("PATH", git_env_path())
         .output()
         .await
@@ -144,6 +431,7 @@ pub(crate) async fn git_remote_branch_exists_local(
             &format!("refs/remotes/{remote}/{branch}"),
         ])
         .current_dir(repo_path)
+        .envs(workspace_env_for(repo_path))
         .env("PATH", git_env_path())
         .status()
         .await
@@ -239,3 +527,200 @@ pub(crate) async fn git_get_origin_url(repo_path: &PathBuf) -> Option<String> {
         .await
         .ok()
 }
+
+pub(crate) fn normalize_diff_exclude_globs(globs: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for glob in globs {
+        let trimmed = glob.trim();
+        if trimmed.is_empty() || normalized.iter().any(|existing| existing == trimmed) {
+            continue;
+        }
+        normalized.push(trimmed.to_string());
+    }
+    normalized
+}
+
+/// The workspace `diffExcludeGlobs` overlay. Every glob goes through a
+/// gitignore-style matcher; literal names and paths are also handed to libgit2
+/// as negative pathspecs, so a status or diff walk skips them (e.g. a large
+/// untracked `node_modules`) instead of filtering them out afterwards.
+/// Wildcard globs stay matcher-only because pathspec wildcards do not follow
+/// gitignore rules.
+pub(crate) struct DiffExcludeMatcher {
+    matcher: Gitignore,
+    pathspecs: Vec<String>,
+}
+
+impl DiffExcludeMatcher {
+    /// Negative pathspecs followed by a match-all entry (libgit2 uses the
+    /// first pathspec that matches a path). Empty when no glob is literal.
+    pub(crate) fn pathspecs(&self) -> &[String] {
+        &self.pathspecs
+    }
+}
+
+/// Pathspecs excluding what the gitignore-style `glob` excludes, or nothing
+/// when the glob has wildcards. An unanchored name matches at any depth.
+fn literal_exclude_pathspecs(glob: &str) -> Vec<String> {
+    let glob = glob.trim();
+    let dir_only = glob.ends_with('/');
+    let path = glob.trim_end_matches('/');
+    let anchored = path.contains('/');
+    let path = path.trim_start_matches('/');
+    if path.is_empty() || path.contains(['*', '?', '[', '\\']) {
+        return Vec::new();
+    }
+    let mut specs = Vec::new();
+    if dir_only {
+        specs.push(format!("!{path}/*"));
+    } else {
+        specs.push(format!("!{path}"));
+    }
+    if !anchored {
+        specs.push(format!("!*/{path}/*"));
+        if !dir_only {
+            specs.push(format!("!*/{path}"));
+        }
+    }
+    specs
+}
+
+/// Builds the matcher for the workspace `diffExcludeGlobs` overlay. Returns
+/// `None` when no globs are configured so callers can skip matching.
+pub(crate) fn build_diff_exclude_matcher(
+    repo_root: &Path,
+    globs: &[String],
+) -> Result<Option<DiffExcludeMatcher>, String> {
+    if globs.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GitignoreBuilder::new(repo_root);
+    let mut pathspecs = Vec::new();
+    for glob in globs {
+        if glob.starts_with('!') {
+            return Err(format!(
+                "Invalid diff exclude glob `{glob}`: negated patterns are not supported."
+            ));
+        }
+        builder
+            .add_line(None, glob)
+            .map_err(|err| format!("Invalid diff exclude glob `{glob}`: {err}"))?;
+        pathspecs.extend(literal_exclude_pathspecs(glob));
+    }
+    let matcher = builder
+        .build()
+        .map_err(|err| format!("Invalid diff exclude globs: {err}"))?;
+    if !pathspecs.is_empty() {
+        pathspecs.push("*".to_string());
+    }
+    Ok(Some(DiffExcludeMatcher { matcher, pathspecs }))
+}
+
+pub(crate) fn validate_diff_exclude_globs(globs: &[String]) -> Result<(), String> {
+    build_diff_exclude_matcher(Path::new(""), globs).map(|_| ())
+}
+
+pub(crate) fn is_diff_excluded(matcher: Option<&DiffExcludeMatcher>, path: &str) -> bool {
+    let Some(matcher) = matcher else {
+        return false;
+    };
+    matcher
+        .matcher
+        .matched_path_or_any_parents(Path::new(path), false)
+        .is_ignore()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_op_limiter_resizes_at_runtime() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("create runtime");
+        runtime.block_on(async {
+            let limiter = GitOpLimiter::new(2);
+            let first = limiter.acquire().await.expect("first permit");
+            let _second = limiter.acquire().await.expect("second permit");
+            assert_eq!(limiter.semaphore.available_permits(), 0);
+
+            limiter.set_limit(4);
+            assert_eq!(limiter.limit(), 4);
+            assert_eq!(limiter.semaphore.available_permits(), 2);
+
+            limiter.set_limit(1);
+            assert_eq!(limiter.limit(), 1);
+            assert_eq!(limiter.semaphore.available_permits(), 0);
+            drop(first);
+            tokio::task::yield_now().await;
+            assert_eq!(limiter.semaphore.available_permits(), 0);
+
+            limiter.set_limit(0);
+            assert_eq!(limiter.limit(), 1);
+        });
+    }
+
+    #[test]
+    fn protected_branches_match_globs_and_allow_override() {
+        let patterns = vec!["main".to_string(), "release/*".to_string()];
+        assert_eq!(protected_branch_pattern(&patterns, "main"), Ok(Some("main")));
+        assert_eq!(
+            protected_branch_pattern(&patterns, "release/1.2"),
+            Ok(Some("release/*"))
+        );
+        assert_eq!(protected_branch_pattern(&patterns, "feature/main"), Ok(None));
+        assert!(validate_protected_branches(&patterns).is_ok());
+
+        let invalid = vec!["release/[".to_string()];
+        assert!(protected_branch_pattern(&invalid, "release/[").is_err());
+        assert!(validate_protected_branches(&invalid).is_err());
+        assert!(ensure_branch_unprotected(&invalid, Some("main"), false).is_err());
+
+        let error = ensure_branch_unprotected(&patterns, Some("main"), false)
+            .expect_err("main is protected");
+        assert!(error.starts_with(PROTECTED_BRANCH_ERROR));
+        assert!(ensure_branch_unprotected(&patterns, Some("main"), true).is_ok());
+        assert!(ensure_branch_unprotected(&patterns, None, false).is_ok());
+    }
+
+    #[test]
+    fn parse_gh_auth_login_handles_old_and_new_output() {
+        let newer = "github.com\n  ✓ Logged in to github.com account octo-cat (keyring)\n";
+        assert_eq!(parse_gh_auth_login(newer), Some("octo-cat".to_string()));
+        let older = "github.com\n  ✓ Logged in to github.com as octocat (oauth_token)\n";
+        assert_eq!(parse_gh_auth_login(older), Some("octocat".to_string()));
+        assert_eq!(parse_gh_auth_login("You are not logged into any GitHub hosts."), None);
+    }
+
+    #[test]
+    fn diff_exclude_matcher_matches_nested_paths() {
+        let globs = vec!["node_modules".to_string(), "*.generated.ts".to_string()];
+        let matcher = build_diff_exclude_matcher(Path::new("/repo"), &globs)
+            .expect("build matcher");
+        assert!(is_diff_excluded(matcher.as_ref(), "node_modules/pkg/index.js"));
+        assert!(is_diff_excluded(matcher.as_ref(), "web/node_modules/pkg/index.js"));
+        assert!(is_diff_excluded(matcher.as_ref(), "src/api.generated.ts"));
+        assert!(!is_diff_excluded(matcher.as_ref(), "src/api.ts"));
+        assert_eq!(
+            matcher.expect("matcher").pathspecs(),
+            ["!node_modules", "!*/node_modules/*", "!*/node_modules", "*"]
+        );
+    }
+
+    #[test]
+    fn literal_exclude_pathspecs_skip_wildcards() {
+        assert_eq!(literal_exclude_pathspecs("/dist/gen"), vec!["!dist/gen"]);
+        assert_eq!(literal_exclude_pathspecs("target/"), vec!["!target/*", "!*/target/*"]);
+        assert!(literal_exclude_pathspecs("*.generated.ts").is_empty());
+        assert!(literal_exclude_pathspecs("src/[ab].rs").is_empty());
+    }
+
+    #[test]
+    fn validate_diff_exclude_globs_rejects_invalid_patterns() {
+        assert!(validate_diff_exclude_globs(&["target/".to_string()]).is_ok());
+        assert!(validate_diff_exclude_globs(&["src/[".to_string()]).is_err());
+        assert!(validate_diff_exclude_globs(&["!keep".to_string()]).is_err());
+    }
+}
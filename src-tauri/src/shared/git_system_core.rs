@@ -0,0 +1,471 @@
+#![allow(dead_code)]
+
+//! System-`git` implementations of read operations that normally go through
+//! libgit2, used when the `gitBackend` setting is `systemGit`. Output shapes
+//! match the libgit2 versions so callers can switch freely.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::shared::git_core::{
+    is_diff_excluded, run_git_command, run_git_command_bytes, DiffExcludeMatcher,
+};
+use crate::types::{BranchInfo, GitFileStatus, GitLogEntry, GitLogResponse, GitUntrackedMode};
+use crate::utils::normalize_git_path;
+
+/// Untracked files larger than this are not read to count their lines.
+const MAX_UNTRACKED_COUNT_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct PorcelainStatus {
+    pub(crate) head_oid: Option<String>,
+    pub(crate) head_name: Option<String>,
+    /// `(path, index status, worktree status)`, statuses in the same
+    /// single-letter form as `status_for_index`/`status_for_workdir`.
+    pub(crate) entries: Vec<(String, Option<&'static str>, Option<&'static str>)>,
+}
+
+fn porcelain_code(code: char) -> Option<&'static str> {
+    match code {
+        'A' | 'C' => Some("A"),
+        'M' => Some("M"),
+        'D' => Some("D"),
+        'R' => Some("R"),
+        'T' => Some("T"),
+        _ => None,
+    }
+}
+
+/// Parses `git status --porcelain=v2 --branch -z`. Unmerged (`u`) and
+/// ignored (`!`) entries are skipped, as the libgit2 status does.
+pub(crate) fn parse_porcelain_v2(output: &str) -> PorcelainStatus {
+    let mut status = PorcelainStatus::default();
+    let mut records = output.split('\0');
+    while let Some(record) = records.next() {
+        if let Some(oid) = record.strip_prefix("# branch.oid ") {
+            status.head_oid = (oid != "(initial)").then(|| oid.to_string());
+        } else if let Some(head) = record.strip_prefix("# branch.head ") {
+            status.head_name = Some(head.to_string());
+        } else if let Some(path) = record.strip_prefix("? ") {
+            status.entries.push((path.to_string(), None, Some("A")));
+        } else if record.starts_with("1 ") || record.starts_with("2 ") {
+            let renamed = record.starts_with("2 ");
+            let field_count = if renamed { 10 } else { 9 };
+            let fields: Vec<&str> = record.splitn(field_count, ' ').collect();
+            if renamed {
+                // The original path follows as its own NUL-terminated record.
+                records.next();
+            }
+            let (Some(xy), Some(path)) = (fields.get(1), fields.get(field_count - 1)) else {
+                continue;
+            };
+            let mut codes = xy.chars();
+            let index = codes.next().and_then(porcelain_code);
+            let worktree = codes.next().and_then(porcelain_code);
+            status.entries.push((path.to_string(), index, worktree));
+        }
+    }
+    status
+}
+
+/// Parses `git diff --numstat -z --no-renames` into per-path line counts.
+/// Binary files (`-\t-`) count as zero.
+pub(crate) fn parse_numstat(output: &str) -> HashMap<String, (i64, i64)> {
+    output
+        .split('\0')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\t');
+            let additions = fields.next()?.parse::<i64>().unwrap_or(0);
+            let deletions = fields.next()?.parse::<i64>().unwrap_or(0);
+            let path = fields.next().filter(|path| !path.is_empty())?;
+            Some((path.to_string(), (additions, deletions)))
+        })
+        .collect()
+}
+
+fn untracked_line_count(path: &Path) -> i64 {
+    let Ok(metadata) = fs::metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_file() || metadata.len() > MAX_UNTRACKED_COUNT_BYTES {
+        return 0;
+    }
+    match fs::read(path) {
+        Ok(bytes) if !bytes.contains(&0) => {
+            let newlines = bytes.iter().filter(|byte| **byte == b'\n').count();
+            let trailing = usize::from(!bytes.is_empty() && !bytes.ends_with(b"\n"));
+            (newlines + trailing) as i64
+        }
+        _ => 0,
+    }
+}
+
+/// Same JSON shape as `git_status_in_repo`, built from `git status` and
+/// `git diff --numstat` output.
+pub(crate) async fn git_status_via_cli(
+    repo_root: &Path,
+    exclude_matcher: Option<&DiffExcludeMatcher>,
+    untracked_mode: GitUntrackedMode,
+    large_file_warn_bytes: Option<u64>,
+) -> Result<Value, String> {
+    let repo_root = repo_root.to_path_buf();
+    let untracked = match untracked_mode {
+        GitUntrackedMode::All => "--untracked-files=all",
+        GitUntrackedMode::Normal | GitUntrackedMode::Folders => "--untracked-files=normal",
+    };
+    let status_output = run_git_command_bytes(
+        &repo_root,
+        &["--no-optional-locks", "status", "--porcelain=v2", "--branch", "-z", untracked],
+    )
+    .await?;
+    let status = parse_porcelain_v2(&String::from_utf8_lossy(&status_output));
+    let staged_stats = parse_numstat(&String::from_utf8_lossy(
+        &run_git_command_bytes(&repo_root, &["diff", "--cached", "--numstat", "-z", "--no-renames"])
+            .await?,
+    ));
+    let unstaged_stats = parse_numstat(&String::from_utf8_lossy(
+        &run_git_command_bytes(&repo_root, &["diff", "--numstat", "-z", "--no-renames"]).await?,
+    ));
+
+    let branch_name = match status.head_name.as_deref() {
+        Some("(detached)") => "HEAD".to_string(),
+        Some(name) if status.head_oid.is_some() => name.to_string(),
+        _ => "unknown".to_string(),
+    };
+    let detached_head = status.head_name.as_deref() == Some("(detached)");
+
+    let mut files = Vec::new();
+    let mut staged_files = Vec::new();
+    let mut unstaged_files = Vec::new();
+    let mut total_additions = 0i64;
+    let mut total_deletions = 0i64;
+    for (path, index_status, worktree_status) in status.entries {
+        if path.is_empty() || is_diff_excluded(exclude_matcher, &path) {
+            continue;
+        }
+        let is_dir = path.ends_with('/');
+        let normalized_path = normalize_git_path(path.trim_end_matches('/'));
+        let untracked = index_status.is_none() && worktree_status == Some("A");
+        let large_size = large_file_warn_bytes
+            .filter(|_| {
+                !is_dir
+                    && (matches!(index_status, Some("A" | "M"))
+                        || matches!(worktree_status, Some("A" | "M")))
+            })
+            .and_then(|threshold| {
+                fs::metadata(repo_root.join(&path))
+                    .ok()
+                    .filter(|metadata| metadata.is_file())
+                    .map(|metadata| metadata.len())
+                    .filter(|size| *size >= threshold)
+            });
+        let file_status = |status: &str, (additions, deletions): (i64, i64)| GitFileStatus {
+            path: normalized_path.clone(),
+            status: status.to_string(),
+            additions,
+            deletions,
+            is_dir,
+            is_large: large_size.is_some(),
+            size: large_size,
+        };
+
+        let mut combined = (0i64, 0i64);
+        if let Some(index_status) = index_status {
+            let stats = staged_stats.get(&path).copied().unwrap_or((0, 0));
+            staged_files.push(file_status(index_status, stats));
+            combined = (combined.0 + stats.0, combined.1 + stats.1);
+        }
+        if let Some(worktree_status) = worktree_status {
+            let stats = if is_dir {
+                (0, 0)
+            } else if untracked {
+                (untracked_line_count(&repo_root.join(&path)), 0)
+            } else {
+                unstaged_stats.get(&path).copied().unwrap_or((0, 0))
+            };
+            unstaged_files.push(file_status(worktree_status, stats));
+            combined = (combined.0 + stats.0, combined.1 + stats.1);
+        }
+        if index_status.is_some() || worktree_status.is_some() {
+            let status = worktree_status.or(index_status).unwrap_or("--");
+            files.push(file_status(status, combined));
+        }
+        total_additions += combined.0;
+        total_deletions += combined.1;
+    }
+
+    Ok(json!({
+        "branchName": branch_name,
+        "detachedHead": detached_head,
+        "headSha": status.head_oid,
+        "files": files,
+        "stagedFiles": staged_files,
+        "unstagedFiles": unstaged_files,
+        "totalAdditions": total_additions,
+        "totalDeletions": total_deletions,
+    }))
+}
+
+const LOG_FORMAT: &str = "--format=%x1e%H%x1f%an%x1f%ct %ci%x1f%s%x1f%b%x1f";
+
+/// Splits a `<unix seconds> <date ... +HHMM>` field into seconds and the UTC
+/// offset in minutes. A bare timestamp yields an offset of zero.
+fn parse_time_with_offset(field: &str) -> (i64, i32) {
+    let mut parts = field.split_whitespace();
+    let seconds = parts
+        .next()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(0);
+    let offset = parts
+        .last()
+        .filter(|zone| zone.len() == 5)
+        .and_then(|zone| {
+            let sign = match zone.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let hours = zone[1..3].parse::<i32>().ok()?;
+            let minutes = zone[3..5].parse::<i32>().ok()?;
+            Some(sign * (hours * 60 + minutes))
+        })
+        .unwrap_or(0);
+    (seconds, offset)
+}
+
+/// Parses `git log -z` output produced with `LOG_FORMAT`, optionally followed
+/// by NUL-terminated `--numstat` records per commit. Detail fields are only
+/// filled when `detail`.
+pub(crate) fn parse_log_records(output: &str, detail: bool) -> Vec<GitLogEntry> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.splitn(6, '\x1f');
+            let sha = fields.next()?.trim().to_string();
+            if sha.is_empty() {
+                return None;
+            }
+            let author = fields.next()?.to_string();
+            let (timestamp, timezone_offset) = parse_time_with_offset(fields.next()?);
+            let summary = fields.next()?.to_string();
+            let body = fields.next().unwrap_or("").trim_end().to_string();
+            let numstat = fields.next().unwrap_or("");
+            let mut entry = GitLogEntry {
+                sha,
+                summary,
+                author,
+                timestamp,
+                timezone_offset,
+                body: None,
+                files_changed: None,
+                additions: None,
+                deletions: None,
+                signature_status: None,
+                signer: None,
+                has_notes: None,
+            };
+            if detail {
+                let mut files = Vec::new();
+                let (mut additions, mut deletions) = (0usize, 0usize);
+                for record in numstat.split('\0') {
+                    let mut parts = record.trim_start_matches('\n').splitn(3, '\t');
+                    let (Some(added), Some(deleted), Some(path)) =
+                        (parts.next(), parts.next(), parts.next())
+                    else {
+                        continue;
+                    };
+                    additions += added.parse::<usize>().unwrap_or(0);
+                    deletions += deleted.parse::<usize>().unwrap_or(0);
+                    files.push(normalize_git_path(path));
+                }
+                entry.body = Some(body);
+                entry.files_changed = Some(files);
+                entry.additions = Some(additions);
+                entry.deletions = Some(deletions);
+            }
+            Some(entry)
+        })
+        .collect()
+}
+
+async fn log_entries_via_cli(
+    repo_root: &PathBuf,
+    range: &str,
+    max_items: usize,
+    pathspec: &[String],
+    detail: bool,
+) -> Result<Vec<GitLogEntry>, String> {
+    let max_count = format!("--max-count={max_items}");
+    // Signatures are verified separately; `log.showSignature` would splice
+    // gpg output into the records.
+    let mut args = vec![
+        "-c",
+        "log.showSignature=false",
+        "log",
+        "-z",
+        LOG_FORMAT,
+        max_count.as_str(),
+    ];
+    if detail {
+        args.extend(["--numstat", "--no-renames", "--diff-merges=first-parent"]);
+    }
+    args.push(range);
+    if !pathspec.is_empty() {
+        args.push("--");
+        args.extend(pathspec.iter().map(String::as_str));
+    }
+    let output = run_git_command(repo_root, &args).await?;
+    Ok(parse_log_records(&output, detail))
+}
+
+/// Same result as the libgit2 `get_git_log` walk, built from `git log` and
+/// `git rev-list`. Signatures are left to the caller.
+pub(crate) async fn git_log_via_cli(
+    repo_root: &PathBuf,
+    max_items: usize,
+    pathspec: &[String],
+    detail: bool,
+) -> Result<GitLogResponse, String> {
+    // Counting a pathspec-filtered log diffs every commit, so it only asks for
+    // one extra entry to learn whether more follow.
+    let (entries, total, has_more) = if pathspec.is_empty() {
+        let entries = log_entries_via_cli(repo_root, "HEAD", max_items, pathspec, detail).await?;
+        let total = run_git_command(repo_root, &["rev-list", "--count", "HEAD"])
+            .await?
+            .parse::<usize>()
+            .unwrap_or(0);
+        let has_more = total > entries.len();
+        (entries, Some(total), has_more)
+    } else {
+        let mut entries =
+            log_entries_via_cli(repo_root, "HEAD", max_items + 1, pathspec, detail).await?;
+        let has_more = entries.len() > max_items;
+        entries.truncate(max_items);
+        (entries, None, has_more)
+    };
+    let shallow = run_git_command(repo_root, &["rev-parse", "--is-shallow-repository"])
+        .await
+        .map(|output| output == "true")
+        .unwrap_or(false);
+
+    let mut response = GitLogResponse {
+        total,
+        has_more,
+        entries,
+        ahead: 0,
+        behind: 0,
+        ahead_entries: Vec::new(),
+        behind_entries: Vec::new(),
+        upstream: None,
+        shallow,
+    };
+    // Fails when HEAD is detached or the branch has no upstream.
+    let Ok(upstream) =
+        run_git_command(repo_root, &["rev-parse", "--abbrev-ref", "@{upstream}"]).await
+    else {
+        return Ok(response);
+    };
+    let counts = run_git_command(
+        repo_root,
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+    )
+    .await?;
+    let mut counts = counts.split_whitespace();
+    response.ahead = counts.next().and_then(|value| value.parse().ok()).unwrap_or(0);
+    response.behind = counts.next().and_then(|value| value.parse().ok()).unwrap_or(0);
+    response.ahead_entries =
+        log_entries_via_cli(repo_root, "@{upstream}..HEAD", max_items, &[], false).await?;
+    response.behind_entries =
+        log_entries_via_cli(repo_root, "HEAD..@{upstream}", max_items, &[], false).await?;
+    response.upstream = Some(upstream);
+    Ok(response)
+}
+
+/// Local branches, most recently committed first, from `git for-each-ref`.
+pub(crate) async fn local_branches_via_cli(repo_root: &PathBuf) -> Result<Vec<BranchInfo>, String> {
+    let output = run_git_command(
+        repo_root,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)%1f%(committerdate:unix) %(committerdate:iso)%1f%(symref)",
+            "refs/heads",
+        ],
+    )
+    .await?;
+    let mut branches: Vec<BranchInfo> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            let name = fields.next()?.trim();
+            let (last_commit, last_commit_offset) = parse_time_with_offset(fields.next()?);
+            let symref = fields.next().unwrap_or("").trim();
+            (!name.is_empty() && symref.is_empty()).then(|| BranchInfo {
+                name: name.to_string(),
+                last_commit,
+                last_commit_offset,
+            })
+        })
+        .collect();
+    branches.sort_by(|a, b| b.last_commit.cmp(&a.last_commit));
+    Ok(branches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_porcelain_v2_reads_branch_and_entries() {
+        let output = "# branch.oid 0123abcd\0# branch.head main\0\
+1 M. N... 100644 100644 100644 aaa bbb src/lib.rs\0\
+1 .D N... 100644 100644 000000 aaa aaa gone.txt\0\
+2 R. N... 100644 100644 100644 aaa aaa R100 new name.rs\0old name.rs\0\
+u UU N... 100644 100644 100644 100644 aaa bbb ccc conflict.rs\0\
+? notes/\0";
+        let status = parse_porcelain_v2(output);
+        assert_eq!(status.head_oid.as_deref(), Some("0123abcd"));
+        assert_eq!(status.head_name.as_deref(), Some("main"));
+        assert_eq!(
+            status.entries,
+            vec![
+                ("src/lib.rs".to_string(), Some("M"), None),
+                ("gone.txt".to_string(), None, Some("D")),
+                ("new name.rs".to_string(), Some("R"), None),
+                ("notes/".to_string(), None, Some("A")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_numstat_counts_binary_as_zero() {
+        let stats = parse_numstat("3\t1\tsrc/lib.rs\0-\t-\tlogo.png\0");
+        assert_eq!(stats.get("src/lib.rs"), Some(&(3, 1)));
+        assert_eq!(stats.get("logo.png"), Some(&(0, 0)));
+    }
+
+    #[test]
+    fn parse_log_records_fills_detail_from_numstat() {
+        let output = "\x1eabc\x1fAda\x1f1700000000\x1fFix it\x1fLonger body\n\x1f\0\n2\t1\tsrc/a b.rs\0-\t-\tlogo.png\0\
+\x1edef\x1fBob\x1f1690000000 2023-07-22 06:26:40 -0330\x1fInit\x1f\x1f\0";
+        let entries = parse_log_records(output, true);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].sha, "abc");
+        assert_eq!(entries[0].summary, "Fix it");
+        assert_eq!(entries[0].body.as_deref(), Some("Longer body"));
+        assert_eq!(
+            entries[0].files_changed,
+            Some(vec!["src/a b.rs".to_string(), "logo.png".to_string()])
+        );
+        assert_eq!(entries[0].additions, Some(2));
+        assert_eq!(entries[0].timezone_offset, 0);
+        assert_eq!(entries[1].timestamp, 1690000000);
+        assert_eq!(entries[1].timezone_offset, -210);
+        assert_eq!(entries[1].files_changed, Some(Vec::new()));
+
+        let entries = parse_log_records(output, false);
+        assert!(entries[0].body.is_none());
+    }
+}
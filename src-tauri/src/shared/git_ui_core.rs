@@ -0,0 +1,4830 @@
+#![allow(dead_code)]
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use git2::{DiffOptions, Repository, Sort, Status, StatusOptions};
+use serde_json::{json, Value};
+use tokio::sync::{Mutex, Semaphore};
+
+use crate::git_utils::{
+    checkout_branch, commit_to_entry, decode_text, diff_patch_to_string, diff_stats_for_path,
+    forge_blob_url, image_mime_type, resolve_git_root,
+};
+use crate::shared::git_core::{
+    build_diff_exclude_matcher, ensure_branch_unprotected, git_op_limiter, is_diff_excluded,
+    run_git_command, run_git_command_with_env, spawn_git_blocking, DiffExcludeMatcher,
+};
+use crate::shared::git_system_core::{git_log_via_cli, git_status_via_cli, local_branches_via_cli};
+use crate::shared::git_undo_core::{
+    capture_head, pop_stash_by_id, push_undo_entry, record_after, record_if_head_moved,
+    stash_worktree, GitUndoJournal,
+};
+use crate::shared::process_core::{tokio_command, workspace_env_for};
+use crate::types::{
+    AppSettings, BinaryDiffSummary, BisectResult, BranchInfo, CheckoutCommitResult,
+    CommitAndPushResult, CommitResult, ConfigScope, DefaultBranch, DiffWhitespace,
+    DiffWhitespaceError, GcReport, GitAheadBehind, GitChangeSummary, GitCommitDiff,
+    GitConflictVersion, GitConflictVersions, GitFileDiff, GitFileStatus, GitLogEntry,
+    GitLogResponse, GitSignatureStatus, GitUndoKind, GitUntrackedMode, IgnoreExplanation,
+    IndexLockReport, PullRequestRefsFetch, RecentBranch, RefType, RemoteFetchResult,
+    RepoOperation, RepoRepairReport, RepoStats, ResolvedRef, WorkingTreeCleanliness,
+    WorkspaceEntry,
+};
+use crate::utils::{git_env_path, normalize_git_path, resolve_git_binary};
+
+pub(crate) const INDEX_SKIP_WORKTREE_FLAG: u16 = 0x4000;
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+const MAX_TEXT_DIFF_BYTES: usize = 2 * 1024 * 1024;
+
+fn encode_image_base64(data: &[u8]) -> Option<String> {
+    if data.len() > MAX_IMAGE_BYTES {
+        return None;
+    }
+    Some(STANDARD.encode(data))
+}
+
+pub(crate) fn blob_to_base64(blob: git2::Blob) -> Option<String> {
+    if blob.size() > MAX_IMAGE_BYTES {
+        return None;
+    }
+    encode_image_base64(blob.content())
+}
+
+fn read_image_base64(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_IMAGE_BYTES as u64 {
+        return None;
+    }
+    let data = fs::read(path).ok()?;
+    encode_image_base64(&data)
+}
+
+fn split_lines_preserving_newlines(content: &str) -> Vec<String> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+    content
+        .split_inclusive('\n')
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Text decoded for display, with the detected encoding and line-ending style.
+pub(crate) struct DecodedText {
+    pub(crate) lines: Vec<String>,
+    pub(crate) encoding: &'static str,
+    pub(crate) line_ending: &'static str,
+}
+
+fn line_ending_style(text: &str) -> &'static str {
+    let crlf = text.matches("\r\n").count();
+    let lf = text.matches('\n').count() - crlf;
+    match (crlf, lf) {
+        (0, 0) => "none",
+        (0, _) => "lf",
+        (_, 0) => "crlf",
+        _ => "mixed",
+    }
+}
+
+pub(crate) fn decode_text_lines(bytes: &[u8], normalize_line_endings: bool) -> Option<DecodedText> {
+    let (text, encoding) = decode_text(bytes)?;
+    let line_ending = line_ending_style(&text);
+    let text = if normalize_line_endings && line_ending != "lf" {
+        text.replace("\r\n", "\n")
+    } else {
+        text
+    };
+    Some(DecodedText {
+        lines: split_lines_preserving_newlines(&text),
+        encoding,
+        line_ending,
+    })
+}
+
+fn blob_to_text(blob: git2::Blob<'_>, normalize_line_endings: bool) -> Option<DecodedText> {
+    if blob.size() > MAX_TEXT_DIFF_BYTES {
+        return None;
+    }
+    decode_text_lines(blob.content(), normalize_line_endings)
+}
+
+pub(crate) fn blob_to_lines(blob: git2::Blob<'_>) -> Option<Vec<String>> {
+    blob_to_text(blob, false).map(|decoded| decoded.lines)
+}
+
+fn read_text(path: &Path, normalize_line_endings: bool) -> Option<DecodedText> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_TEXT_DIFF_BYTES as u64 {
+        return None;
+    }
+    let data = fs::read(path).ok()?;
+    decode_text_lines(&data, normalize_line_endings)
+}
+
+
+pub(crate) fn action_paths_for_file(repo_root: &Path, path: &str) -> Vec<String> {
+    let target = normalize_git_path(path).trim().to_string();
+    if target.is_empty() {
+        return Vec::new();
+    }
+
+    let repo = match Repository::open(repo_root) {
+        Ok(repo) => repo,
+        Err(_) => return vec![target],
+    };
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true)
+        .include_ignored(false);
+
+    let statuses = match repo.statuses(Some(&mut status_options)) {
+        Ok(statuses) => statuses,
+        Err(_) => return vec![target],
+    };
+
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if !(status.contains(Status::WT_RENAMED) || status.contains(Status::INDEX_RENAMED)) {
+            continue;
+        }
+        let delta = entry.index_to_workdir().or_else(|| entry.head_to_index());
+        let Some(delta) = delta else {
+            continue;
+        };
+        let (Some(old_path), Some(new_path)) =
+            (delta.old_file().path(), delta.new_file().path())
+        else {
+            continue;
+        };
+        let old_path = normalize_git_path(old_path.to_string_lossy().as_ref());
+        let new_path = normalize_git_path(new_path.to_string_lossy().as_ref());
+        if old_path != target && new_path != target {
+            continue;
+        }
+        if old_path == new_path || new_path.is_empty() {
+            return vec![target];
+        }
+        let mut result = Vec::new();
+        if !old_path.is_empty() {
+            result.push(old_path);
+        }
+        if !new_path.is_empty() && !result.contains(&new_path) {
+            result.push(new_path);
+        }
+        return if result.is_empty() { vec![target] } else { result };
+    }
+
+    vec![target]
+}
+
+
+pub(crate) fn any_path_has_staged_changes(repo_root: &Path, paths: &[String]) -> Result<bool, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let staged = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED
+        | Status::INDEX_TYPECHANGE;
+    Ok(paths.iter().any(|path| {
+        repo.status_file(Path::new(path))
+            .map(|status| status.intersects(staged))
+            .unwrap_or(false)
+    }))
+}
+
+
+pub(crate) fn status_for_index(status: Status) -> Option<&'static str> {
+    if status.contains(Status::INDEX_NEW) {
+        Some("A")
+    } else if status.contains(Status::INDEX_MODIFIED) {
+        Some("M")
+    } else if status.contains(Status::INDEX_DELETED) {
+        Some("D")
+    } else if status.contains(Status::INDEX_RENAMED) {
+        Some("R")
+    } else if status.contains(Status::INDEX_TYPECHANGE) {
+        Some("T")
+    } else {
+        None
+    }
+}
+
+
+pub(crate) fn status_for_workdir(status: Status) -> Option<&'static str> {
+    if status.contains(Status::WT_NEW) {
+        Some("A")
+    } else if status.contains(Status::WT_MODIFIED) {
+        Some("M")
+    } else if status.contains(Status::WT_DELETED) {
+        Some("D")
+    } else if status.contains(Status::WT_RENAMED) {
+        Some("R")
+    } else if status.contains(Status::WT_TYPECHANGE) {
+        Some("T")
+    } else {
+        None
+    }
+}
+
+
+pub(crate) fn status_for_delta(status: git2::Delta) -> &'static str {
+    match status {
+        git2::Delta::Added | git2::Delta::Untracked => "A",
+        git2::Delta::Modified => "M",
+        git2::Delta::Deleted => "D",
+        git2::Delta::Renamed => "R",
+        git2::Delta::Typechange => "T",
+        _ => "M",
+    }
+}
+
+const BINARY_HEX_PREVIEW_BYTES: usize = 32;
+
+fn hex_preview(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take(BINARY_HEX_PREVIEW_BYTES)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub(crate) fn blob_size_and_preview(repo: &Repository, oid: git2::Oid) -> Option<(u64, String)> {
+    if oid.is_zero() {
+        return None;
+    }
+    let blob = repo.find_blob(oid).ok()?;
+    Some((blob.size() as u64, hex_preview(blob.content())))
+}
+
+fn file_size_and_preview(path: &Path) -> Option<(u64, String)> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).ok()?;
+    let size = file.metadata().ok()?.len();
+    let mut head = Vec::with_capacity(BINARY_HEX_PREVIEW_BYTES);
+    file.by_ref()
+        .take(BINARY_HEX_PREVIEW_BYTES as u64)
+        .read_to_end(&mut head)
+        .ok()?;
+    Some((size, hex_preview(&head)))
+}
+
+pub(crate) fn binary_diff_summary(
+    status: git2::Delta,
+    old_side: Option<(u64, String)>,
+    new_side: Option<(u64, String)>,
+) -> BinaryDiffSummary {
+    let change = match status {
+        git2::Delta::Added | git2::Delta::Untracked => "added",
+        git2::Delta::Deleted => "deleted",
+        git2::Delta::Renamed => "renamed",
+        git2::Delta::Copied => "copied",
+        _ => "modified",
+    };
+    let (old_size, old_preview) = old_side.unzip();
+    let (new_size, new_preview) = new_side.unzip();
+    BinaryDiffSummary {
+        change: change.to_string(),
+        old_size,
+        new_size,
+        hex_preview: new_preview.or(old_preview).filter(|preview| !preview.is_empty()),
+    }
+}
+
+const MAX_SUBMODULE_COMMITS: usize = 20;
+
+/// Commits reachable from `new` but not `old` in the submodule checked out
+/// at `path`. `None` when the submodule is not checked out or lacks `new`
+/// (not fetched yet).
+pub(crate) fn submodule_commits_between(
+    repo: &Repository,
+    path: &Path,
+    old: git2::Oid,
+    new: git2::Oid,
+) -> Option<Vec<GitLogEntry>> {
+    let submodule = Repository::open(repo.workdir()?.join(path)).ok()?;
+    let mut revwalk = submodule.revwalk().ok()?;
+    if new.is_zero() {
+        return Some(Vec::new());
+    }
+    revwalk.push(new).ok()?;
+    if !old.is_zero() {
+        // An unknown old sha just means the whole history up to the cap.
+        let _ = revwalk.hide(old);
+    }
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME).ok()?;
+    Some(
+        revwalk
+            .take(MAX_SUBMODULE_COMMITS)
+            .filter_map(|oid| oid.ok())
+            .filter_map(|oid| submodule.find_commit(oid).ok())
+            .map(commit_to_entry)
+            .collect(),
+    )
+}
+
+/// `submodule_commits_between` for a delta, if it is a submodule change.
+pub(crate) fn submodule_commits(
+    repo: &Repository,
+    delta: &git2::DiffDelta<'_>,
+) -> Option<Vec<GitLogEntry>> {
+    let (old_file, new_file) = (delta.old_file(), delta.new_file());
+    let is_submodule = old_file.mode() == git2::FileMode::Commit
+        || new_file.mode() == git2::FileMode::Commit;
+    if !is_submodule {
+        return None;
+    }
+    let path = new_file.path().or(old_file.path())?;
+    submodule_commits_between(repo, path, old_file.id(), new_file.id())
+}
+
+/// Context used for "full file" diffs; more lines than any file we render.
+pub(crate) const FULL_FILE_CONTEXT_LINES: u32 = 1_000_000;
+
+pub(crate) fn diff_context_lines(setting: u32, full_file: bool) -> u32 {
+    if full_file {
+        FULL_FILE_CONTEXT_LINES
+    } else {
+        setting
+    }
+}
+
+pub(crate) fn apply_diff_whitespace(options: &mut DiffOptions, whitespace: DiffWhitespace) {
+    match whitespace {
+        DiffWhitespace::None => {}
+        DiffWhitespace::IgnoreEol => {
+            options.ignore_whitespace_eol(true);
+        }
+        DiffWhitespace::IgnoreChange => {
+            options.ignore_whitespace_change(true);
+        }
+        DiffWhitespace::IgnoreAll => {
+            options.ignore_whitespace(true);
+        }
+    }
+}
+
+/// The line-level `core.whitespace` rules. `blank-at-eof` needs the whole
+/// file rather than a line, so it isn't checked here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WhitespaceRules {
+    blank_at_eol: bool,
+    space_before_tab: bool,
+    indent_with_non_tab: bool,
+    tab_in_indent: bool,
+    cr_at_eol: bool,
+    tab_width: usize,
+}
+
+impl Default for WhitespaceRules {
+    fn default() -> Self {
+        WhitespaceRules {
+            blank_at_eol: true,
+            space_before_tab: true,
+            indent_with_non_tab: false,
+            tab_in_indent: false,
+            cr_at_eol: false,
+            tab_width: 8,
+        }
+    }
+}
+
+impl WhitespaceRules {
+    /// Applies a `core.whitespace` value (comma-separated, `-` disables) on
+    /// top of git's defaults. Unknown entries are ignored, as git does.
+    pub(crate) fn from_config(value: Option<&str>) -> Self {
+        let mut rules = WhitespaceRules::default();
+        for item in value.unwrap_or("").split(',') {
+            let item = item.trim();
+            let (enabled, name) = match item.strip_prefix('-') {
+                Some(name) => (false, name),
+                None => (true, item),
+            };
+            match name {
+                "blank-at-eol" | "trailing-space" => rules.blank_at_eol = enabled,
+                "space-before-tab" => rules.space_before_tab = enabled,
+                "indent-with-non-tab" => rules.indent_with_non_tab = enabled,
+                "tab-in-indent" => rules.tab_in_indent = enabled,
+                "cr-at-eol" => rules.cr_at_eol = enabled,
+                _ => {
+                    if let Some(width) = name
+                        .strip_prefix("tabwidth=")
+                        .and_then(|width| width.parse::<usize>().ok())
+                        .filter(|width| (1..=63).contains(width))
+                    {
+                        rules.tab_width = width;
+                    }
+                }
+            }
+        }
+        rules
+    }
+
+    /// Rule names broken by `line` (without its `\n`).
+    pub(crate) fn line_errors(&self, line: &str) -> Vec<&'static str> {
+        let line = if self.cr_at_eol {
+            line.strip_suffix('\r').unwrap_or(line)
+        } else {
+            line
+        };
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let indent = &line[..indent_len];
+        let mut errors = Vec::new();
+        if self.blank_at_eol && line.ends_with([' ', '\t', '\r']) {
+            errors.push("blank-at-eol");
+        }
+        if self.space_before_tab && indent.contains(" \t") {
+            errors.push("space-before-tab");
+        }
+        if self.indent_with_non_tab && indent.contains(&" ".repeat(self.tab_width)) {
+            errors.push("indent-with-non-tab");
+        }
+        if self.tab_in_indent && indent.contains('\t') {
+            errors.push("tab-in-indent");
+        }
+        errors
+    }
+}
+
+/// Whitespace errors on the added lines of `patch`, by new-file line number.
+fn patch_whitespace_errors(patch: &git2::Patch, rules: WhitespaceRules) -> Vec<DiffWhitespaceError> {
+    let mut errors = Vec::new();
+    for hunk in 0..patch.num_hunks() {
+        let Ok(line_count) = patch.num_lines_in_hunk(hunk) else {
+            continue;
+        };
+        for index in 0..line_count {
+            let Ok(line) = patch.line_in_hunk(hunk, index) else {
+                continue;
+            };
+            if line.origin() != '+' {
+                continue;
+            }
+            let content = String::from_utf8_lossy(line.content());
+            let kinds = rules.line_errors(content.strip_suffix('\n').unwrap_or(&content));
+            if !kinds.is_empty() {
+                errors.push(DiffWhitespaceError {
+                    line: line.new_lineno().unwrap_or(0),
+                    kinds: kinds.into_iter().map(str::to_string).collect(),
+                });
+            }
+        }
+    }
+    errors
+}
+
+/// Per-file diffs from `old_tree` (or an empty tree) to `new_tree`.
+pub(crate) fn diff_trees_to_commit_diffs(
+    repo: &Repository,
+    old_tree: Option<&git2::Tree>,
+    new_tree: &git2::Tree,
+    whitespace: DiffWhitespace,
+    context_lines: u32,
+) -> Result<Vec<GitCommitDiff>, String> {
+    let mut options = DiffOptions::new();
+    options.context_lines(context_lines);
+    apply_diff_whitespace(&mut options, whitespace);
+    let diff = repo
+        .diff_tree_to_tree(old_tree, Some(new_tree), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+
+    let mut results = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let old_path = delta.old_file().path();
+        let new_path = delta.new_file().path();
+        let display_path = new_path.or(old_path);
+        let Some(display_path) = display_path else {
+            continue;
+        };
+        let old_path_str = old_path.map(|path| path.to_string_lossy());
+        let new_path_str = new_path.map(|path| path.to_string_lossy());
+        let display_path_str = display_path.to_string_lossy();
+        let normalized_path = normalize_git_path(&display_path_str);
+        let old_image_mime = old_path_str.as_deref().and_then(image_mime_type);
+        let new_image_mime = new_path_str.as_deref().and_then(image_mime_type);
+        let is_image = old_image_mime.is_some() || new_image_mime.is_some();
+        let is_deleted = delta.status() == git2::Delta::Deleted;
+        let is_added = delta.status() == git2::Delta::Added;
+
+        let old_lines = if !is_added {
+            old_tree
+                .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
+                .and_then(|entry| repo.find_blob(entry.id()).ok())
+                .and_then(blob_to_lines)
+        } else {
+            None
+        };
+
+        let new_lines = if !is_deleted {
+            new_path
+                .and_then(|path| new_tree.get_path(path).ok())
+                .and_then(|entry| repo.find_blob(entry.id()).ok())
+                .and_then(blob_to_lines)
+        } else {
+            None
+        };
+
+        if is_image {
+            let old_image_data = if !is_added && old_image_mime.is_some() {
+                old_tree
+                    .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
+                    .and_then(|entry| repo.find_blob(entry.id()).ok())
+                    .and_then(blob_to_base64)
+            } else {
+                None
+            };
+
+            let new_image_data = if !is_deleted && new_image_mime.is_some() {
+                new_path
+                    .and_then(|path| new_tree.get_path(path).ok())
+                    .and_then(|entry| repo.find_blob(entry.id()).ok())
+                    .and_then(blob_to_base64)
+            } else {
+                None
+            };
+
+            results.push(GitCommitDiff {
+                path: normalized_path,
+                status: status_for_delta(delta.status()).to_string(),
+                diff: String::new(),
+                old_lines: None,
+                new_lines: None,
+                is_binary: true,
+                is_image: true,
+                old_image_data,
+                new_image_data,
+                old_image_mime: old_image_mime.map(str::to_string),
+                new_image_mime: new_image_mime.map(str::to_string),
+                binary_summary: None,
+                submodule_commits: None,
+            });
+            continue;
+        }
+
+        let patch = match git2::Patch::from_diff(&diff, index) {
+            Ok(patch) => patch,
+            Err(_) => continue,
+        };
+        let Some(mut patch) = patch else {
+            continue;
+        };
+        let content = match diff_patch_to_string(&mut patch) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let binary_summary = patch.delta().flags().is_binary().then(|| {
+            let old_side = (!is_added)
+                .then(|| blob_size_and_preview(repo, delta.old_file().id()))
+                .flatten();
+            let new_side = (!is_deleted)
+                .then(|| blob_size_and_preview(repo, delta.new_file().id()))
+                .flatten();
+            binary_diff_summary(delta.status(), old_side, new_side)
+        });
+        results.push(GitCommitDiff {
+            path: normalized_path,
+            status: status_for_delta(delta.status()).to_string(),
+            diff: content,
+            old_lines: if binary_summary.is_some() { None } else { old_lines },
+            new_lines: if binary_summary.is_some() { None } else { new_lines },
+            is_binary: binary_summary.is_some(),
+            is_image: false,
+            old_image_data: None,
+            new_image_data: None,
+            old_image_mime: None,
+            new_image_mime: None,
+            binary_summary,
+            submodule_commits: submodule_commits(repo, &delta),
+        });
+    }
+
+    Ok(results)
+}
+
+pub(crate) fn collect_git_file_diffs(
+    repo_root: &Path,
+    exclude_matcher: Option<&DiffExcludeMatcher>,
+    whitespace: DiffWhitespace,
+    context_lines: u32,
+    normalize_line_endings: bool,
+    whitespace_check: bool,
+    pathspec: Option<&str>,
+) -> Result<Vec<GitFileDiff>, String> {
+    let mut results = Vec::new();
+    visit_git_file_diffs(
+        repo_root,
+        exclude_matcher,
+        whitespace,
+        context_lines,
+        normalize_line_endings,
+        whitespace_check,
+        pathspec,
+        None,
+        |diff| {
+            results.push(diff);
+            true
+        },
+    )?;
+    Ok(results)
+}
+
+/// Produces working tree diffs one file at a time. Stops early when `cancel`
+/// is set or `on_diff` returns false; the result reports whether every delta
+/// was visited. With `whitespace_check`, added lines are checked against the
+/// repo's `core.whitespace` rules.
+pub(crate) fn visit_git_file_diffs(
+    repo_root: &Path,
+    exclude_matcher: Option<&DiffExcludeMatcher>,
+    whitespace: DiffWhitespace,
+    context_lines: u32,
+    normalize_line_endings: bool,
+    whitespace_check: bool,
+    pathspec: Option<&str>,
+    cancel: Option<&AtomicBool>,
+    mut on_diff: impl FnMut(GitFileDiff) -> bool,
+) -> Result<bool, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let head_tree = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_tree().ok());
+    let whitespace_rules = whitespace_check.then(|| {
+        let config = repo
+            .config()
+            .ok()
+            .and_then(|config| config.get_string("core.whitespace").ok());
+        let mut rules = WhitespaceRules::from_config(config.as_deref());
+        // Line endings are being normalized away, so a CR isn't an error.
+        rules.cr_at_eol |= normalize_line_endings;
+        rules
+    });
+
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true)
+        .context_lines(context_lines);
+    apply_diff_whitespace(&mut options, whitespace);
+    if let Some(pathspec) = pathspec {
+        // Treat the path literally so names containing glob characters work.
+        options.pathspec(pathspec).disable_pathspec_match(true);
+    } else if let Some(matcher) = exclude_matcher {
+        for spec in matcher.pathspecs() {
+            options.pathspec(spec);
+        }
+    }
+
+    let diff = match head_tree.as_ref() {
+        Some(tree) => repo
+            .diff_tree_to_workdir_with_index(Some(tree), Some(&mut options))
+            .map_err(|e| e.to_string())?,
+        None => repo
+            .diff_tree_to_workdir_with_index(None, Some(&mut options))
+            .map_err(|e| e.to_string())?,
+    };
+
+    for (index, delta) in diff.deltas().enumerate() {
+        if cancel.is_some_and(|cancel| cancel.load(Ordering::SeqCst)) {
+            return Ok(false);
+        }
+        let old_path = delta.old_file().path();
+        let new_path = delta.new_file().path();
+        let display_path = new_path.or(old_path);
+        let Some(display_path) = display_path else {
+            continue;
+        };
+        let old_path_str = old_path.map(|path| path.to_string_lossy());
+        let new_path_str = new_path.map(|path| path.to_string_lossy());
+        let display_path_str = display_path.to_string_lossy();
+        let normalized_path = normalize_git_path(&display_path_str);
+        if is_diff_excluded(exclude_matcher, &normalized_path) {
+            continue;
+        }
+        let old_image_mime = old_path_str.as_deref().and_then(image_mime_type);
+        let new_image_mime = new_path_str.as_deref().and_then(image_mime_type);
+        let is_image = old_image_mime.is_some() || new_image_mime.is_some();
+        let is_deleted = delta.status() == git2::Delta::Deleted;
+        let is_added = delta.status() == git2::Delta::Added;
+
+        let old_text = if !is_added {
+            head_tree
+                .as_ref()
+                .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
+                .and_then(|entry| repo.find_blob(entry.id()).ok())
+                .and_then(|blob| blob_to_text(blob, normalize_line_endings))
+        } else {
+            None
+        };
+
+        let new_text = if !is_deleted {
+            match new_path {
+                Some(path) => {
+                    let full_path = repo_root.join(path);
+                    read_text(&full_path, normalize_line_endings)
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+        // Report what the user sees now: the working copy, or HEAD for deletions.
+        let (encoding, line_ending) = new_text
+            .as_ref()
+            .or(old_text.as_ref())
+            .map(|text| (Some(text.encoding.to_string()), Some(text.line_ending.to_string())))
+            .unwrap_or((None, None));
+        let old_lines = old_text.map(|text| text.lines);
+        let new_lines = new_text.map(|text| text.lines);
+
+        if is_image {
+            let old_image_data = if !is_added && old_image_mime.is_some() {
+                head_tree
+                    .as_ref()
+                    .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
+                    .and_then(|entry| repo.find_blob(entry.id()).ok())
+                    .and_then(blob_to_base64)
+            } else {
+                None
+            };
+
+            let new_image_data = if !is_deleted && new_image_mime.is_some() {
+                match new_path {
+                    Some(path) => {
+                        let full_path = repo_root.join(path);
+                        read_image_base64(&full_path)
+                    }
+                    None => None,
+                }
+            } else {
+                None
+            };
+
+            let emitted = on_diff(GitFileDiff {
+                path: normalized_path,
+                diff: String::new(),
+                old_lines: None,
+                new_lines: None,
+                is_binary: true,
+                is_image: true,
+                old_image_data,
+                new_image_data,
+                old_image_mime: old_image_mime.map(str::to_string),
+                new_image_mime: new_image_mime.map(str::to_string),
+                binary_summary: None,
+                encoding: None,
+                line_ending: None,
+                submodule_commits: None,
+                whitespace_errors: None,
+            });
+            if !emitted {
+                return Ok(false);
+            }
+            continue;
+        }
+
+        let patch = match git2::Patch::from_diff(&diff, index) {
+            Ok(patch) => patch,
+            Err(_) => continue,
+        };
+        let Some(patch) = patch else {
+            continue;
+        };
+        // libgit2 treats UTF-16 as binary (it is full of NULs), so diff the decoded text.
+        let decoded_sides = (patch.delta().flags().is_binary()
+            && encoding.as_deref().is_some_and(|name| name.starts_with("utf-16")))
+        .then(|| {
+            (
+                old_lines.as_deref().unwrap_or_default().concat(),
+                new_lines.as_deref().unwrap_or_default().concat(),
+            )
+        });
+        let mut patch = match &decoded_sides {
+            Some((old, new)) => match git2::Patch::from_buffers(
+                old.as_bytes(),
+                old_path,
+                new.as_bytes(),
+                new_path,
+                None,
+            ) {
+                Ok(patch) => patch,
+                Err(_) => continue,
+            },
+            None => patch,
+        };
+        let whitespace_errors = whitespace_rules
+            .filter(|_| !patch.delta().flags().is_binary())
+            .map(|rules| patch_whitespace_errors(&patch, rules));
+        let content = match diff_patch_to_string(&mut patch) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let content = if normalize_line_endings {
+            content.replace("\r\n", "\n")
+        } else {
+            content
+        };
+        let binary_summary = patch.delta().flags().is_binary().then(|| {
+            let old_side = (!is_added)
+                .then(|| blob_size_and_preview(&repo, delta.old_file().id()))
+                .flatten();
+            let new_side = if is_deleted {
+                None
+            } else {
+                new_path.and_then(|path| file_size_and_preview(&repo_root.join(path)))
+            };
+            binary_diff_summary(delta.status(), old_side, new_side)
+        });
+        let emitted = on_diff(GitFileDiff {
+            path: normalized_path,
+            diff: content,
+            old_lines: if binary_summary.is_some() { None } else { old_lines },
+            new_lines: if binary_summary.is_some() { None } else { new_lines },
+            is_binary: binary_summary.is_some(),
+            is_image: false,
+            old_image_data: None,
+            new_image_data: None,
+            old_image_mime: None,
+            new_image_mime: None,
+            encoding: if binary_summary.is_some() { None } else { encoding },
+            line_ending: if binary_summary.is_some() { None } else { line_ending },
+            binary_summary,
+            submodule_commits: submodule_commits(&repo, &delta),
+            whitespace_errors,
+        });
+        if !emitted {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+pub(crate) fn git_status_in_repo(
+    repo_root: &Path,
+    exclude_matcher: Option<&DiffExcludeMatcher>,
+    untracked_mode: GitUntrackedMode,
+    large_file_warn_bytes: Option<u64>,
+) -> Result<Value, String> {
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+
+    let branch_name = repo
+        .head()
+        .ok()
+        .and_then(|head| head.shorthand().map(|s| s.to_string()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let detached_head = repo.head_detached().unwrap_or(false);
+    let head_sha = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string());
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(untracked_mode == GitUntrackedMode::All)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true)
+        .include_ignored(false);
+    if let Some(matcher) = exclude_matcher {
+        for spec in matcher.pathspecs() {
+            status_options.pathspec(spec);
+        }
+    }
+
+    let statuses = repo
+        .statuses(Some(&mut status_options))
+        .map_err(|e| e.to_string())?;
+
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let index = repo.index().ok();
+
+    let mut files = Vec::new();
+    let mut staged_files = Vec::new();
+    let mut unstaged_files = Vec::new();
+    let mut total_additions = 0i64;
+    let mut total_deletions = 0i64;
+    for entry in statuses.iter() {
+        let path = entry.path().unwrap_or("");
+        if path.is_empty() || is_diff_excluded(exclude_matcher, path) {
+            continue;
+        }
+        if let Some(index) = index.as_ref() {
+            if let Some(entry) = index.get_path(Path::new(path), 0) {
+                if entry.flags_extended & INDEX_SKIP_WORKTREE_FLAG != 0 {
+                    continue;
+                }
+            }
+        }
+        let status = entry.status();
+        // Collapsed untracked directories come back with a trailing slash.
+        let is_dir = path.ends_with('/');
+        let normalized_path = normalize_git_path(path.trim_end_matches('/'));
+        let include_index = status.intersects(
+            Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::INDEX_TYPECHANGE,
+        );
+        let include_workdir = status.intersects(
+            Status::WT_NEW
+                | Status::WT_MODIFIED
+                | Status::WT_DELETED
+                | Status::WT_RENAMED
+                | Status::WT_TYPECHANGE,
+        );
+        let mut combined_additions = 0i64;
+        let mut combined_deletions = 0i64;
+        let large_size = large_file_warn_bytes
+            .filter(|_| {
+                !is_dir
+                    && status.intersects(
+                        Status::INDEX_NEW
+                            | Status::INDEX_MODIFIED
+                            | Status::WT_NEW
+                            | Status::WT_MODIFIED,
+                    )
+            })
+            .and_then(|threshold| {
+                fs::metadata(repo_root.join(path))
+                    .ok()
+                    .map(|metadata| metadata.len())
+                    .filter(|size| *size >= threshold)
+            });
+
+        if include_index {
+            let (additions, deletions) =
+                diff_stats_for_path(&repo, head_tree.as_ref(), path, true, false)
+                    .unwrap_or((0, 0));
+            if let Some(status_str) = status_for_index(status) {
+                staged_files.push(GitFileStatus {
+                    path: normalized_path.clone(),
+                    status: status_str.to_string(),
+                    additions,
+                    deletions,
+                    is_dir,
+                    is_large: large_size.is_some(),
+                    size: large_size,
+                });
+            }
+            combined_additions += additions;
+            combined_deletions += deletions;
+            total_additions += additions;
+            total_deletions += deletions;
+        }
+
+        if include_workdir {
+            let (additions, deletions) = if is_dir {
+                (0, 0)
+            } else {
+                diff_stats_for_path(&repo, head_tree.as_ref(), path, false, true)
+                    .unwrap_or((0, 0))
+            };
+            if let Some(status_str) = status_for_workdir(status) {
+                unstaged_files.push(GitFileStatus {
+                    path: normalized_path.clone(),
+                    status: status_str.to_string(),
+                    additions,
+                    deletions,
+                    is_dir,
+                    is_large: large_size.is_some(),
+                    size: large_size,
+                });
+            }
+            combined_additions += additions;
+            combined_deletions += deletions;
+            total_additions += additions;
+            total_deletions += deletions;
+        }
+
+        if include_index || include_workdir {
+            let status_str = status_for_workdir(status)
+                .or_else(|| status_for_index(status))
+                .unwrap_or("--");
+            files.push(GitFileStatus {
+                path: normalized_path,
+                status: status_str.to_string(),
+                additions: combined_additions,
+                deletions: combined_deletions,
+                is_dir,
+                is_large: large_size.is_some(),
+                size: large_size,
+            });
+        }
+    }
+
+    Ok(json!({
+        "branchName": branch_name,
+        "detachedHead": detached_head,
+        "headSha": head_sha,
+        "files": files,
+        "stagedFiles": staged_files,
+        "unstagedFiles": unstaged_files,
+        "totalAdditions": total_additions,
+        "totalDeletions": total_deletions,
+    }))
+}
+
+/// Repo-relative path with forward slashes; rejects absolute paths and `..`.
+fn checked_relative_path(path: &str) -> Result<String, String> {
+    let path = normalize_git_path(path.trim());
+    let relative = Path::new(&path);
+    if path.is_empty()
+        || relative.is_absolute()
+        || relative
+            .components()
+            .any(|component| matches!(component, std::path::Component::ParentDir))
+    {
+        return Err("Invalid path.".to_string());
+    }
+    Ok(path)
+}
+
+fn conflict_version(repo: &Repository, index: &git2::Index, path: &str, stage: i32) -> GitConflictVersion {
+    let absent = GitConflictVersion {
+        present: false,
+        lines: None,
+        is_binary: false,
+        too_large: false,
+    };
+    let Some(entry) = index.get_path(Path::new(path), stage) else {
+        return absent;
+    };
+    let Ok(blob) = repo.find_blob(entry.id) else {
+        return absent;
+    };
+    let too_large = blob.size() > MAX_TEXT_DIFF_BYTES;
+    let lines = if too_large { None } else { blob_to_lines(blob) };
+    GitConflictVersion {
+        present: true,
+        is_binary: !too_large && lines.is_none(),
+        too_large,
+        lines,
+    }
+}
+
+fn is_conflicted(index: &git2::Index, path: &str) -> bool {
+    (1..=3).any(|stage| index.get_path(Path::new(path), stage).is_some())
+}
+
+/// Base/ours/theirs contents of a conflicted path, read from index stages
+/// 1/2/3, for a three-way merge view.
+pub(crate) fn conflict_versions_in_repo(
+    repo_root: &Path,
+    path: &str,
+) -> Result<GitConflictVersions, String> {
+    let path = checked_relative_path(path)?;
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+    if !is_conflicted(&index, &path) {
+        return Err(format!("{path} is not conflicted."));
+    }
+    Ok(GitConflictVersions {
+        base: conflict_version(&repo, &index, &path, 1),
+        ours: conflict_version(&repo, &index, &path, 2),
+        theirs: conflict_version(&repo, &index, &path, 3),
+        path,
+    })
+}
+
+/// Writes merged `content` over a conflicted path and stages it, which
+/// clears the path's conflict entries like `git add` does.
+pub(crate) fn resolve_conflict_with_content_in_repo(
+    repo_root: &Path,
+    path: &str,
+    content: &str,
+) -> Result<(), String> {
+    let path = checked_relative_path(path)?;
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    if !is_conflicted(&index, &path) {
+        return Err(format!("{path} is not conflicted."));
+    }
+    let target = repo_root.join(&path);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&target, content).map_err(|e| e.to_string())?;
+    index.add_path(Path::new(&path)).map_err(|e| e.to_string())?;
+    index.write().map_err(|e| e.to_string())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+pub(crate) fn repo_stats_in_repo(repo_root: &Path) -> Result<RepoStats, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    // An unborn HEAD has no history yet.
+    let commit_count = match repo.head().ok().and_then(|head| head.target()) {
+        Some(head) => {
+            let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+            revwalk.push(head).map_err(|e| e.to_string())?;
+            revwalk.count()
+        }
+        None => 0,
+    };
+    let count_branches = |kind| -> Result<usize, String> {
+        Ok(repo
+            .branches(Some(kind))
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .filter(|(branch, _)| branch.get().symbolic_target().is_none())
+            .count())
+    };
+    let branch_count = count_branches(git2::BranchType::Local)?;
+    let remote_branch_count = count_branches(git2::BranchType::Remote)?;
+    let tag_count = repo.tag_names(None).map_err(|e| e.to_string())?.len();
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let tracked_files = index.len();
+    let worktree_size = index
+        .iter()
+        .filter_map(|entry| {
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            fs::symlink_metadata(repo_root.join(path)).ok()
+        })
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum();
+    Ok(RepoStats {
+        commit_count,
+        branch_count,
+        remote_branch_count,
+        tag_count,
+        tracked_files,
+        worktree_size,
+        git_dir_size: dir_size(repo.path()),
+    })
+}
+
+/// Walks history and both the work tree and `.git` on disk, so it runs on
+/// the blocking pool.
+pub(crate) async fn get_repo_stats_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<RepoStats, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || repo_stats_in_repo(&repo_root))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Parses `git check-ignore -v -z` output:
+/// `<source> NUL <line> NUL <pattern> NUL <path> NUL`.
+pub(crate) fn parse_check_ignore_verbose(output: &str) -> IgnoreExplanation {
+    let mut fields = output.split('\0');
+    let source = fields.next().unwrap_or("");
+    let line = fields.next().and_then(|line| line.parse::<u32>().ok());
+    let pattern = fields.next().unwrap_or("");
+    if source.is_empty() || pattern.is_empty() {
+        return IgnoreExplanation {
+            ignored: false,
+            source: None,
+            line: None,
+            pattern: None,
+        };
+    }
+    IgnoreExplanation {
+        ignored: !pattern.starts_with('!'),
+        source: Some(normalize_git_path(source)),
+        line,
+        pattern: Some(pattern.to_string()),
+    }
+}
+
+/// Which `.gitignore`/exclude rule, if any, decides whether `path` is ignored.
+pub(crate) async fn explain_ignore_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+) -> Result<IgnoreExplanation, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let path = checked_relative_path(&path)?;
+    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+    let _permit = git_op_limiter().acquire().await;
+    let output = tokio_command(git_bin)
+        .args(["check-ignore", "-v", "-z", "--", &path])
+        .current_dir(&repo_root)
+        .envs(workspace_env_for(&repo_root))
+        .env("PATH", git_env_path())
+        .output()
+        .await
+        .map_err(|err| format!("Failed to run git: {err}"))?;
+    // Exit status 1 means no rule matched; `-v` still prints negated matches
+    // with status 0.
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(parse_check_ignore_verbose(&String::from_utf8_lossy(
+            &output.stdout,
+        ))),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.trim().is_empty() {
+                Err("Git command failed.".to_string())
+            } else {
+                Err(stderr.trim().to_string())
+            }
+        }
+    }
+}
+
+/// Note text attached to `sha` on `notes_ref` (default `refs/notes/commits`),
+/// or `None` when the commit has no note there. A bare ref name like `ci` is
+/// read as `refs/notes/ci`.
+pub(crate) fn commit_notes_in_repo(
+    repo: &Repository,
+    sha: &str,
+    notes_ref: Option<&str>,
+) -> Result<Option<String>, String> {
+    let oid = repo
+        .revparse_single(sha.trim())
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| e.message().to_string())?
+        .id();
+    let notes_ref = notes_ref
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            if name.starts_with("refs/") {
+                name.to_string()
+            } else {
+                format!("refs/notes/{name}")
+            }
+        });
+    match repo.find_note(notes_ref.as_deref(), oid) {
+        Ok(note) => Ok(Some(note.message().unwrap_or("").to_string())),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err.message().to_string()),
+    }
+}
+
+pub(crate) async fn get_commit_notes_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    sha: String,
+    notes_ref: Option<String>,
+) -> Result<Option<String>, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        commit_notes_in_repo(&repo, &sha, notes_ref.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn stash_apply_error(spec: &str, error: String) -> String {
+    if error.to_lowercase().contains("conflict") {
+        format!("Applying {spec} produced conflicts; resolve them to finish. The stash was kept.\n{error}")
+    } else {
+        error
+    }
+}
+
+/// `git stash apply stash@{index}`: like pop, but the entry stays in the
+/// stash list so it can be applied elsewhere too.
+pub(crate) async fn stash_apply_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    index: usize,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let spec = format!("stash@{{{index}}}");
+    run_git_command(&repo_root, &["stash", "apply", &spec])
+        .await
+        .map(|_| ())
+        .map_err(|error| stash_apply_error(&spec, error))
+}
+
+pub(crate) async fn get_conflict_versions_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+) -> Result<GitConflictVersions, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || conflict_versions_in_repo(&repo_root, &path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+pub(crate) async fn resolve_conflict_with_content_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+    content: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        resolve_conflict_with_content_in_repo(&repo_root, &path, &content)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Forge URL pointing at `line` of `path` as of `rev` (default `HEAD`),
+/// pinned to the full commit sha so the link survives later pushes.
+pub(crate) fn permalink_in_repo(
+    repo_root: &Path,
+    path: &str,
+    line: u32,
+    rev: Option<&str>,
+) -> Result<String, String> {
+    let path = checked_relative_path(path)?;
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let rev = rev.map(str::trim).filter(|value| !value.is_empty()).unwrap_or("HEAD");
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| format!("Cannot resolve {rev} to a commit."))?;
+    let remotes = repo.remotes().map_err(|e| e.to_string())?;
+    let name = if remotes.iter().any(|remote| remote == Some("origin")) {
+        "origin"
+    } else {
+        remotes
+            .iter()
+            .flatten()
+            .next()
+            .ok_or("No git remote configured.")?
+    };
+    let remote = repo.find_remote(name).map_err(|e| e.to_string())?;
+    let remote_url = remote.url().ok_or("Remote has no URL configured.")?;
+    forge_blob_url(remote_url, &commit.id().to_string(), &path, line.max(1))
+}
+
+pub(crate) async fn build_permalink_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+    line: u32,
+    rev: Option<String>,
+) -> Result<String, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        permalink_in_repo(&repo_root, &path, line, rev.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// A lock younger than this may belong to an operation that is still running.
+const STALE_INDEX_LOCK_AGE: Duration = Duration::from_secs(60);
+
+/// Whether any git process is running; `None` if that cannot be determined.
+#[cfg(not(windows))]
+async fn git_process_running() -> Option<bool> {
+    let output = tokio_command("pgrep").args(["-x", "git"]).output().await.ok()?;
+    match output.status.code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+async fn git_process_running() -> Option<bool> {
+    let output = tokio_command("tasklist")
+        .args(["/FI", "IMAGENAME eq git.exe", "/NH"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).to_ascii_lowercase();
+    Some(stdout.contains("git.exe"))
+}
+
+/// Removes `<git_dir>/index.lock` only when it is old and no git process is
+/// known to be running; when in doubt the lock is kept and `note` says why.
+pub(crate) fn clear_stale_index_lock(git_dir: &Path, git_running: Option<bool>) -> IndexLockReport {
+    let lock = git_dir.join("index.lock");
+    let Ok(metadata) = fs::metadata(&lock) else {
+        return IndexLockReport {
+            found: false,
+            age_secs: None,
+            removed: false,
+            note: None,
+        };
+    };
+    let age = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    let keep_reason = match (age, git_running) {
+        (None, _) => Some("Could not determine the lock's age.".to_string()),
+        (Some(age), _) if age < STALE_INDEX_LOCK_AGE => {
+            Some("The lock is recent; a git operation may still be running.".to_string())
+        }
+        (_, Some(true)) => Some("A git process is running and may own the lock.".to_string()),
+        (_, None) => Some("Could not check for running git processes.".to_string()),
+        (Some(_), Some(false)) => None,
+    };
+    let (removed, note) = match keep_reason {
+        Some(reason) => (false, Some(reason)),
+        None => match fs::remove_file(&lock) {
+            Ok(()) => (true, None),
+            Err(err) => (false, Some(format!("Failed to remove index.lock: {err}"))),
+        },
+    };
+    IndexLockReport {
+        found: true,
+        age_secs: age.map(|age| age.as_secs()),
+        removed,
+        note,
+    }
+}
+
+/// Clears a stale `index.lock` left by an interrupted operation, then runs a
+/// connectivity-only fsck so object corruption is reported too.
+pub(crate) async fn repair_repo_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<RepoRepairReport, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let git_dir = Repository::open(&repo_root)
+        .map_err(|e| e.to_string())?
+        .path()
+        .to_path_buf();
+    // Only pay for the process scan when there is a lock to judge.
+    let git_running = if git_dir.join("index.lock").exists() {
+        git_process_running().await
+    } else {
+        Some(false)
+    };
+    let index_lock = clear_stale_index_lock(&git_dir, git_running);
+    let (fsck_ok, fsck_output) =
+        match run_git_command(&repo_root, &["fsck", "--connectivity-only", "--no-dangling"]).await {
+            Ok(output) => (true, output),
+            Err(detail) => (false, detail),
+        };
+    Ok(RepoRepairReport {
+        index_lock,
+        fsck_ok,
+        fsck_output,
+    })
+}
+
+fn read_progress_counter(path: &Path) -> Option<usize> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Detects an in-progress operation from the marker files git leaves in the
+/// git dir. Rebase is checked first because it can leave other markers behind
+/// (e.g. `CHERRY_PICK_HEAD`) while replaying commits.
+pub(crate) fn repo_operation_state(repo: &Repository) -> RepoOperation {
+    let git_dir = repo.path();
+    let rebase_merge = git_dir.join("rebase-merge");
+    if rebase_merge.is_dir() {
+        return RepoOperation::Rebase {
+            step: read_progress_counter(&rebase_merge.join("msgnum")),
+            total: read_progress_counter(&rebase_merge.join("end")),
+        };
+    }
+    let rebase_apply = git_dir.join("rebase-apply");
+    if rebase_apply.is_dir() {
+        return RepoOperation::Rebase {
+            step: read_progress_counter(&rebase_apply.join("next")),
+            total: read_progress_counter(&rebase_apply.join("last")),
+        };
+    }
+    if git_dir.join("MERGE_HEAD").is_file() {
+        return RepoOperation::Merge;
+    }
+    if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+        return RepoOperation::CherryPick;
+    }
+    if git_dir.join("REVERT_HEAD").is_file() {
+        return RepoOperation::Revert;
+    }
+    if git_dir.join("BISECT_LOG").is_file() {
+        return RepoOperation::Bisect;
+    }
+    RepoOperation::None
+}
+
+/// Splits complete lines off `pending`. Git redraws progress with `\r`, so
+/// both `\r` and `\n` end a line; blank lines are dropped.
+pub(crate) fn drain_progress_lines(pending: &mut String) -> Vec<String> {
+    let mut lines = Vec::new();
+    while let Some(end) = pending.find(['\r', '\n']) {
+        let line = pending[..end].trim().to_string();
+        pending.drain(..=end);
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Runs git with stderr piped and hands each progress line to `on_line` as it
+/// arrives. On failure the last line printed is the error.
+pub(crate) async fn run_git_streaming(
+    repo_root: &Path,
+    args: &[&str],
+    mut on_line: impl FnMut(&str) + Send,
+) -> Result<(), String> {
+    use tokio::io::AsyncReadExt;
+
+    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+    let _permit = git_op_limiter().acquire().await;
+    let mut child = tokio_command(git_bin)
+        .args(args)
+        .current_dir(repo_root)
+        .envs(workspace_env_for(repo_root))
+        .env("PATH", git_env_path())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Failed to run git: {err}"))?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| "Failed to capture git output.".to_string())?;
+    let mut buffer = [0u8; 4096];
+    let mut pending = String::new();
+    let mut last_line = String::new();
+    loop {
+        let read = stderr
+            .read(&mut buffer)
+            .await
+            .map_err(|err| format!("Failed to read git output: {err}"))?;
+        if read == 0 {
+            break;
+        }
+        pending.push_str(&String::from_utf8_lossy(&buffer[..read]));
+        for line in drain_progress_lines(&mut pending) {
+            on_line(&line);
+            last_line = line;
+        }
+    }
+    let tail = pending.trim();
+    if !tail.is_empty() {
+        on_line(tail);
+        last_line = tail.to_string();
+    }
+    let status = child
+        .wait()
+        .await
+        .map_err(|err| format!("Failed to run git: {err}"))?;
+    if status.success() {
+        Ok(())
+    } else if last_line.is_empty() {
+        Err("Git command failed.".to_string())
+    } else {
+        Err(last_line)
+    }
+}
+
+/// `git gc` (optionally `--aggressive`), streaming its progress lines.
+/// Refuses to run mid merge/rebase/etc. so it can't race the operation.
+pub(crate) async fn gc_repo_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    aggressive: bool,
+    on_progress: impl FnMut(&str) + Send,
+) -> Result<GcReport, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let git_dir = {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        let operation = match repo_operation_state(&repo) {
+            RepoOperation::None => None,
+            RepoOperation::Merge => Some("merge"),
+            RepoOperation::Rebase { .. } => Some("rebase"),
+            RepoOperation::CherryPick => Some("cherry-pick"),
+            RepoOperation::Revert => Some("revert"),
+            RepoOperation::Bisect => Some("bisect"),
+        };
+        if let Some(operation) = operation {
+            return Err(format!(
+                "A {operation} is in progress; finish or abort it before running gc."
+            ));
+        }
+        repo.path().to_path_buf()
+    };
+    if git_dir.join("index.lock").exists() {
+        return Err("Another git process holds index.lock; try again when it finishes.".to_string());
+    }
+
+    let size_dir = git_dir.clone();
+    let git_dir_size_before = spawn_git_blocking(move || dir_size(&size_dir))
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut args = vec!["gc", "--progress"];
+    if aggressive {
+        args.push("--aggressive");
+    }
+    run_git_streaming(&repo_root, &args, on_progress).await?;
+    let git_dir_size_after = spawn_git_blocking(move || dir_size(&git_dir))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(GcReport {
+        git_dir_size_before,
+        git_dir_size_after,
+    })
+}
+
+pub(crate) async fn workspace_git_root(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: &str,
+) -> Result<(WorkspaceEntry, PathBuf), String> {
+    let entry = workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or_else(|| "workspace not found".to_string())?;
+    let repo_root = resolve_git_root(&entry)?;
+    Ok((entry, repo_root))
+}
+
+fn head_state(repo: &Repository) -> &'static str {
+    match repo.head() {
+        Ok(_) if repo.head_detached().unwrap_or(false) => "detached",
+        Ok(_) => "branch",
+        Err(err) if err.code() == git2::ErrorCode::UnbornBranch => "unborn",
+        Err(_) => "unknown",
+    }
+}
+
+/// Health check for a workspace's git root, reported instead of failing so
+/// the UI can flag workspaces that are no longer repositories.
+pub(crate) fn validate_git_root(entry: &WorkspaceEntry) -> Value {
+    let git_root_override = entry
+        .settings
+        .git_root
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|configured| {
+            let path = Path::new(&entry.path).join(configured);
+            let error = match Repository::open(&path) {
+                Ok(_) => None,
+                Err(err) => Some(err.message().to_string()),
+            };
+            json!({
+                "path": configured,
+                "valid": error.is_none(),
+                "error": error,
+            })
+        });
+    let failure = |error: String| {
+        json!({
+            "ok": false,
+            "repoRoot": Value::Null,
+            "isBare": false,
+            "headState": Value::Null,
+            "error": error,
+            "gitRootOverride": git_root_override,
+        })
+    };
+    let repo_root = match resolve_git_root(entry) {
+        Ok(root) => root,
+        Err(err) => return failure(err),
+    };
+    let repo = match Repository::open(&repo_root) {
+        Ok(repo) => repo,
+        Err(err) => {
+            return failure(format!(
+                "Not a git repository: {} ({})",
+                repo_root.display(),
+                err.message()
+            ))
+        }
+    };
+    json!({
+        "ok": true,
+        "repoRoot": repo_root.to_string_lossy(),
+        "isBare": repo.is_bare(),
+        "headState": head_state(&repo),
+        "error": Value::Null,
+        "gitRootOverride": git_root_override,
+    })
+}
+
+pub(crate) async fn validate_workspace_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Value, String> {
+    let entry = workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or_else(|| "workspace not found".to_string())?;
+    spawn_git_blocking(move || validate_git_root(&entry))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) async fn get_git_status_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    untracked_mode: Option<GitUntrackedMode>,
+) -> Result<Value, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let exclude_matcher =
+        build_diff_exclude_matcher(&repo_root, &entry.settings.diff_exclude_globs)?;
+    let (large_file_warn_bytes, git_backend) = {
+        let settings = app_settings.lock().await;
+        (settings.large_file_warn_bytes, settings.git_backend)
+    };
+    if git_backend.uses_system_git() {
+        return git_status_via_cli(
+            &repo_root,
+            exclude_matcher.as_ref(),
+            untracked_mode.unwrap_or_default(),
+            large_file_warn_bytes,
+        )
+        .await;
+    }
+    git_status_in_repo(
+        &repo_root,
+        exclude_matcher.as_ref(),
+        untracked_mode.unwrap_or_default(),
+        large_file_warn_bytes,
+    )
+}
+
+/// Counts of changed, staged, unstaged and conflicted files, without the
+/// per-file diff stats `git_status_in_repo` computes.
+pub(crate) fn summarize_changes(
+    repo: &Repository,
+    exclude_matcher: Option<&DiffExcludeMatcher>,
+) -> Result<GitChangeSummary, String> {
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true)
+        .include_ignored(false);
+    if let Some(matcher) = exclude_matcher {
+        for spec in matcher.pathspecs() {
+            status_options.pathspec(spec);
+        }
+    }
+    let statuses = repo
+        .statuses(Some(&mut status_options))
+        .map_err(|e| e.to_string())?;
+    let index = repo.index().ok();
+
+    let mut changed_files = 0usize;
+    let mut staged = 0usize;
+    let mut unstaged = 0usize;
+    let mut conflicted = 0usize;
+    for entry in statuses.iter() {
+        let path = entry.path().unwrap_or("");
+        if path.is_empty() || is_diff_excluded(exclude_matcher, path) {
+            continue;
+        }
+        if let Some(index) = index.as_ref() {
+            if let Some(entry) = index.get_path(Path::new(path), 0) {
+                if entry.flags_extended & INDEX_SKIP_WORKTREE_FLAG != 0 {
+                    continue;
+                }
+            }
+        }
+        let status = entry.status();
+        if status.contains(Status::CONFLICTED) {
+            conflicted += 1;
+            changed_files += 1;
+            continue;
+        }
+        let include_index = status.intersects(
+            Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::INDEX_TYPECHANGE,
+        );
+        let include_workdir = status.intersects(
+            Status::WT_NEW
+                | Status::WT_MODIFIED
+                | Status::WT_DELETED
+                | Status::WT_RENAMED
+                | Status::WT_TYPECHANGE,
+        );
+        if include_index {
+            staged += 1;
+        }
+        if include_workdir {
+            unstaged += 1;
+        }
+        if include_index || include_workdir {
+            changed_files += 1;
+        }
+    }
+
+    Ok(GitChangeSummary {
+        changed_files,
+        staged,
+        unstaged,
+        conflicted,
+    })
+}
+
+pub(crate) async fn get_git_change_summary_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<GitChangeSummary, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        let exclude_matcher =
+            build_diff_exclude_matcher(&repo_root, &entry.settings.diff_exclude_globs)?;
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        summarize_changes(&repo, exclude_matcher.as_ref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Cheapest clean/dirty check: a HEAD-to-index and an index-to-workdir diff
+/// with no content stats. Unless `count_changes`, it stops at the first
+/// change. Every path counts here: diff exclude globs and skip-worktree
+/// entries only hide files from the diff view, they don't make a tree clean.
+pub(crate) fn working_tree_cleanliness(
+    repo: &Repository,
+    count_changes: bool,
+) -> Result<WorkingTreeCleanliness, String> {
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let mut result = WorkingTreeCleanliness::default();
+    let mut conflicted_paths = HashSet::new();
+    if index.has_conflicts() {
+        for conflict in index.conflicts().map_err(|e| e.to_string())? {
+            let conflict = conflict.map_err(|e| e.to_string())?;
+            if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+                conflicted_paths.insert(entry.path);
+            }
+            if !count_changes {
+                break;
+            }
+        }
+        result.conflicted = conflicted_paths.len();
+    }
+
+    let done = |result: &WorkingTreeCleanliness| {
+        !count_changes && result.staged + result.unstaged + result.untracked + result.conflicted > 0
+    };
+    if !done(&result) {
+        let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+        let mut options = DiffOptions::new();
+        options.ignore_submodules(true);
+        let staged = repo
+            .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut options))
+            .map_err(|e| e.to_string())?;
+        for_each_delta(&staged, &conflicted_paths, count_changes, |_| result.staged += 1)?;
+    }
+    if !done(&result) {
+        let mut options = DiffOptions::new();
+        options
+            .ignore_submodules(true)
+            .include_untracked(true)
+            .recurse_untracked_dirs(count_changes);
+        let worktree = repo
+            .diff_index_to_workdir(Some(&index), Some(&mut options))
+            .map_err(|e| e.to_string())?;
+        for_each_delta(&worktree, &conflicted_paths, count_changes, |status| {
+            if status == git2::Delta::Untracked {
+                result.untracked += 1;
+            } else {
+                result.unstaged += 1;
+            }
+        })?;
+    }
+    result.clean = result.staged + result.unstaged + result.untracked + result.conflicted == 0;
+    Ok(result)
+}
+
+/// Calls `on_delta` for each delta outside `conflicted_paths`, stopping after
+/// the first one unless `visit_all`.
+fn for_each_delta(
+    diff: &git2::Diff<'_>,
+    conflicted_paths: &HashSet<Vec<u8>>,
+    visit_all: bool,
+    mut on_delta: impl FnMut(git2::Delta),
+) -> Result<(), String> {
+    let walked = diff.foreach(
+        &mut |delta, _| {
+            let path = delta
+                .new_file()
+                .path_bytes()
+                .or_else(|| delta.old_file().path_bytes());
+            if delta.status() == git2::Delta::Conflicted
+                || path.is_some_and(|path| conflicted_paths.contains(path))
+            {
+                return true;
+            }
+            on_delta(delta.status());
+            visit_all
+        },
+        None,
+        None,
+        None,
+    );
+    match walked {
+        Err(err) if err.code() == git2::ErrorCode::User => Ok(()),
+        other => other.map_err(|e| e.to_string()),
+    }
+}
+
+pub(crate) async fn is_working_tree_clean_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    count_changes: bool,
+) -> Result<WorkingTreeCleanliness, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        working_tree_cleanliness(&repo, count_changes)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Turns a plain workspace folder into a repository. Refuses when the folder
+/// already sits inside a repository so we never create nested repos.
+pub(crate) async fn init_repo_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    initial_branch: Option<String>,
+) -> Result<String, String> {
+    let entry = workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or_else(|| "workspace not found".to_string())?;
+    let workspace_path = PathBuf::from(&entry.path);
+    if !workspace_path.is_dir() {
+        return Err(format!("Workspace path not found: {}", entry.path));
+    }
+    if let Ok(existing) = Repository::discover(&workspace_path) {
+        let existing_root = existing
+            .workdir()
+            .unwrap_or_else(|| existing.path())
+            .to_string_lossy()
+            .to_string();
+        return Err(format!("Already inside a git repository: {existing_root}"));
+    }
+    let initial_branch = initial_branch
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty());
+    let mut args = vec!["init"];
+    if let Some(branch) = initial_branch.as_deref() {
+        if branch.starts_with('-') {
+            return Err(format!("Invalid branch name: {branch}"));
+        }
+        args.push("-b");
+        args.push(branch);
+    }
+    run_git_command(&workspace_path, &args).await?;
+
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = Repository::discover(&repo_root).map_err(|e| e.to_string())?;
+    let root = repo.workdir().unwrap_or_else(|| repo.path());
+    Ok(normalize_git_path(&root.to_string_lossy())
+        .trim_end_matches('/')
+        .to_string())
+}
+
+/// Prefix for pushes rejected because the remote repository does not exist.
+pub(crate) const REMOTE_NOT_FOUND_PREFIX: &str = "Remote repository not found";
+/// Prefix for pushes rejected because the credentials lack access.
+pub(crate) const REMOTE_PERMISSION_DENIED_PREFIX: &str = "Permission denied by remote";
+
+pub(crate) fn classify_remote_push_error(detail: String) -> String {
+    let lowered = detail.to_lowercase();
+    if lowered.contains("repository not found")
+        || lowered.contains("does not appear to be a git repository")
+    {
+        return format!("{REMOTE_NOT_FOUND_PREFIX}: {detail}");
+    }
+    // Match git's HTTP status phrasing rather than a bare "403", which also
+    // shows up in hashes, ref names and hook output.
+    if lowered.contains("permission denied")
+        || lowered.contains("authentication failed")
+        || lowered.contains("the requested url returned error: 403")
+        || lowered.contains("403 forbidden")
+    {
+        return format!("{REMOTE_PERMISSION_DENIED_PREFIX}: {detail}");
+    }
+    detail
+}
+
+/// Adds `remote_name` (unless it already points at `url`), pushes `branch`
+/// with `-u`, and returns the resulting upstream such as `origin/main`.
+/// `branch` defaults to the current branch. A remote added here is removed
+/// again when the push fails, so a mistyped URL can simply be retried.
+pub(crate) async fn set_upstream_and_push_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    remote_name: String,
+    url: String,
+    branch: Option<String>,
+) -> Result<String, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let remote_name = remote_name.trim().to_string();
+    let url = url.trim().to_string();
+    if remote_name.is_empty() || remote_name.starts_with('-') {
+        return Err("Remote name is required.".to_string());
+    }
+    if url.is_empty() {
+        return Err("Remote URL is required.".to_string());
+    }
+    let (branch, added_remote) = {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        let branch = match branch
+            .map(|branch| branch.trim().to_string())
+            .filter(|branch| !branch.is_empty())
+        {
+            Some(branch) => branch,
+            None => {
+                let head = repo
+                    .head()
+                    .map_err(|_| "Create a commit before pushing.".to_string())?;
+                if !head.is_branch() {
+                    return Err("HEAD is detached; choose a branch to push.".to_string());
+                }
+                head.shorthand()
+                    .ok_or("Current branch name is not valid UTF-8.")?
+                    .to_string()
+            }
+        };
+        if branch.starts_with('-') {
+            return Err(format!("Invalid branch name: {branch}"));
+        }
+        let added_remote = match repo.find_remote(&remote_name) {
+            Ok(remote) => {
+                let existing = remote.url().unwrap_or("");
+                if existing != url {
+                    return Err(format!(
+                        "Remote '{remote_name}' already exists with URL {existing}"
+                    ));
+                }
+                false
+            }
+            Err(_) => {
+                repo.remote(&remote_name, &url)
+                    .map_err(|e| e.message().to_string())?;
+                true
+            }
+        };
+        (branch, added_remote)
+    };
+
+    let pushed =
+        run_git_command(&repo_root, &["push", "-u", remote_name.as_str(), branch.as_str()]).await;
+    if let Err(error) = pushed {
+        if added_remote {
+            if let Ok(repo) = Repository::open(&repo_root) {
+                let _ = repo.remote_delete(&remote_name);
+            }
+        }
+        return Err(classify_remote_push_error(error));
+    }
+
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    let local = repo
+        .find_branch(&branch, git2::BranchType::Local)
+        .map_err(|e| e.to_string())?;
+    let upstream = local.upstream().map_err(|e| e.to_string())?;
+    let upstream_name = upstream
+        .name()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default()
+        .to_string();
+    Ok(upstream_name)
+}
+
+/// GitHub's pull request heads. GitLab uses `refs/merge-requests/*/head`.
+pub(crate) const DEFAULT_PR_REFSPEC: &str = "+refs/pull/*/head:refs/remotes/{remote}/pr/*";
+
+/// Local ref prefix a `src:dst` wildcard refspec writes into. The destination
+/// must sit under `refs/remotes/<remote>/` so a forced fetch can never
+/// overwrite local branches or tags.
+pub(crate) fn refspec_destination_prefix(refspec: &str, remote: &str) -> Result<String, String> {
+    let trimmed = refspec.trim();
+    let (source, destination) = trimmed
+        .trim_start_matches('+')
+        .split_once(':')
+        .ok_or_else(|| format!("Refspec `{trimmed}` must have the form src:dst."))?;
+    let remote_prefix = format!("refs/remotes/{remote}/");
+    let destination_prefix = destination.strip_suffix('*').filter(|prefix| {
+        !prefix.contains('*') && !prefix.contains("..") && prefix.starts_with(&remote_prefix)
+    });
+    match destination_prefix {
+        Some(prefix) if source.contains('*') && !trimmed.starts_with('-') => {
+            Ok(prefix.to_string())
+        }
+        _ => Err(format!(
+            "Refspec `{trimmed}` must map a wildcard source to a {remote_prefix}...* destination."
+        )),
+    }
+}
+
+/// Orders ref names so a trailing number sorts numerically (`pr/2` before
+/// `pr/10`).
+fn sort_refs_naturally(names: &mut [String]) {
+    names.sort_by_cached_key(|name| {
+        let (parent, last) = name.rsplit_once('/').unwrap_or(("", name.as_str()));
+        (parent.to_string(), last.parse::<u64>().ok(), last.to_string())
+    });
+}
+
+fn refs_with_prefix(repo_root: &Path, prefix: &str) -> Result<HashMap<String, git2::Oid>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let references = repo
+        .references_glob(&format!("{prefix}*"))
+        .map_err(|e| e.to_string())?;
+    Ok(references
+        .flatten()
+        .filter_map(|reference| Some((reference.name()?.to_string(), reference.target()?)))
+        .collect())
+}
+
+/// Fetches every pull request head from `remote` (default `origin`) as a
+/// remote-tracking ref such as `origin/pr/12`. `refspec` overrides the GitHub
+/// layout for other forges; `{remote}` in it is replaced by the remote name,
+/// and its destination must stay under `refs/remotes/<remote>/`.
+pub(crate) async fn fetch_all_open_prs_as_branches_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    remote: Option<String>,
+    refspec: Option<String>,
+) -> Result<PullRequestRefsFetch, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let remote = remote
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "origin".to_string());
+    if remote.starts_with('-') {
+        return Err(format!("Invalid remote name: {remote}"));
+    }
+    {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        repo.find_remote(&remote)
+            .map_err(|_| format!("Remote '{remote}' is not configured."))?;
+    }
+    let refspec = refspec
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| DEFAULT_PR_REFSPEC.to_string())
+        .replace("{remote}", &remote);
+    let prefix = refspec_destination_prefix(&refspec, &remote)?;
+
+    let before = refs_with_prefix(&repo_root, &prefix)?;
+    let fetched = run_git_command(&repo_root, &["fetch", remote.as_str(), refspec.as_str()]).await;
+    if let Err(error) = fetched {
+        // A concrete source that doesn't exist is the only way a wildcard
+        // fetch fails for lack of refs; anything else is a real error.
+        if error.contains("couldn't find remote ref") {
+            return Ok(PullRequestRefsFetch {
+                remote,
+                refspec,
+                ..PullRequestRefsFetch::default()
+            });
+        }
+        return Err(error);
+    }
+    let after = refs_with_prefix(&repo_root, &prefix)?;
+
+    let short_name = |name: &str| name.strip_prefix("refs/remotes/").unwrap_or(name).to_string();
+    let mut created = Vec::new();
+    let mut updated = Vec::new();
+    for (name, oid) in &after {
+        match before.get(name) {
+            None => created.push(short_name(name)),
+            Some(previous) if previous != oid => updated.push(short_name(name)),
+            Some(_) => {}
+        }
+    }
+    sort_refs_naturally(&mut created);
+    sort_refs_naturally(&mut updated);
+    Ok(PullRequestRefsFetch {
+        remote,
+        refspec,
+        supported: !after.is_empty(),
+        created,
+        updated,
+        total: after.len(),
+    })
+}
+
+const STATUS_BATCH_CONCURRENCY: usize = 4;
+
+fn batch_status_for_entry(
+    entry: &WorkspaceEntry,
+    summary_only: bool,
+    large_file_warn_bytes: Option<u64>,
+) -> Result<Value, String> {
+    let repo_root = resolve_git_root(entry)?;
+    let exclude_matcher =
+        build_diff_exclude_matcher(&repo_root, &entry.settings.diff_exclude_globs)?;
+    if summary_only {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        let summary = summarize_changes(&repo, exclude_matcher.as_ref())?;
+        return serde_json::to_value(summary).map_err(|e| e.to_string());
+    }
+    git_status_in_repo(
+        &repo_root,
+        exclude_matcher.as_ref(),
+        GitUntrackedMode::default(),
+        large_file_warn_bytes,
+    )
+}
+
+/// Status for several workspaces at once, keyed by workspace id. Each value is
+/// `{ "status": ... }` or `{ "error": ... }`, so one broken repo doesn't fail
+/// the batch. With `summary_only`, statuses are `summarize_changes` counts.
+pub(crate) async fn get_git_status_batch_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_ids: Vec<String>,
+    summary_only: bool,
+) -> Result<Value, String> {
+    let (large_file_warn_bytes, git_backend) = {
+        let settings = app_settings.lock().await;
+        (settings.large_file_warn_bytes, settings.git_backend)
+    };
+    let entries: Vec<(String, Option<WorkspaceEntry>)> = {
+        let workspaces = workspaces.lock().await;
+        let mut seen = std::collections::HashSet::new();
+        workspace_ids
+            .into_iter()
+            .filter(|id| seen.insert(id.clone()))
+            .map(|id| {
+                let entry = workspaces.get(&id).cloned();
+                (id, entry)
+            })
+            .collect()
+    };
+
+    let semaphore = Arc::new(Semaphore::new(STATUS_BATCH_CONCURRENCY));
+    let mut tasks = tokio::task::JoinSet::new();
+    for (workspace_id, entry) in entries {
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let result = async {
+                let entry = entry.ok_or_else(|| "workspace not found".to_string())?;
+                let _permit = semaphore.acquire_owned().await.map_err(|e| e.to_string())?;
+                if git_backend.uses_system_git() && !summary_only {
+                    let repo_root = resolve_git_root(&entry)?;
+                    let exclude_matcher = build_diff_exclude_matcher(
+                        &repo_root,
+                        &entry.settings.diff_exclude_globs,
+                    )?;
+                    return git_status_via_cli(
+                        &repo_root,
+                        exclude_matcher.as_ref(),
+                        GitUntrackedMode::default(),
+                        large_file_warn_bytes,
+                    )
+                    .await;
+                }
+                spawn_git_blocking(move || {
+                    batch_status_for_entry(&entry, summary_only, large_file_warn_bytes)
+                })
+                .await
+                .map_err(|e| e.to_string())?
+            }
+            .await;
+            (workspace_id, result)
+        });
+    }
+
+    let mut statuses = serde_json::Map::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (workspace_id, result) = joined.map_err(|e| e.to_string())?;
+        let value = match result {
+            Ok(status) => json!({ "status": status }),
+            Err(error) => json!({ "error": error }),
+        };
+        statuses.insert(workspace_id, value);
+    }
+    Ok(Value::Object(statuses))
+}
+
+pub(crate) async fn get_git_diffs_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    whitespace_check: bool,
+    full_file: bool,
+) -> Result<Vec<GitFileDiff>, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let exclude_matcher =
+        build_diff_exclude_matcher(&repo_root, &entry.settings.diff_exclude_globs)?;
+    let (whitespace, context_lines, normalize_line_endings) = {
+        let settings = app_settings.lock().await;
+        (
+            settings.git_diff_whitespace,
+            diff_context_lines(settings.diff_context_lines, full_file),
+            settings.git_diff_normalize_line_endings,
+        )
+    };
+    spawn_git_blocking(move || {
+        collect_git_file_diffs(
+            &repo_root,
+            exclude_matcher.as_ref(),
+            whitespace,
+            context_lines,
+            normalize_line_endings,
+            whitespace_check,
+            None,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Changes recorded in `stash@{index}`: the stash commit against the HEAD it
+/// was made on, followed by the untracked files that `git stash -u` keeps in
+/// the commit's third parent.
+pub(crate) fn stash_diff_in_repo(
+    repo: &Repository,
+    index: usize,
+    whitespace: DiffWhitespace,
+    context_lines: u32,
+) -> Result<Vec<GitCommitDiff>, String> {
+    let spec = format!("stash@{{{index}}}");
+    let stash = repo
+        .revparse_single(&spec)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| format!("{spec} does not exist."))?;
+    let stash_tree = stash.tree().map_err(|e| e.to_string())?;
+    let base_tree = stash
+        .parent(0)
+        .and_then(|parent| parent.tree())
+        .map_err(|e| e.to_string())?;
+    let mut diffs = diff_trees_to_commit_diffs(
+        repo,
+        Some(&base_tree),
+        &stash_tree,
+        whitespace,
+        context_lines,
+    )?;
+    if let Ok(untracked) = stash.parent(2) {
+        let untracked_tree = untracked.tree().map_err(|e| e.to_string())?;
+        diffs.extend(diff_trees_to_commit_diffs(
+            repo,
+            None,
+            &untracked_tree,
+            whitespace,
+            context_lines,
+        )?);
+    }
+    Ok(diffs)
+}
+
+pub(crate) async fn show_stash_diff_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    index: usize,
+) -> Result<Vec<GitCommitDiff>, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let (whitespace, context_lines) = {
+        let settings = app_settings.lock().await;
+        (settings.git_diff_whitespace, settings.diff_context_lines)
+    };
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        stash_diff_in_repo(&repo, index, whitespace, context_lines)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Cancellation flags of in-flight diff streams, keyed by stream id.
+pub(crate) type GitDiffStreams = Mutex<HashMap<String, Arc<AtomicBool>>>;
+
+/// Sends working tree diffs to `on_diff` as they are produced, for changesets
+/// too large to build in one response. Resolves to false when the stream was
+/// cancelled via `cancel_git_diff_stream_core` or `on_diff` returned false.
+/// Starting a stream with an id that is still running cancels the old one.
+pub(crate) async fn stream_git_diffs_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    streams: &GitDiffStreams,
+    workspace_id: String,
+    stream_id: String,
+    whitespace_check: bool,
+    on_diff: impl FnMut(GitFileDiff) -> bool + Send + 'static,
+) -> Result<bool, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let exclude_matcher =
+        build_diff_exclude_matcher(&repo_root, &entry.settings.diff_exclude_globs)?;
+    let (whitespace, context_lines, normalize_line_endings) = {
+        let settings = app_settings.lock().await;
+        (
+            settings.git_diff_whitespace,
+            settings.diff_context_lines,
+            settings.git_diff_normalize_line_endings,
+        )
+    };
+    let cancel = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = streams.lock().await.insert(stream_id.clone(), cancel.clone()) {
+        previous.store(true, Ordering::SeqCst);
+    }
+    let stream_cancel = cancel.clone();
+    let result = spawn_git_blocking(move || {
+        visit_git_file_diffs(
+            &repo_root,
+            exclude_matcher.as_ref(),
+            whitespace,
+            context_lines,
+            normalize_line_endings,
+            whitespace_check,
+            None,
+            Some(&stream_cancel),
+            on_diff,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string());
+    {
+        let mut streams = streams.lock().await;
+        if streams
+            .get(&stream_id)
+            .is_some_and(|current| Arc::ptr_eq(current, &cancel))
+        {
+            streams.remove(&stream_id);
+        }
+    }
+    result?
+}
+
+pub(crate) async fn cancel_git_diff_stream_core(streams: &GitDiffStreams, stream_id: &str) {
+    if let Some(cancel) = streams.lock().await.get(stream_id) {
+        cancel.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Per-file status and line counts between `base_ref`'s tree and the working
+/// tree (index changes included). Only line stats are computed; no patch
+/// text is built.
+pub(crate) fn changed_paths_against_ref(
+    repo_root: &Path,
+    base_ref: &str,
+    exclude_matcher: Option<&DiffExcludeMatcher>,
+) -> Result<Vec<GitFileStatus>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let base_ref = base_ref.trim();
+    let base_tree = repo
+        .revparse_single(base_ref)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|e| format!("Failed to resolve '{base_ref}': {}", e.message()))?;
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    if let Some(matcher) = exclude_matcher {
+        for spec in matcher.pathspecs() {
+            options.pathspec(spec);
+        }
+    }
+    let diff = repo
+        .diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+
+    // Count lines from the diff callbacks rather than building a Patch per delta.
+    let line_counts = RefCell::new(vec![(0i64, 0i64); diff.deltas().len()]);
+    let file_index = Cell::new(0usize);
+    diff.foreach(
+        &mut |_, _| {
+            file_index.set(file_index.get() + 1);
+            true
+        },
+        None,
+        None,
+        Some(&mut |_, _, line| {
+            let mut counts = line_counts.borrow_mut();
+            if let Some(entry) = counts.get_mut(file_index.get().wrapping_sub(1)) {
+                match line.origin() {
+                    '+' => entry.0 += 1,
+                    '-' => entry.1 += 1,
+                    _ => {}
+                }
+            }
+            true
+        }),
+    )
+    .map_err(|e| e.to_string())?;
+    let line_counts = line_counts.into_inner();
+
+    let mut changed = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let path = normalize_git_path(&path.to_string_lossy());
+        if is_diff_excluded(exclude_matcher, &path) {
+            continue;
+        }
+        let (additions, deletions) = line_counts[index];
+        changed.push(GitFileStatus {
+            path,
+            status: status_for_delta(delta.status()).to_string(),
+            additions,
+            deletions,
+            is_dir: false,
+            is_large: false,
+            size: None,
+        });
+    }
+    Ok(changed)
+}
+
+pub(crate) fn resolve_ref_in_repo(repo: &Repository, ref_name: &str) -> Result<ResolvedRef, String> {
+    let ref_name = ref_name.trim();
+    if ref_name.is_empty() {
+        return Err("Ref name is required.".to_string());
+    }
+    let commit = repo
+        .revparse_single(ref_name)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| format!("Failed to resolve '{ref_name}': {}", e.message()))?;
+    let ref_type = match repo.resolve_reference_from_short_name(ref_name) {
+        Ok(reference) if reference.is_branch() => RefType::Branch,
+        Ok(reference) if reference.is_tag() => RefType::Tag,
+        Ok(reference) if reference.is_remote() => RefType::Remote,
+        _ => RefType::Commit,
+    };
+    let short_sha = commit
+        .as_object()
+        .short_id()
+        .ok()
+        .and_then(|buf| buf.as_str().map(str::to_string))
+        .unwrap_or_else(|| commit.id().to_string().chars().take(7).collect());
+    Ok(ResolvedRef {
+        sha: commit.id().to_string(),
+        short_sha,
+        ref_type,
+        commit: commit_to_entry(commit),
+    })
+}
+
+pub(crate) async fn resolve_ref_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    ref_name: String,
+) -> Result<ResolvedRef, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        resolve_ref_in_repo(&repo, &ref_name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub(crate) async fn list_changed_paths_against_ref_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    base_ref: String,
+) -> Result<Vec<GitFileStatus>, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let exclude_matcher =
+        build_diff_exclude_matcher(&repo_root, &entry.settings.diff_exclude_globs)?;
+    spawn_git_blocking(move || {
+        changed_paths_against_ref(&repo_root, &base_ref, exclude_matcher.as_ref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Joins subject and body with the conventional blank line. The subject must
+/// be a single non-empty line; an empty body yields a subject-only message.
+pub(crate) fn compose_commit_message(subject: &str, body: Option<&str>) -> Result<String, String> {
+    let subject = subject.trim();
+    if subject.is_empty() {
+        return Err("Commit subject cannot be empty.".to_string());
+    }
+    if subject.contains('\n') {
+        return Err("Commit subject must be a single line; put details in the body.".to_string());
+    }
+    let body = body.unwrap_or("").trim_matches('\n').trim_end();
+    if body.is_empty() {
+        return Ok(subject.to_string());
+    }
+    Ok(format!("{subject}\n\n{body}"))
+}
+
+pub(crate) fn subject_length_warning(subject: &str, max_len: u32) -> Option<String> {
+    let len = subject.trim().chars().count();
+    (max_len > 0 && len > max_len as usize).then(|| {
+        format!("Commit subject is {len} characters; keep it to {max_len} or fewer.")
+    })
+}
+
+/// Commits staged changes with a subject and optional multi-paragraph body.
+/// An overlong subject is reported in the result's warnings, not rejected.
+pub(crate) async fn commit_with_body_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    subject: String,
+    body: Option<String>,
+) -> Result<CommitResult, String> {
+    let message = compose_commit_message(&subject, body.as_deref())?;
+    let max_len = app_settings.lock().await.commit_subject_max_len;
+    commit_git_core(workspaces, workspace_id, message, None).await?;
+    Ok(CommitResult {
+        warnings: subject_length_warning(&subject, max_len).into_iter().collect(),
+    })
+}
+
+pub(crate) async fn commit_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    message: String,
+    paths: Option<Vec<String>>,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let mut selected: Vec<String> = Vec::new();
+    for path in paths.unwrap_or_default() {
+        for expanded in action_paths_for_file(&repo_root, &path) {
+            if !selected.contains(&expanded) {
+                selected.push(expanded);
+            }
+        }
+    }
+    if selected.is_empty() {
+        return run_git_command(&repo_root, &["commit", "-m", &message])
+            .await
+            .map(|_| ());
+    }
+    if !any_path_has_staged_changes(&repo_root, &selected)? {
+        return Err("None of the selected paths have staged changes.".to_string());
+    }
+    commit_selected_paths_in_repo(&repo_root, &message, &selected)
+        .await
+        .map(|_| ())
+}
+
+/// Writes a throwaway index holding HEAD plus the staged entries of `paths`
+/// into the git dir and returns its location.
+fn write_selection_index(repo_root: &Path, paths: &[String]) -> Result<PathBuf, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let index_path = repo
+        .path()
+        .join(format!("codex-monitor-index-{}", uuid::Uuid::new_v4()));
+    let mut selection = git2::Index::open(&index_path).map_err(|e| e.to_string())?;
+    match repo.head() {
+        Ok(head) => {
+            let tree = head.peel_to_tree().map_err(|e| e.to_string())?;
+            selection.read_tree(&tree).map_err(|e| e.to_string())?;
+        }
+        Err(err) if err.code() == git2::ErrorCode::UnbornBranch => {}
+        Err(err) => return Err(err.to_string()),
+    }
+    for path in paths {
+        let path = Path::new(path);
+        match index.get_path(path, 0) {
+            Some(entry) => selection.add(&entry).map_err(|e| e.to_string())?,
+            // Not in the index: a staged deletion (or nothing to take).
+            None if selection.get_path(path, 0).is_some() => {
+                selection.remove_path(path).map_err(|e| e.to_string())?
+            }
+            None => {}
+        }
+    }
+    selection.write().map_err(|e| e.to_string())?;
+    Ok(index_path)
+}
+
+/// Commits the staged index entries of `paths` on top of HEAD. Unlike
+/// `git commit -- <paths>`, unstaged hunks of those paths stay out of the
+/// commit, and every other staged entry is left in the index. The selection
+/// is committed by `git commit` through `GIT_INDEX_FILE`, so hooks and commit
+/// signing apply as they would to any other commit.
+pub(crate) async fn commit_selected_paths_in_repo(
+    repo_root: &PathBuf,
+    message: &str,
+    paths: &[String],
+) -> Result<String, String> {
+    let index_path = {
+        let repo_root = repo_root.clone();
+        let paths = paths.to_vec();
+        spawn_git_blocking(move || write_selection_index(&repo_root, &paths))
+            .await
+            .map_err(|err| err.to_string())??
+    };
+    let committed = run_git_command_with_env(
+        repo_root,
+        &["commit", "-m", message],
+        &[("GIT_INDEX_FILE", index_path.as_os_str())],
+    )
+    .await;
+    let _ = fs::remove_file(&index_path);
+    committed?;
+    run_git_command(repo_root, &["rev-parse", "HEAD"]).await
+}
+
+fn current_branch_name(repo_root: &Path) -> Option<String> {
+    let repo = Repository::open(repo_root).ok()?;
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    head.shorthand().map(|name| name.to_string())
+}
+
+/// Refuses to rewrite or publish the current branch when it matches one of
+/// the workspace's protected patterns, unless the caller overrides it.
+pub(crate) fn ensure_current_branch_unprotected(
+    entry: &WorkspaceEntry,
+    repo_root: &Path,
+    override_protection: Option<bool>,
+) -> Result<(), String> {
+    ensure_branch_unprotected(
+        &entry.settings.protected_branches,
+        current_branch_name(repo_root).as_deref(),
+        override_protection.unwrap_or(false),
+    )
+}
+
+fn parse_upstream_ref(name: &str) -> Option<(String, String)> {
+    let trimmed = name.strip_prefix("refs/remotes/").unwrap_or(name);
+    let mut parts = trimmed.splitn(2, '/');
+    let remote = parts.next()?;
+    let branch = parts.next()?;
+    if remote.is_empty() || branch.is_empty() {
+        return None;
+    }
+    Some((remote.to_string(), branch.to_string()))
+}
+
+pub(crate) fn upstream_remote_and_branch(repo_root: &Path) -> Result<Option<(String, String)>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(_) => return Ok(None),
+    };
+    if !head.is_branch() {
+        return Ok(None);
+    }
+    let branch_name = match head.shorthand() {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    let branch = repo
+        .find_branch(branch_name, git2::BranchType::Local)
+        .map_err(|e| e.to_string())?;
+    let upstream_branch = match branch.upstream() {
+        Ok(upstream) => upstream,
+        Err(_) => return Ok(None),
+    };
+    let upstream_ref = upstream_branch.get();
+    let upstream_name = upstream_ref
+        .name()
+        .or_else(|| upstream_ref.shorthand());
+    Ok(upstream_name.and_then(parse_upstream_ref))
+}
+
+/// Prefix for push errors caused by a failing pre-push hook, so the UI can
+/// offer to retry with `--no-verify`.
+pub(crate) const PRE_PUSH_HOOK_FAILED_PREFIX: &str = "Pre-push hook failed";
+
+fn pre_push_hook_installed(repo_root: &Path) -> bool {
+    let Ok(repo) = Repository::open(repo_root) else {
+        return false;
+    };
+    let hooks_dir = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("core.hooksPath").ok())
+        .map(|path| {
+            if path.is_absolute() {
+                path
+            } else {
+                repo_root.join(path)
+            }
+        })
+        .unwrap_or_else(|| repo.path().join("hooks"));
+    hooks_dir.join("pre-push").is_file()
+}
+
+/// Git does not label pre-push hook failures itself: the hook's output is
+/// followed by a generic "failed to push some refs". A failure with no
+/// rejected ref lines while a hook is installed is attributed to the hook.
+pub(crate) fn classify_push_error(detail: String, hook_installed: bool) -> String {
+    let rejected_by_remote = detail.contains("[rejected]")
+        || detail.contains("[remote rejected]")
+        || detail.contains("non-fast-forward");
+    if hook_installed && !rejected_by_remote && detail.contains("failed to push some refs") {
+        return format!("{PRE_PUSH_HOOK_FAILED_PREFIX}: {detail}");
+    }
+    detail
+}
+
+pub(crate) async fn push_with_upstream(repo_root: &PathBuf, no_verify: bool) -> Result<(), String> {
+    let upstream = upstream_remote_and_branch(repo_root)?;
+    let mut args = vec!["push"];
+    if no_verify {
+        args.push("--no-verify");
+    }
+    let refspec;
+    if let Some((remote, branch)) = upstream.as_ref() {
+        // Refresh remote-tracking refs before push so ahead/behind state is current
+        // and we can surface pull/sync requirements before attempting the push.
+        // This is best-effort because some setups intentionally allow push but not fetch.
+        let _ = run_git_command(repo_root, &["fetch", "--prune", remote.as_str()]).await;
+        refspec = format!("HEAD:{branch}");
+        args.push(remote.as_str());
+        args.push(refspec.as_str());
+    }
+    run_git_command(repo_root, &args)
+        .await
+        .map(|_| ())
+        .map_err(|detail| {
+            if no_verify {
+                detail
+            } else {
+                classify_push_error(detail, pre_push_hook_installed(repo_root))
+            }
+        })
+}
+
+pub(crate) async fn fetch_with_default_remote(
+    repo_root: &PathBuf,
+    fetch_depth: Option<u32>,
+) -> Result<(), String> {
+    let depth_arg = match fetch_depth {
+        Some(0) => return Err("Fetch depth must be greater than zero.".to_string()),
+        Some(depth) => Some(format!("--depth={depth}")),
+        None => None,
+    };
+    let upstream = upstream_remote_and_branch(repo_root)?;
+    let mut args = vec!["fetch", "--prune"];
+    if let Some(depth_arg) = depth_arg.as_deref() {
+        args.push(depth_arg);
+    }
+    if let Some((remote, _)) = upstream.as_ref() {
+        args.push(remote.as_str());
+    }
+    run_git_command(repo_root, &args).await.map(|_| ())
+}
+
+async fn fetch_unshallow_with_default_remote(repo_root: &PathBuf) -> Result<(), String> {
+    let is_shallow = Repository::open(repo_root)
+        .map(|repo| repo.is_shallow())
+        .map_err(|e| e.to_string())?;
+    if !is_shallow {
+        return Err("Repository already has complete history.".to_string());
+    }
+    let upstream = upstream_remote_and_branch(repo_root)?;
+    let mut args = vec!["fetch", "--prune", "--unshallow"];
+    if let Some((remote, _)) = upstream.as_ref() {
+        args.push(remote.as_str());
+    }
+    run_git_command(repo_root, &args).await.map(|_| ())
+}
+
+async fn pull_with_default_strategy(repo_root: &PathBuf) -> Result<(), String> {
+    fn autostash_unsupported(lower: &str) -> bool {
+        lower.contains("unknown option") && lower.contains("autostash")
+    }
+
+    fn needs_reconcile_strategy(lower: &str) -> bool {
+        lower.contains("need to specify how to reconcile divergent branches")
+            || lower.contains("you have divergent branches")
+    }
+
+    // Respect user/repo git config first, while still allowing dirty worktrees.
+    match run_git_command(repo_root, &["pull", "--autostash"]).await {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            let lower = err.to_lowercase();
+            if autostash_unsupported(&lower) {
+                match run_git_command(repo_root, &["pull"]).await {
+                    Ok(_) => Ok(()),
+                    Err(no_autostash_err) => {
+                        let no_autostash_lower = no_autostash_err.to_lowercase();
+                        if needs_reconcile_strategy(&no_autostash_lower) {
+                            return run_git_command(repo_root, &["pull", "--no-rebase"])
+                                .await
+                                .map(|_| ());
+                        }
+                        Err(no_autostash_err)
+                    }
+                }
+            } else if needs_reconcile_strategy(&lower) {
+                match run_git_command(repo_root, &["pull", "--no-rebase", "--autostash"]).await {
+                    Ok(_) => Ok(()),
+                    Err(merge_err) => {
+                        let merge_lower = merge_err.to_lowercase();
+                        if autostash_unsupported(&merge_lower) {
+                            return run_git_command(repo_root, &["pull", "--no-rebase"])
+                                .await
+                                .map(|_| ());
+                        }
+                        Err(merge_err)
+                    }
+                }
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+pub(crate) async fn push_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    no_verify: Option<bool>,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    push_with_upstream(&repo_root, no_verify.unwrap_or(false)).await
+}
+
+/// Pulls with the repo's configured strategy, journaling the previous HEAD
+/// when the pull moved it.
+pub(crate) async fn pull_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let undo_entry = Repository::open(&repo_root)
+        .ok()
+        .and_then(|repo| capture_head(&repo, "pull", GitUndoKind::ResetHead).ok());
+    pull_with_default_strategy(&repo_root).await?;
+    record_if_head_moved(journal, &workspace_id, &repo_root, undo_entry).await;
+    Ok(())
+}
+
+pub(crate) async fn fetch_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    fetch_depth: Option<u32>,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    fetch_with_default_remote(&repo_root, fetch_depth).await
+}
+
+pub(crate) async fn fetch_git_unshallow_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    fetch_unshallow_with_default_remote(&repo_root).await
+}
+
+pub(crate) async fn sync_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    // Pull first, then push (like VSCode sync)
+    pull_with_default_strategy(&repo_root).await?;
+    push_with_upstream(&repo_root, false).await
+}
+
+/// Appends `Co-authored-by` trailers, skipping blanks and any already present.
+pub(crate) fn append_co_author_trailers(message: &str, co_authors: &[String]) -> String {
+    let message = message.trim_end();
+    let trailers: Vec<String> = co_authors
+        .iter()
+        .map(|author| author.trim())
+        .filter(|author| !author.is_empty())
+        .map(|author| format!("Co-authored-by: {author}"))
+        .filter(|trailer| !message.lines().any(|line| line.trim() == trailer))
+        .collect();
+    if trailers.is_empty() {
+        return message.to_string();
+    }
+    format!("{message}\n\n{}", trailers.join("\n"))
+}
+
+/// Optionally stages everything, commits and pushes. Failures are reported
+/// in the result with the step that stopped, never by undoing earlier steps.
+pub(crate) async fn commit_and_push_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    message: String,
+    stage_all: bool,
+    co_authors: Vec<String>,
+    override_protection: bool,
+) -> Result<CommitAndPushResult, String> {
+    // Validate before trailers are appended: a message of only trailers is
+    // still an empty message.
+    if message.trim().is_empty() {
+        return Err("Commit message cannot be empty.".to_string());
+    }
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    ensure_current_branch_unprotected(&entry, &repo_root, Some(override_protection))?;
+    let message = append_co_author_trailers(&message, &co_authors);
+    let mut result = CommitAndPushResult::default();
+    fn fail(
+        mut result: CommitAndPushResult,
+        step: &str,
+        error: String,
+    ) -> Result<CommitAndPushResult, String> {
+        result.failed_step = Some(step.to_string());
+        result.error = Some(error);
+        Ok(result)
+    }
+
+    if stage_all {
+        if let Err(error) = run_git_command(&repo_root, &["add", "-A"]).await {
+            return fail(result, "stage", error);
+        }
+        result.staged = true;
+    }
+    if let Err(error) = commit_git_core(workspaces, workspace_id, message, None).await {
+        return fail(result, "commit", error);
+    }
+    result.committed = true;
+    result.commit_sha = Repository::open(&repo_root)
+        .ok()
+        .and_then(|repo| repo.head().ok()?.target())
+        .map(|oid| oid.to_string());
+    if let Err(error) = push_with_upstream(&repo_root, false).await {
+        return fail(result, "push", error);
+    }
+    result.pushed = true;
+    Ok(result)
+}
+
+pub(crate) fn resolve_ref_commit<'repo>(
+    repo: &'repo Repository,
+    label: &str,
+    spec: &str,
+) -> Result<git2::Commit<'repo>, String> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Err(format!("{label} ref is required."));
+    }
+    repo.revparse_single(spec)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| format!("Failed to resolve {label} ref '{spec}': {}", e.message()))
+}
+
+/// `origin/HEAD` when the remote advertises it; otherwise the first of
+/// `main`/`master` found locally or on origin, marked as inferred.
+pub(crate) fn default_branch_in_repo(repo: &Repository) -> Result<DefaultBranch, String> {
+    if let Ok(reference) = repo.find_reference("refs/remotes/origin/HEAD") {
+        if let Some(name) = reference
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/remotes/origin/"))
+        {
+            return Ok(DefaultBranch {
+                name: name.to_string(),
+                inferred: false,
+            });
+        }
+    }
+    for candidate in ["main", "master"] {
+        let exists = repo.find_branch(candidate, git2::BranchType::Local).is_ok()
+            || repo
+                .find_branch(&format!("origin/{candidate}"), git2::BranchType::Remote)
+                .is_ok();
+        if exists {
+            return Ok(DefaultBranch {
+                name: candidate.to_string(),
+                inferred: true,
+            });
+        }
+    }
+    Err("Could not determine the default branch.".to_string())
+}
+
+/// Branches of `branch_type`, most recently committed first. Symbolic refs
+/// such as `origin/HEAD` are skipped.
+pub(crate) fn branches_in_repo(
+    repo: &Repository,
+    branch_type: git2::BranchType,
+) -> Result<Vec<BranchInfo>, String> {
+    let mut branches = Vec::new();
+    let refs = repo
+        .branches(Some(branch_type))
+        .map_err(|e| e.to_string())?;
+    for branch_result in refs {
+        let (branch, _) = branch_result.map_err(|e| e.to_string())?;
+        if branch.get().symbolic_target().is_some() {
+            continue;
+        }
+        let name = branch.name().ok().flatten().unwrap_or("").to_string();
+        if name.is_empty() {
+            continue;
+        }
+        let (last_commit, last_commit_offset) = branch
+            .get()
+            .target()
+            .and_then(|oid| repo.find_commit(oid).ok())
+            .map(|commit| (commit.time().seconds(), commit.time().offset_minutes()))
+            .unwrap_or((0, 0));
+        branches.push(BranchInfo {
+            name,
+            last_commit,
+            last_commit_offset,
+        });
+    }
+    branches.sort_by(|a, b| b.last_commit.cmp(&a.last_commit));
+    Ok(branches)
+}
+
+pub(crate) fn ahead_behind_against_in_repo(
+    repo: &Repository,
+    other_ref: &str,
+    max_items: usize,
+) -> Result<GitAheadBehind, String> {
+    let head_oid = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .ok_or("HEAD does not point to a commit.")?;
+    let other_oid = resolve_ref_commit(repo, "comparison", other_ref)?.id();
+    let (ahead, behind) = repo
+        .graph_ahead_behind(head_oid, other_oid)
+        .map_err(|e| e.to_string())?;
+
+    let walk_entries = |push: git2::Oid, hide: git2::Oid| -> Result<Vec<GitLogEntry>, String> {
+        let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+        revwalk.push(push).map_err(|e| e.to_string())?;
+        revwalk.hide(hide).map_err(|e| e.to_string())?;
+        revwalk
+            .set_sorting(Sort::TIME)
+            .map_err(|e| e.to_string())?;
+        let mut entries = Vec::new();
+        for oid_result in revwalk.take(max_items) {
+            let oid = oid_result.map_err(|e| e.to_string())?;
+            let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+            entries.push(commit_to_entry(commit));
+        }
+        Ok(entries)
+    };
+    let ahead_entries = walk_entries(head_oid, other_oid)?;
+    let behind_entries = walk_entries(other_oid, head_oid)?;
+
+    Ok(GitAheadBehind {
+        other_ref: other_ref.trim().to_string(),
+        other_sha: other_oid.to_string(),
+        ahead,
+        behind,
+        ahead_entries,
+        behind_entries,
+    })
+}
+
+/// Branches in the order they were last checked out, from HEAD's reflog
+/// (`checkout: moving from A to B`). Branches that no longer exist are
+/// skipped; an empty reflog falls back to commit-time order.
+pub(crate) fn recent_branches_in_repo(
+    repo: &Repository,
+    limit: usize,
+) -> Result<Vec<RecentBranch>, String> {
+    let current = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(|name| name.to_string()));
+    let mut recent: Vec<RecentBranch> = Vec::new();
+    if let Ok(reflog) = repo.reflog("HEAD") {
+        for entry in reflog.iter() {
+            if recent.len() >= limit {
+                break;
+            }
+            let Some(target) = entry
+                .message()
+                .and_then(|message| message.strip_prefix("checkout: moving from "))
+                .and_then(|rest| rest.rsplit_once(" to "))
+                .map(|(_, to)| to.trim())
+            else {
+                continue;
+            };
+            if recent.iter().any(|branch| branch.name == target)
+                || repo.find_branch(target, git2::BranchType::Local).is_err()
+            {
+                continue;
+            }
+            recent.push(RecentBranch {
+                name: target.to_string(),
+                last_used: entry.committer().when().seconds(),
+            });
+        }
+    }
+    // A fresh clone has no checkout entries yet, but its branch is still the
+    // most recent one.
+    if let Some(current) = current {
+        if !recent.iter().any(|branch| branch.name == current) && recent.len() < limit {
+            let last_used = repo
+                .head()
+                .ok()
+                .and_then(|head| head.peel_to_commit().ok())
+                .map(|commit| commit.time().seconds())
+                .unwrap_or(0);
+            recent.insert(
+                0,
+                RecentBranch {
+                    name: current,
+                    last_used,
+                },
+            );
+        }
+    }
+    if recent.len() <= 1 {
+        for branch in branches_in_repo(repo, git2::BranchType::Local)? {
+            if recent.len() >= limit {
+                break;
+            }
+            if !recent.iter().any(|known| known.name == branch.name) {
+                recent.push(RecentBranch {
+                    name: branch.name,
+                    last_used: branch.last_commit,
+                });
+            }
+        }
+    }
+    Ok(recent)
+}
+
+/// Checks out `rev` with a detached HEAD. Tracked changes block it unless
+/// `force`, which discards them; untracked files are left alone.
+pub(crate) fn checkout_commit_in_repo(
+    repo: &Repository,
+    rev: &str,
+    force: bool,
+) -> Result<CheckoutCommitResult, String> {
+    let rev = rev.trim();
+    if rev.is_empty() {
+        return Err("Commit is required.".to_string());
+    }
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| format!("Failed to resolve '{rev}': {}", e.message()))?;
+    if !force {
+        let state = working_tree_cleanliness(repo, true)?;
+        if state.staged + state.unstaged + state.conflicted > 0 {
+            return Err(
+                "Working tree has uncommitted changes; commit or stash them, or force the checkout."
+                    .to_string(),
+            );
+        }
+    }
+    let previous_branch = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(|name| name.to_string()));
+
+    let mut options = git2::build::CheckoutBuilder::new();
+    if force {
+        options.force();
+    } else {
+        options.safe();
+    }
+    repo.checkout_tree(commit.as_object(), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+    repo.set_head_detached(commit.id())
+        .map_err(|e| e.to_string())?;
+    Ok(CheckoutCommitResult {
+        detached_head: true,
+        head_sha: commit.id().to_string(),
+        summary: commit.summary().unwrap_or("").to_string(),
+        previous_branch,
+    })
+}
+
+/// Names the current HEAD commit as a new branch and attaches HEAD to it. The
+/// working tree and index are left untouched.
+pub(crate) fn create_branch_at_head_in_repo(
+    repo: &Repository,
+    name: &str,
+) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Branch name cannot be empty.".to_string());
+    }
+    let target = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    let branch = repo
+        .branch(name, &target, false)
+        .map_err(|e| e.to_string())?;
+    let reference = branch
+        .get()
+        .name()
+        .ok_or("Invalid branch name.")?
+        .to_string();
+    repo.set_head(&reference).map_err(|e| e.to_string())?;
+    Ok(name.to_string())
+}
+
+/// Ahead/behind counts of HEAD against an arbitrary ref, independent of the
+/// branch's tracking configuration.
+pub(crate) async fn get_ahead_behind_against_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    other_ref: String,
+    limit: Option<usize>,
+) -> Result<GitAheadBehind, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let limit = limit.unwrap_or(40);
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        ahead_behind_against_in_repo(&repo, &other_ref, limit)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub(crate) async fn list_recent_branches_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<RecentBranch>, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let limit = limit.unwrap_or(10);
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        recent_branches_in_repo(&repo, limit)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Journaled as a checkout, so it can be undone.
+pub(crate) async fn checkout_commit_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: String,
+    sha: String,
+    force: bool,
+) -> Result<CheckoutCommitResult, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let checkout_root = repo_root.clone();
+    let (result, undo_entry) = spawn_git_blocking(move || {
+        let repo = Repository::open(&checkout_root).map_err(|e| e.to_string())?;
+        let undo_entry = capture_head(&repo, "checkout", GitUndoKind::Checkout).ok();
+        checkout_commit_in_repo(&repo, &sha, force).map(|result| (result, undo_entry))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    record_if_head_moved(journal, &workspace_id, &repo_root, undo_entry).await;
+    Ok(result)
+}
+
+/// Default branch per repo root, filled lazily by `get_default_branch_core`.
+pub(crate) type DefaultBranchCache = Mutex<HashMap<PathBuf, DefaultBranch>>;
+
+/// The repo's default branch, cached per repo root until `refresh` is set.
+pub(crate) async fn get_default_branch_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    cache: &DefaultBranchCache,
+    workspace_id: String,
+    refresh: bool,
+) -> Result<DefaultBranch, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    if !refresh {
+        if let Some(cached) = cache.lock().await.get(&repo_root) {
+            return Ok(cached.clone());
+        }
+    }
+    let lookup_root = repo_root.clone();
+    let branch = spawn_git_blocking(move || {
+        let repo = Repository::open(&lookup_root).map_err(|e| e.to_string())?;
+        default_branch_in_repo(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    cache.lock().await.insert(repo_root, branch.clone());
+    Ok(branch)
+}
+
+pub(crate) async fn create_branch_at_head_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    name: String,
+) -> Result<String, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        create_branch_at_head_in_repo(&repo, &name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub(crate) async fn stage_git_file_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    // If libgit2 reports a rename, we want a single UI action to stage both the
+    // old + new paths so the change actually moves to the staged section.
+    for path in action_paths_for_file(&repo_root, &path) {
+        run_git_command(&repo_root, &["add", "-A", "--", &path]).await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn stage_git_all_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    run_git_command(&repo_root, &["add", "-A"]).await.map(|_| ())
+}
+
+pub(crate) async fn unstage_git_file_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    for path in action_paths_for_file(&repo_root, &path) {
+        run_git_command(&repo_root, &["restore", "--staged", "--", &path]).await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn revert_git_file_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    for path in action_paths_for_file(&repo_root, &path) {
+        if run_git_command(
+            &repo_root,
+            &["restore", "--staged", "--worktree", "--", &path],
+        )
+        .await
+        .is_ok()
+        {
+            continue;
+        }
+        run_git_command(&repo_root, &["clean", "-f", "--", &path]).await?;
+    }
+    Ok(())
+}
+
+/// Discards every change in the worktree and index, stashing it first so
+/// the discard is journaled and can be undone.
+pub(crate) async fn revert_git_all_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: String,
+    override_protection: Option<bool>,
+) -> Result<(), String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    ensure_current_branch_unprotected(&entry, &repo_root, override_protection)?;
+    let stash_root = repo_root.clone();
+    let undo_entry = spawn_git_blocking(move || {
+        let mut repo = Repository::open(&stash_root).map_err(|e| e.to_string())?;
+        let Ok(mut undo_entry) = capture_head(&repo, "revert all", GitUndoKind::StashPop) else {
+            // Nothing can be stashed before the first commit.
+            return Ok(None);
+        };
+        undo_entry.stash = stash_worktree(&mut repo, "CodexMonitor: before revert all")?;
+        Ok::<_, String>(Some(undo_entry))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    run_git_command(&repo_root, &["restore", "--staged", "--worktree", "--", "."]).await?;
+    run_git_command(&repo_root, &["clean", "-f", "-d"]).await?;
+    if let Some(undo_entry) = undo_entry.filter(|entry| entry.stash.is_some()) {
+        push_undo_entry(&mut *journal.lock().await, &workspace_id, undo_entry);
+    }
+    Ok(())
+}
+
+/// Fills the optional detail fields of `entry`: the message body and the
+/// files/line stats of `commit` against its first parent.
+fn add_commit_detail(
+    repo: &Repository,
+    commit: &git2::Commit,
+    entry: &mut GitLogEntry,
+) -> Result<(), String> {
+    let body = commit.body().map(|body| body.trim_end().to_string());
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree().map_err(|e| e.to_string())?),
+        Err(_) => None,
+    };
+    let diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .map_err(|e| e.to_string())?;
+    let stats = diff.stats().map_err(|e| e.to_string())?;
+    let files = diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| normalize_git_path(&path.to_string_lossy()))
+        .collect();
+    entry.body = Some(body.unwrap_or_default());
+    entry.files_changed = Some(files);
+    entry.additions = Some(stats.insertions());
+    entry.deletions = Some(stats.deletions());
+    Ok(())
+}
+
+pub(crate) fn log_entry(
+    repo: &Repository,
+    commit: git2::Commit,
+    detail: bool,
+) -> Result<GitLogEntry, String> {
+    if !detail {
+        return Ok(commit_to_entry(commit));
+    }
+    let mut entry = commit_to_entry(commit.clone());
+    add_commit_detail(repo, &commit, &mut entry)?;
+    Ok(entry)
+}
+
+/// Flags entries that have a note on the default notes ref. Leaves
+/// `has_notes` unset when the repository has no notes ref.
+pub(crate) fn mark_entries_with_notes(repo: &Repository, entries: &mut [GitLogEntry]) {
+    let Ok(notes) = repo.notes(None) else {
+        return;
+    };
+    let annotated: HashSet<git2::Oid> = notes
+        .filter_map(|note| note.ok().map(|(_, annotated_id)| annotated_id))
+        .collect();
+    for entry in entries.iter_mut() {
+        let has_notes = git2::Oid::from_str(&entry.sha)
+            .map(|oid| annotated.contains(&oid))
+            .unwrap_or(false);
+        entry.has_notes = Some(has_notes);
+    }
+}
+
+pub(crate) fn signature_status_from_code(code: &str) -> GitSignatureStatus {
+    match code {
+        "G" => GitSignatureStatus::Good,
+        "U" => GitSignatureStatus::Untrusted,
+        "B" | "R" => GitSignatureStatus::Bad,
+        "N" | "" => GitSignatureStatus::None,
+        _ => GitSignatureStatus::Unknown,
+    }
+}
+
+/// Parses `git show -s --format=%H%x1f%G?%x1f%GS%x1e` output into
+/// `sha -> (status, signer)`.
+pub(crate) fn parse_signature_records(
+    output: &str,
+) -> HashMap<String, (GitSignatureStatus, Option<String>)> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_matches(['\n', '\r']).split('\x1f');
+            let sha = fields.next()?.trim();
+            if sha.is_empty() {
+                return None;
+            }
+            let status = signature_status_from_code(fields.next().unwrap_or("").trim());
+            let signer = fields
+                .next()
+                .map(str::trim)
+                .filter(|signer| !signer.is_empty())
+                .map(str::to_string);
+            Some((sha.to_string(), (status, signer)))
+        })
+        .collect()
+}
+
+/// Checks signatures with git itself so GPG, SSH and X.509 signing are all
+/// verified against the user's configured keyring and allowed signers.
+async fn verify_log_signatures(
+    repo_root: &PathBuf,
+    entries: &mut [GitLogEntry],
+) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut args = vec!["show", "-s", "--format=%H%x1f%G?%x1f%GS%x1e"];
+    args.extend(entries.iter().map(|entry| entry.sha.as_str()));
+    let output = run_git_command(repo_root, &args).await?;
+    let mut records = parse_signature_records(&output);
+    for entry in entries.iter_mut() {
+        let (status, signer) = records
+            .remove(&entry.sha)
+            .unwrap_or((GitSignatureStatus::Unknown, None));
+        entry.signature_status = Some(status);
+        entry.signer = signer;
+    }
+    Ok(())
+}
+
+/// Walks HEAD newest first and returns up to `max_items` entries, the total
+/// commit count, and whether more commits follow. Without a pathspec the walk
+/// only counts oids, so the total stays cheap. With one, every commit needs a
+/// tree diff, so the walk stops at the first match past the page and the
+/// total is left unknown.
+pub(crate) fn walk_log_entries(
+    repo: &Repository,
+    max_items: usize,
+    pathspec: &[String],
+    detail: bool,
+) -> Result<(Vec<GitLogEntry>, Option<usize>, bool), String> {
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    revwalk.push_head().map_err(|e| e.to_string())?;
+    revwalk
+        .set_sorting(Sort::TIME)
+        .map_err(|e| e.to_string())?;
+
+    let mut entries = Vec::new();
+    if pathspec.is_empty() {
+        let mut total = 0usize;
+        for oid_result in revwalk {
+            let oid = oid_result.map_err(|e| e.to_string())?;
+            if entries.len() < max_items {
+                let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+                entries.push(log_entry(repo, commit, detail)?);
+            }
+            total += 1;
+        }
+        return Ok((entries, Some(total), total > entries.len()));
+    }
+    for oid_result in revwalk {
+        let oid = oid_result.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        if !commit_touches_pathspec(repo, &commit, pathspec)? {
+            continue;
+        }
+        if entries.len() == max_items {
+            return Ok((entries, None, true));
+        }
+        entries.push(log_entry(repo, commit, detail)?);
+    }
+    Ok((entries, None, false))
+}
+
+/// Matches git's default history simplification: a commit touches the
+/// pathspec unless its tree is unchanged (for those paths) against some parent.
+pub(crate) fn commit_touches_pathspec(
+    repo: &Repository,
+    commit: &git2::Commit,
+    pathspec: &[String],
+) -> Result<bool, String> {
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    let parent_trees = commit
+        .parents()
+        .map(|parent| parent.tree().map(Some))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let parent_trees = if parent_trees.is_empty() {
+        vec![None]
+    } else {
+        parent_trees
+    };
+    for parent_tree in parent_trees {
+        let mut options = DiffOptions::new();
+        for spec in pathspec {
+            options.pathspec(spec);
+        }
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))
+            .map_err(|e| e.to_string())?;
+        if diff.deltas().len() == 0 {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Commits on HEAD, plus what HEAD is ahead of and behind its upstream.
+pub(crate) async fn get_git_log_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    limit: Option<usize>,
+    pathspec: Option<Vec<String>>,
+    detail: Option<bool>,
+    verify: Option<bool>,
+) -> Result<GitLogResponse, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let max_items = limit.unwrap_or(40);
+    // Per-commit diffs are costly over long histories, so detail is opt-in.
+    let detail = detail.unwrap_or(false);
+    let pathspec: Vec<String> = pathspec
+        .unwrap_or_default()
+        .iter()
+        .map(|spec| normalize_git_path(spec.trim()))
+        .filter(|spec| !spec.is_empty())
+        .collect();
+    let mut response = if app_settings.lock().await.git_backend.uses_system_git() {
+        let mut response = git_log_via_cli(&repo_root, max_items, &pathspec, detail).await?;
+        if let Ok(repo) = Repository::open(&repo_root) {
+            mark_entries_with_notes(&repo, &mut response.entries);
+        }
+        response
+    } else {
+        let log_root = repo_root.clone();
+        spawn_git_blocking(move || {
+            let repo = Repository::open(&log_root).map_err(|e| e.to_string())?;
+            log_in_repo(&repo, max_items, &pathspec, detail)
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    };
+    if verify.unwrap_or(false) {
+        verify_log_signatures(&repo_root, &mut response.entries).await?;
+    }
+    Ok(response)
+}
+
+fn log_in_repo(
+    repo: &Repository,
+    max_items: usize,
+    pathspec: &[String],
+    detail: bool,
+) -> Result<GitLogResponse, String> {
+    // In a shallow clone the revwalk stops at the graft boundary, so totals and
+    // ahead/behind counts only cover the fetched part of history.
+    let shallow = repo.is_shallow();
+    let (mut entries, total, has_more) = walk_log_entries(repo, max_items, pathspec, detail)?;
+
+    let mut ahead = 0usize;
+    let mut behind = 0usize;
+    let mut ahead_entries = Vec::new();
+    let mut behind_entries = Vec::new();
+    let mut upstream = None;
+
+    if let Ok(head) = repo.head() {
+        if head.is_branch() {
+            if let Some(branch_name) = head.shorthand() {
+                if let Ok(branch) = repo.find_branch(branch_name, git2::BranchType::Local) {
+                    if let Ok(upstream_branch) = branch.upstream() {
+                        let upstream_ref = upstream_branch.get();
+                        upstream = upstream_ref
+                            .shorthand()
+                            .map(|name| name.to_string())
+                            .or_else(|| upstream_ref.name().map(|name| name.to_string()));
+                        if let (Some(head_oid), Some(upstream_oid)) =
+                            (head.target(), upstream_ref.target())
+                        {
+                            let (ahead_count, behind_count) = repo
+                                .graph_ahead_behind(head_oid, upstream_oid)
+                                .map_err(|e| e.to_string())?;
+                            ahead = ahead_count;
+                            behind = behind_count;
+
+                            let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+                            revwalk.push(head_oid).map_err(|e| e.to_string())?;
+                            revwalk.hide(upstream_oid).map_err(|e| e.to_string())?;
+                            revwalk
+                                .set_sorting(Sort::TIME)
+                                .map_err(|e| e.to_string())?;
+                            for oid_result in revwalk.take(max_items) {
+                                let oid = oid_result.map_err(|e| e.to_string())?;
+                                let commit =
+                                    repo.find_commit(oid).map_err(|e| e.to_string())?;
+                                ahead_entries.push(commit_to_entry(commit));
+                            }
+
+                            let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+                            revwalk.push(upstream_oid).map_err(|e| e.to_string())?;
+                            revwalk.hide(head_oid).map_err(|e| e.to_string())?;
+                            revwalk
+                                .set_sorting(Sort::TIME)
+                                .map_err(|e| e.to_string())?;
+                            for oid_result in revwalk.take(max_items) {
+                                let oid = oid_result.map_err(|e| e.to_string())?;
+                                let commit =
+                                    repo.find_commit(oid).map_err(|e| e.to_string())?;
+                                behind_entries.push(commit_to_entry(commit));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    mark_entries_with_notes(repo, &mut entries);
+
+    Ok(GitLogResponse {
+        total,
+        has_more,
+        entries,
+        ahead,
+        behind,
+        ahead_entries,
+        behind_entries,
+        upstream,
+        shallow,
+    })
+}
+
+pub(crate) async fn get_git_commit_diff_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    sha: String,
+    full_file: Option<bool>,
+) -> Result<Vec<GitCommitDiff>, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let (whitespace, context_lines) = {
+        let settings = app_settings.lock().await;
+        (
+            settings.git_diff_whitespace,
+            diff_context_lines(settings.diff_context_lines, full_file.unwrap_or(false)),
+        )
+    };
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        let oid = git2::Oid::from_str(&sha).map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        let commit_tree = commit.tree().map_err(|e| e.to_string())?;
+        let parent_tree = commit
+            .parent(0)
+            .ok()
+            .and_then(|parent| parent.tree().ok());
+        diff_trees_to_commit_diffs(
+            &repo,
+            parent_tree.as_ref(),
+            &commit_tree,
+            whitespace,
+            context_lines,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// URL of `origin`, or of the first configured remote when there is no
+/// `origin`.
+pub(crate) async fn get_git_remote_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Option<String>, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    let remotes = repo.remotes().map_err(|e| e.to_string())?;
+    let name = if remotes.iter().any(|remote| remote == Some("origin")) {
+        "origin".to_string()
+    } else {
+        remotes
+            .iter()
+            .flatten()
+            .next()
+            .unwrap_or("")
+            .to_string()
+    };
+    if name.is_empty() {
+        return Ok(None);
+    }
+    let remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
+    Ok(remote.url().map(|url| url.to_string()))
+}
+
+pub(crate) async fn list_git_branches_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    if app_settings.lock().await.git_backend.uses_system_git() {
+        let branches = local_branches_via_cli(&repo_root).await?;
+        return Ok(json!({ "branches": branches }));
+    }
+    let branches = spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        branches_in_repo(&repo, git2::BranchType::Local)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(json!({ "branches": branches }))
+}
+
+/// Checks out a local branch, journaling the previous HEAD when it moved.
+pub(crate) async fn checkout_git_branch_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: String,
+    name: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let checkout_root = repo_root.clone();
+    let undo_entry = spawn_git_blocking(move || {
+        let repo = Repository::open(&checkout_root).map_err(|e| e.to_string())?;
+        let undo_entry = capture_head(&repo, "checkout", GitUndoKind::Checkout).ok();
+        checkout_branch(&repo, &name).map_err(|e| e.to_string())?;
+        Ok::<_, String>(undo_entry)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    record_if_head_moved(journal, &workspace_id, &repo_root, undo_entry).await;
+    Ok(())
+}
+
+/// Creates a branch at HEAD and checks it out.
+pub(crate) async fn create_git_branch_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    name: String,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        let head = repo.head().map_err(|e| e.to_string())?;
+        let target = head.peel_to_commit().map_err(|e| e.to_string())?;
+        repo.branch(&name, &target, false)
+            .map_err(|e| e.to_string())?;
+        checkout_branch(&repo, &name).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub(crate) fn reword_head_commit_in_repo(
+    repo: &Repository,
+    message: &str,
+    allow_merge: bool,
+) -> Result<String, String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("Commit message cannot be empty.".to_string());
+    }
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    if head.parent_count() > 1 && !allow_merge {
+        return Err("HEAD is a merge commit; pass allowMerge to reword it.".to_string());
+    }
+    // Amending without a tree keeps HEAD's tree, so staged changes stay staged.
+    let oid = head
+        .amend(Some("HEAD"), None, None, None, Some(message), None)
+        .map_err(|e| e.to_string())?;
+    Ok(oid.to_string())
+}
+
+/// Rewrites HEAD's message, keeping its tree, and journals the old HEAD.
+pub(crate) async fn reword_head_commit_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: String,
+    message: String,
+    allow_merge: Option<bool>,
+    override_protection: Option<bool>,
+) -> Result<String, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    ensure_current_branch_unprotected(&entry, &repo_root, override_protection)?;
+    let (sha, undo_entry) = spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        let mut undo_entry = capture_head(&repo, "reword commit", GitUndoKind::ResetHead)?;
+        let sha = reword_head_commit_in_repo(&repo, &message, allow_merge.unwrap_or(false))?;
+        record_after(&mut undo_entry, &repo)?;
+        Ok::<_, String>((sha, undo_entry))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    push_undo_entry(&mut *journal.lock().await, &workspace_id, undo_entry);
+    Ok(sha)
+}
+
+fn validate_author_identity(name: &str, email: &str) -> Result<(), String> {
+    if name.is_empty() || email.is_empty() {
+        return Err("Author name and email are required.".to_string());
+    }
+    let invalid = |value: &str| value.contains(['<', '>', '\n', '\r']);
+    if invalid(name) || invalid(email) {
+        return Err("Author name and email cannot contain '<', '>' or newlines.".to_string());
+    }
+    if email.chars().any(char::is_whitespace) || !email.contains('@') {
+        return Err(format!("Invalid author email: {email}"));
+    }
+    Ok(())
+}
+
+pub(crate) fn reset_head_author_in_repo(
+    repo: &Repository,
+    name: &str,
+    email: &str,
+) -> Result<String, String> {
+    let name = name.trim();
+    let email = email.trim();
+    validate_author_identity(name, email)?;
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    // Mirrors `--reset-author`: the author time is refreshed along with the
+    // identity, and the committer falls back to the author when unconfigured.
+    let author = git2::Signature::now(name, email).map_err(|e| e.to_string())?;
+    let committer = repo.signature().unwrap_or_else(|_| author.clone());
+    let oid = head
+        .amend(Some("HEAD"), Some(&author), Some(&committer), None, None, None)
+        .map_err(|e| e.to_string())?;
+    Ok(oid.to_string())
+}
+
+pub(crate) fn reset_head_date_in_repo(repo: &Repository, timestamp: i64) -> Result<String, String> {
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    let original = head.author();
+    let time = git2::Time::new(timestamp, original.when().offset_minutes());
+    let author = git2::Signature::new(
+        original.name().unwrap_or_default(),
+        original.email().unwrap_or_default(),
+        &time,
+    )
+    .map_err(|e| e.to_string())?;
+    let oid = head
+        .amend(Some("HEAD"), Some(&author), None, None, None, None)
+        .map_err(|e| e.to_string())?;
+    Ok(oid.to_string())
+}
+
+/// Rewrites HEAD's author identity, like `git commit --amend --reset-author`.
+pub(crate) async fn reset_head_author_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    name: String,
+    email: String,
+    override_protection: Option<bool>,
+) -> Result<String, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    ensure_current_branch_unprotected(&entry, &repo_root, override_protection)?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        reset_head_author_in_repo(&repo, &name, &email)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Rewrites HEAD's author date to `timestamp` (seconds), keeping its offset.
+pub(crate) async fn reset_head_date_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    timestamp: i64,
+    override_protection: Option<bool>,
+) -> Result<String, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    ensure_current_branch_unprotected(&entry, &repo_root, override_protection)?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        reset_head_date_in_repo(&repo, timestamp)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct BisectProgress {
+    pub(crate) remaining_revisions: Option<u32>,
+    pub(crate) remaining_steps: Option<u32>,
+    pub(crate) first_bad: Option<String>,
+}
+
+/// Parses `git bisect` output such as
+/// "Bisecting: 3 revisions left to test after this (roughly 2 steps)" or
+/// "<sha> is the first bad commit".
+pub(crate) fn parse_bisect_output(output: &str) -> BisectProgress {
+    let mut progress = BisectProgress::default();
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("Bisecting: ") {
+            progress.remaining_revisions = rest
+                .split_whitespace()
+                .next()
+                .and_then(|value| value.parse().ok());
+            progress.remaining_steps = rest
+                .split_once("(roughly ")
+                .and_then(|(_, tail)| tail.split_whitespace().next())
+                .and_then(|value| value.parse().ok());
+        } else if let Some(sha) = line.strip_suffix(" is the first bad commit") {
+            progress.first_bad = Some(sha.trim().to_string());
+            progress.remaining_revisions = Some(0);
+            progress.remaining_steps = Some(0);
+        }
+    }
+    progress
+}
+
+/// Resolves the bad and good revisions to commit ids, so nothing the user
+/// typed reaches `git bisect start` as an option.
+pub(crate) fn resolve_bisect_bounds(
+    repo: &Repository,
+    bad: &str,
+    good: &str,
+) -> Result<(String, String), String> {
+    let resolve = |rev: &str, label: &str| {
+        let rev = rev.trim();
+        if rev.is_empty() || rev.starts_with('-') {
+            return Err(format!("Invalid {label} revision."));
+        }
+        repo.revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map(|commit| commit.id().to_string())
+            .map_err(|e| format!("Failed to resolve '{rev}': {}", e.message()))
+    };
+    Ok((resolve(bad, "bad")?, resolve(good, "good")?))
+}
+
+fn bisect_state_response(repo_root: &Path, output: &str) -> Result<Value, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let current = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .ok()
+        .map(commit_to_entry);
+    let progress = parse_bisect_output(output);
+    Ok(json!({
+        "current": current,
+        "remainingRevisions": progress.remaining_revisions,
+        "remainingSteps": progress.remaining_steps,
+        "firstBad": progress.first_bad,
+        "done": progress.first_bad.is_some(),
+        "output": output,
+    }))
+}
+
+/// Starts bisecting between `bad` and `good` and reports the first commit to test.
+pub(crate) async fn bisect_start_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    bad: String,
+    good: String,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let (bad, good) = {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        resolve_bisect_bounds(&repo, &bad, &good)?
+    };
+    let output = run_git_command(&repo_root, &["bisect", "start", &bad, &good, "--"]).await?;
+    bisect_state_response(&repo_root, &output)
+}
+
+pub(crate) async fn bisect_mark_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    result: BisectResult,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let term = match result {
+        BisectResult::Good => "good",
+        BisectResult::Bad => "bad",
+        BisectResult::Skip => "skip",
+    };
+    let output = run_git_command(&repo_root, &["bisect", term]).await?;
+    bisect_state_response(&repo_root, &output)
+}
+
+pub(crate) async fn bisect_reset_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    // Without a commit argument, reset checks out the branch bisect started from.
+    let output = run_git_command(&repo_root, &["bisect", "reset"]).await?;
+    bisect_state_response(&repo_root, &output)
+}
+
+/// The merge, rebase, cherry-pick, revert or bisect in progress, if any.
+pub(crate) async fn get_repo_operation_state_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<RepoOperation, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    Ok(repo_operation_state(&repo))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OperationAction {
+    Continue,
+    Skip,
+    Abort,
+}
+
+pub(crate) fn operation_action_args(
+    operation: RepoOperation,
+    action: OperationAction,
+) -> Result<Vec<&'static str>, String> {
+    let subcommand = match operation {
+        RepoOperation::None => return Err("No operation in progress.".to_string()),
+        RepoOperation::Merge => "merge",
+        RepoOperation::Rebase { .. } => "rebase",
+        RepoOperation::CherryPick => "cherry-pick",
+        RepoOperation::Revert => "revert",
+        RepoOperation::Bisect => {
+            return match action {
+                OperationAction::Continue => {
+                    Err("Bisect continues by marking commits good or bad.".to_string())
+                }
+                OperationAction::Skip => Ok(vec!["bisect", "skip"]),
+                OperationAction::Abort => Ok(vec!["bisect", "reset"]),
+            };
+        }
+    };
+    let flag = match (operation, action) {
+        (RepoOperation::Merge, OperationAction::Skip) => {
+            return Err("A merge cannot be skipped; continue or abort it.".to_string());
+        }
+        (_, OperationAction::Continue) => "--continue",
+        (_, OperationAction::Skip) => "--skip",
+        (_, OperationAction::Abort) => "--abort",
+    };
+    // Continuing may open an editor for the commit message; accept it as-is.
+    Ok(vec!["-c", "core.editor=true", subcommand, flag])
+}
+
+/// Continues, skips or aborts the operation in progress and returns the
+/// state it leaves behind (e.g. the next conflicted rebase step).
+pub(crate) async fn run_operation_action_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    action: OperationAction,
+) -> Result<RepoOperation, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let operation = {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        repo_operation_state(&repo)
+    };
+    let args = operation_action_args(operation, action)?;
+    run_git_command(&repo_root, &args).await?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    Ok(repo_operation_state(&repo))
+}
+
+/// Accepts `section.key` and `section.subsection.key` (e.g. `branch.main.remote`).
+/// Section and key names follow git's rules: alphanumerics and `-`, key starting
+/// with a letter.
+pub(crate) fn validate_git_config_key(key: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid git config key `{key}` (expected section.key).");
+    let (section, rest) = key.split_once('.').ok_or_else(invalid)?;
+    let name = rest.rsplit('.').next().ok_or_else(invalid)?;
+    let valid_section = !section.is_empty()
+        && section
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-');
+    let valid_name = name
+        .chars()
+        .next()
+        .map(|ch| ch.is_ascii_alphabetic())
+        .unwrap_or(false)
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-');
+    let subsection = &rest[..rest.len() - name.len()];
+    let valid_subsection = !subsection.contains('\n');
+    if valid_section && valid_name && valid_subsection {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+fn config_level_label(level: git2::ConfigLevel) -> &'static str {
+    match level {
+        git2::ConfigLevel::ProgramData => "programdata",
+        git2::ConfigLevel::System => "system",
+        git2::ConfigLevel::XDG => "xdg",
+        git2::ConfigLevel::Global => "global",
+        git2::ConfigLevel::Local => "local",
+        _ => "other",
+    }
+}
+
+pub(crate) fn read_git_config_values(
+    repo: &Repository,
+    keys: &[String],
+) -> Result<Value, String> {
+    let config = repo
+        .config()
+        .and_then(|mut config| config.snapshot())
+        .map_err(|e| e.to_string())?;
+    let mut values = serde_json::Map::new();
+    for key in keys {
+        validate_git_config_key(key)?;
+        let value = match config.get_entry(key) {
+            Ok(entry) => json!({
+                "value": entry.value(),
+                "scope": config_level_label(entry.level()),
+            }),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Value::Null,
+            Err(error) => return Err(error.to_string()),
+        };
+        values.insert(key.clone(), value);
+    }
+    Ok(Value::Object(values))
+}
+
+pub(crate) fn write_git_config_value(
+    repo: &Repository,
+    key: &str,
+    value: &str,
+    scope: ConfigScope,
+) -> Result<(), String> {
+    validate_git_config_key(key)?;
+    let mut config = match scope {
+        ConfigScope::Local => repo
+            .config()
+            .and_then(|config| config.open_level(git2::ConfigLevel::Local)),
+        ConfigScope::Global => git2::Config::open_default()
+            .and_then(|config| config.open_global()),
+    }
+    .map_err(|e| e.to_string())?;
+    config.set_str(key, value).map_err(|e| e.to_string())
+}
+
+/// Effective values of `keys` with the scope each was read from; keys that
+/// are not set map to `null`.
+pub(crate) async fn read_git_config_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    keys: Vec<String>,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    read_git_config_values(&repo, &keys)
+}
+
+pub(crate) async fn write_git_config_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    key: String,
+    value: String,
+    scope: ConfigScope,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    write_git_config_value(&repo, key.trim(), &value, scope)
+}
+
+/// Trims and checks user-supplied pathspecs. Pathspec magic (e.g.
+/// `:!Cargo.lock`) is allowed, but specs must stay inside the repo.
+pub(crate) fn validate_pathspecs(pathspec: &[String]) -> Result<Vec<String>, String> {
+    let specs: Vec<String> = pathspec
+        .iter()
+        .map(|spec| normalize_git_path(spec.trim()))
+        .filter(|spec| !spec.is_empty())
+        .collect();
+    if specs.is_empty() {
+        return Err("Pathspec cannot be empty.".to_string());
+    }
+    for spec in &specs {
+        let path_part = if spec.starts_with(':') {
+            spec.trim_start_matches(|c: char| c == ':' || c == '!' || c == '^')
+        } else {
+            spec.as_str()
+        };
+        if path_part.starts_with('/') || path_part.split('/').any(|part| part == "..") {
+            return Err(format!("Pathspec must be relative to the repository: {spec}"));
+        }
+    }
+    Ok(specs)
+}
+
+/// Stages every path matching `pathspec`, which may use pathspec magic.
+pub(crate) async fn stage_git_pattern_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    pathspec: Vec<String>,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let specs = validate_pathspecs(&pathspec)?;
+    let mut args = vec!["add", "-A", "--"];
+    args.extend(specs.iter().map(String::as_str));
+    run_git_command(&repo_root, &args).await.map(|_| ())
+}
+
+/// Unstages every path matching `pathspec`, leaving the worktree untouched.
+pub(crate) async fn unstage_git_pattern_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    pathspec: Vec<String>,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let specs = validate_pathspecs(&pathspec)?;
+    let mut args = vec!["restore", "--staged", "--"];
+    args.extend(specs.iter().map(String::as_str));
+    run_git_command(&repo_root, &args).await.map(|_| ())
+}
+
+fn switch_to_new_branch(
+    repo: &Repository,
+    name: &str,
+    target: &git2::Commit,
+) -> Result<(), git2::Error> {
+    let mut branch = repo.branch(name, target, false)?;
+    let mut options = git2::build::CheckoutBuilder::new();
+    options.safe();
+    if let Err(err) = repo.checkout_tree(target.as_object(), Some(&mut options)) {
+        let _ = branch.delete();
+        return Err(err);
+    }
+    repo.set_head(&format!("refs/heads/{name}"))
+}
+
+/// Creates `name` at `start_point` and switches to it, carrying uncommitted
+/// changes along like `git checkout -b`. Without a start point this is
+/// `create_branch_at_head_in_repo`, which can never conflict. When the
+/// switch would overwrite local changes it fails without touching anything,
+/// unless `stash` is set: then the changes are stashed, the branch checked
+/// out, and the stash popped on top.
+pub(crate) fn move_changes_to_new_branch_in_repo(
+    repo: &mut Repository,
+    name: &str,
+    start_point: Option<&str>,
+    stash: bool,
+) -> Result<String, String> {
+    let Some(start_point) = start_point.map(str::trim).filter(|value| !value.is_empty()) else {
+        return create_branch_at_head_in_repo(repo, name);
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Branch name cannot be empty.".to_string());
+    }
+    if repo.find_branch(name, git2::BranchType::Local).is_ok() {
+        return Err(format!("Branch {name} already exists."));
+    }
+    let target = repo
+        .revparse_single(start_point)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| e.to_string())?
+        .id();
+    let stashed = if stash {
+        stash_worktree(repo, &format!("codex-monitor: move changes to {name}"))?
+    } else {
+        None
+    };
+    let switched = repo
+        .find_commit(target)
+        .and_then(|commit| switch_to_new_branch(repo, name, &commit));
+    if let Err(err) = switched {
+        if let Some(stash) = &stashed {
+            let _ = pop_stash_by_id(repo, stash);
+        }
+        if err.code() == git2::ErrorCode::Conflict {
+            return Err(format!(
+                "Switching to {start_point} would overwrite local changes. Stash them to carry them over."
+            ));
+        }
+        return Err(err.to_string());
+    }
+    if let Some(stash) = stashed {
+        pop_stash_by_id(repo, &stash).map_err(|_| {
+            format!(
+                "Switched to {name}, but your changes conflict with {start_point}; they were kept in the stash."
+            )
+        })?;
+    }
+    Ok(name.to_string())
+}
+
+/// Moves uncommitted changes onto a new branch, journaling the previous HEAD
+/// when the switch moved it.
+pub(crate) async fn move_changes_to_new_branch_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: String,
+    name: String,
+    start_point: Option<String>,
+    stash: Option<bool>,
+    override_protection: Option<bool>,
+) -> Result<String, String> {
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    ensure_current_branch_unprotected(&entry, &repo_root, override_protection)?;
+    let move_root = repo_root.clone();
+    let (name, undo_entry) = spawn_git_blocking(move || {
+        let mut repo = Repository::open(&move_root).map_err(|e| e.to_string())?;
+        let undo_entry =
+            capture_head(&repo, "move changes to new branch", GitUndoKind::Checkout).ok();
+        move_changes_to_new_branch_in_repo(
+            &mut repo,
+            &name,
+            start_point.as_deref(),
+            stash.unwrap_or(false),
+        )
+        .map(|name| (name, undo_entry))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    record_if_head_moved(journal, &workspace_id, &repo_root, undo_entry).await;
+    Ok(name)
+}
+
+pub(crate) fn restore_file_from_rev_in_repo(
+    repo: &Repository,
+    repo_root: &Path,
+    path: &str,
+    rev: &str,
+    force: bool,
+) -> Result<(), String> {
+    let path = normalize_git_path(path.trim());
+    let relative = Path::new(&path);
+    if path.is_empty()
+        || relative.is_absolute()
+        || relative
+            .components()
+            .any(|component| matches!(component, std::path::Component::ParentDir))
+    {
+        return Err("Invalid path.".to_string());
+    }
+    let commit = repo
+        .revparse_single(rev.trim())
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    let entry = tree
+        .get_path(relative)
+        .map_err(|_| format!("`{path}` does not exist at {rev}."))?;
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(|_| format!("`{path}` is not a file at {rev}."))?;
+
+    let target = repo_root.join(relative);
+    if target.exists() && !force {
+        let status = repo.status_file(relative).unwrap_or(Status::CURRENT);
+        if status.intersects(
+            Status::WT_NEW | Status::WT_MODIFIED | Status::WT_TYPECHANGE | Status::WT_RENAMED,
+        ) {
+            return Err(format!(
+                "`{path}` has unstaged changes; pass force to overwrite."
+            ));
+        }
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&target, blob.content()).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    if entry.filemode() == i32::from(git2::FileMode::BlobExecutable) {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&target, fs::Permissions::from_mode(0o755));
+    }
+    Ok(())
+}
+
+/// Overwrites `path` in the worktree with its content at `rev`. Refuses to
+/// discard unstaged changes to the file unless `force` is set.
+pub(crate) async fn restore_file_from_rev_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+    rev: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        restore_file_from_rev_in_repo(&repo, &repo_root, &path, &rev, force.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub(crate) fn compare_refs_in_repo(
+    repo: &Repository,
+    base: &str,
+    head: &str,
+    whitespace: DiffWhitespace,
+    context_lines: u32,
+) -> Result<Value, String> {
+    let base_commit = resolve_ref_commit(repo, "base", base)?;
+    let head_commit = resolve_ref_commit(repo, "head", head)?;
+    let base_tree = base_commit.tree().map_err(|e| e.to_string())?;
+    let head_tree = head_commit.tree().map_err(|e| e.to_string())?;
+    let diffs =
+        diff_trees_to_commit_diffs(repo, Some(&base_tree), &head_tree, whitespace, context_lines)?;
+
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    revwalk.push(head_commit.id()).map_err(|e| e.to_string())?;
+    revwalk.hide(base_commit.id()).map_err(|e| e.to_string())?;
+    revwalk
+        .set_sorting(Sort::TIME)
+        .map_err(|e| e.to_string())?;
+    let mut commits = Vec::new();
+    for oid_result in revwalk {
+        let oid = oid_result.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        commits.push(commit_to_entry(commit));
+    }
+
+    Ok(json!({
+        "base": base_commit.id().to_string(),
+        "head": head_commit.id().to_string(),
+        "diffs": diffs,
+        "commits": commits,
+    }))
+}
+
+/// Commits reachable from `head` but not `base`, plus the tree diff between
+/// the two refs.
+pub(crate) async fn get_ref_comparison_diff_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    base: String,
+    head: String,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let (whitespace, context_lines) = {
+        let settings = app_settings.lock().await;
+        (settings.git_diff_whitespace, settings.diff_context_lines)
+    };
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        compare_refs_in_repo(&repo, &base, &head, whitespace, context_lines)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Dry-runs a merge of `theirs` into `ours` in memory and lists the paths that
+/// would conflict. Neither the index nor the working tree is modified.
+pub(crate) fn merge_conflict_paths(
+    repo: &Repository,
+    ours: git2::Oid,
+    theirs: git2::Oid,
+) -> Result<Vec<String>, String> {
+    let ours = repo.find_commit(ours).map_err(|e| e.to_string())?;
+    let theirs = repo.find_commit(theirs).map_err(|e| e.to_string())?;
+    let index = repo
+        .merge_commits(&ours, &theirs, None)
+        .map_err(|e| e.to_string())?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    for conflict in index.conflicts().map_err(|e| e.to_string())? {
+        let conflict = conflict.map_err(|e| e.to_string())?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            let path = normalize_git_path(&String::from_utf8_lossy(&entry.path));
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn sync_preview_in_repo(repo: &Repository) -> Result<Value, String> {
+    let head = repo.head().map_err(|e| e.to_string())?;
+    let upstream = if head.is_branch() {
+        head.shorthand()
+            .and_then(|name| repo.find_branch(name, git2::BranchType::Local).ok())
+            .and_then(|branch| branch.upstream().ok())
+    } else {
+        None
+    };
+    let (Some(head_oid), Some(upstream_ref)) = (head.target(), upstream) else {
+        return Err("Current branch has no upstream to sync with.".to_string());
+    };
+    let upstream_name = upstream_ref.get().shorthand().map(str::to_string);
+    let upstream_oid = upstream_ref
+        .get()
+        .target()
+        .ok_or("Upstream branch has no target.")?;
+    let (ahead, behind) = repo
+        .graph_ahead_behind(head_oid, upstream_oid)
+        .map_err(|e| e.to_string())?;
+    // Only a diverged branch needs a real merge; fast-forwards cannot conflict.
+    let conflicting_paths = if ahead > 0 && behind > 0 {
+        merge_conflict_paths(repo, head_oid, upstream_oid)?
+    } else {
+        Vec::new()
+    };
+    Ok(json!({
+        "upstream": upstream_name,
+        "ahead": ahead,
+        "behind": behind,
+        "wouldConflict": !conflicting_paths.is_empty(),
+        "conflictingPaths": conflicting_paths,
+    }))
+}
+
+/// Fetches the default remote, then reports how the current branch compares
+/// with its upstream and which paths a sync would conflict on.
+pub(crate) async fn preview_sync_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    // Fetching only updates remote-tracking refs; the working tree is untouched.
+    fetch_with_default_remote(&repo_root, None).await?;
+    spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        sync_preview_in_repo(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn fetch_all_remotes(repo_root: &PathBuf) -> Result<Vec<RemoteFetchResult>, String> {
+    let remotes: Vec<String> = {
+        let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+        let names = repo.remotes().map_err(|e| e.to_string())?;
+        names.iter().flatten().map(|name| name.to_string()).collect()
+    };
+    if remotes.is_empty() {
+        return Err("No remotes configured.".to_string());
+    }
+    // Fetch remotes one at a time so an unreachable remote is reported
+    // without blocking the others.
+    let mut results = Vec::with_capacity(remotes.len());
+    for remote in remotes {
+        let result = run_git_command(repo_root, &["fetch", "--prune", remote.as_str()]).await;
+        results.push(RemoteFetchResult {
+            remote,
+            ok: result.is_ok(),
+            error: result.err(),
+        });
+    }
+    Ok(results)
+}
+
+/// Fetches (with `--prune`) every configured remote, reporting each one's
+/// outcome separately.
+pub(crate) async fn fetch_all_git_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Vec<RemoteFetchResult>, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    fetch_all_remotes(&repo_root).await
+}
+
+pub(crate) fn remote_branch_names(repo: &Repository, remote: &str) -> Result<Vec<String>, String> {
+    let prefix = format!("{remote}/");
+    Ok(branches_in_repo(repo, git2::BranchType::Remote)?
+        .into_iter()
+        .map(|branch| branch.name)
+        .filter(|name| name.starts_with(&prefix))
+        .collect())
+}
+
+pub(crate) async fn list_remote_branches_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Value, String> {
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let branches = spawn_git_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        branches_in_repo(&repo, git2::BranchType::Remote)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(json!({ "branches": branches }))
+}
+
+/// Runs `git remote prune <remote>` and reports the remote-tracking branches
+/// it removed.
+pub(crate) async fn prune_remote_branches_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    remote: String,
+) -> Result<Vec<String>, String> {
+    let remote = remote.trim().to_string();
+    if remote.is_empty() {
+        return Err("remote is required".to_string());
+    }
+    let (_, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let before = {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        repo.find_remote(&remote)
+            .map_err(|_| format!("Unknown remote: {remote}"))?;
+        remote_branch_names(&repo, &remote)?
+    };
+    run_git_command(&repo_root, &["remote", "prune", &remote]).await?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    let after = remote_branch_names(&repo, &remote)?;
+    Ok(before
+        .into_iter()
+        .filter(|name| !after.contains(name))
+        .collect())
+}
@@ -0,0 +1,254 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use git2::{
+    ErrorCode, Oid, Repository, ResetType, Signature, StashApplyOptions, StashFlags, Status,
+    StatusOptions,
+};
+use tokio::sync::Mutex;
+
+use crate::git_utils::checkout_branch;
+use crate::shared::git_core::spawn_git_blocking;
+use crate::shared::git_ui_core::{ensure_current_branch_unprotected, workspace_git_root};
+use crate::types::{GitUndoEntry, GitUndoKind, WorkspaceEntry};
+
+const MAX_UNDO_ENTRIES: usize = 20;
+
+/// Per-workspace stack of states recorded before destructive git actions.
+/// Kept in memory only; it does not survive a restart.
+pub(crate) type GitUndoJournal = HashMap<String, VecDeque<GitUndoEntry>>;
+
+pub(crate) fn push_undo_entry(
+    journal: &mut GitUndoJournal,
+    workspace_id: &str,
+    entry: GitUndoEntry,
+) {
+    let entries = journal.entry(workspace_id.to_string()).or_default();
+    entries.push_back(entry);
+    while entries.len() > MAX_UNDO_ENTRIES {
+        entries.pop_front();
+    }
+}
+
+/// Snapshot of HEAD (and the checked-out branch, if any) before `action`.
+pub(crate) fn capture_head(
+    repo: &Repository,
+    action: &str,
+    kind: GitUndoKind,
+) -> Result<GitUndoEntry, String> {
+    let head = repo.head().map_err(|e| e.to_string())?;
+    let sha = head
+        .peel_to_commit()
+        .map_err(|e| e.to_string())?
+        .id()
+        .to_string();
+    let branch = if head.is_branch() {
+        head.shorthand().map(|name| name.to_string())
+    } else {
+        None
+    };
+    Ok(GitUndoEntry {
+        action: action.to_string(),
+        kind,
+        head: sha,
+        branch,
+        stash: None,
+        after: None,
+        recorded_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// Records where the journaled action left HEAD.
+pub(crate) fn record_after(entry: &mut GitUndoEntry, repo: &Repository) -> Result<(), String> {
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    entry.after = Some(head.id().to_string());
+    Ok(())
+}
+
+/// A hard reset discards commits made after the action and any uncommitted
+/// edits, so it only runs while HEAD and the tracked files are exactly as the
+/// action left them. Untracked files survive a reset and don't count.
+fn ensure_reset_is_lossless(repo: &Repository, entry: &GitUndoEntry) -> Result<(), String> {
+    let expected = entry
+        .after
+        .as_deref()
+        .ok_or("This undo entry doesn't record where the action left HEAD")?;
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    if head.id().to_string() != expected {
+        return Err(format!(
+            "HEAD has moved since \"{}\"; undoing it would discard later commits.",
+            entry.action
+        ));
+    }
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let dirty = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| e.to_string())?
+        .iter()
+        .any(|status| status.status() != Status::CURRENT);
+    if dirty {
+        return Err(format!(
+            "Commit or stash your changes before undoing \"{}\".",
+            entry.action
+        ));
+    }
+    Ok(())
+}
+
+/// Stashes tracked and untracked changes so a discard can be reversed.
+/// Returns `None` when there was nothing to stash.
+pub(crate) fn stash_worktree(
+    repo: &mut Repository,
+    message: &str,
+) -> Result<Option<String>, String> {
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("CodexMonitor", "codex-monitor@localhost"))
+        .map_err(|e| e.to_string())?;
+    match repo.stash_save(&signature, message, Some(StashFlags::INCLUDE_UNTRACKED)) {
+        Ok(oid) => Ok(Some(oid.to_string())),
+        Err(err) if err.code() == ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Pops the stash entry with commit id `stash`, wherever it now sits in the
+/// stash list, restoring staged changes to the index like `git stash pop
+/// --index`.
+pub(crate) fn pop_stash_by_id(repo: &mut Repository, stash: &str) -> Result<(), String> {
+    let target = Oid::from_str(stash).map_err(|e| e.to_string())?;
+    let mut found = None;
+    repo.stash_foreach(|index, _, oid| {
+        if *oid == target {
+            found = Some(index);
+            false
+        } else {
+            true
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    let index = found.ok_or("The recorded stash no longer exists")?;
+    let mut options = StashApplyOptions::new();
+    options.reinstantiate_index();
+    repo.stash_pop(index, Some(&mut options))
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn undo_entry_in_repo(
+    repo: &mut Repository,
+    entry: &GitUndoEntry,
+) -> Result<(), String> {
+    match entry.kind {
+        GitUndoKind::StashPop => {
+            let stash = entry
+                .stash
+                .as_deref()
+                .ok_or("Undo entry has no stash to restore")?;
+            pop_stash_by_id(repo, stash)
+        }
+        GitUndoKind::Checkout => {
+            if let Some(branch) = entry.branch.as_deref() {
+                return checkout_branch(repo, branch).map_err(|e| e.to_string());
+            }
+            let oid = Oid::from_str(&entry.head).map_err(|e| e.to_string())?;
+            let target = repo.find_object(oid, None).map_err(|e| e.to_string())?;
+            let mut options = git2::build::CheckoutBuilder::new();
+            options.safe();
+            repo.checkout_tree(&target, Some(&mut options))
+                .map_err(|e| e.to_string())?;
+            repo.set_head_detached(oid).map_err(|e| e.to_string())
+        }
+        GitUndoKind::ResetHead => {
+            ensure_reset_is_lossless(repo, entry)?;
+            let oid = Oid::from_str(&entry.head).map_err(|e| e.to_string())?;
+            let target = repo.find_object(oid, None).map_err(|e| e.to_string())?;
+            repo.reset(&target, ResetType::Hard, None)
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Journals `entry` unless the action left HEAD where it was (e.g. a pull
+/// that was already up to date, or checking out the current branch).
+pub(crate) async fn record_if_head_moved(
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: &str,
+    repo_root: &Path,
+    entry: Option<GitUndoEntry>,
+) {
+    let Some(mut entry) = entry else {
+        return;
+    };
+    let now = Repository::open(repo_root)
+        .ok()
+        .and_then(|repo| capture_head(&repo, &entry.action, entry.kind).ok());
+    if now
+        .as_ref()
+        .is_some_and(|now| now.head == entry.head && now.branch == entry.branch)
+    {
+        return;
+    }
+    entry.after = now.map(|now| now.head);
+    push_undo_entry(&mut *journal.lock().await, workspace_id, entry);
+}
+
+/// Journaled actions for a workspace, most recent first.
+pub(crate) async fn get_git_undo_stack_core(
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: &str,
+) -> Vec<GitUndoEntry> {
+    journal
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entries| entries.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Restores the state recorded before the most recent journaled action.
+/// Undo can itself discard work (`reset --hard`), so callers must confirm.
+pub(crate) async fn undo_last_git_action_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    journal: &Mutex<GitUndoJournal>,
+    workspace_id: String,
+    confirm: bool,
+    override_protection: Option<bool>,
+) -> Result<GitUndoEntry, String> {
+    if !confirm {
+        return Err("Undo must be confirmed".to_string());
+    }
+    let (entry, repo_root) = workspace_git_root(workspaces, &workspace_id).await?;
+    let mut journal = journal.lock().await;
+    let resets_head = journal
+        .get(&workspace_id)
+        .and_then(|entries| entries.back())
+        .is_some_and(|last| matches!(last.kind, GitUndoKind::ResetHead));
+    if resets_head {
+        ensure_current_branch_unprotected(&entry, &repo_root, override_protection)?;
+    }
+    let undo_entry = journal
+        .get_mut(&workspace_id)
+        .and_then(|entries| entries.pop_back())
+        .ok_or("Nothing to undo")?;
+    let target = undo_entry.clone();
+    let result = spawn_git_blocking(move || {
+        Repository::open(&repo_root)
+            .map_err(|e| e.to_string())
+            .and_then(|mut repo| undo_entry_in_repo(&mut repo, &target))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    if let Err(err) = result {
+        push_undo_entry(&mut journal, &workspace_id, undo_entry);
+        return Err(err);
+    }
+    Ok(undo_entry)
+}
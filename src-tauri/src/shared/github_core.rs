@@ -0,0 +1,609 @@
+//! `gh`-backed GitHub queries. Every call goes through `run_gh_with_retry`,
+//! which retries transient failures and keeps the per-host auth cache honest.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use git2::Repository;
+use tokio::sync::Mutex;
+
+use crate::git_utils::{parse_github_repo, parse_remote_host_and_path};
+use crate::shared::git_ui_core::workspace_git_root;
+use crate::shared::process_core::{tokio_command, workspace_env_for};
+use crate::types::{
+    AppSettings, GitHubIssue, GitHubIssuesResponse, GitHubPullRequest, GitHubPullRequestComment,
+    GitHubPullRequestDiff, GitHubPullRequestsResponse, WorkspaceEntry,
+};
+use crate::utils::{git_env_path, normalize_git_path};
+
+const GH_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+pub(crate) const GH_RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+
+/// Recognizes `gh` failures worth retrying: server errors and dropped
+/// connections. Rate limits are handled separately, by `gh_rate_limit_wait`.
+/// Auth and "repo not found" errors are returned as-is.
+pub(crate) fn gh_error_is_transient(detail: &str) -> bool {
+    let lower = detail.to_ascii_lowercase();
+    ["http 500", "http 502", "http 503", "http 504"]
+        .iter()
+        .any(|code| lower.contains(code))
+        || lower.contains("connection reset")
+        || lower.contains("connection refused")
+        || lower.contains("timeout")
+        || lower.contains("tls handshake")
+}
+
+/// Recognizes `gh` failures caused by a missing, expired or revoked token.
+pub(crate) fn gh_error_is_auth_failure(detail: &str) -> bool {
+    let lower = detail.to_ascii_lowercase();
+    lower.contains("http 401")
+        || lower.contains("bad credentials")
+        || lower.contains("gh auth login")
+        || lower.contains("authentication required")
+}
+
+pub(crate) fn gh_error_is_rate_limited(detail: &str) -> bool {
+    detail.to_ascii_lowercase().contains("rate limit")
+}
+
+/// Decides how to handle a rate-limited call from `gh api rate_limit` output
+/// and the current Unix time. Waits for the core quota to reset when that
+/// fits in `GH_RETRY_MAX_DELAY`, fails naming the reset time when it does
+/// not, and returns `Ok(None)` (no retry) when the core quota isn't what ran
+/// out, e.g. for secondary rate limits.
+pub(crate) fn gh_rate_limit_wait(
+    rate_limit_json: &str,
+    now: i64,
+) -> Result<Option<Duration>, String> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(rate_limit_json) else {
+        return Ok(None);
+    };
+    let core = &value["resources"]["core"];
+    let (Some(remaining), Some(reset)) = (core["remaining"].as_i64(), core["reset"].as_i64())
+    else {
+        return Ok(None);
+    };
+    if remaining > 0 {
+        return Ok(None);
+    }
+    let wait = Duration::from_secs(reset.saturating_sub(now).max(0) as u64);
+    if wait <= GH_RETRY_MAX_DELAY {
+        return Ok(Some(wait));
+    }
+    let reset_at = chrono::DateTime::from_timestamp(reset, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| reset.to_string());
+    Err(format!("GitHub API rate limit exceeded; it resets at {reset_at}."))
+}
+
+pub(crate) fn gh_retry_delay(attempt: u32) -> Duration {
+    GH_RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(GH_RETRY_MAX_DELAY)
+}
+
+async fn run_gh(
+    repo_root: &Path,
+    host: &str,
+    args: &[&str],
+) -> Result<std::process::Output, String> {
+    tokio_command("gh")
+        .args(args)
+        .current_dir(repo_root)
+        .envs(workspace_env_for(repo_root))
+        .env("GH_HOST", host)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh: {e}"))
+}
+
+async fn run_gh_with_retry(
+    repo_root: &Path,
+    host: &str,
+    args: &[&str],
+    max_attempts: u32,
+) -> Result<std::process::Output, String> {
+    let max_attempts = max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let output = run_gh(repo_root, host, args).await?;
+        if output.status.success() {
+            return Ok(output);
+        }
+        let detail = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stderr),
+            String::from_utf8_lossy(&output.stdout)
+        );
+        if gh_error_is_auth_failure(&detail) {
+            forget_gh_authenticated_host(host);
+        }
+        if attempt >= max_attempts {
+            return Ok(output);
+        }
+        let delay = if gh_error_is_transient(&detail) {
+            gh_retry_delay(attempt)
+        } else if gh_error_is_rate_limited(&detail) {
+            // `rate_limit` itself doesn't count against the quota.
+            let limits = run_gh(repo_root, host, &["api", "rate_limit"]).await?;
+            let now = chrono::Utc::now().timestamp();
+            match gh_rate_limit_wait(&String::from_utf8_lossy(&limits.stdout), now)? {
+                Some(wait) => wait,
+                None => return Ok(output),
+            }
+        } else {
+            return Ok(output);
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// A GitHub (or GitHub Enterprise) repository behind a workspace's remote.
+struct GitHubRemote {
+    host: String,
+    repo: String,
+}
+
+/// Hosts `gh auth status` has already confirmed, so the check runs once per
+/// host rather than before every call. A host is dropped again as soon as a
+/// `gh` call against it fails with an auth error.
+pub(crate) static GH_AUTHENTICATED_HOSTS: OnceLock<std::sync::Mutex<HashSet<String>>> =
+    OnceLock::new();
+
+pub(crate) fn forget_gh_authenticated_host(host: &str) {
+    if let Some(hosts) = GH_AUTHENTICATED_HOSTS.get() {
+        hosts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(host);
+    }
+}
+
+/// Host names of forges `gh` cannot talk to. Any other host may be a GitHub
+/// Enterprise instance, so it is left to `gh auth status` to decide.
+const NON_GITHUB_FORGE_HOSTS: &[&str] = &[
+    "gitlab",
+    "bitbucket",
+    "codeberg",
+    "gitea",
+    "forgejo",
+    "sr.ht",
+    "dev.azure.com",
+    "visualstudio.com",
+];
+
+async fn ensure_gh_authenticated(path: &Path, host: &str) -> Result<(), String> {
+    let hosts = GH_AUTHENTICATED_HOSTS.get_or_init(Default::default);
+    // A workspace env may carry its own token, so its result isn't shared.
+    let workspace_env = workspace_env_for(path);
+    let cacheable = workspace_env.is_empty();
+    if cacheable
+        && hosts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .contains(host)
+    {
+        return Ok(());
+    }
+    let output = tokio_command("gh")
+        .args(["auth", "status", "--hostname", host])
+        .envs(workspace_env)
+        .env("PATH", git_env_path())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "GitHub CLI is not authenticated for {host}. Run `gh auth login --hostname {host}`."
+        ));
+    }
+    if cacheable {
+        hosts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(host.to_string());
+    }
+    Ok(())
+}
+
+async fn github_remote_for_path(path: &Path) -> Result<GitHubRemote, String> {
+    let remote = github_repo_from_path(path)?;
+    ensure_gh_authenticated(path, &remote.host).await?;
+    Ok(remote)
+}
+
+pub(crate) fn github_repo_from_path(path: &Path) -> Result<GitHubRemote, String> {
+    let repo = Repository::open(path).map_err(|e| e.to_string())?;
+    let remotes = repo.remotes().map_err(|e| e.to_string())?;
+    let name = if remotes.iter().any(|remote| remote == Some("origin")) {
+        "origin".to_string()
+    } else {
+        remotes.iter().flatten().next().unwrap_or("").to_string()
+    };
+    if name.is_empty() {
+        return Err("No git remote configured.".to_string());
+    }
+    let remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
+    let remote_url = remote.url().ok_or("Remote has no URL configured.")?;
+    if let Some(repo) = parse_github_repo(remote_url) {
+        return Ok(GitHubRemote {
+            host: "github.com".to_string(),
+            repo,
+        });
+    }
+    let (host, repo) = parse_remote_host_and_path(remote_url)
+        .ok_or("Remote is not a GitHub repository.".to_string())?;
+    if NON_GITHUB_FORGE_HOSTS
+        .iter()
+        .any(|forge| host.contains(forge))
+    {
+        return Err(format!("Remote is not a GitHub repository ({host})."));
+    }
+    Ok(GitHubRemote { host, repo })
+}
+
+/// The workspace's GitHub remote, after checking `gh` is signed in to its
+/// host, plus the configured retry budget.
+async fn workspace_github_remote(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: &str,
+) -> Result<(PathBuf, GitHubRemote, u32), String> {
+    let (_, repo_root) = workspace_git_root(workspaces, workspace_id).await?;
+    let remote = github_remote_for_path(&repo_root).await?;
+    let gh_max_attempts = app_settings.lock().await.gh_max_attempts;
+    Ok((repo_root, remote, gh_max_attempts))
+}
+
+/// Runs `gh` through `run_gh_with_retry` and returns its stdout, or the error
+/// it printed when it failed.
+async fn run_gh_checked(
+    repo_root: &Path,
+    host: &str,
+    args: &[&str],
+    max_attempts: u32,
+) -> Result<Vec<u8>, String> {
+    let output = run_gh_with_retry(repo_root, host, args, max_attempts).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let detail = if stderr.trim().is_empty() {
+            stdout.trim()
+        } else {
+            stderr.trim()
+        };
+        if detail.is_empty() {
+            return Err("GitHub CLI command failed.".to_string());
+        }
+        return Err(detail.to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Full metadata for one PR by number, including mergeability, review
+/// decision and CI checks, regardless of whether it falls in the list window.
+pub(crate) async fn get_github_pull_request_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    pr_number: u64,
+) -> Result<GitHubPullRequest, String> {
+    let (repo_root, remote, gh_max_attempts) =
+        workspace_github_remote(workspaces, app_settings, &workspace_id).await?;
+    let stdout = run_gh_checked(
+        &repo_root,
+        &remote.host,
+        &[
+            "pr",
+            "view",
+            &pr_number.to_string(),
+            "--repo",
+            &remote.repo,
+            "--json",
+            "number,title,url,updatedAt,createdAt,body,headRefName,baseRefName,isDraft,author,mergeable,reviewDecision,statusCheckRollup",
+        ],
+        gh_max_attempts,
+    )
+    .await?;
+    serde_json::from_slice(&stdout).map_err(|e| e.to_string())
+}
+
+/// The unified diff of a PR exactly as `gh pr diff` prints it.
+async fn github_pull_request_diff_text(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: &str,
+    pr_number: u64,
+) -> Result<String, String> {
+    let (repo_root, remote, gh_max_attempts) =
+        workspace_github_remote(workspaces, app_settings, workspace_id).await?;
+    let stdout = run_gh_checked(
+        &repo_root,
+        &remote.host,
+        &[
+            "pr",
+            "diff",
+            &pr_number.to_string(),
+            "--repo",
+            &remote.repo,
+            "--color",
+            "never",
+        ],
+        gh_max_attempts,
+    )
+    .await?;
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
+
+/// Old and new paths from a `diff --git a/<old> b/<new>` header. Splits on
+/// ` b/` rather than whitespace so paths containing spaces survive; when the
+/// header is ambiguous, `rename from`/`rename to` lines still override it.
+fn parse_diff_git_header(line: &str) -> (Option<String>, Option<String>) {
+    let rest = line.trim_start_matches("diff --git ").trim();
+    let rest = rest.strip_prefix("a/").unwrap_or(rest);
+    let (old_part, new_part) = match rest.find(" b/") {
+        Some(index) => (&rest[..index], &rest[index + " b/".len()..]),
+        None => {
+            let mut parts = rest.splitn(2, ' ');
+            (
+                parts.next().unwrap_or(""),
+                parts.next().unwrap_or("").trim_start_matches("b/"),
+            )
+        }
+    };
+    let to_path = |value: &str| {
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+    (to_path(old_part), to_path(new_part))
+}
+
+pub(crate) fn parse_pr_diff(diff: &str) -> Vec<GitHubPullRequestDiff> {
+    let mut entries = Vec::new();
+    let mut current_lines: Vec<&str> = Vec::new();
+    let mut current_old_path: Option<String> = None;
+    let mut current_new_path: Option<String> = None;
+    let mut current_status: Option<String> = None;
+
+    let mut current_binary = false;
+
+    let finalize = |lines: &Vec<&str>,
+                    old_path: &Option<String>,
+                    new_path: &Option<String>,
+                    status: &Option<String>,
+                    is_binary: bool,
+                    results: &mut Vec<GitHubPullRequestDiff>| {
+        if lines.is_empty() {
+            return;
+        }
+        let diff_text = lines.join("\n");
+        if diff_text.trim().is_empty() {
+            return;
+        }
+        let status_value = status.clone().unwrap_or_else(|| "M".to_string());
+        let path = if status_value == "D" {
+            old_path.clone().unwrap_or_default()
+        } else {
+            new_path
+                .clone()
+                .or_else(|| old_path.clone())
+                .unwrap_or_default()
+        };
+        if path.is_empty() {
+            return;
+        }
+        results.push(GitHubPullRequestDiff {
+            path: normalize_git_path(&path),
+            status: status_value,
+            diff: diff_text,
+            is_binary,
+        });
+    };
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            finalize(
+                &current_lines,
+                &current_old_path,
+                &current_new_path,
+                &current_status,
+                current_binary,
+                &mut entries,
+            );
+            current_lines = vec![line];
+            current_status = None;
+            current_binary = false;
+
+            let (old_part, new_part) = parse_diff_git_header(line);
+            current_old_path = old_part;
+            current_new_path = new_part;
+            continue;
+        }
+        if line.starts_with("new file mode ") {
+            current_status = Some("A".to_string());
+        } else if line.starts_with("deleted file mode ") {
+            current_status = Some("D".to_string());
+        } else if line.starts_with("rename from ") {
+            current_status = Some("R".to_string());
+            let path = line.trim_start_matches("rename from ").trim();
+            if !path.is_empty() {
+                current_old_path = Some(path.to_string());
+            }
+        } else if line.starts_with("rename to ") {
+            current_status = Some("R".to_string());
+            let path = line.trim_start_matches("rename to ").trim();
+            if !path.is_empty() {
+                current_new_path = Some(path.to_string());
+            }
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            current_binary = true;
+        }
+        current_lines.push(line);
+    }
+
+    finalize(
+        &current_lines,
+        &current_old_path,
+        &current_new_path,
+        &current_status,
+        current_binary,
+        &mut entries,
+    );
+
+    entries
+}
+
+pub(crate) async fn get_github_pull_request_diff_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    pr_number: u64,
+) -> Result<Vec<GitHubPullRequestDiff>, String> {
+    let diff_text =
+        github_pull_request_diff_text(workspaces, app_settings, &workspace_id, pr_number).await?;
+    Ok(parse_pr_diff(&diff_text))
+}
+
+/// Unparsed variant of `get_github_pull_request_diff_core`, for external
+/// viewers or applying the patch locally.
+pub(crate) async fn get_github_pull_request_raw_diff_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    pr_number: u64,
+) -> Result<String, String> {
+    github_pull_request_diff_text(workspaces, app_settings, &workspace_id, pr_number).await
+}
+
+/// Open item count from the search API, or `fallback` when the search fails
+/// (the search API has its own, stricter rate limit).
+async fn search_open_total(
+    repo_root: &Path,
+    host: &str,
+    query: &str,
+    max_attempts: u32,
+    fallback: usize,
+) -> usize {
+    let search_query = query.replace(' ', "+");
+    match run_gh_with_retry(
+        repo_root,
+        host,
+        &[
+            "api",
+            &format!("/search/issues?q={search_query}"),
+            "--jq",
+            ".total_count",
+        ],
+        max_attempts,
+    )
+    .await
+    {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<usize>()
+            .unwrap_or(fallback),
+        _ => fallback,
+    }
+}
+
+pub(crate) async fn get_github_issues_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+) -> Result<GitHubIssuesResponse, String> {
+    let (repo_root, remote, gh_max_attempts) =
+        workspace_github_remote(workspaces, app_settings, &workspace_id).await?;
+    let stdout = run_gh_checked(
+        &repo_root,
+        &remote.host,
+        &[
+            "issue",
+            "list",
+            "--repo",
+            &remote.repo,
+            "--limit",
+            "50",
+            "--json",
+            "number,title,url,updatedAt",
+        ],
+        gh_max_attempts,
+    )
+    .await?;
+    let issues: Vec<GitHubIssue> = serde_json::from_slice(&stdout).map_err(|e| e.to_string())?;
+    let total = search_open_total(
+        &repo_root,
+        &remote.host,
+        &format!("repo:{} is:issue is:open", remote.repo),
+        gh_max_attempts,
+        issues.len(),
+    )
+    .await;
+    Ok(GitHubIssuesResponse { total, issues })
+}
+
+pub(crate) async fn get_github_pull_requests_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+) -> Result<GitHubPullRequestsResponse, String> {
+    let (repo_root, remote, gh_max_attempts) =
+        workspace_github_remote(workspaces, app_settings, &workspace_id).await?;
+    let stdout = run_gh_checked(
+        &repo_root,
+        &remote.host,
+        &[
+            "pr",
+            "list",
+            "--repo",
+            &remote.repo,
+            "--state",
+            "open",
+            "--limit",
+            "50",
+            "--json",
+            "number,title,url,updatedAt,createdAt,body,headRefName,baseRefName,isDraft,author",
+        ],
+        gh_max_attempts,
+    )
+    .await?;
+    let pull_requests: Vec<GitHubPullRequest> =
+        serde_json::from_slice(&stdout).map_err(|e| e.to_string())?;
+    let total = search_open_total(
+        &repo_root,
+        &remote.host,
+        &format!("repo:{} is:pr is:open", remote.repo),
+        gh_max_attempts,
+        pull_requests.len(),
+    )
+    .await;
+    Ok(GitHubPullRequestsResponse {
+        total,
+        pull_requests,
+    })
+}
+
+pub(crate) async fn get_github_pull_request_comments_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    pr_number: u64,
+) -> Result<Vec<GitHubPullRequestComment>, String> {
+    let (repo_root, remote, gh_max_attempts) =
+        workspace_github_remote(workspaces, app_settings, &workspace_id).await?;
+    let comments_endpoint = format!(
+        "/repos/{}/issues/{pr_number}/comments?per_page=30",
+        remote.repo
+    );
+    let jq_filter = r#"[.[] | {id, body, createdAt: .created_at, url: .html_url, author: (if .user then {login: .user.login} else null end)}]"#;
+    let stdout = run_gh_checked(
+        &repo_root,
+        &remote.host,
+        &["api", &comments_endpoint, "--jq", jq_filter],
+        gh_max_attempts,
+    )
+    .await?;
+    serde_json::from_slice(&stdout).map_err(|e| e.to_string())
+}
@@ -1,7 +1,12 @@
 pub(crate) mod account;
 pub(crate) mod codex_core;
+pub(crate) mod compression_core;
 pub(crate) mod files_core;
 pub(crate) mod git_core;
+pub(crate) mod git_system_core;
+pub(crate) mod git_ui_core;
+pub(crate) mod git_undo_core;
+pub(crate) mod github_core;
 pub(crate) mod process_core;
 pub(crate) mod settings_core;
 pub(crate) mod worktree_core;
@@ -1,7 +1,12 @@
+use std::collections::HashMap;
 use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex as StdMutex, OnceLock};
 
 use tokio::process::Command;
 
+use crate::types::WorkspaceEntry;
+
 /// On Windows, spawning a console app from a GUI subsystem app will open a new
 /// console window unless we explicitly disable it.
 fn hide_console_on_windows(_command: &mut std::process::Command) {
@@ -17,4 +22,99 @@ pub(crate) fn tokio_command(program: impl AsRef<OsStr>) -> Command {
     let mut command = Command::new(program);
     hide_console_on_windows(command.as_std_mut());
     command
-}
\ No newline at end of file
+}
+
+type WorkspaceEnvs = Vec<(PathBuf, HashMap<String, String>)>;
+
+/// Environment overrides from workspace settings, keyed by workspace path.
+/// Subprocess helpers only see a directory, so they look their env up here.
+fn workspace_envs() -> &'static StdMutex<WorkspaceEnvs> {
+    static ENVS: OnceLock<StdMutex<WorkspaceEnvs>> = OnceLock::new();
+    ENVS.get_or_init(|| StdMutex::new(Vec::new()))
+}
+
+/// Resolves symlinks and `..` so roots and command directories compare by
+/// the directory they name rather than by spelling.
+fn normalize_dir(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Replaces the registered workspace environments. Called whenever the
+/// workspace list is loaded or persisted so edits apply to the next command.
+pub(crate) fn sync_workspace_envs<'a>(entries: impl IntoIterator<Item = &'a WorkspaceEntry>) {
+    let envs: WorkspaceEnvs = entries
+        .into_iter()
+        .filter(|entry| !entry.settings.env.is_empty())
+        .map(|entry| (normalize_dir(Path::new(&entry.path)), entry.settings.env.clone()))
+        .collect();
+    let mut guard = workspace_envs()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *guard = envs;
+}
+
+/// Picks the env of the most specific workspace containing `dir`, so a
+/// worktree nested under its parent gets its own variables. Roots in `envs`
+/// are already normalized.
+fn env_for_dir(envs: &WorkspaceEnvs, dir: &Path) -> HashMap<String, String> {
+    let dir = normalize_dir(dir);
+    envs.iter()
+        .filter(|(root, _)| dir.starts_with(root))
+        .max_by_key(|(root, _)| root.components().count())
+        .map(|(_, env)| env.clone())
+        .unwrap_or_default()
+}
+
+/// Returns the extra environment for commands run in `dir`. Values may hold
+/// secrets, so callers pass them straight to the child and never log them.
+pub(crate) fn workspace_env_for(dir: &Path) -> HashMap<String, String> {
+    let guard = workspace_envs()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    env_for_dir(&guard, dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{env_for_dir, normalize_dir};
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    fn env(key: &str, value: &str) -> HashMap<String, String> {
+        [(key.to_string(), value.to_string())].into_iter().collect()
+    }
+
+    #[test]
+    fn env_for_dir_prefers_the_most_specific_workspace() {
+        let envs = vec![
+            (PathBuf::from("/repos/app"), env("TOKEN", "parent")),
+            (PathBuf::from("/repos/app/.worktrees/feat"), env("TOKEN", "child")),
+        ];
+
+        assert_eq!(
+            env_for_dir(&envs, Path::new("/repos/app/src"))["TOKEN"],
+            "parent"
+        );
+        assert_eq!(
+            env_for_dir(&envs, Path::new("/repos/app/.worktrees/feat"))["TOKEN"],
+            "child"
+        );
+        assert!(env_for_dir(&envs, Path::new("/repos/application")).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn env_for_dir_matches_through_symlinks() {
+        let base =
+            std::env::temp_dir().join(format!("codex-monitor-env-{}", uuid::Uuid::new_v4()));
+        let real = base.join("real");
+        std::fs::create_dir_all(real.join("src")).expect("create workspace");
+        let link = base.join("link");
+        std::os::unix::fs::symlink(&real, &link).expect("symlink workspace");
+        let envs = vec![(normalize_dir(&link), env("TOKEN", "linked"))];
+
+        assert_eq!(env_for_dir(&envs, &real.join("src"))["TOKEN"], "linked");
+        assert_eq!(env_for_dir(&envs, &link.join("src/.."))["TOKEN"], "linked");
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
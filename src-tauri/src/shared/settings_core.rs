@@ -1,10 +1,19 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
 
-use tokio::sync::Mutex;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, watch, Mutex};
 
+use crate::codex::args::{profile_from_codex_args, resolve_workspace_codex_args};
 use crate::codex::config as codex_config;
+use crate::shared::git_core::git_op_limiter;
 use crate::storage::write_settings;
-use crate::types::AppSettings;
+use crate::types::{
+    AppSettings, CodexConfigReload, FeatureFlag, SettingsProfileExport, WorkspaceEntry,
+};
 
 fn normalize_personality(value: &str) -> Option<&'static str> {
     match value.trim() {
@@ -16,83 +25,655 @@ fn normalize_personality(value: &str) -> Option<&'static str> {
 
 pub(crate) async fn get_app_settings_core(app_settings: &Mutex<AppSettings>) -> AppSettings {
     let mut settings = app_settings.lock().await.clone();
+    if settings_write_pending() {
+        // config.toml hasn't caught up with the latest update yet.
+        return settings;
+    }
+    apply_codex_config(&mut settings);
+    settings
+}
+
+/// Re-reads the config.toml-backed settings (feature flags, personality) into
+/// the in-memory copy, plus the auth store, for edits made outside the app.
+pub(crate) async fn reload_codex_config_core(
+    app_settings: &Mutex<AppSettings>,
+) -> Result<CodexConfigReload, String> {
+    let (settings, changed) = {
+        let mut current = app_settings.lock().await;
+        let (reloaded, changed) = reloaded_settings(&current, |settings| {
+            if !settings_write_pending() {
+                apply_codex_config(settings);
+            }
+        });
+        *current = reloaded.clone();
+        (reloaded, changed)
+    };
+    let auth_store = codex_config::read_auth_store_with_settings(Some(&settings))?;
+    Ok(CodexConfigReload {
+        settings,
+        auth_store,
+        changed,
+    })
+}
+
+/// Applies `reload` to a copy of `current` and reports whether anything the
+/// app serializes moved, so callers only announce real changes.
+fn reloaded_settings(
+    current: &AppSettings,
+    reload: impl FnOnce(&mut AppSettings),
+) -> (AppSettings, bool) {
+    let mut reloaded = current.clone();
+    reload(&mut reloaded);
+    let changed = serde_json::to_value(current).ok() != serde_json::to_value(&reloaded).ok();
+    (reloaded, changed)
+}
+
+fn apply_codex_config(settings: &mut AppSettings) {
     if let Ok(Some(collab_enabled)) =
-        codex_config::read_collab_enabled_with_settings(Some(&settings))
+        codex_config::read_collab_enabled_with_settings(Some(&*settings))
     {
         settings.experimental_collab_enabled = collab_enabled;
     }
     if let Ok(Some(collaboration_modes_enabled)) =
-        codex_config::read_collaboration_modes_enabled_with_settings(Some(&settings))
+        codex_config::read_collaboration_modes_enabled_with_settings(Some(&*settings))
     {
         settings.collaboration_modes_enabled = collaboration_modes_enabled;
     }
     if let Ok(Some(steer_enabled)) =
-        codex_config::read_steer_enabled_with_settings(Some(&settings))
+        codex_config::read_steer_enabled_with_settings(Some(&*settings))
     {
         settings.steer_enabled = steer_enabled;
     }
     if let Ok(Some(unified_exec_enabled)) =
-        codex_config::read_unified_exec_enabled_with_settings(Some(&settings))
+        codex_config::read_unified_exec_enabled_with_settings(Some(&*settings))
     {
         settings.unified_exec_enabled = unified_exec_enabled;
     }
     if let Ok(Some(apps_enabled)) =
-        codex_config::read_apps_enabled_with_settings(Some(&settings))
+        codex_config::read_apps_enabled_with_settings(Some(&*settings))
     {
         settings.experimental_apps_enabled = apps_enabled;
     }
-    if let Ok(personality) = codex_config::read_personality_with_settings(Some(&settings)) {
+    if let Ok(personality) = codex_config::read_personality_with_settings(Some(&*settings)) {
         settings.personality = personality
             .as_deref()
             .and_then(normalize_personality)
             .unwrap_or("friendly")
             .to_string();
     }
-    settings
 }
 
-pub(crate) async fn update_app_settings_core(
+const SETTINGS_WRITE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+// Bumped on every update. The background writer persists only the newest
+// snapshot queued within the debounce window and publishes the generation it
+// wrote; the flushed generation also lets reads know whether config.toml still
+// lags behind the in-memory settings.
+static SETTINGS_WRITE_GENERATION: AtomicU64 = AtomicU64::new(0);
+static SETTINGS_FLUSHED_GENERATION: AtomicU64 = AtomicU64::new(0);
+static SETTINGS_WRITER: StdMutex<Option<SettingsWriter>> = StdMutex::new(None);
+// The last snapshot known to match settings.json: seeded with the settings in
+// memory before the first update and replaced after every successful write.
+// A failed write rolls the in-memory copy back to it.
+static PERSISTED_SETTINGS: StdMutex<Option<AppSettings>> = StdMutex::new(None);
+
+fn persisted_settings() -> std::sync::MutexGuard<'static, Option<AppSettings>> {
+    PERSISTED_SETTINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+struct PendingSettingsWrite {
+    generation: u64,
+    settings: AppSettings,
+    settings_path: PathBuf,
+}
+
+#[derive(Clone)]
+struct SettingsFlush {
+    generation: u64,
+    result: Result<Vec<String>, String>,
+}
+
+struct SettingsWriter {
+    queue: mpsc::UnboundedSender<PendingSettingsWrite>,
+    flushed: watch::Receiver<SettingsFlush>,
+}
+
+/// Hands a snapshot to the background writer and returns its generation plus
+/// a receiver that reports each completed write. Call it while holding the
+/// settings lock so generations follow the order of the in-memory updates.
+fn queue_settings_write(
     settings: AppSettings,
-    app_settings: &Mutex<AppSettings>,
     settings_path: &PathBuf,
-) -> Result<AppSettings, String> {
-    let _ = codex_config::write_collab_enabled_with_settings(
-        settings.experimental_collab_enabled,
-        Some(&settings),
+) -> (u64, watch::Receiver<SettingsFlush>) {
+    let mut writer = SETTINGS_WRITER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let generation = SETTINGS_WRITE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let mut pending = PendingSettingsWrite {
+        generation,
+        settings,
+        settings_path: settings_path.clone(),
+    };
+    if let Some(active) = writer.as_ref() {
+        match active.queue.send(pending) {
+            Ok(()) => return (generation, active.flushed.clone()),
+            Err(mpsc::error::SendError(returned)) => pending = returned,
+        }
+    }
+    // No writer yet, or the runtime that hosted the previous one shut down.
+    let (queue, receiver) = mpsc::unbounded_channel();
+    let (flushed_tx, flushed) = watch::channel(SettingsFlush {
+        generation: SETTINGS_FLUSHED_GENERATION.load(Ordering::SeqCst),
+        result: Ok(Vec::new()),
+    });
+    let _ = queue.send(pending);
+    tokio::spawn(run_settings_writer(receiver, flushed_tx));
+    *writer = Some(SettingsWriter {
+        queue,
+        flushed: flushed.clone(),
+    });
+    (generation, flushed)
+}
+
+async fn run_settings_writer(
+    mut queue: mpsc::UnboundedReceiver<PendingSettingsWrite>,
+    flushed: watch::Sender<SettingsFlush>,
+) {
+    while let Some(mut pending) = queue.recv().await {
+        tokio::time::sleep(SETTINGS_WRITE_DEBOUNCE).await;
+        while let Ok(newer) = queue.try_recv() {
+            pending = newer;
+        }
+        let generation = pending.generation;
+        let written = pending.settings.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            persist_app_settings(&pending.settings, &pending.settings_path)
+        })
+        .await
+        .unwrap_or_else(|err| Err(err.to_string()));
+        if result.is_ok() {
+            *persisted_settings() = Some(written);
+        }
+        SETTINGS_FLUSHED_GENERATION.store(generation, Ordering::SeqCst);
+        flushed.send_replace(SettingsFlush { generation, result });
+    }
+}
+
+fn settings_write_pending() -> bool {
+    SETTINGS_WRITE_GENERATION.load(Ordering::SeqCst)
+        != SETTINGS_FLUSHED_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Raw config.toml contents captured before a settings write, so a failed
+/// write can put the file back exactly as it was (including "did not exist").
+struct ConfigTomlSnapshot {
+    path: PathBuf,
+    contents: Option<Vec<u8>>,
+}
+
+impl ConfigTomlSnapshot {
+    fn capture(settings: &AppSettings) -> Option<Self> {
+        let path = codex_config::config_toml_path_with_settings(Some(settings))?;
+        let contents = std::fs::read(&path).ok();
+        Some(Self { path, contents })
+    }
+
+    fn restore(&self) -> Result<(), String> {
+        match self.contents.as_ref() {
+            Some(contents) => std::fs::write(&self.path, contents),
+            None if self.path.exists() => std::fs::remove_file(&self.path),
+            None => Ok(()),
+        }
+        .map_err(|err| format!("failed to restore {}: {err}", self.path.display()))
+    }
+}
+
+/// Writes the config.toml flags and settings.json. A failed config.toml write
+/// rolls config.toml back and is reported in the returned list, while
+/// settings.json is still saved; a failed settings.json write rolls back
+/// config.toml and fails the whole update.
+fn persist_app_settings(
+    settings: &AppSettings,
+    settings_path: &PathBuf,
+) -> Result<Vec<String>, String> {
+    let snapshot = ConfigTomlSnapshot::capture(settings);
+    let mut errors = Vec::new();
+    let mut record = |label: &str, result: Result<(), String>| {
+        if let Err(err) = result {
+            errors.push(format!("{label}: {err}"));
+        }
+    };
+    record(
+        "collab",
+        codex_config::write_collab_enabled_with_settings(
+            settings.experimental_collab_enabled,
+            Some(settings),
+        ),
+    );
+    record(
+        "collaboration_modes",
+        codex_config::write_collaboration_modes_enabled_with_settings(
+            settings.collaboration_modes_enabled,
+            Some(settings),
+        ),
     );
-    let _ = codex_config::write_collaboration_modes_enabled_with_settings(
-        settings.collaboration_modes_enabled,
-        Some(&settings),
+    record(
+        "steer",
+        codex_config::write_steer_enabled_with_settings(settings.steer_enabled, Some(settings)),
     );
-    let _ = codex_config::write_steer_enabled_with_settings(
-        settings.steer_enabled,
-        Some(&settings),
+    record(
+        "unified_exec",
+        codex_config::write_unified_exec_enabled_with_settings(
+            settings.unified_exec_enabled,
+            Some(settings),
+        ),
     );
-    let _ = codex_config::write_unified_exec_enabled_with_settings(
-        settings.unified_exec_enabled,
-        Some(&settings),
+    record(
+        "apps",
+        codex_config::write_apps_enabled_with_settings(
+            settings.experimental_apps_enabled,
+            Some(settings),
+        ),
     );
-    let _ =
-        codex_config::write_apps_enabled_with_settings(settings.experimental_apps_enabled, Some(&settings));
-    let _ = codex_config::write_personality_with_settings(
-        settings.personality.as_str(),
-        Some(&settings),
+    record(
+        "personality",
+        codex_config::write_personality_with_settings(
+            settings.personality.as_str(),
+            Some(settings),
+        ),
+    );
+    let rollback = || match snapshot.as_ref() {
+        Some(snapshot) => snapshot.restore(),
+        None => Ok(()),
+    };
+    if !errors.is_empty() {
+        // Keep config.toml consistent rather than half-applied.
+        if let Err(err) = rollback() {
+            errors.push(err);
+        }
+    }
+    let settings_error = match write_settings(settings_path, settings) {
+        Ok(()) => return Ok(errors),
+        Err(err) => err,
+    };
+
+    let mut message = format!("Failed to save settings (settings.json: {settings_error}).");
+    if !errors.is_empty() {
+        message.push_str(&format!(" config.toml errors: {}.", errors.join("; ")));
+    }
+    match rollback() {
+        Ok(()) => message.push_str(" config.toml was restored to its previous contents."),
+        Err(err) => message.push_str(&format!(" Rollback also failed: {err}.")),
+    }
+    Err(message)
+}
+
+pub(crate) async fn update_app_settings_core(
+    settings: AppSettings,
+    app_settings: &Mutex<AppSettings>,
+    settings_path: &PathBuf,
+) -> Result<AppSettings, String> {
+    update_app_settings_with(app_settings, settings_path, |current| {
+        *current = settings;
+        Ok(())
+    })
+    .await
+    .map(|((), settings)| settings)
+}
+
+/// Applies `apply` to the current settings and persists the result. The
+/// settings lock is held from the read until the write is queued, so
+/// concurrent read-modify-write updates can't drop each other's changes.
+pub(crate) async fn update_app_settings_with<T>(
+    app_settings: &Mutex<AppSettings>,
+    settings_path: &PathBuf,
+    apply: impl FnOnce(&mut AppSettings) -> Result<T, String>,
+) -> Result<(T, AppSettings), String> {
+    let (value, mut settings, generation, mut flushed) = {
+        let mut current = app_settings.lock().await;
+        let mut settings = current.clone();
+        let value = apply(&mut settings)?;
+        settings.config_write_errors.clear();
+        let previous = std::mem::replace(&mut *current, settings.clone());
+        persisted_settings().get_or_insert(previous);
+        git_op_limiter().set_limit(settings.max_concurrent_git_ops as usize);
+        let (generation, flushed) = queue_settings_write(settings.clone(), settings_path);
+        (value, settings, generation, flushed)
+    };
+    // Wait for the first write that includes this update; a newer update in
+    // the same debounce window is written (and reported) along with it.
+    let flush = SettingsFlush::clone(
+        &*flushed
+            .wait_for(|flush| flush.generation >= generation)
+            .await
+            .map_err(|_| "Settings writer stopped before saving.".to_string())?,
     );
-    write_settings(settings_path, &settings)?;
-    let mut current = app_settings.lock().await;
-    *current = settings.clone();
-    Ok(settings)
+    match flush.result {
+        Ok(config_write_errors) => {
+            settings.config_write_errors = config_write_errors;
+            Ok((value, settings))
+        }
+        Err(error) => {
+            let mut current = app_settings.lock().await;
+            // Only roll back when nothing newer is queued; a newer update
+            // already replaced the in-memory copy and gets its own write.
+            if SETTINGS_WRITE_GENERATION.load(Ordering::SeqCst) == flush.generation {
+                if let Some(persisted) = persisted_settings().clone() {
+                    git_op_limiter().set_limit(persisted.max_concurrent_git_ops as usize);
+                    *current = persisted;
+                }
+            }
+            Err(error)
+        }
+    }
+}
+
+const SETTINGS_PROFILE_VERSION: u64 = 1;
+
+/// Settings keys that are redacted from exported profiles by default.
+/// The daemon token and the host it is sent to travel together: exports drop
+/// both unless secrets are included.
+const SECRET_SETTINGS_KEYS: &[&str] = &["remoteBackendToken", "remoteBackendHost"];
+
+/// Settings that decide which daemon the token is presented to.
+const REMOTE_TARGET_KEYS: &[&str] = &["backendMode", "remoteBackendHost", "remoteBackendProvider"];
+
+/// Serializes `settings` as a profile, dropping secrets unless
+/// `include_secrets`. Only secrets that were actually set are listed as
+/// omitted.
+pub(crate) fn settings_profile(
+    settings: &AppSettings,
+    include_secrets: bool,
+) -> Result<SettingsProfileExport, String> {
+    let mut value = serde_json::to_value(settings).map_err(|err| err.to_string())?;
+    let mut omitted = Vec::new();
+    if let Value::Object(map) = &mut value {
+        map.remove("configWriteErrors");
+        if !include_secrets {
+            for key in SECRET_SETTINGS_KEYS {
+                if map.remove(*key).is_some_and(|secret| !secret.is_null()) {
+                    omitted.push(key.to_string());
+                }
+            }
+        }
+    }
+    Ok(SettingsProfileExport {
+        profile: json!({ "version": SETTINGS_PROFILE_VERSION, "settings": value }),
+        omitted,
+    })
+}
+
+pub(crate) async fn export_settings_profile_core(
+    app_settings: &Mutex<AppSettings>,
+    include_secrets: bool,
+) -> Result<SettingsProfileExport, String> {
+    let settings = get_app_settings_core(app_settings).await;
+    settings_profile(&settings, include_secrets)
+}
+
+/// Validates a profile (or a bare settings object) and builds the settings to
+/// apply. With `merge`, only the profile's keys change; otherwise anything the
+/// profile leaves out resets to its default. Secrets missing from the profile
+/// are kept, since exports redact them, except that a kept token is dropped
+/// when the profile points the app at a different daemon.
+pub(crate) fn apply_settings_profile(
+    current: &AppSettings,
+    profile: &Value,
+    merge: bool,
+) -> Result<AppSettings, String> {
+    let incoming = match profile.get("settings") {
+        Some(settings) => {
+            let version = profile.get("version").and_then(Value::as_u64).unwrap_or(1);
+            if version > SETTINGS_PROFILE_VERSION {
+                return Err(format!(
+                    "Settings profile version {version} is newer than this app supports."
+                ));
+            }
+            settings
+        }
+        None => profile,
+    };
+    let Value::Object(incoming) = incoming else {
+        return Err("Settings profile must be a JSON object.".to_string());
+    };
+    let mut base = if merge {
+        serde_json::to_value(current).map_err(|err| err.to_string())?
+    } else {
+        json!({})
+    };
+    let current_value = serde_json::to_value(current).map_err(|err| err.to_string())?;
+    if let Value::Object(base) = &mut base {
+        for (key, value) in incoming {
+            base.insert(key.clone(), value.clone());
+        }
+        base.remove("configWriteErrors");
+        for key in SECRET_SETTINGS_KEYS {
+            if !incoming.contains_key(*key) {
+                if let Some(secret) = current_value.get(*key) {
+                    base.insert(key.to_string(), secret.clone());
+                }
+            }
+        }
+        let retargeted = REMOTE_TARGET_KEYS.iter().any(|key| {
+            incoming.contains_key(*key) && base.get(*key) != current_value.get(*key)
+        });
+        if retargeted && !incoming.contains_key("remoteBackendToken") {
+            base.insert("remoteBackendToken".to_string(), Value::Null);
+        }
+    }
+    serde_json::from_value(base).map_err(|err| format!("Invalid settings profile: {err}"))
+}
+
+/// Settings to pass to `update_app_settings_core` for an imported profile.
+pub(crate) async fn settings_from_profile_core(
+    app_settings: &Mutex<AppSettings>,
+    profile: &Value,
+    merge: bool,
+) -> Result<AppSettings, String> {
+    let current = get_app_settings_core(app_settings).await;
+    apply_settings_profile(&current, profile, merge)
+}
+
+pub(crate) async fn diff_config_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+) -> Result<Value, String> {
+    let settings = app_settings.lock().await.clone();
+    let global_path = codex_config::config_toml_path_with_settings(Some(&settings))
+        .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())?;
+    let (workspace_path, _) =
+        resolve_config_path_core(workspaces, &settings, Some(workspace_id)).await?;
+    codex_config::diff_config_files(&global_path, &workspace_path)
+}
+
+async fn resolve_config_path_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    settings: &AppSettings,
+    workspace_id: Option<String>,
+) -> Result<(PathBuf, Option<String>), String> {
+    let (path, codex_args) = match workspace_id {
+        Some(workspace_id) => {
+            let workspaces = workspaces.lock().await;
+            let entry = workspaces
+                .get(&workspace_id)
+                .ok_or_else(|| "workspace not found".to_string())?;
+            let parent_entry = entry
+                .parent_id
+                .as_ref()
+                .and_then(|parent_id| workspaces.get(parent_id));
+            (
+                codex_config::config_toml_path_for_workspace_with_settings(
+                    entry,
+                    parent_entry,
+                    Some(settings),
+                ),
+                resolve_workspace_codex_args(entry, parent_entry, Some(settings)),
+            )
+        }
+        None => (
+            codex_config::config_toml_path_with_settings(Some(settings)),
+            settings.codex_args.clone(),
+        ),
+    };
+    let path = path.ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())?;
+    Ok((path, profile_from_codex_args(codex_args.as_deref())))
 }
 
 pub(crate) async fn get_codex_config_path_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     app_settings: &Mutex<AppSettings>,
+    workspace_id: Option<String>,
 ) -> Result<String, String> {
     let settings = app_settings.lock().await.clone();
-    codex_config::config_toml_path_with_settings(Some(&settings))
+    let (path, _) = resolve_config_path_core(workspaces, &settings, workspace_id).await?;
+    path.to_str()
+        .map(|value| value.to_string())
         .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
-        .and_then(|path| {
-            path.to_str()
-                .map(|value| value.to_string())
-                .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
-        })
+}
+
+pub(crate) async fn read_project_trust_core(
+    app_settings: &Mutex<AppSettings>,
+    path: String,
+) -> Result<Option<bool>, String> {
+    let settings = app_settings.lock().await.clone();
+    codex_config::read_project_trust_with_settings(&path, Some(&settings))
+}
+
+pub(crate) async fn write_project_trust_core(
+    app_settings: &Mutex<AppSettings>,
+    path: String,
+    trusted: bool,
+) -> Result<(), String> {
+    let settings = app_settings.lock().await.clone();
+    codex_config::write_project_trust_with_settings(&path, trusted, Some(&settings))
+}
+
+pub(crate) async fn list_feature_flags_core(
+    app_settings: &Mutex<AppSettings>,
+) -> Result<Vec<FeatureFlag>, String> {
+    let settings = app_settings.lock().await.clone();
+    codex_config::list_feature_flags_with_settings(Some(&settings))
+}
+
+pub(crate) async fn read_feature_flag_core(
+    app_settings: &Mutex<AppSettings>,
+    key: String,
+) -> Result<Option<bool>, String> {
+    let key = codex_config::validate_feature_key(&key)?;
+    let settings = app_settings.lock().await.clone();
+    codex_config::read_feature_flag_with_settings(key, Some(&settings))
+}
+
+/// Writes any `[features]` key. Flags that also back a typed setting (steer,
+/// collab, ...) are refreshed in memory so the next settings read agrees.
+pub(crate) async fn write_feature_flag_core(
+    app_settings: &Mutex<AppSettings>,
+    key: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let key = codex_config::validate_feature_key(&key)?;
+    let mut settings = app_settings.lock().await;
+    codex_config::write_feature_flag_with_settings(key, enabled, Some(&*settings))?;
+    apply_codex_config(&mut settings);
+    Ok(())
+}
+
+pub(crate) async fn get_effective_config_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: Option<String>,
+) -> Result<Value, String> {
+    let settings = app_settings.lock().await.clone();
+    let (path, profile) = resolve_config_path_core(workspaces, &settings, workspace_id).await?;
+    codex_config::read_effective_config(&path, profile.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_profile_redacts_secrets_and_round_trips() {
+        let settings = AppSettings {
+            remote_backend_token: Some("secret".to_string()),
+            commit_subject_max_len: 50,
+            ..AppSettings::default()
+        };
+
+        let export = settings_profile(&settings, false).expect("export");
+        assert_eq!(
+            export.omitted,
+            vec!["remoteBackendToken".to_string(), "remoteBackendHost".to_string()]
+        );
+        assert!(export.profile["settings"].get("remoteBackendToken").is_none());
+        assert!(export.profile["settings"].get("remoteBackendHost").is_none());
+        let with_secrets = settings_profile(&settings, true).expect("export secrets");
+        assert!(with_secrets.omitted.is_empty());
+        assert_eq!(with_secrets.profile["settings"]["remoteBackendToken"], "secret");
+
+        let current = AppSettings {
+            remote_backend_token: Some("mine".to_string()),
+            ..AppSettings::default()
+        };
+        let imported =
+            apply_settings_profile(&current, &export.profile, false).expect("import profile");
+        assert_eq!(imported.commit_subject_max_len, 50);
+        assert_eq!(imported.remote_backend_token.as_deref(), Some("mine"));
+
+        let teammate = json!({
+            "settings": { "backendMode": "remote", "remoteBackendHost": "10.0.0.9:4732" }
+        });
+        let retargeted =
+            apply_settings_profile(&current, &teammate, true).expect("import teammate profile");
+        assert_eq!(retargeted.remote_backend_host, "10.0.0.9:4732");
+        assert!(retargeted.remote_backend_token.is_none());
+        let same_host = json!({ "settings": { "remoteBackendHost": current.remote_backend_host } });
+        let kept = apply_settings_profile(&current, &same_host, true).expect("same host");
+        assert_eq!(kept.remote_backend_token.as_deref(), Some("mine"));
+    }
+
+    #[test]
+    fn reloaded_settings_reports_only_real_changes() {
+        let current = AppSettings {
+            steer_enabled: false,
+            ..AppSettings::default()
+        };
+        let (same, changed) = reloaded_settings(&current, |settings| {
+            settings.steer_enabled = false;
+        });
+        assert!(!changed);
+        assert!(!same.steer_enabled);
+
+        let (flipped, changed) = reloaded_settings(&current, |settings| {
+            settings.steer_enabled = true;
+        });
+        assert!(changed);
+        assert!(flipped.steer_enabled);
+        assert!(!current.steer_enabled);
+    }
+
+    #[test]
+    fn apply_settings_profile_merges_or_resets() {
+        let current = AppSettings {
+            commit_subject_max_len: 50,
+            gh_max_attempts: 9,
+            ..AppSettings::default()
+        };
+        let partial = json!({ "ghMaxAttempts": 5 });
+
+        let merged = apply_settings_profile(&current, &partial, true).expect("merge");
+        assert_eq!(merged.gh_max_attempts, 5);
+        assert_eq!(merged.commit_subject_max_len, 50);
+        let replaced = apply_settings_profile(&current, &partial, false).expect("replace");
+        assert_eq!(replaced.gh_max_attempts, 5);
+        assert_eq!(replaced.commit_subject_max_len, 72);
+
+        assert!(apply_settings_profile(&current, &json!([1, 2]), true).is_err());
+        assert!(apply_settings_profile(&current, &json!({ "ghMaxAttempts": "x" }), true).is_err());
+        let future = json!({ "version": 99, "settings": {} });
+        assert!(apply_settings_profile(&current, &future, true).is_err());
+    }
 }
@@ -1,23 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use tokio::sync::Mutex;
 
 use crate::backend::app_server::WorkspaceSession;
 use crate::codex::args::resolve_workspace_codex_args;
-use crate::codex::home::resolve_workspace_codex_home_with_settings;
+use crate::codex::home::{
+    resolve_workspace_codex_home_with_settings, validate_workspace_codex_home,
+};
+use crate::git_utils::list_git_roots;
+use crate::shared::git_core::{
+    normalize_diff_exclude_globs, validate_diff_exclude_globs, validate_protected_branches,
+};
+use crate::shared::settings_core::update_app_settings_with;
 use crate::storage::write_workspaces;
 use crate::types::{
-    AppSettings, WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeInfo,
-    WorktreeSetupStatus,
+    AppSettings, WorkspaceEntry, WorkspaceGroup, WorkspaceInfo, WorkspaceKind, WorkspaceSettings,
+    WorktreeInfo, WorktreeSetupStatus,
 };
 use uuid::Uuid;
 
 pub(crate) const WORKTREE_SETUP_MARKERS_DIR: &str = "worktree-setup";
 pub(crate) const WORKTREE_SETUP_MARKER_EXT: &str = "ran";
 const AGENTS_MD_FILE_NAME: &str = "AGENTS.md";
+const MAX_DISCOVERED_WORKSPACES: usize = 200;
+/// Label the sidebar uses for workspaces without a group.
+const RESERVED_GROUP_NAME: &str = "Ungrouped";
 
 fn copy_agents_md_from_parent_to_worktree(
     parent_repo_root: &PathBuf,
@@ -91,13 +101,45 @@ pub(crate) async fn list_workspaces_core(
             kind: entry.kind.clone(),
             parent_id: entry.parent_id.clone(),
             worktree: entry.worktree.clone(),
-            settings: entry.settings.clone(),
+            settings: entry.settings.redacted(),
         });
     }
     sort_workspaces(&mut result);
     result
 }
 
+/// Assigns `sort_order` 0..N following `ordered_ids` in one pass. The IDs must
+/// be exactly the registered workspaces so a stale UI list cannot drop one.
+pub(crate) async fn reorder_workspaces_core(
+    ordered_ids: Vec<String>,
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    storage_path: &PathBuf,
+) -> Result<Vec<WorkspaceInfo>, String> {
+    {
+        let mut workspaces = workspaces.lock().await;
+        let unique: HashSet<&String> = ordered_ids.iter().collect();
+        if unique.len() != ordered_ids.len() {
+            return Err("Workspace order contains duplicate IDs.".to_string());
+        }
+        if ordered_ids.len() != workspaces.len()
+            || ordered_ids.iter().any(|id| !workspaces.contains_key(id))
+        {
+            return Err("Workspace order must list every workspace exactly once.".to_string());
+        }
+        let mut updated = workspaces.clone();
+        for (index, id) in ordered_ids.iter().enumerate() {
+            if let Some(entry) = updated.get_mut(id) {
+                entry.settings.sort_order = Some(index as u32);
+            }
+        }
+        let list: Vec<_> = updated.values().cloned().collect();
+        write_workspaces(storage_path, &list)?;
+        *workspaces = updated;
+    }
+    Ok(list_workspaces_core(workspaces, sessions).await)
+}
+
 async fn resolve_entry_and_parent(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
@@ -245,10 +287,59 @@ where
         kind: entry.kind,
         parent_id: entry.parent_id,
         worktree: entry.worktree,
-        settings: entry.settings,
+        settings: entry.settings.redacted(),
     })
 }
 
+fn comparable_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Finds git repositories under `root` (up to `depth` levels) that are not
+/// registered yet and returns unsaved workspace stubs for bulk-adding.
+pub(crate) async fn discover_workspaces_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    root: String,
+    depth: Option<usize>,
+) -> Result<Vec<WorkspaceEntry>, String> {
+    let root = PathBuf::from(root.trim());
+    if !root.is_dir() {
+        return Err("Discovery root must be a folder.".to_string());
+    }
+    let depth = depth.unwrap_or(2).clamp(1, 6);
+    let registered: HashSet<PathBuf> = {
+        let workspaces = workspaces.lock().await;
+        workspaces
+            .values()
+            .map(|entry| comparable_path(Path::new(&entry.path)))
+            .collect()
+    };
+
+    let mut stubs = Vec::new();
+    for relative in list_git_roots(&root, depth, MAX_DISCOVERED_WORKSPACES) {
+        let path = root.join(&relative);
+        if registered.contains(&comparable_path(&path)) {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Workspace")
+            .to_string();
+        stubs.push(WorkspaceEntry {
+            id: Uuid::new_v4().to_string(),
+            name,
+            path: path.to_string_lossy().to_string(),
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        });
+    }
+    Ok(stubs)
+}
+
 pub(crate) fn run_git_command_unit<F, Fut>(
     repo_path: &PathBuf,
     args: &[&str],
@@ -424,7 +515,7 @@ where
         kind: entry.kind,
         parent_id: entry.parent_id,
         worktree: entry.worktree,
-        settings: entry.settings,
+        settings: entry.settings.redacted(),
     })
 }
 
@@ -799,7 +890,7 @@ where
         kind: entry_snapshot.kind,
         parent_id: entry_snapshot.parent_id,
         worktree: entry_snapshot.worktree,
-        settings: entry_snapshot.settings,
+        settings: entry_snapshot.settings.redacted(),
     })
 }
 
@@ -936,6 +1027,9 @@ where
     FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, String>>,
 {
     settings.worktree_setup_script = normalize_setup_script(settings.worktree_setup_script);
+    settings.diff_exclude_globs = normalize_diff_exclude_globs(settings.diff_exclude_globs);
+    validate_diff_exclude_globs(&settings.diff_exclude_globs)?;
+    validate_protected_branches(&settings.protected_branches)?;
 
     let (
         previous_entry,
@@ -951,6 +1045,10 @@ where
             .get(&id)
             .cloned()
             .ok_or_else(|| "workspace not found".to_string())?;
+        settings.restore_redacted_env(&previous_entry.settings);
+        if let Some(codex_home) = settings.codex_home.as_deref() {
+            validate_workspace_codex_home(codex_home, &previous_entry.path)?;
+        }
         let previous_codex_home = previous_entry.settings.codex_home.clone();
         let previous_codex_args = previous_entry.settings.codex_args.clone();
         let previous_worktree_setup_script = previous_entry.settings.worktree_setup_script.clone();
@@ -1092,7 +1190,7 @@ where
         kind: entry_snapshot.kind,
         parent_id: entry_snapshot.parent_id,
         worktree: entry_snapshot.worktree,
-        settings: entry_snapshot.settings,
+        settings: entry_snapshot.settings.redacted(),
     })
 }
 
@@ -1260,7 +1358,7 @@ pub(crate) async fn update_workspace_codex_bin_core(
         kind: entry_snapshot.kind,
         parent_id: entry_snapshot.parent_id,
         worktree: entry_snapshot.worktree,
-        settings: entry_snapshot.settings,
+        settings: entry_snapshot.settings.redacted(),
     })
 }
 
@@ -1302,6 +1400,174 @@ fn sort_workspaces(workspaces: &mut [WorkspaceInfo]) {
     });
 }
 
+pub(crate) fn validate_group_name(
+    groups: &[WorkspaceGroup],
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Group name is required.".to_string());
+    }
+    if trimmed.eq_ignore_ascii_case(RESERVED_GROUP_NAME) {
+        return Err(format!("\"{RESERVED_GROUP_NAME}\" is reserved."));
+    }
+    let duplicate = groups.iter().any(|group| {
+        Some(group.id.as_str()) != exclude_id && group.name.trim().eq_ignore_ascii_case(trimmed)
+    });
+    if duplicate {
+        return Err("Group name already exists.".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+pub(crate) fn normalize_group_color(color: Option<String>) -> Result<Option<String>, String> {
+    let Some(color) = color else {
+        return Ok(None);
+    };
+    let trimmed = color.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let hex = trimmed.strip_prefix('#').unwrap_or("");
+    if !matches!(hex.len(), 3 | 6) || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(format!("Invalid group color: {trimmed}"));
+    }
+    Ok(Some(format!("#{}", hex.to_ascii_lowercase())))
+}
+
+async fn update_workspace_groups<T>(
+    app_settings: &Mutex<AppSettings>,
+    settings_path: &PathBuf,
+    apply: impl FnOnce(&mut Vec<WorkspaceGroup>) -> Result<T, String>,
+) -> Result<T, String> {
+    update_app_settings_with(app_settings, settings_path, |settings| {
+        apply(&mut settings.workspace_groups)
+    })
+    .await
+    .map(|(result, _)| result)
+}
+
+fn find_group_mut<'a>(
+    groups: &'a mut [WorkspaceGroup],
+    group_id: &str,
+) -> Result<&'a mut WorkspaceGroup, String> {
+    groups
+        .iter_mut()
+        .find(|group| group.id == group_id)
+        .ok_or_else(|| "group not found".to_string())
+}
+
+pub(crate) async fn create_group_core(
+    name: String,
+    color: Option<String>,
+    app_settings: &Mutex<AppSettings>,
+    settings_path: &PathBuf,
+) -> Result<WorkspaceGroup, String> {
+    let color = normalize_group_color(color)?;
+    update_workspace_groups(app_settings, settings_path, |groups| {
+        let name = validate_group_name(groups, &name, None)?;
+        let sort_order = groups
+            .iter()
+            .filter_map(|group| group.sort_order)
+            .max()
+            .map_or(0, |max| max + 1);
+        let group = WorkspaceGroup {
+            id: Uuid::new_v4().to_string(),
+            name,
+            sort_order: Some(sort_order),
+            copies_folder: None,
+            color,
+            collapsed: false,
+        };
+        groups.push(group.clone());
+        Ok(group)
+    })
+    .await
+}
+
+pub(crate) async fn rename_group_core(
+    group_id: String,
+    name: String,
+    app_settings: &Mutex<AppSettings>,
+    settings_path: &PathBuf,
+) -> Result<WorkspaceGroup, String> {
+    update_workspace_groups(app_settings, settings_path, |groups| {
+        let name = validate_group_name(groups, &name, Some(&group_id))?;
+        let group = find_group_mut(groups, &group_id)?;
+        group.name = name;
+        Ok(group.clone())
+    })
+    .await
+}
+
+pub(crate) async fn set_group_color_core(
+    group_id: String,
+    color: Option<String>,
+    app_settings: &Mutex<AppSettings>,
+    settings_path: &PathBuf,
+) -> Result<WorkspaceGroup, String> {
+    let color = normalize_group_color(color)?;
+    update_workspace_groups(app_settings, settings_path, |groups| {
+        let group = find_group_mut(groups, &group_id)?;
+        group.color = color;
+        Ok(group.clone())
+    })
+    .await
+}
+
+pub(crate) async fn set_group_collapsed_core(
+    group_id: String,
+    collapsed: bool,
+    app_settings: &Mutex<AppSettings>,
+    settings_path: &PathBuf,
+) -> Result<WorkspaceGroup, String> {
+    update_workspace_groups(app_settings, settings_path, |groups| {
+        let group = find_group_mut(groups, &group_id)?;
+        group.collapsed = collapsed;
+        Ok(group.clone())
+    })
+    .await
+}
+
+/// Removes a group after moving its members to no group. Members are updated
+/// first, so a failed settings write leaves an empty group rather than
+/// workspaces pointing at a group that no longer exists. The workspaces lock
+/// is held throughout so no workspace can join the group mid-delete.
+pub(crate) async fn delete_group_core(
+    group_id: String,
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    storage_path: &PathBuf,
+    settings_path: &PathBuf,
+) -> Result<(), String> {
+    let mut workspaces = workspaces.lock().await;
+    {
+        let settings = app_settings.lock().await;
+        if !settings.workspace_groups.iter().any(|group| group.id == group_id) {
+            return Err("group not found".to_string());
+        }
+    }
+    let mut updated = workspaces.clone();
+    let mut changed = false;
+    for entry in updated.values_mut() {
+        if entry.settings.group_id.as_deref() == Some(group_id.as_str()) {
+            entry.settings.group_id = None;
+            changed = true;
+        }
+    }
+    if changed {
+        let list: Vec<_> = updated.values().cloned().collect();
+        write_workspaces(storage_path, &list)?;
+        *workspaces = updated;
+    }
+    update_workspace_groups(app_settings, settings_path, |groups| {
+        groups.retain(|group| group.id != group_id);
+        Ok(())
+    })
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::copy_agents_md_from_parent_to_worktree;
@@ -1,11 +1,16 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tokio::sync::Mutex;
 
 use crate::dictation::DictationState;
 use crate::shared::codex_core::CodexLoginCancelState;
+use crate::shared::git_core::git_op_limiter;
+use crate::shared::git_ui_core::{DefaultBranchCache, GitDiffStreams};
+use crate::shared::git_undo_core::GitUndoJournal;
+use crate::shared::process_core::sync_workspace_envs;
 use crate::storage::{read_settings, read_workspaces};
 use crate::types::{AppSettings, WorkspaceEntry};
 
@@ -20,6 +25,10 @@ pub(crate) struct AppState {
     pub(crate) app_settings: Mutex<AppSettings>,
     pub(crate) dictation: Mutex<DictationState>,
     pub(crate) codex_login_cancels: Mutex<HashMap<String, CodexLoginCancelState>>,
+    pub(crate) git_diff_streams: GitDiffStreams,
+    pub(crate) file_tail_streams: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    pub(crate) git_undo_journal: Mutex<GitUndoJournal>,
+    pub(crate) default_branch_cache: DefaultBranchCache,
 }
 
 impl AppState {
@@ -31,7 +40,9 @@ impl AppState {
         let storage_path = data_dir.join("workspaces.json");
         let settings_path = data_dir.join("settings.json");
         let workspaces = read_workspaces(&storage_path).unwrap_or_default();
+        sync_workspace_envs(workspaces.values());
         let app_settings = read_settings(&settings_path).unwrap_or_default();
+        git_op_limiter().set_limit(app_settings.max_concurrent_git_ops as usize);
         Self {
             workspaces: Mutex::new(workspaces),
             sessions: Mutex::new(HashMap::new()),
@@ -42,6 +53,10 @@ impl AppState {
             app_settings: Mutex::new(app_settings),
             dictation: Mutex::new(DictationState::default()),
             codex_login_cancels: Mutex::new(HashMap::new()),
+            git_diff_streams: Mutex::new(HashMap::new()),
+            file_tail_streams: Mutex::new(HashMap::new()),
+            git_undo_journal: Mutex::new(HashMap::new()),
+            default_branch_cache: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use serde_json::Value;
+
+use crate::shared::process_core::sync_workspace_envs;
 use crate::types::{AppSettings, WorkspaceEntry};
 
 pub(crate) fn read_workspaces(path: &PathBuf) -> Result<HashMap<String, WorkspaceEntry>, String> {
@@ -20,7 +23,16 @@ pub(crate) fn write_workspaces(path: &PathBuf, entries: &[WorkspaceEntry]) -> Re
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
     let data = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+    // Write beside the target and rename so a crash never leaves a torn file.
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        e.to_string()
+    })?;
+    // Every workspace edit is persisted here, so refresh subprocess envs too.
+    sync_workspace_envs(entries);
+    Ok(())
 }
 
 pub(crate) fn read_settings(path: &PathBuf) -> Result<AppSettings, String> {
@@ -28,7 +40,26 @@ pub(crate) fn read_settings(path: &PathBuf) -> Result<AppSettings, String> {
         return Ok(AppSettings::default());
     }
     let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&data).map_err(|e| e.to_string())
+    let mut value: Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    migrate_settings_value(&mut value);
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// Rewrites settings keys from older releases into their current shape.
+fn migrate_settings_value(value: &mut Value) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    if let Some(legacy) = object.remove("gitDiffIgnoreWhitespaceChanges") {
+        if !object.contains_key("gitDiffWhitespace") {
+            let mode = if legacy.as_bool().unwrap_or(false) {
+                "ignoreChange"
+            } else {
+                "none"
+            };
+            object.insert("gitDiffWhitespace".to_string(), Value::String(mode.to_string()));
+        }
+    }
 }
 
 pub(crate) fn write_settings(path: &PathBuf, settings: &AppSettings) -> Result<(), String> {
@@ -41,10 +72,31 @@ pub(crate) fn write_settings(path: &PathBuf, settings: &AppSettings) -> Result<(
 
 #[cfg(test)]
 mod tests {
-    use super::{read_workspaces, write_workspaces};
-    use crate::types::{WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
+    use super::{read_settings, read_workspaces, write_workspaces};
+    use crate::types::{DiffWhitespace, WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
     use uuid::Uuid;
 
+    #[test]
+    fn read_settings_migrates_legacy_whitespace_toggle() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).expect("create temp dir");
+        let path = temp_dir.join("settings.json");
+
+        std::fs::write(&path, r#"{"gitDiffIgnoreWhitespaceChanges": true}"#)
+            .expect("write settings");
+        let settings = read_settings(&path).expect("read settings");
+        assert_eq!(settings.git_diff_whitespace, DiffWhitespace::IgnoreChange);
+
+        std::fs::write(
+            &path,
+            r#"{"gitDiffIgnoreWhitespaceChanges": true, "gitDiffWhitespace": "ignoreAll"}"#,
+        )
+        .expect("write settings");
+        let settings = read_settings(&path).expect("read settings");
+        assert_eq!(settings.git_diff_whitespace, DiffWhitespace::IgnoreAll);
+    }
+
     #[test]
     fn write_read_workspaces_persists_sort_and_group() {
         let temp_dir =
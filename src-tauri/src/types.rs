@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -6,6 +8,106 @@ pub(crate) struct GitFileStatus {
     pub(crate) status: String,
     pub(crate) additions: i64,
     pub(crate) deletions: i64,
+    #[serde(default, rename = "isDir")]
+    pub(crate) is_dir: bool,
+    /// Set when the file is at least `largeFileWarnBytes`; `size` is only
+    /// reported alongside it.
+    #[serde(default, rename = "isLarge", skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) is_large: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) size: Option<u64>,
+}
+
+/// Where `open_path` opens a path.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum OpenMode {
+    Editor,
+    Terminal,
+    FileManager,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BisectResult {
+    Good,
+    Bad,
+    Skip,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum RepoOperation {
+    None,
+    Merge,
+    Rebase {
+        step: Option<usize>,
+        total: Option<usize>,
+    },
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ConfigScope {
+    Local,
+    Global,
+}
+
+/// How untracked files are listed in git status. `Normal` and `Folders` both
+/// collapse untracked directories into a single entry (like `git status
+/// -unormal`); `All` lists every file inside them.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GitUntrackedMode {
+    #[default]
+    All,
+    Normal,
+    Folders,
+}
+
+/// Which implementation serves read operations that exist both on libgit2
+/// and on the system `git` (status, log, branch list). `Auto` keeps the
+/// built-in choice, which is libgit2 for all of them today.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum GitBackend {
+    Libgit2,
+    SystemGit,
+    #[default]
+    Auto,
+}
+
+impl GitBackend {
+    pub(crate) fn uses_system_git(self) -> bool {
+        matches!(self, GitBackend::SystemGit)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DiffWhitespace {
+    #[default]
+    None,
+    IgnoreEol,
+    IgnoreChange,
+    IgnoreAll,
+}
+
+/// Size and change kind for binary (non-image) deltas, which have no
+/// displayable patch.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct BinaryDiffSummary {
+    pub(crate) change: String,
+    #[serde(rename = "oldSize")]
+    pub(crate) old_size: Option<u64>,
+    #[serde(rename = "newSize")]
+    pub(crate) new_size: Option<u64>,
+    /// Hex of the leading bytes of the new (or, when deleted, old) content.
+    #[serde(rename = "hexPreview")]
+    pub(crate) hex_preview: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,6 +130,52 @@ pub(crate) struct GitFileDiff {
     pub(crate) old_image_mime: Option<String>,
     #[serde(rename = "newImageMime")]
     pub(crate) new_image_mime: Option<String>,
+    #[serde(
+        default,
+        rename = "binarySummary",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) binary_summary: Option<BinaryDiffSummary>,
+    /// Detected text encoding; anything other than utf-8 is worth flagging.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) encoding: Option<String>,
+    /// `lf`, `crlf`, `mixed`, or `none`, as found on disk before normalizing.
+    #[serde(default, rename = "lineEnding", skip_serializing_if = "Option::is_none")]
+    pub(crate) line_ending: Option<String>,
+    /// For submodule pointer changes: commits between the old and new
+    /// submodule shas, newest first and capped.
+    #[serde(
+        default,
+        rename = "submoduleCommits",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) submodule_commits: Option<Vec<GitLogEntry>>,
+    /// Added lines that break the repo's `core.whitespace` rules. Only filled
+    /// when the diff is requested with a whitespace check.
+    #[serde(
+        default,
+        rename = "whitespaceErrors",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) whitespace_errors: Option<Vec<DiffWhitespaceError>>,
+}
+
+/// One diff of a `stream_git_diffs` run, as the daemon sends it to the client
+/// that started the stream.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitDiffStreamEvent {
+    pub(crate) stream_id: String,
+    pub(crate) diff: GitFileDiff,
+}
+
+/// Whitespace problems on one added line, named as in `core.whitespace`
+/// (e.g. `blank-at-eol`, `space-before-tab`).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct DiffWhitespaceError {
+    /// 1-based line number in the new file.
+    pub(crate) line: u32,
+    pub(crate) kinds: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -51,6 +199,20 @@ pub(crate) struct GitCommitDiff {
     pub(crate) old_image_mime: Option<String>,
     #[serde(rename = "newImageMime")]
     pub(crate) new_image_mime: Option<String>,
+    #[serde(
+        default,
+        rename = "binarySummary",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) binary_summary: Option<BinaryDiffSummary>,
+    /// For submodule pointer changes: commits between the old and new
+    /// submodule shas, newest first and capped.
+    #[serde(
+        default,
+        rename = "submoduleCommits",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) submodule_commits: Option<Vec<GitLogEntry>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -59,11 +221,85 @@ pub(crate) struct GitLogEntry {
     pub(crate) summary: String,
     pub(crate) author: String,
     pub(crate) timestamp: i64,
+    /// Committer's UTC offset in minutes, so the UI can show their local time.
+    #[serde(default, rename = "timezoneOffset")]
+    pub(crate) timezone_offset: i32,
+    /// Detail fields, only filled when the log is requested with `detail`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) body: Option<String>,
+    #[serde(default, rename = "filesChanged", skip_serializing_if = "Option::is_none")]
+    pub(crate) files_changed: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) additions: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) deletions: Option<usize>,
+    /// Only filled when the log is requested with `verify`.
+    #[serde(default, rename = "signatureStatus", skip_serializing_if = "Option::is_none")]
+    pub(crate) signature_status: Option<GitSignatureStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) signer: Option<String>,
+    /// Whether the commit has a note on the default notes ref. Omitted when
+    /// the repository has no notes at all.
+    #[serde(default, rename = "hasNotes", skip_serializing_if = "Option::is_none")]
+    pub(crate) has_notes: Option<bool>,
+}
+
+/// Outcome of `git`'s signature check (`%G?`) for a commit.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GitSignatureStatus {
+    /// Valid signature from a trusted key (`G`).
+    Good,
+    /// Valid signature, but the key's trust is unknown or undefined (`U`).
+    Untrusted,
+    /// Bad signature or revoked key (`B`, `R`).
+    Bad,
+    /// Signed, but it could not be checked: missing or expired key (`E`, `X`, `Y`).
+    Unknown,
+    /// Not signed.
+    None,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct RemoteFetchResult {
+    pub(crate) remote: String,
+    pub(crate) ok: bool,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum GitUndoKind {
+    /// `git reset --hard` back to the recorded HEAD.
+    ResetHead,
+    /// Check the recorded branch (or detached HEAD) back out.
+    Checkout,
+    /// Pop the stash taken before discarding changes.
+    StashPop,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitUndoEntry {
+    pub(crate) action: String,
+    pub(crate) kind: GitUndoKind,
+    pub(crate) head: String,
+    pub(crate) branch: Option<String>,
+    pub(crate) stash: Option<String>,
+    /// HEAD once the action finished; a hard-reset undo only runs from here.
+    #[serde(default)]
+    pub(crate) after: Option<String>,
+    pub(crate) recorded_at: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitLogResponse {
-    pub(crate) total: usize,
+    /// Commits reachable from HEAD. `None` for pathspec-filtered logs, where
+    /// counting would mean tree-diffing the whole history; use `has_more`.
+    #[serde(default)]
+    pub(crate) total: Option<usize>,
+    /// Whether commits beyond `entries` match.
+    #[serde(default, rename = "hasMore")]
+    pub(crate) has_more: bool,
     pub(crate) entries: Vec<GitLogEntry>,
     #[serde(default)]
     pub(crate) ahead: usize,
@@ -75,6 +311,8 @@ pub(crate) struct GitLogResponse {
     pub(crate) behind_entries: Vec<GitLogEntry>,
     #[serde(default)]
     pub(crate) upstream: Option<String>,
+    #[serde(default)]
+    pub(crate) shallow: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -115,6 +353,34 @@ pub(crate) struct GitHubPullRequest {
     pub(crate) is_draft: bool,
     #[serde(default)]
     pub(crate) author: Option<GitHubPullRequestAuthor>,
+    /// Only populated by `get_github_pull_request`; the list omits these to
+    /// keep `gh pr list` cheap.
+    #[serde(default)]
+    pub(crate) mergeable: Option<String>,
+    #[serde(default, rename = "reviewDecision")]
+    pub(crate) review_decision: Option<String>,
+    #[serde(default, rename = "statusCheckRollup")]
+    pub(crate) status_check_rollup: Vec<GitHubStatusCheck>,
+}
+
+/// One entry of `statusCheckRollup`: a check run (`name`, `status`,
+/// `conclusion`) or a legacy commit status (`context`, `state`).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHubStatusCheck {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    #[serde(default)]
+    pub(crate) context: Option<String>,
+    #[serde(default)]
+    pub(crate) status: Option<String>,
+    #[serde(default)]
+    pub(crate) conclusion: Option<String>,
+    #[serde(default)]
+    pub(crate) state: Option<String>,
+    #[serde(default, rename = "detailsUrl")]
+    pub(crate) details_url: Option<String>,
+    #[serde(default, rename = "targetUrl")]
+    pub(crate) target_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -129,6 +395,8 @@ pub(crate) struct GitHubPullRequestDiff {
     pub(crate) path: String,
     pub(crate) status: String,
     pub(crate) diff: String,
+    #[serde(default, rename = "isBinary")]
+    pub(crate) is_binary: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -187,10 +455,232 @@ pub(crate) struct LocalUsageSnapshot {
     pub(crate) top_models: Vec<LocalUsageModel>,
 }
 
+/// One side of a conflicted file. `lines` is `None` when the side is absent
+/// (`present: false`), binary, or over the text size limit.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitConflictVersion {
+    pub(crate) present: bool,
+    pub(crate) lines: Option<Vec<String>>,
+    pub(crate) is_binary: bool,
+    pub(crate) too_large: bool,
+}
+
+/// Index stages 1/2/3 of a conflicted path.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitConflictVersions {
+    pub(crate) path: String,
+    pub(crate) base: GitConflictVersion,
+    pub(crate) ours: GitConflictVersion,
+    pub(crate) theirs: GitConflictVersion,
+}
+
+/// A repo's default branch. `inferred` is false only when it came from
+/// `origin/HEAD`; otherwise it was guessed from which of `main`/`master` exist.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DefaultBranch {
+    pub(crate) name: String,
+    pub(crate) inferred: bool,
+}
+
+/// What `repair_repo` found about `.git/index.lock`. `note` explains why a
+/// lock that was found was left in place.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IndexLockReport {
+    pub(crate) found: bool,
+    pub(crate) age_secs: Option<u64>,
+    pub(crate) removed: bool,
+    pub(crate) note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RepoRepairReport {
+    pub(crate) index_lock: IndexLockReport,
+    pub(crate) fsck_ok: bool,
+    pub(crate) fsck_output: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GcReport {
+    pub(crate) git_dir_size_before: u64,
+    pub(crate) git_dir_size_after: u64,
+}
+
+/// Outcome of fetching every pull request head as a remote-tracking ref.
+/// `supported` is false when the remote exposes no refs matching the refspec.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PullRequestRefsFetch {
+    pub(crate) remote: String,
+    pub(crate) refspec: String,
+    pub(crate) supported: bool,
+    pub(crate) created: Vec<String>,
+    pub(crate) updated: Vec<String>,
+    pub(crate) total: usize,
+}
+
+/// Clean/dirty check for the working tree. Unless full counts were requested
+/// the scan stops at the first change, so the counts only cover that change.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkingTreeCleanliness {
+    pub(crate) clean: bool,
+    pub(crate) staged: usize,
+    pub(crate) unstaged: usize,
+    pub(crate) untracked: usize,
+    pub(crate) conflicted: usize,
+}
+
+/// Per-category change counts for a workspace; a file staged and then edited
+/// again counts once in `changed_files` but in both `staged` and `unstaged`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitChangeSummary {
+    pub(crate) changed_files: usize,
+    pub(crate) staged: usize,
+    pub(crate) unstaged: usize,
+    pub(crate) conflicted: usize,
+}
+
+/// A shareable settings profile plus the settings keys left out of it
+/// (secrets, unless explicitly included).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SettingsProfileExport {
+    pub(crate) profile: serde_json::Value,
+    pub(crate) omitted: Vec<String>,
+}
+
+/// A boolean entry in config.toml's `[features]` table.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct FeatureFlag {
+    pub(crate) key: String,
+    pub(crate) enabled: bool,
+}
+
+/// Settings after re-reading config.toml, plus the configured auth store
+/// (which is not part of `AppSettings`).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CodexConfigReload {
+    pub(crate) settings: AppSettings,
+    pub(crate) auth_store: Option<String>,
+    /// Whether any in-memory setting differed from config.toml.
+    pub(crate) changed: bool,
+}
+
+/// Outcome of a commit that succeeded but may deserve a second look, e.g. an
+/// overlong subject line.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CommitResult {
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Which steps of a commit-and-push ran. When a step fails, `failed_step`
+/// names it and the earlier steps stay done (a rejected push leaves the
+/// commit in place).
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CommitAndPushResult {
+    pub(crate) staged: bool,
+    pub(crate) committed: bool,
+    pub(crate) pushed: bool,
+    pub(crate) commit_sha: Option<String>,
+    pub(crate) failed_step: Option<String>,
+    pub(crate) error: Option<String>,
+}
+
+/// Which ignore rule matched a path. A matching negated (`!`) pattern is
+/// reported with `ignored: false`; no match leaves the rule fields empty.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IgnoreExplanation {
+    pub(crate) ignored: bool,
+    pub(crate) source: Option<String>,
+    pub(crate) line: Option<u32>,
+    pub(crate) pattern: Option<String>,
+}
+
+/// Read-only overview for the repo info panel. Sizes are in bytes;
+/// `worktree_size` only counts tracked files.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RepoStats {
+    pub(crate) commit_count: usize,
+    pub(crate) branch_count: usize,
+    pub(crate) remote_branch_count: usize,
+    pub(crate) tag_count: usize,
+    pub(crate) tracked_files: usize,
+    pub(crate) worktree_size: u64,
+    pub(crate) git_dir_size: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct BranchInfo {
     pub(crate) name: String,
     pub(crate) last_commit: i64,
+    /// UTC offset in minutes of `last_commit`.
+    #[serde(default)]
+    pub(crate) last_commit_offset: i32,
+}
+
+/// What kind of name a resolved ref was given as. Anything that isn't a
+/// branch, tag or remote-tracking ref (a sha, `HEAD~2`, ...) is `Commit`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RefType {
+    Branch,
+    Tag,
+    Commit,
+    Remote,
+}
+
+/// A ref peeled to its commit; annotated tags resolve to their target.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolvedRef {
+    pub(crate) sha: String,
+    pub(crate) short_sha: String,
+    pub(crate) ref_type: RefType,
+    pub(crate) commit: GitLogEntry,
+}
+
+/// A branch for the recent-branches switcher. `last_used` is when it was last
+/// checked out, or its last commit time when the reflog has nothing.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecentBranch {
+    pub(crate) name: String,
+    pub(crate) last_used: i64,
+}
+
+/// HEAD compared with an arbitrary ref; the entry lists are capped by the
+/// request's limit, the counts are not.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitAheadBehind {
+    pub(crate) other_ref: String,
+    pub(crate) other_sha: String,
+    pub(crate) ahead: usize,
+    pub(crate) behind: usize,
+    pub(crate) ahead_entries: Vec<GitLogEntry>,
+    pub(crate) behind_entries: Vec<GitLogEntry>,
+}
+
+/// Result of checking out a commit with a detached HEAD. `previous_branch`
+/// is the branch that was left, so the UI can point the way back.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CheckoutCommitResult {
+    pub(crate) detached_head: bool,
+    pub(crate) head_sha: String,
+    pub(crate) summary: String,
+    pub(crate) previous_branch: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -258,9 +748,14 @@ pub(crate) struct WorkspaceGroup {
     pub(crate) sort_order: Option<u32>,
     #[serde(default, rename = "copiesFolder")]
     pub(crate) copies_folder: Option<String>,
+    /// Hex color such as `#3b82f6`.
+    #[serde(default)]
+    pub(crate) color: Option<String>,
+    #[serde(default)]
+    pub(crate) collapsed: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct WorkspaceSettings {
     #[serde(default, rename = "sidebarCollapsed")]
     pub(crate) sidebar_collapsed: bool,
@@ -280,6 +775,63 @@ pub(crate) struct WorkspaceSettings {
     pub(crate) launch_scripts: Option<Vec<LaunchScriptEntry>>,
     #[serde(default, rename = "worktreeSetupScript")]
     pub(crate) worktree_setup_script: Option<String>,
+    #[serde(default, rename = "diffExcludeGlobs")]
+    pub(crate) diff_exclude_globs: Vec<String>,
+    #[serde(default = "default_protected_branches", rename = "protectedBranches")]
+    pub(crate) protected_branches: Vec<String>,
+    #[serde(default, rename = "autoFetchIntervalSecs")]
+    pub(crate) auto_fetch_interval_secs: Option<u64>,
+    /// Extra environment for git/gh/codex subprocesses run in this workspace.
+    /// Values may be tokens, so they are never logged and are masked with
+    /// [`REDACTED_ENV_VALUE`] whenever settings leave the backend.
+    #[serde(default)]
+    pub(crate) env: HashMap<String, String>,
+}
+
+/// Stand-in for a workspace env value in listings sent to clients.
+pub(crate) const REDACTED_ENV_VALUE: &str = "********";
+
+impl WorkspaceSettings {
+    /// A copy with every `env` value masked, for listings sent to clients.
+    pub(crate) fn redacted(&self) -> Self {
+        let mut settings = self.clone();
+        for value in settings.env.values_mut() {
+            *value = REDACTED_ENV_VALUE.to_string();
+        }
+        settings
+    }
+
+    /// Puts back stored `env` values a client echoed as masked, so saving
+    /// settings read from a listing doesn't overwrite the real secrets.
+    pub(crate) fn restore_redacted_env(&mut self, stored: &WorkspaceSettings) {
+        for (key, value) in self.env.iter_mut() {
+            if value == REDACTED_ENV_VALUE {
+                if let Some(stored_value) = stored.env.get(key) {
+                    *value = stored_value.clone();
+                }
+            }
+        }
+    }
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            sidebar_collapsed: false,
+            sort_order: None,
+            group_id: None,
+            git_root: None,
+            codex_home: None,
+            codex_args: None,
+            launch_script: None,
+            launch_scripts: None,
+            worktree_setup_script: None,
+            diff_exclude_globs: Vec::new(),
+            protected_branches: default_protected_branches(),
+            auto_fetch_interval_secs: None,
+            env: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -343,6 +895,28 @@ pub(crate) struct AppSettings {
     pub(crate) remote_backend_host: String,
     #[serde(default, rename = "remoteBackendToken")]
     pub(crate) remote_backend_token: Option<String>,
+    /// How `remoteBackendHost` is reached: `host:port` over TCP, or a socket
+    /// path for `unix`.
+    #[serde(default, rename = "remoteBackendProvider")]
+    pub(crate) remote_backend_provider: RemoteBackendProvider,
+    /// Seconds between keepalive pings to the remote backend; 0 disables them.
+    #[serde(
+        default = "default_remote_keepalive_interval_secs",
+        rename = "remoteKeepaliveIntervalSecs"
+    )]
+    pub(crate) remote_keepalive_interval_secs: u64,
+    /// How long a ping may go unanswered before it counts as missed.
+    #[serde(
+        default = "default_remote_keepalive_timeout_secs",
+        rename = "remoteKeepaliveTimeoutSecs"
+    )]
+    pub(crate) remote_keepalive_timeout_secs: u64,
+    /// Consecutive missed pings after which the connection is dropped.
+    #[serde(
+        default = "default_remote_keepalive_max_missed",
+        rename = "remoteKeepaliveMaxMissed"
+    )]
+    pub(crate) remote_keepalive_max_missed: u32,
     #[serde(default = "default_access_mode", rename = "defaultAccessMode")]
     pub(crate) default_access_mode: String,
     #[serde(default = "default_review_delivery_mode", rename = "reviewDeliveryMode")]
@@ -451,11 +1025,11 @@ pub(crate) struct AppSettings {
     pub(crate) notification_sounds_enabled: bool,
     #[serde(default = "default_preload_git_diffs", rename = "preloadGitDiffs")]
     pub(crate) preload_git_diffs: bool,
-    #[serde(
-        default = "default_git_diff_ignore_whitespace_changes",
-        rename = "gitDiffIgnoreWhitespaceChanges"
-    )]
-    pub(crate) git_diff_ignore_whitespace_changes: bool,
+    #[serde(default, rename = "gitDiffWhitespace")]
+    pub(crate) git_diff_whitespace: DiffWhitespace,
+    /// Show CRLF line endings as LF in working tree diffs.
+    #[serde(default, rename = "gitDiffNormalizeLineEndings")]
+    pub(crate) git_diff_normalize_line_endings: bool,
     #[serde(
         default = "default_system_notifications_enabled",
         rename = "systemNotificationsEnabled"
@@ -534,6 +1108,45 @@ pub(crate) struct AppSettings {
     pub(crate) open_app_targets: Vec<OpenAppTarget>,
     #[serde(default = "default_selected_open_app_id", rename = "selectedOpenAppId")]
     pub(crate) selected_open_app_id: String,
+    /// Editor used by `open_path`; falls back to `$VISUAL` / `$EDITOR`.
+    #[serde(default, rename = "editorCommand")]
+    pub(crate) editor_command: Option<String>,
+    #[serde(default = "default_gh_max_attempts", rename = "ghMaxAttempts")]
+    pub(crate) gh_max_attempts: u32,
+    /// Flag new or modified files at least this large in git status. Off when
+    /// unset.
+    #[serde(default, rename = "largeFileWarnBytes")]
+    pub(crate) large_file_warn_bytes: Option<u64>,
+    #[serde(default, rename = "gitBackend")]
+    pub(crate) git_backend: GitBackend,
+    /// Commit subjects longer than this get a warning (never a rejection).
+    /// 0 turns the check off.
+    #[serde(default = "default_commit_subject_max_len", rename = "commitSubjectMaxLen")]
+    pub(crate) commit_subject_max_len: u32,
+    /// Upper bound on git subprocesses and blocking git jobs running at once
+    /// across all workspaces. Applied immediately when settings change.
+    #[serde(default = "default_max_concurrent_git_ops", rename = "maxConcurrentGitOps")]
+    pub(crate) max_concurrent_git_ops: u32,
+    /// Unchanged lines shown around each hunk in working tree, commit, stash
+    /// and ref comparison diffs.
+    #[serde(default = "default_diff_context_lines", rename = "diffContextLines")]
+    pub(crate) diff_context_lines: u32,
+    /// config.toml writes that failed during the last update. Only populated
+    /// on the settings returned from an update; never persisted.
+    #[serde(
+        default,
+        rename = "configWriteErrors",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub(crate) config_write_errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RemoteBackendProvider {
+    #[default]
+    Tcp,
+    Unix,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -549,6 +1162,10 @@ impl Default for BackendMode {
     }
 }
 
+fn default_protected_branches() -> Vec<String> {
+    vec!["main".to_string(), "master".to_string()]
+}
+
 fn default_access_mode() -> String {
     "current".to_string()
 }
@@ -561,6 +1178,18 @@ fn default_remote_backend_host() -> String {
     "127.0.0.1:4732".to_string()
 }
 
+fn default_remote_keepalive_interval_secs() -> u64 {
+    15
+}
+
+fn default_remote_keepalive_timeout_secs() -> u64 {
+    10
+}
+
+fn default_remote_keepalive_max_missed() -> u32 {
+    3
+}
+
 fn default_ui_scale() -> f64 {
     1.0
 }
@@ -671,10 +1300,6 @@ fn default_preload_git_diffs() -> bool {
     true
 }
 
-fn default_git_diff_ignore_whitespace_changes() -> bool {
-    false
-}
-
 fn default_experimental_collab_enabled() -> bool {
     false
 }
@@ -804,6 +1429,22 @@ fn default_open_app_targets() -> Vec<OpenAppTarget> {
     ]
 }
 
+fn default_gh_max_attempts() -> u32 {
+    3
+}
+
+fn default_commit_subject_max_len() -> u32 {
+    72
+}
+
+fn default_max_concurrent_git_ops() -> u32 {
+    8
+}
+
+fn default_diff_context_lines() -> u32 {
+    3
+}
+
 fn default_selected_open_app_id() -> String {
     "vscode".to_string()
 }
@@ -818,6 +1459,10 @@ impl Default for AppSettings {
             backend_mode: BackendMode::Local,
             remote_backend_host: default_remote_backend_host(),
             remote_backend_token: None,
+            remote_backend_provider: RemoteBackendProvider::default(),
+            remote_keepalive_interval_secs: default_remote_keepalive_interval_secs(),
+            remote_keepalive_timeout_secs: default_remote_keepalive_timeout_secs(),
+            remote_keepalive_max_missed: default_remote_keepalive_max_missed(),
             default_access_mode: "current".to_string(),
             review_delivery_mode: default_review_delivery_mode(),
             composer_model_shortcut: default_composer_model_shortcut(),
@@ -849,7 +1494,8 @@ impl Default for AppSettings {
             notification_sounds_enabled: true,
             system_notifications_enabled: true,
             preload_git_diffs: default_preload_git_diffs(),
-            git_diff_ignore_whitespace_changes: default_git_diff_ignore_whitespace_changes(),
+            git_diff_whitespace: DiffWhitespace::default(),
+            git_diff_normalize_line_endings: false,
             experimental_collab_enabled: false,
             collaboration_modes_enabled: true,
             steer_enabled: true,
@@ -872,6 +1518,14 @@ impl Default for AppSettings {
             workspace_groups: default_workspace_groups(),
             open_app_targets: default_open_app_targets(),
             selected_open_app_id: default_selected_open_app_id(),
+            editor_command: None,
+            gh_max_attempts: default_gh_max_attempts(),
+            large_file_warn_bytes: None,
+            git_backend: GitBackend::default(),
+            commit_subject_max_len: default_commit_subject_max_len(),
+            max_concurrent_git_ops: default_max_concurrent_git_ops(),
+            diff_context_lines: default_diff_context_lines(),
+            config_write_errors: Vec::new(),
         }
     }
 }
@@ -879,8 +1533,42 @@ impl Default for AppSettings {
 #[cfg(test)]
 mod tests {
     use super::{
-        AppSettings, BackendMode, WorkspaceEntry, WorkspaceGroup, WorkspaceKind, WorkspaceSettings,
+        AppSettings, BackendMode, DiffWhitespace, GitHubPullRequest, RemoteBackendProvider,
+        WorkspaceEntry, WorkspaceGroup, WorkspaceKind, WorkspaceSettings, REDACTED_ENV_VALUE,
     };
+    use std::collections::HashMap;
+
+    #[test]
+    fn github_pull_request_reads_view_only_fields() {
+        let base = serde_json::json!({
+            "number": 7,
+            "title": "Fix",
+            "url": "https://github.com/o/r/pull/7",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "body": "",
+            "headRefName": "fix",
+            "baseRefName": "main",
+            "isDraft": false,
+        });
+        let listed: GitHubPullRequest =
+            serde_json::from_value(base.clone()).expect("list entry deserialize");
+        assert!(listed.mergeable.is_none());
+        assert!(listed.status_check_rollup.is_empty());
+
+        let mut viewed = base;
+        viewed["mergeable"] = "MERGEABLE".into();
+        viewed["reviewDecision"] = serde_json::Value::Null;
+        viewed["statusCheckRollup"] = serde_json::json!([
+            { "__typename": "CheckRun", "name": "ci", "status": "COMPLETED", "conclusion": "SUCCESS" },
+            { "__typename": "StatusContext", "context": "deploy", "state": "PENDING" },
+        ]);
+        let viewed: GitHubPullRequest = serde_json::from_value(viewed).expect("view deserialize");
+        assert_eq!(viewed.mergeable.as_deref(), Some("MERGEABLE"));
+        assert!(viewed.review_decision.is_none());
+        assert_eq!(viewed.status_check_rollup.len(), 2);
+        assert_eq!(viewed.status_check_rollup[1].state.as_deref(), Some("PENDING"));
+    }
 
     #[test]
     fn app_settings_defaults_from_empty_json() {
@@ -891,6 +1579,13 @@ mod tests {
         assert!(matches!(settings.backend_mode, BackendMode::Local));
         assert_eq!(settings.remote_backend_host, "127.0.0.1:4732");
         assert!(settings.remote_backend_token.is_none());
+        assert_eq!(settings.remote_backend_provider, RemoteBackendProvider::Tcp);
+        assert_eq!(settings.remote_keepalive_interval_secs, 15);
+        assert_eq!(settings.remote_keepalive_timeout_secs, 10);
+        assert_eq!(settings.remote_keepalive_max_missed, 3);
+        assert_eq!(settings.commit_subject_max_len, 72);
+        assert_eq!(settings.max_concurrent_git_ops, 8);
+        assert_eq!(settings.diff_context_lines, 3);
         assert_eq!(settings.default_access_mode, "current");
         assert_eq!(settings.review_delivery_mode, "inline");
         assert_eq!(
@@ -955,7 +1650,7 @@ mod tests {
         assert!(settings.notification_sounds_enabled);
         assert!(settings.system_notifications_enabled);
         assert!(settings.preload_git_diffs);
-        assert!(!settings.git_diff_ignore_whitespace_changes);
+        assert_eq!(settings.git_diff_whitespace, DiffWhitespace::None);
         assert!(settings.collaboration_modes_enabled);
         assert!(settings.steer_enabled);
         assert!(settings.unified_exec_enabled);
@@ -996,6 +1691,8 @@ mod tests {
             name: "Group".to_string(),
             sort_order: Some(2),
             copies_folder: Some("/tmp/group-copies".to_string()),
+            color: None,
+            collapsed: false,
         }];
 
         let json = serde_json::to_string(&settings).expect("serialize settings");
@@ -1027,5 +1724,25 @@ mod tests {
         assert!(settings.sort_order.is_none());
         assert!(settings.group_id.is_none());
         assert!(settings.git_root.is_none());
+        assert!(settings.env.is_empty());
+    }
+
+    #[test]
+    fn workspace_settings_env_is_redacted_and_restored() {
+        let stored = WorkspaceSettings {
+            env: HashMap::from([
+                ("GH_TOKEN".to_string(), "secret".to_string()),
+                ("MODE".to_string(), "ci".to_string()),
+            ]),
+            ..WorkspaceSettings::default()
+        };
+        let mut echoed = stored.redacted();
+        assert_eq!(echoed.env["GH_TOKEN"], REDACTED_ENV_VALUE);
+        assert_eq!(stored.env["GH_TOKEN"], "secret");
+
+        echoed.env.insert("MODE".to_string(), "local".to_string());
+        echoed.restore_redacted_env(&stored);
+        assert_eq!(echoed.env["GH_TOKEN"], "secret");
+        assert_eq!(echoed.env["MODE"], "local");
     }
 }
@@ -1,17 +1,31 @@
 use std::env;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[allow(dead_code)]
 pub(crate) fn normalize_git_path(path: &str) -> String {
     path.replace('\\', "/")
 }
 
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
 fn find_in_path(binary: &str) -> Option<PathBuf> {
     let path_var = env::var_os("PATH")?;
     for dir in env::split_paths(&path_var) {
         let candidate = dir.join(binary);
-        if candidate.is_file() {
+        if is_executable(&candidate) {
             return Some(candidate);
         }
     }
@@ -45,7 +59,7 @@ pub(crate) fn resolve_git_binary() -> Result<PathBuf, String> {
 
     for candidate in candidates {
         let path = PathBuf::from(candidate);
-        if path.exists() {
+        if is_executable(&path) {
             return Ok(path);
         }
     }
@@ -87,10 +101,27 @@ pub(crate) fn git_env_path() -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::normalize_git_path;
+    use super::{is_executable, normalize_git_path};
 
     #[test]
     fn normalize_git_path_replaces_backslashes() {
         assert_eq!(normalize_git_path("foo\\bar\\baz"), "foo/bar/baz");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_executable_requires_exec_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("codex-monitor-exec-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let file = dir.join("tool");
+        std::fs::write(&file, "#!/bin/sh\n").expect("write file");
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o644)).expect("chmod");
+        assert!(!is_executable(&file));
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        assert!(is_executable(&file));
+        assert!(!is_executable(&dir));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
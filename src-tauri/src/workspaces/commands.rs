@@ -32,8 +32,10 @@ use crate::shared::workspaces_core;
 use crate::state::AppState;
 use crate::storage::write_workspaces;
 use crate::types::{
-    WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeSetupStatus,
+    OpenMode, WorkspaceEntry, WorkspaceGroup, WorkspaceInfo, WorkspaceKind, WorkspaceSettings,
+    WorktreeSetupStatus,
 };
+use crate::shared::process_core::workspace_env_for;
 use crate::utils::{git_env_path, resolve_git_binary};
 
 fn spawn_with_app(
@@ -293,7 +295,7 @@ pub(crate) async fn add_clone(
         kind: entry.kind,
         parent_id: entry.parent_id,
         worktree: entry.worktree,
-        settings: entry.settings,
+        settings: entry.settings.redacted(),
     })
 }
 
@@ -664,6 +666,7 @@ pub(crate) async fn apply_worktree_changes(
     let mut child = tokio_command(git_bin)
         .args(["apply", "--3way", "--whitespace=nowarn", "-"])
         .current_dir(&parent_root)
+        .envs(workspace_env_for(&parent_root))
         .env("PATH", git_env_path())
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
@@ -715,6 +718,148 @@ pub(crate) async fn apply_worktree_changes(
 }
 
 
+#[tauri::command]
+pub(crate) async fn reorder_workspaces(
+    ordered_ids: Vec<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<WorkspaceInfo>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "reorder_workspaces",
+            json!({ "orderedIds": ordered_ids }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    workspaces_core::reorder_workspaces_core(
+        ordered_ids,
+        &state.workspaces,
+        &state.sessions,
+        &state.storage_path,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_workspace_group(
+    name: String,
+    color: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceGroup, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "create_workspace_group",
+            json!({ "name": name, "color": color }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    workspaces_core::create_group_core(name, color, &state.app_settings, &state.settings_path).await
+}
+
+#[tauri::command]
+pub(crate) async fn rename_workspace_group(
+    group_id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceGroup, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "rename_workspace_group",
+            json!({ "groupId": group_id, "name": name }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    workspaces_core::rename_group_core(group_id, name, &state.app_settings, &state.settings_path).await
+}
+
+#[tauri::command]
+pub(crate) async fn set_workspace_group_color(
+    group_id: String,
+    color: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceGroup, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "set_workspace_group_color",
+            json!({ "groupId": group_id, "color": color }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    workspaces_core::set_group_color_core(group_id, color, &state.app_settings, &state.settings_path).await
+}
+
+#[tauri::command]
+pub(crate) async fn set_workspace_group_collapsed(
+    group_id: String,
+    collapsed: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceGroup, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "set_workspace_group_collapsed",
+            json!({ "groupId": group_id, "collapsed": collapsed }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    workspaces_core::set_group_collapsed_core(
+        group_id,
+        collapsed,
+        &state.app_settings,
+        &state.settings_path,
+    ).await
+}
+
+#[tauri::command]
+pub(crate) async fn delete_workspace_group(
+    group_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "delete_workspace_group",
+            json!({ "groupId": group_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    workspaces_core::delete_group_core(
+        group_id,
+        &state.workspaces,
+        &state.app_settings,
+        &state.storage_path,
+        &state.settings_path,
+    )
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn update_workspace_settings(
     id: String,
@@ -829,6 +974,26 @@ pub(crate) async fn list_workspace_files(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn discover_workspaces(
+    root: String,
+    depth: Option<usize>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<WorkspaceEntry>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "discover_workspaces",
+            json!({ "root": root, "depth": depth }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    workspaces_core::discover_workspaces_core(&state.workspaces, root, depth).await
+}
 
 #[tauri::command]
 pub(crate) async fn open_workspace_in(
@@ -873,6 +1038,121 @@ pub(crate) async fn open_workspace_in(
     ))
 }
 
+/// Resolves `path` against the workspace git root and rejects anything that
+/// escapes it (`..`, absolute paths elsewhere, symlinks pointing outside).
+pub(super) fn resolve_path_within_root(root: &std::path::Path, path: &str) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("Failed to resolve workspace root: {err}"))?;
+    let trimmed = path.trim();
+    let candidate = if trimmed.is_empty() {
+        root.clone()
+    } else {
+        root.join(trimmed)
+    };
+    let resolved = candidate
+        .canonicalize()
+        .map_err(|err| format!("Failed to resolve `{trimmed}`: {err}"))?;
+    if !resolved.starts_with(&root) {
+        return Err("Path escapes the workspace root.".to_string());
+    }
+    Ok(resolved)
+}
+
+fn editor_command_parts(configured: Option<&str>) -> Result<Vec<String>, String> {
+    let command = configured
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or_else(|| std::env::var("VISUAL").ok().filter(|value| !value.trim().is_empty()))
+        .or_else(|| std::env::var("EDITOR").ok().filter(|value| !value.trim().is_empty()))
+        .ok_or_else(|| "No editor configured. Set an editor command or $EDITOR.".to_string())?;
+    let parts = shell_words::split(&command).map_err(|err| format!("Invalid editor command: {err}"))?;
+    if parts.is_empty() {
+        return Err("No editor configured. Set an editor command or $EDITOR.".to_string());
+    }
+    Ok(parts)
+}
+
+fn open_path_command(
+    mode: OpenMode,
+    target: &std::path::Path,
+    editor: Option<&str>,
+) -> Result<tokio::process::Command, String> {
+    let directory = if target.is_dir() {
+        target.to_path_buf()
+    } else {
+        target
+            .parent()
+            .map(|parent| parent.to_path_buf())
+            .unwrap_or_else(|| target.to_path_buf())
+    };
+    let command = match mode {
+        OpenMode::Editor => {
+            let parts = editor_command_parts(editor)?;
+            let mut command = tokio_command(&parts[0]);
+            command.args(&parts[1..]).arg(target).current_dir(&directory);
+            command
+        }
+        OpenMode::Terminal => {
+            if cfg!(target_os = "macos") {
+                let mut command = tokio_command("open");
+                command.arg("-a").arg("Terminal").arg(&directory);
+                command
+            } else if cfg!(windows) {
+                let mut command = tokio_command("cmd");
+                command.args(["/C", "start", "cmd"]).current_dir(&directory);
+                command
+            } else {
+                let mut command = tokio_command("x-terminal-emulator");
+                command.current_dir(&directory);
+                command
+            }
+        }
+        OpenMode::FileManager => {
+            if cfg!(target_os = "macos") {
+                let mut command = tokio_command("open");
+                command.arg("-R").arg(target);
+                command
+            } else if cfg!(windows) {
+                let mut command = tokio_command("explorer");
+                command.arg(format!("/select,{}", target.display()));
+                command
+            } else {
+                let mut command = tokio_command("xdg-open");
+                command.arg(&directory);
+                command
+            }
+        }
+    };
+    Ok(command)
+}
+
+#[tauri::command]
+pub(crate) async fn open_path(
+    workspace_id: String,
+    path: String,
+    mode: OpenMode,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let editor = state.app_settings.lock().await.editor_command.clone();
+    let root = resolve_git_root(&entry)?;
+    let target = resolve_path_within_root(&root, &path)?;
+    let mut command = open_path_command(mode, &target, editor.as_deref())?;
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|err| format!("Failed to open `{}`: {err}", target.display()))
+}
 
 #[tauri::command]
 pub(crate) async fn get_open_app_icon(app_name: String) -> Result<Option<String>, String> {
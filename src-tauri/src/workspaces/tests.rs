@@ -3,12 +3,16 @@ use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use super::commands::resolve_path_within_root;
 use super::settings::{apply_workspace_settings_update, sort_workspaces};
 use super::worktree::{
     build_clone_destination_path, sanitize_clone_dir_name, sanitize_worktree_name,
 };
 use crate::backend::app_server::WorkspaceSession;
-use crate::shared::workspaces_core::rename_worktree_core;
+use crate::shared::workspaces_core::{
+    create_group_core, delete_group_core, discover_workspaces_core, normalize_group_color,
+    rename_group_core, rename_worktree_core, reorder_workspaces_core,
+};
 use crate::storage::{read_workspaces, write_workspaces};
 use crate::types::{
     AppSettings, WorktreeInfo, WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings,
@@ -56,6 +60,10 @@ fn workspace_with_id_and_kind(
             launch_script: None,
             launch_scripts: None,
             worktree_setup_script: None,
+            diff_exclude_globs: Vec::new(),
+            protected_branches: Vec::new(),
+            auto_fetch_interval_secs: None,
+            env: HashMap::new(),
         },
     }
 }
@@ -379,3 +387,207 @@ fn rename_worktree_updates_name_when_unmodified() {
     assert_eq!(updated.name, "feature/new");
     });
 }
+
+#[test]
+fn discover_workspaces_skips_registered_repos() {
+    run_async(async {
+        let root = std::env::temp_dir().join(format!("codex-monitor-discover-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("alpha/.git")).expect("create alpha");
+        std::fs::create_dir_all(root.join("beta/.git")).expect("create beta");
+        std::fs::create_dir_all(root.join("notes")).expect("create plain dir");
+
+        let registered = WorkspaceEntry {
+            id: "alpha".to_string(),
+            name: "Alpha".to_string(),
+            path: root.join("alpha").to_string_lossy().to_string(),
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        };
+        let workspaces = Mutex::new(HashMap::from([(registered.id.clone(), registered)]));
+
+        let stubs = discover_workspaces_core(&workspaces, root.to_string_lossy().to_string(), None)
+            .await
+            .expect("discover");
+        assert_eq!(stubs.len(), 1);
+        assert_eq!(stubs[0].name, "beta");
+        assert_eq!(stubs[0].path, root.join("beta").to_string_lossy());
+
+        assert!(discover_workspaces_core(&workspaces, root.join("missing").to_string_lossy().to_string(), None)
+            .await
+            .is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    });
+}
+
+#[test]
+fn reorder_workspaces_assigns_dense_order_and_rejects_mismatches() {
+    run_async(async {
+        let temp_dir = std::env::temp_dir().join(format!("codex-monitor-reorder-{}", Uuid::new_v4()));
+        let storage_path = temp_dir.join("workspaces.json");
+        let entry = |id: &str, sort_order: Option<u32>| WorkspaceEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: "/tmp".to_string(),
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings {
+                sort_order,
+                ..WorkspaceSettings::default()
+            },
+        };
+        let workspaces = Mutex::new(HashMap::from([
+            ("a".to_string(), entry("a", Some(0))),
+            ("b".to_string(), entry("b", Some(0))),
+            ("c".to_string(), entry("c", None)),
+        ]));
+        let sessions: Mutex<HashMap<String, Arc<WorkspaceSession>>> = Mutex::new(HashMap::new());
+
+        let ids = |list: &[&str]| list.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        assert!(reorder_workspaces_core(ids(&["c", "a"]), &workspaces, &sessions, &storage_path)
+            .await
+            .is_err());
+        assert!(reorder_workspaces_core(ids(&["c", "a", "a"]), &workspaces, &sessions, &storage_path)
+            .await
+            .is_err());
+        assert!(reorder_workspaces_core(ids(&["c", "a", "x"]), &workspaces, &sessions, &storage_path)
+            .await
+            .is_err());
+        assert!(!storage_path.exists());
+
+        let ordered =
+            reorder_workspaces_core(ids(&["c", "a", "b"]), &workspaces, &sessions, &storage_path)
+                .await
+                .expect("reorder");
+        let order: Vec<_> = ordered.iter().map(|workspace| workspace.id.as_str()).collect();
+        assert_eq!(order, vec!["c", "a", "b"]);
+
+        let stored = read_workspaces(&storage_path).expect("read stored");
+        assert_eq!(stored["c"].settings.sort_order, Some(0));
+        assert_eq!(stored["a"].settings.sort_order, Some(1));
+        assert_eq!(stored["b"].settings.sort_order, Some(2));
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    });
+}
+
+#[test]
+fn normalize_group_color_accepts_hex_only() {
+    assert_eq!(normalize_group_color(None), Ok(None));
+    assert_eq!(normalize_group_color(Some("  ".to_string())), Ok(None));
+    assert_eq!(
+        normalize_group_color(Some("#A1B2C3".to_string())),
+        Ok(Some("#a1b2c3".to_string()))
+    );
+    assert_eq!(
+        normalize_group_color(Some("#fff".to_string())),
+        Ok(Some("#fff".to_string()))
+    );
+    assert!(normalize_group_color(Some("red".to_string())).is_err());
+    assert!(normalize_group_color(Some("#12345".to_string())).is_err());
+}
+
+#[test]
+fn workspace_groups_validate_names_and_ungroup_members_on_delete() {
+    run_async(async {
+        let temp_dir = std::env::temp_dir().join(format!("codex-monitor-groups-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).expect("create temp dir");
+        let storage_path = temp_dir.join("workspaces.json");
+        let settings_path = temp_dir.join("settings.json");
+        let app_settings = Mutex::new(AppSettings::default());
+
+        let alpha = create_group_core("Alpha".to_string(), None, &app_settings, &settings_path)
+            .await
+            .expect("create alpha");
+        let beta = create_group_core(
+            " Beta ".to_string(),
+            Some("#ABC".to_string()),
+            &app_settings,
+            &settings_path,
+        )
+        .await
+        .expect("create beta");
+        assert_eq!(beta.name, "Beta");
+        assert_eq!(beta.color.as_deref(), Some("#abc"));
+        assert_eq!(beta.sort_order, Some(alpha.sort_order.unwrap_or(0) + 1));
+
+        assert!(create_group_core("alpha".to_string(), None, &app_settings, &settings_path)
+            .await
+            .is_err());
+        assert!(create_group_core("Ungrouped".to_string(), None, &app_settings, &settings_path)
+            .await
+            .is_err());
+        assert!(rename_group_core(beta.id.clone(), "ALPHA".to_string(), &app_settings, &settings_path)
+            .await
+            .is_err());
+        let renamed =
+            rename_group_core(beta.id.clone(), "beta".to_string(), &app_settings, &settings_path)
+                .await
+                .expect("rename to same name with new case");
+        assert_eq!(renamed.name, "beta");
+
+        let workspaces = Mutex::new(HashMap::from([(
+            "w1".to_string(),
+            WorkspaceEntry {
+                id: "w1".to_string(),
+                name: "w1".to_string(),
+                path: "/tmp".to_string(),
+                codex_bin: None,
+                kind: WorkspaceKind::Main,
+                parent_id: None,
+                worktree: None,
+                settings: WorkspaceSettings {
+                    group_id: Some(alpha.id.clone()),
+                    ..WorkspaceSettings::default()
+                },
+            },
+        )]));
+        delete_group_core(
+            alpha.id.clone(),
+            &workspaces,
+            &app_settings,
+            &storage_path,
+            &settings_path,
+        )
+        .await
+        .expect("delete alpha");
+
+        assert_eq!(workspaces.lock().await["w1"].settings.group_id, None);
+        let stored = read_workspaces(&storage_path).expect("read stored");
+        assert_eq!(stored["w1"].settings.group_id, None);
+        let groups = app_settings.lock().await.workspace_groups.clone();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].id, beta.id);
+        assert!(delete_group_core(
+            alpha.id,
+            &workspaces,
+            &app_settings,
+            &storage_path,
+            &settings_path,
+        )
+        .await
+        .is_err());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    });
+}
+
+#[test]
+fn resolve_path_within_root_rejects_escapes() {
+    let root = std::env::temp_dir().join(format!("codex-monitor-open-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("src")).expect("create root");
+    std::fs::write(root.join("src/main.rs"), "fn main() {}\n").expect("write file");
+
+    let resolved = resolve_path_within_root(&root, "src/main.rs").expect("inside root");
+    assert!(resolved.ends_with("src/main.rs"));
+    assert!(resolve_path_within_root(&root, "").is_ok());
+    assert!(resolve_path_within_root(&root, "../").is_err());
+    assert!(resolve_path_within_root(&root, "src/../../").is_err());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
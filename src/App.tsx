@@ -366,7 +366,8 @@ function MainApp() {
   } = useGitPanelController({
     activeWorkspace,
     gitDiffPreloadEnabled: appSettings.preloadGitDiffs,
-    gitDiffIgnoreWhitespaceChanges: appSettings.gitDiffIgnoreWhitespaceChanges,
+    gitDiffWhitespace: appSettings.gitDiffWhitespace,
+    gitStatusPolling: appSettings.backendMode === "remote",
     isCompact,
     isTablet,
     activeTab,
@@ -1945,7 +1946,7 @@ function MainApp() {
     onGitPanelModeChange: handleGitPanelModeChange,
     gitDiffViewStyle,
     gitDiffIgnoreWhitespaceChanges:
-      appSettings.gitDiffIgnoreWhitespaceChanges && diffSource !== "pr",
+      appSettings.gitDiffWhitespace !== "none" && diffSource !== "pr",
     worktreeApplyLabel: "apply",
     worktreeApplyTitle: activeParentWorkspace?.name
       ? `Apply changes to ${activeParentWorkspace.name}`
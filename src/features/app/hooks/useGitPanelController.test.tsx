@@ -37,7 +37,8 @@ function makeProps(overrides?: Partial<Parameters<typeof useGitPanelController>[
   return {
     activeWorkspace: workspace,
     gitDiffPreloadEnabled: false,
-    gitDiffIgnoreWhitespaceChanges: false,
+    gitDiffWhitespace: "none" as const,
+    gitStatusPolling: false,
     isCompact: false,
     isTablet: false,
     activeTab: "codex" as const,
@@ -1,5 +1,10 @@
 import { useCallback, useEffect, useRef, useState } from "react";
-import type { GitHubPullRequest, GitHubPullRequestDiff, WorkspaceInfo } from "../../../types";
+import type {
+  DiffWhitespace,
+  GitHubPullRequest,
+  GitHubPullRequestDiff,
+  WorkspaceInfo,
+} from "../../../types";
 import { useGitStatus } from "../../git/hooks/useGitStatus";
 import { useGitDiffs } from "../../git/hooks/useGitDiffs";
 import { useGitLog } from "../../git/hooks/useGitLog";
@@ -8,7 +13,8 @@ import { useGitCommitDiffs } from "../../git/hooks/useGitCommitDiffs";
 export function useGitPanelController({
   activeWorkspace,
   gitDiffPreloadEnabled,
-  gitDiffIgnoreWhitespaceChanges,
+  gitDiffWhitespace,
+  gitStatusPolling,
   isCompact,
   isTablet,
   activeTab,
@@ -20,7 +26,8 @@ export function useGitPanelController({
 }: {
   activeWorkspace: WorkspaceInfo | null;
   gitDiffPreloadEnabled: boolean;
-  gitDiffIgnoreWhitespaceChanges: boolean;
+  gitDiffWhitespace: DiffWhitespace;
+  gitStatusPolling: boolean;
   isCompact: boolean;
   isTablet: boolean;
   activeTab: "projects" | "codex" | "git" | "log";
@@ -54,6 +61,7 @@ export function useGitPanelController({
 
   const { status: gitStatus, refresh: refreshGitStatus } = useGitStatus(
     activeWorkspace,
+    { poll: gitStatusPolling },
   );
   const gitStatusRefreshTimeoutRef = useRef<number | null>(null);
   const activeWorkspaceIdRef = useRef<string | null>(activeWorkspace?.id ?? null);
@@ -123,7 +131,7 @@ export function useGitPanelController({
     activeWorkspace,
     gitStatus.files,
     shouldLoadLocalDiffs,
-    gitDiffIgnoreWhitespaceChanges,
+    gitDiffWhitespace,
   );
 
   useEffect(() => {
@@ -163,7 +171,7 @@ export function useGitPanelController({
     activeWorkspace,
     selectedCommitSha,
     shouldLoadDiffs && diffSource === "commit",
-    gitDiffIgnoreWhitespaceChanges,
+    gitDiffWhitespace,
   );
 
   const activeDiffs =
@@ -6,6 +6,7 @@ import type {
   GitHubPullRequestDiff,
   WorkspaceInfo,
 } from "../../../types";
+import { useGitHubCliStatus } from "../hooks/useGitHubCliStatus";
 import { useGitHubIssues } from "../hooks/useGitHubIssues";
 import { useGitHubPullRequests } from "../hooks/useGitHubPullRequests";
 import { useGitHubPullRequestDiffs } from "../hooks/useGitHubPullRequestDiffs";
@@ -60,10 +61,22 @@ export function GitHubPanelData({
   onPullRequestDiffsChange,
   onPullRequestCommentsChange,
 }: GitHubPanelDataProps) {
-  const issuesEnabled = gitPanelMode === "issues";
-  const pullRequestsEnabled = gitPanelMode === "prs" && Boolean(activeWorkspace);
-  const pullRequestDiffsEnabled =
+  const wantsIssues = gitPanelMode === "issues";
+  const wantsPullRequests = gitPanelMode === "prs" && Boolean(activeWorkspace);
+  const wantsPullRequestDiffs =
     shouldLoadDiffs && diffSource === "pr" && Boolean(activeWorkspace);
+  const { ready: githubReady, error: githubCliError } = useGitHubCliStatus(
+    wantsIssues || wantsPullRequests || wantsPullRequestDiffs,
+  );
+  // Skip gh calls once we know the CLI is missing or signed out; each panel
+  // shows the single configuration error instead.
+  const githubUsable = githubReady !== false;
+  const issuesEnabled = wantsIssues && githubUsable;
+  const pullRequestsEnabled = wantsPullRequests && githubUsable;
+  const pullRequestDiffsEnabled = wantsPullRequestDiffs && githubUsable;
+  const issuesCliError = wantsIssues ? githubCliError : null;
+  const pullRequestsCliError = wantsPullRequests ? githubCliError : null;
+  const pullRequestDiffsCliError = wantsPullRequestDiffs ? githubCliError : null;
   const pullRequestCommentsEnabled = pullRequestDiffsEnabled;
 
   const {
@@ -105,18 +118,26 @@ export function GitHubPanelData({
       issues,
       total: issuesTotal,
       isLoading: issuesLoading,
-      error: issuesError,
+      error: issuesCliError ?? issuesError,
     });
-  }, [issues, issuesError, issuesLoading, issuesTotal, onIssuesChange]);
+  }, [
+    issuesCliError,
+    issues,
+    issuesError,
+    issuesLoading,
+    issuesTotal,
+    onIssuesChange,
+  ]);
 
   useEffect(() => {
     onPullRequestsChange({
       pullRequests,
       total: pullRequestsTotal,
       isLoading: pullRequestsLoading,
-      error: pullRequestsError,
+      error: pullRequestsCliError ?? pullRequestsError,
     });
   }, [
+    pullRequestsCliError,
     onPullRequestsChange,
     pullRequests,
     pullRequestsError,
@@ -128,9 +149,10 @@ export function GitHubPanelData({
     onPullRequestDiffsChange({
       diffs: pullRequestDiffs,
       isLoading: pullRequestDiffsLoading,
-      error: pullRequestDiffsError,
+      error: pullRequestDiffsCliError ?? pullRequestDiffsError,
     });
   }, [
+    pullRequestDiffsCliError,
     onPullRequestDiffsChange,
     pullRequestDiffs,
     pullRequestDiffsError,
@@ -141,9 +163,10 @@ export function GitHubPanelData({
     onPullRequestCommentsChange({
       comments: pullRequestComments,
       isLoading: pullRequestCommentsLoading,
-      error: pullRequestCommentsError,
+      error: pullRequestDiffsCliError ?? pullRequestCommentsError,
     });
   }, [
+    pullRequestDiffsCliError,
     onPullRequestCommentsChange,
     pullRequestComments,
     pullRequestCommentsError,
@@ -50,6 +50,9 @@ export function useGitBranches({ activeWorkspace, onDebug }: UseGitBranchesOptio
         ? data.map((item: any) => ({
             name: String(item?.name ?? ""),
             lastCommit: Number(item?.lastCommit ?? item?.last_commit ?? 0),
+            lastCommitOffset: Number(
+              item?.lastCommitOffset ?? item?.last_commit_offset ?? 0,
+            ),
           }))
         : [];
       setBranches(normalized.filter((branch) => branch.name));
@@ -1,5 +1,5 @@
 import { useCallback, useEffect, useRef, useState } from "react";
-import type { GitCommitDiff, WorkspaceInfo } from "../../../types";
+import type { DiffWhitespace, GitCommitDiff, WorkspaceInfo } from "../../../types";
 import { getGitCommitDiff } from "../../../services/tauri";
 
 type CommitDiffState = {
@@ -18,13 +18,13 @@ export function useGitCommitDiffs(
   activeWorkspace: WorkspaceInfo | null,
   sha: string | null,
   enabled: boolean,
-  ignoreWhitespaceChanges: boolean,
+  whitespace: DiffWhitespace,
 ) {
   const [state, setState] = useState<CommitDiffState>(emptyState);
   const requestIdRef = useRef(0);
   const workspaceIdRef = useRef<string | null>(activeWorkspace?.id ?? null);
   const shaRef = useRef<string | null>(sha ?? null);
-  const ignoreWhitespaceChangesRef = useRef(ignoreWhitespaceChanges);
+  const whitespaceRef = useRef(whitespace);
 
   const refresh = useCallback(async () => {
     if (!activeWorkspace || !sha) {
@@ -41,7 +41,7 @@ export function useGitCommitDiffs(
         requestIdRef.current !== requestId ||
         workspaceIdRef.current !== workspaceId ||
         shaRef.current !== sha ||
-        ignoreWhitespaceChangesRef.current !== ignoreWhitespaceChanges
+        whitespaceRef.current !== whitespace
       ) {
         return;
       }
@@ -52,7 +52,7 @@ export function useGitCommitDiffs(
         requestIdRef.current !== requestId ||
         workspaceIdRef.current !== workspaceId ||
         shaRef.current !== sha ||
-        ignoreWhitespaceChangesRef.current !== ignoreWhitespaceChanges
+        whitespaceRef.current !== whitespace
       ) {
         return;
       }
@@ -62,7 +62,7 @@ export function useGitCommitDiffs(
         error: error instanceof Error ? error.message : String(error),
       });
     }
-  }, [activeWorkspace, ignoreWhitespaceChanges, sha]);
+  }, [activeWorkspace, whitespace, sha]);
 
   useEffect(() => {
     const workspaceId = activeWorkspace?.id ?? null;
@@ -82,12 +82,12 @@ export function useGitCommitDiffs(
   }, [sha]);
 
   useEffect(() => {
-    if (ignoreWhitespaceChangesRef.current !== ignoreWhitespaceChanges) {
-      ignoreWhitespaceChangesRef.current = ignoreWhitespaceChanges;
+    if (whitespaceRef.current !== whitespace) {
+      whitespaceRef.current = whitespace;
       requestIdRef.current += 1;
       setState(emptyState);
     }
-  }, [ignoreWhitespaceChanges]);
+  }, [whitespace]);
 
   useEffect(() => {
     if (!enabled) {
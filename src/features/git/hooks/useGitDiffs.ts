@@ -1,6 +1,11 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { getGitDiffs } from "../../../services/tauri";
-import type { GitFileDiff, GitFileStatus, WorkspaceInfo } from "../../../types";
+import type {
+  DiffWhitespace,
+  GitFileDiff,
+  GitFileStatus,
+  WorkspaceInfo,
+} from "../../../types";
 
 type GitDiffState = {
   diffs: GitFileDiff[];
@@ -18,7 +23,7 @@ export function useGitDiffs(
   activeWorkspace: WorkspaceInfo | null,
   files: GitFileStatus[],
   enabled: boolean,
-  ignoreWhitespaceChanges: boolean,
+  whitespace: DiffWhitespace,
 ) {
   const [state, setState] = useState<GitDiffState>(emptyState);
   const requestIdRef = useRef(0);
@@ -43,7 +48,7 @@ export function useGitDiffs(
       return;
     }
     const workspaceId = activeWorkspace.id;
-    const cacheKey = `${workspaceId}|ignoreWhitespaceChanges:${ignoreWhitespaceChanges ? "1" : "0"}`;
+    const cacheKey = `${workspaceId}|whitespace:${whitespace}`;
     const requestId = requestIdRef.current + 1;
     requestIdRef.current = requestId;
     setState((prev) => ({ ...prev, isLoading: true, error: null }));
@@ -71,12 +76,12 @@ export function useGitDiffs(
         error: error instanceof Error ? error.message : String(error),
       });
     }
-  }, [activeWorkspace, ignoreWhitespaceChanges]);
+  }, [activeWorkspace, whitespace]);
 
   useEffect(() => {
     const workspaceId = activeWorkspace?.id ?? null;
     const nextCacheKey = workspaceId
-      ? `${workspaceId}|ignoreWhitespaceChanges:${ignoreWhitespaceChanges ? "1" : "0"}`
+      ? `${workspaceId}|whitespace:${whitespace}`
       : null;
     if (cacheKeyRef.current !== nextCacheKey) {
       cacheKeyRef.current = nextCacheKey;
@@ -92,7 +97,7 @@ export function useGitDiffs(
         error: null,
       });
     }
-  }, [activeWorkspace?.id, ignoreWhitespaceChanges]);
+  }, [activeWorkspace?.id, whitespace]);
 
   useEffect(() => {
     if (!enabled) {
@@ -0,0 +1,36 @@
+import { useEffect, useState } from "react";
+import type { GitHubCliStatus } from "../../../types";
+import { diagnoseGitHub } from "../../../services/tauri";
+
+export const GITHUB_CLI_NOT_CONFIGURED = "GitHub CLI not configured";
+
+export function useGitHubCliStatus(enabled: boolean) {
+  const [status, setStatus] = useState<GitHubCliStatus | null>(null);
+
+  useEffect(() => {
+    if (!enabled || status) {
+      return;
+    }
+    let cancelled = false;
+    diagnoseGitHub()
+      .then((result) => {
+        if (!cancelled) {
+          setStatus(result);
+        }
+      })
+      .catch((error) => {
+        console.error("Failed to check GitHub CLI", error);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [enabled, status]);
+
+  const ready = status ? status.installed && status.authenticated : null;
+  const error =
+    status && !ready
+      ? `${GITHUB_CLI_NOT_CONFIGURED}: ${status.error ?? "run `gh auth login`."}`
+      : null;
+
+  return { status, ready, error };
+}
@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import type { GitLogEntry, WorkspaceInfo } from "../../../types";
+import { subscribeGitRemoteUpdated } from "../../../services/events";
 import { getGitLog } from "../../../services/tauri";
 
 type GitLogState = {
@@ -55,7 +56,7 @@ export function useGitLog(
       }
       setState({
         entries: response.entries,
-        total: response.total,
+        total: response.total ?? response.entries.length,
         ahead: response.ahead,
         behind: response.behind,
         aheadEntries: response.aheadEntries,
@@ -103,8 +104,15 @@ export function useGitLog(
     const interval = window.setInterval(() => {
       refresh().catch(() => {});
     }, REFRESH_INTERVAL_MS);
+    const workspaceId = activeWorkspace.id;
+    const unsubscribe = subscribeGitRemoteUpdated((event) => {
+      if (event.workspaceId === workspaceId && event.ok) {
+        refresh().catch(() => {});
+      }
+    });
     return () => {
       window.clearInterval(interval);
+      unsubscribe();
     };
   }, [activeWorkspace, enabled, refresh]);
 
@@ -2,6 +2,7 @@
 import { act, renderHook } from "@testing-library/react";
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
 import type { WorkspaceInfo } from "../../../types";
+import { subscribeGitStatusDirty } from "../../../services/events";
 import { getGitStatus } from "../../../services/tauri";
 import { useGitStatus } from "./useGitStatus";
 
@@ -9,6 +10,10 @@ vi.mock("../../../services/tauri", () => ({
   getGitStatus: vi.fn(),
 }));
 
+vi.mock("../../../services/events", () => ({
+  subscribeGitStatusDirty: vi.fn(() => () => {}),
+}));
+
 const workspace: WorkspaceInfo = {
   id: "workspace-1",
   name: "CodexMonitor",
@@ -51,7 +56,8 @@ describe("useGitStatus", () => {
       .mockResolvedValueOnce(makeStatus("next", 3, 4));
 
     const { result, unmount } = renderHook(
-      ({ active }: { active: WorkspaceInfo | null }) => useGitStatus(active),
+      ({ active }: { active: WorkspaceInfo | null }) =>
+        useGitStatus(active, { poll: true }),
       { initialProps: { active: workspace } },
     );
     await act(async () => {
@@ -76,6 +82,38 @@ describe("useGitStatus", () => {
     unmount();
   });
 
+  it("refreshes on dirty events instead of polling by default", async () => {
+    const getGitStatusMock = vi.mocked(getGitStatus);
+    getGitStatusMock
+      .mockResolvedValueOnce(makeStatus("main", 1, 0))
+      .mockResolvedValueOnce(makeStatus("dirty", 2, 0));
+
+    const { result, unmount } = renderHook(
+      ({ active }: { active: WorkspaceInfo | null }) => useGitStatus(active),
+      { initialProps: { active: workspace } },
+    );
+    await act(async () => {
+      await Promise.resolve();
+    });
+
+    await act(async () => {
+      vi.advanceTimersByTime(9000);
+    });
+    expect(getGitStatusMock).toHaveBeenCalledTimes(1);
+
+    const onDirty = vi.mocked(subscribeGitStatusDirty).mock.calls[0][0];
+    await act(async () => {
+      onDirty({ workspaceId: "workspace-2" });
+      onDirty({ workspaceId: "workspace-1" });
+      await Promise.resolve();
+    });
+
+    expect(getGitStatusMock).toHaveBeenCalledTimes(2);
+    expect(result.current.status.branchName).toBe("dirty");
+
+    unmount();
+  });
+
   it("refresh triggers a new fetch", async () => {
     const getGitStatusMock = vi.mocked(getGitStatus);
     getGitStatusMock
@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import type { GitFileStatus, WorkspaceInfo } from "../../../types";
+import { subscribeGitStatusDirty } from "../../../services/events";
 import { getGitStatus } from "../../../services/tauri";
 
 type GitStatusState = {
@@ -23,7 +24,17 @@ const emptyStatus: GitStatusState = {
 };
 
 const REFRESH_INTERVAL_MS = 3000;
-export function useGitStatus(activeWorkspace: WorkspaceInfo | null) {
+
+type UseGitStatusOptions = {
+  // The backend file watcher emits `git-status-dirty` for local workspaces.
+  // It does not run against a remote daemon, so poll there instead.
+  poll?: boolean;
+};
+
+export function useGitStatus(
+  activeWorkspace: WorkspaceInfo | null,
+  { poll = false }: UseGitStatusOptions = {},
+) {
   const [status, setStatus] = useState<GitStatusState>(emptyStatus);
   const requestIdRef = useRef(0);
   const workspaceIdRef = useRef<string | null>(activeWorkspace?.id ?? null);
@@ -110,12 +121,22 @@ export function useGitStatus(activeWorkspace: WorkspaceInfo | null) {
     };
 
     fetchStatus();
-    const interval = window.setInterval(fetchStatus, REFRESH_INTERVAL_MS);
+    const interval = poll
+      ? window.setInterval(fetchStatus, REFRESH_INTERVAL_MS)
+      : null;
+    const unsubscribe = subscribeGitStatusDirty((event) => {
+      if (event.workspaceId === workspaceId) {
+        fetchStatus();
+      }
+    });
 
     return () => {
-      window.clearInterval(interval);
+      if (interval !== null) {
+        window.clearInterval(interval);
+      }
+      unsubscribe();
     };
-  }, [refresh, workspaceId]);
+  }, [poll, refresh, workspaceId]);
 
   return { status, refresh };
 }
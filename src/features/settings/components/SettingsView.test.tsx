@@ -60,7 +60,7 @@ const baseSettings: AppSettings = {
   notificationSoundsEnabled: true,
   systemNotificationsEnabled: true,
   preloadGitDiffs: true,
-  gitDiffIgnoreWhitespaceChanges: false,
+  gitDiffWhitespace: "none",
   experimentalCollabEnabled: false,
   collaborationModesEnabled: true,
   steerEnabled: true,
@@ -20,6 +20,7 @@ import type {
   AppSettings,
   CodexDoctorResult,
   DictationModelStatus,
+  DiffWhitespace,
   WorkspaceSettings,
   OpenAppTarget,
   WorkspaceGroup,
@@ -2940,23 +2941,47 @@ export function SettingsView({
                     <span className="settings-toggle-knob" />
                   </button>
                 </div>
+                <div className="settings-field">
+                  <label className="settings-field-label" htmlFor="git-diff-whitespace">
+                    Whitespace in diffs
+                  </label>
+                  <select
+                    id="git-diff-whitespace"
+                    className="settings-select"
+                    value={appSettings.gitDiffWhitespace}
+                    onChange={(event) =>
+                      void onUpdateAppSettings({
+                        ...appSettings,
+                        gitDiffWhitespace: event.target.value as DiffWhitespace,
+                      })
+                    }
+                  >
+                    <option value="none">Show all changes</option>
+                    <option value="ignoreEol">Ignore whitespace at line end</option>
+                    <option value="ignoreChange">Ignore whitespace changes</option>
+                    <option value="ignoreAll">Ignore all whitespace</option>
+                  </select>
+                  <div className="settings-help">
+                    Applies to local and commit diffs.
+                  </div>
+                </div>
                 <div className="settings-toggle-row">
                   <div>
-                    <div className="settings-toggle-title">Ignore whitespace changes</div>
+                    <div className="settings-toggle-title">Normalize line endings</div>
                     <div className="settings-toggle-subtitle">
-                      Hides whitespace-only changes in local and commit diffs.
+                      Show CRLF files with LF line endings in local diffs.
                     </div>
                   </div>
                   <button
                     type="button"
-                    className={`settings-toggle ${appSettings.gitDiffIgnoreWhitespaceChanges ? "on" : ""}`}
+                    className={`settings-toggle ${appSettings.gitDiffNormalizeLineEndings ? "on" : ""}`}
                     onClick={() =>
                       void onUpdateAppSettings({
                         ...appSettings,
-                        gitDiffIgnoreWhitespaceChanges: !appSettings.gitDiffIgnoreWhitespaceChanges,
+                        gitDiffNormalizeLineEndings: !appSettings.gitDiffNormalizeLineEndings,
                       })
                     }
-                    aria-pressed={appSettings.gitDiffIgnoreWhitespaceChanges}
+                    aria-pressed={Boolean(appSettings.gitDiffNormalizeLineEndings)}
                   >
                     <span className="settings-toggle-knob" />
                   </button>
@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useState } from "react";
 import type { AppSettings } from "../../../types";
 import { getAppSettings, runCodexDoctor, updateAppSettings } from "../../../services/tauri";
+import { pushErrorToast } from "../../../services/toasts";
 import { clampUiScale, UI_SCALE_DEFAULT } from "../../../utils/uiScale";
 import {
   DEFAULT_CODE_FONT_FAMILY,
@@ -60,7 +61,8 @@ const defaultSettings: AppSettings = {
   notificationSoundsEnabled: true,
   systemNotificationsEnabled: true,
   preloadGitDiffs: true,
-  gitDiffIgnoreWhitespaceChanges: false,
+  gitDiffWhitespace: "none",
+  gitDiffNormalizeLineEndings: false,
   experimentalCollabEnabled: false,
   collaborationModesEnabled: true,
   steerEnabled: true,
@@ -164,6 +166,12 @@ export function useAppSettings() {
   const saveSettings = useCallback(async (next: AppSettings) => {
     const normalized = normalizeAppSettings(next);
     const saved = await updateAppSettings(normalized);
+    if (saved.configWriteErrors && saved.configWriteErrors.length > 0) {
+      pushErrorToast({
+        title: "Couldn't update Codex config",
+        message: saved.configWriteErrors.join("\n"),
+      });
+    }
     setSettings(
       normalizeAppSettings({
         ...defaultSettings,
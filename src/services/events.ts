@@ -1,5 +1,10 @@
 import { listen } from "@tauri-apps/api/event";
-import type { AppServerEvent, DictationEvent, DictationModelStatus } from "../types";
+import type {
+  AppServerEvent,
+  AppSettings,
+  DictationEvent,
+  DictationModelStatus,
+} from "../types";
 
 export type Unsubscribe = () => void;
 
@@ -9,6 +14,16 @@ export type TerminalOutputEvent = {
   data: string;
 };
 
+export type GitStatusDirtyEvent = {
+  workspaceId: string;
+};
+
+export type GitRemoteUpdatedEvent = {
+  workspaceId: string;
+  ok: boolean;
+  error: string | null;
+};
+
 export type TerminalExitEvent = {
   workspaceId: string;
   terminalId: string;
@@ -86,6 +101,9 @@ const dictationDownloadHub = createEventHub<DictationModelStatus>("dictation-dow
 const dictationEventHub = createEventHub<DictationEvent>("dictation-event");
 const terminalOutputHub = createEventHub<TerminalOutputEvent>("terminal-output");
 const terminalExitHub = createEventHub<TerminalExitEvent>("terminal-exit");
+const gitStatusDirtyHub = createEventHub<GitStatusDirtyEvent>("git-status-dirty");
+const gitRemoteUpdatedHub = createEventHub<GitRemoteUpdatedEvent>("git-remote-updated");
+const settingsChangedHub = createEventHub<AppSettings>("settings-changed");
 const updaterCheckHub = createEventHub<void>("updater-check");
 const menuNewAgentHub = createEventHub<void>("menu-new-agent");
 const menuNewWorktreeAgentHub = createEventHub<void>("menu-new-worktree-agent");
@@ -146,6 +164,27 @@ export function subscribeTerminalExit(
   return terminalExitHub.subscribe(onEvent, options);
 }
 
+export function subscribeSettingsChanged(
+  onEvent: (settings: AppSettings) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return settingsChangedHub.subscribe(onEvent, options);
+}
+
+export function subscribeGitStatusDirty(
+  onEvent: (event: GitStatusDirtyEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return gitStatusDirtyHub.subscribe(onEvent, options);
+}
+
+export function subscribeGitRemoteUpdated(
+  onEvent: (event: GitRemoteUpdatedEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return gitRemoteUpdatedHub.subscribe(onEvent, options);
+}
+
 export function subscribeUpdaterCheck(
   onEvent: () => void,
   options?: SubscriptionOptions,
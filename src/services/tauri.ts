@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import type { Options as NotificationOptions } from "@tauri-apps/plugin-notification";
 import type {
@@ -6,20 +6,66 @@ import type {
   CodexDoctorResult,
   DictationModelStatus,
   DictationSessionState,
+  DiscoveredWorkspace,
   LocalUsageSnapshot,
+  WorkspaceGroup,
   WorkspaceInfo,
   WorkspaceSettings,
 } from "../types";
 import type {
+  CheckoutCommitResult,
+  CodexAuthStore,
+  CodexConfigDiff,
+  CodexConfigReload,
+  CommitAndPushResult,
+  CommitResult,
+  DefaultBranch,
+  EffectiveCodexConfig,
+  FeatureFlag,
+  FileEdit,
+  FileKind,
+  FileScope,
+  FileTailLines,
+  GcReport,
+  GitAheadBehind,
+  GitBisectResult,
+  GitBisectState,
+  GitChangeSummary,
+  GitCommitDiff,
+  GitConfigScope,
+  GitConfigValues,
+  GitConflictVersions,
+  GitDiagnostics,
   GitFileDiff,
   GitFileStatus,
-  GitCommitDiff,
+  GitHubCliStatus,
   GitHubIssuesResponse,
+  GitHubPullRequest,
   GitHubPullRequestComment,
   GitHubPullRequestDiff,
   GitHubPullRequestsResponse,
+  GitLogEntry,
   GitLogResponse,
+  GitRefComparison,
+  GitStatusBatchEntry,
+  GitSyncPreview,
+  GitUndoEntry,
+  GitUntrackedMode,
+  IgnoreExplanation,
+  OpenPathMode,
+  PullRequestRefsFetch,
+  RecentBranch,
+  RemoteFetchResult,
+  RepoOperation,
+  RepoRepairReport,
+  RepoStats,
+  ResolvedRef,
   ReviewTarget,
+  SettingsProfileExport,
+  TailFileResponse,
+  TextFileRangeResponse,
+  WorkingTreeCleanliness,
+  WorkspaceGitValidation,
 } from "../types";
 
 function isMissingTauriInvokeError(error: unknown) {
@@ -68,7 +114,10 @@ export async function listWorkspaces(): Promise<WorkspaceInfo[]> {
   }
 }
 
-export async function getCodexConfigPath(): Promise<string> {
+export async function getCodexConfigPath(workspaceId?: string): Promise<string> {
+  if (workspaceId) {
+    return invoke<string>("get_codex_config_path", { workspaceId });
+  }
   return invoke<string>("get_codex_config_path");
 }
 
@@ -82,8 +131,6 @@ export type GlobalAgentsResponse = TextFileResponse;
 export type GlobalCodexConfigResponse = TextFileResponse;
 export type AgentMdResponse = TextFileResponse;
 
-type FileScope = "workspace" | "global";
-type FileKind = "agents" | "config";
 
 async function fileRead(
   scope: FileScope,
@@ -102,6 +149,60 @@ async function fileWrite(
   return invoke("file_write", { scope, kind, workspaceId, content });
 }
 
+export async function readGlobalCodexConfigTomlRange(
+  offset: number,
+  length: number,
+): Promise<TextFileRangeResponse> {
+  return invoke<TextFileRangeResponse>("file_read_range", {
+    scope: "global",
+    kind: "config",
+    offset,
+    length,
+  });
+}
+
+/** Tails a file relative to the workspace root, e.g. a task log. */
+export async function tailWorkspaceFile(
+  workspaceId: string,
+  path: string,
+  fromOffset = 0,
+): Promise<TailFileResponse> {
+  return invoke<TailFileResponse>("file_tail", {
+    scope: "workspace",
+    workspaceId,
+    path,
+    fromOffset,
+  });
+}
+
+export async function streamWorkspaceFileTail(
+  workspaceId: string,
+  path: string,
+  streamId: string,
+  onLines: (event: FileTailLines) => void,
+  fromOffset = 0,
+): Promise<number> {
+  const channel = new Channel<FileTailLines>();
+  channel.onmessage = onLines;
+  return invoke<number>("stream_file_tail", {
+    scope: "workspace",
+    workspaceId,
+    path,
+    fromOffset,
+    streamId,
+    onLines: channel,
+  });
+}
+
+export async function cancelFileTailStream(streamId: string): Promise<void> {
+  return invoke("cancel_file_tail_stream", { streamId });
+}
+
+/** Writes every edit or none of them. */
+export async function writeFilesTransaction(edits: FileEdit[]): Promise<void> {
+  return invoke("file_write_transaction", { edits });
+}
+
 export async function readGlobalAgentsMd(): Promise<GlobalAgentsResponse> {
   return fileRead("global", "agents");
 }
@@ -118,6 +219,39 @@ export async function writeGlobalCodexConfigToml(content: string): Promise<void>
   return fileWrite("global", "config", content);
 }
 
+export async function getEffectiveConfig(
+  workspaceId?: string | null,
+): Promise<EffectiveCodexConfig> {
+  return invoke<EffectiveCodexConfig>("get_effective_config", {
+    workspaceId: workspaceId ?? null,
+  });
+}
+
+export async function getProjectTrust(path: string): Promise<boolean | null> {
+  return invoke<boolean | null>("get_project_trust", { path });
+}
+
+export async function setProjectTrust(path: string, trusted: boolean): Promise<void> {
+  return invoke("set_project_trust", { path, trusted });
+}
+
+/** Every flag in config.toml's `[features]`, known to the app or not. */
+export async function listFeatureFlags(): Promise<FeatureFlag[]> {
+  return invoke<FeatureFlag[]>("list_feature_flags");
+}
+
+export async function getFeatureFlag(key: string): Promise<boolean | null> {
+  return invoke<boolean | null>("get_feature_flag", { key });
+}
+
+export async function setFeatureFlag(key: string, enabled: boolean): Promise<void> {
+  return invoke("set_feature_flag", { key, enabled });
+}
+
+export async function diffCodexConfig(workspaceId: string): Promise<CodexConfigDiff> {
+  return invoke<CodexConfigDiff>("diff_codex_config", { workspaceId });
+}
+
 export async function getConfigModel(workspaceId: string): Promise<string | null> {
   const response = await invoke<{ model?: string | null }>("get_config_model", {
     workspaceId,
@@ -137,6 +271,17 @@ export async function addWorkspace(
   return invoke<WorkspaceInfo>("add_workspace", { path, codex_bin });
 }
 
+export async function discoverWorkspaces(
+  root: string,
+  depth?: number,
+): Promise<DiscoveredWorkspace[]> {
+  const payload: Record<string, unknown> = { root };
+  if (depth !== undefined) {
+    payload.depth = depth;
+  }
+  return invoke<DiscoveredWorkspace[]>("discover_workspaces", payload);
+}
+
 export async function isWorkspacePathDir(path: string): Promise<boolean> {
   return invoke<boolean>("is_workspace_path_dir", { path });
 }
@@ -184,6 +329,44 @@ export async function updateWorkspaceSettings(
   return invoke<WorkspaceInfo>("update_workspace_settings", { id, settings });
 }
 
+/** `orderedIds` must contain every workspace ID exactly once. */
+export async function reorderWorkspaces(orderedIds: string[]): Promise<WorkspaceInfo[]> {
+  return invoke<WorkspaceInfo[]>("reorder_workspaces", { orderedIds });
+}
+
+export async function createWorkspaceGroup(
+  name: string,
+  color?: string | null,
+): Promise<WorkspaceGroup> {
+  return invoke<WorkspaceGroup>("create_workspace_group", { name, color: color ?? null });
+}
+
+export async function renameWorkspaceGroup(
+  groupId: string,
+  name: string,
+): Promise<WorkspaceGroup> {
+  return invoke<WorkspaceGroup>("rename_workspace_group", { groupId, name });
+}
+
+export async function setWorkspaceGroupColor(
+  groupId: string,
+  color: string | null,
+): Promise<WorkspaceGroup> {
+  return invoke<WorkspaceGroup>("set_workspace_group_color", { groupId, color });
+}
+
+export async function setWorkspaceGroupCollapsed(
+  groupId: string,
+  collapsed: boolean,
+): Promise<WorkspaceGroup> {
+  return invoke<WorkspaceGroup>("set_workspace_group_collapsed", { groupId, collapsed });
+}
+
+/** Deletes the group; its members become ungrouped. */
+export async function deleteWorkspaceGroup(groupId: string): Promise<void> {
+  return invoke("delete_workspace_group", { groupId });
+}
+
 export async function updateWorkspaceCodexBin(
   id: string,
   codex_bin: string | null,
@@ -218,6 +401,14 @@ export async function applyWorktreeChanges(workspaceId: string): Promise<void> {
   return invoke("apply_worktree_changes", { workspaceId });
 }
 
+export async function openPath(
+  workspaceId: string,
+  path: string,
+  mode: OpenPathMode,
+): Promise<void> {
+  return invoke("open_path", { workspaceId, path, mode });
+}
+
 export async function openWorkspaceIn(
   path: string,
   options: {
@@ -333,17 +524,147 @@ export async function rememberApprovalRule(
   return invoke("remember_approval_rule", { workspaceId, command });
 }
 
-export async function getGitStatus(workspace_id: string): Promise<{
+export async function getGitStatus(
+  workspace_id: string,
+  untrackedMode?: GitUntrackedMode,
+): Promise<{
   branchName: string;
+  detachedHead?: boolean;
+  headSha?: string | null;
   files: GitFileStatus[];
   stagedFiles: GitFileStatus[];
   unstagedFiles: GitFileStatus[];
   totalAdditions: number;
   totalDeletions: number;
 }> {
+  if (untrackedMode) {
+    return invoke("get_git_status", { workspaceId: workspace_id, untrackedMode });
+  }
   return invoke("get_git_status", { workspaceId: workspace_id });
 }
 
+/** Cheap clean/dirty check. Counts are only complete with `countChanges`. */
+export async function isWorkingTreeClean(
+  workspaceId: string,
+  countChanges = false,
+): Promise<WorkingTreeCleanliness> {
+  return invoke<WorkingTreeCleanliness>("is_working_tree_clean", { workspaceId, countChanges });
+}
+
+/** Runs `git init` in a workspace folder and returns the new repo root. */
+export async function initRepo(
+  workspaceId: string,
+  initialBranch?: string,
+): Promise<string> {
+  return invoke<string>("init_repo", { workspaceId, initialBranch });
+}
+
+export const REMOTE_NOT_FOUND_PREFIX = "Remote repository not found";
+export const REMOTE_PERMISSION_DENIED_PREFIX = "Permission denied by remote";
+
+/** Adds the remote if needed, pushes with `-u` and returns e.g. `origin/main`. */
+export async function setUpstreamAndPush(
+  workspaceId: string,
+  remoteName: string,
+  url: string,
+  branch?: string,
+): Promise<string> {
+  return invoke<string>("set_upstream_and_push", {
+    workspaceId,
+    remoteName,
+    url,
+    branch,
+  });
+}
+
+/** Status per workspace id; one failing repo doesn't fail the batch. */
+export async function getGitStatusBatch(
+  workspaceIds: string[],
+  summaryOnly = false,
+): Promise<Record<string, GitStatusBatchEntry<unknown>>> {
+  return invoke("get_git_status_batch", { workspaceIds, summaryOnly });
+}
+
+export async function validateWorkspaceGit(
+  workspaceId: string,
+): Promise<WorkspaceGitValidation> {
+  return invoke<WorkspaceGitValidation>("validate_workspace_git", { workspaceId });
+}
+
+export async function getConflictVersions(
+  workspaceId: string,
+  path: string,
+): Promise<GitConflictVersions> {
+  return invoke<GitConflictVersions>("get_conflict_versions", { workspaceId, path });
+}
+
+/** Saves the merged text for a conflicted file and stages it. */
+export async function resolveConflictWithContent(
+  workspaceId: string,
+  path: string,
+  content: string,
+): Promise<void> {
+  return invoke("resolve_conflict_with_content", { workspaceId, path, content });
+}
+
+/** Which ignore rule hides (or, for `!` patterns, re-includes) a path. */
+export async function explainIgnore(
+  workspaceId: string,
+  path: string,
+): Promise<IgnoreExplanation> {
+  return invoke<IgnoreExplanation>("explain_ignore", { workspaceId, path });
+}
+
+/** Note text for a commit, or `null` when it has none on that notes ref. */
+export async function getCommitNotes(
+  workspaceId: string,
+  sha: string,
+  notesRef?: string,
+): Promise<string | null> {
+  return invoke<string | null>("get_commit_notes", { workspaceId, sha, notesRef });
+}
+
+export async function getRepoStats(workspaceId: string): Promise<RepoStats> {
+  return invoke<RepoStats>("get_repo_stats", { workspaceId });
+}
+
+/** Clears a stale index.lock (never one a running git may own) and runs fsck. */
+export async function repairRepo(workspaceId: string): Promise<RepoRepairReport> {
+  return invoke<RepoRepairReport>("repair_repo", { workspaceId });
+}
+
+/**
+ * Runs `git gc`; `onProgress` receives its progress lines. Progress is local
+ * mode only: against a remote daemon only the final report comes back.
+ */
+export async function gcRepo(
+  workspaceId: string,
+  aggressive = false,
+  onProgress?: (line: string) => void,
+): Promise<GcReport> {
+  const channel = new Channel<string>();
+  if (onProgress) {
+    channel.onmessage = onProgress;
+  }
+  return invoke<GcReport>("gc_repo", { workspaceId, aggressive, onProgress: channel });
+}
+
+/** Forge URL for a file line at `rev` (default HEAD), pinned to its commit sha. */
+export async function buildPermalink(
+  workspaceId: string,
+  path: string,
+  line: number,
+  rev?: string | null,
+): Promise<string> {
+  return invoke<string>("build_permalink", { workspaceId, path, line, rev: rev ?? null });
+}
+
+export async function getGitChangeSummary(
+  workspaceId: string,
+): Promise<GitChangeSummary> {
+  return invoke<GitChangeSummary>("get_git_change_summary", { workspaceId });
+}
+
 export async function listGitRoots(
   workspace_id: string,
   depth: number,
@@ -353,28 +674,207 @@ export async function listGitRoots(
 
 export async function getGitDiffs(
   workspace_id: string,
+  whitespaceCheck = false,
+  fullFile = false,
 ): Promise<GitFileDiff[]> {
-  return invoke("get_git_diffs", { workspaceId: workspace_id });
+  return invoke("get_git_diffs", { workspaceId: workspace_id, whitespaceCheck, fullFile });
+}
+
+export async function getGitFileDiff(
+  workspaceId: string,
+  path: string,
+  whitespaceCheck = false,
+): Promise<GitFileDiff | null> {
+  return invoke<GitFileDiff | null>("get_git_file_diff", {
+    workspaceId,
+    path,
+    whitespaceCheck,
+  });
+}
+
+export async function streamGitDiffs(
+  workspaceId: string,
+  streamId: string,
+  onDiff: (diff: GitFileDiff) => void,
+  whitespaceCheck = false,
+): Promise<boolean> {
+  const channel = new Channel<GitFileDiff>();
+  channel.onmessage = onDiff;
+  return invoke<boolean>("stream_git_diffs", {
+    workspaceId,
+    streamId,
+    whitespaceCheck,
+    onDiff: channel,
+  });
+}
+
+export async function cancelGitDiffStream(streamId: string): Promise<void> {
+  return invoke("cancel_git_diff_stream", { streamId });
+}
+
+export async function getRefComparisonDiff(
+  workspaceId: string,
+  base: string,
+  head: string,
+): Promise<GitRefComparison> {
+  return invoke<GitRefComparison>("get_ref_comparison_diff", {
+    workspaceId,
+    base,
+    head,
+  });
+}
+
+/**
+ * Fetches all PR heads as `<remote>/pr/<n>`. Pass `refspec` for forges other
+ * than GitHub; `{remote}` in it is replaced with the remote name.
+ */
+export async function fetchAllOpenPrsAsBranches(
+  workspaceId: string,
+  remote?: string,
+  refspec?: string,
+): Promise<PullRequestRefsFetch> {
+  return invoke<PullRequestRefsFetch>("fetch_all_open_prs_as_branches", {
+    workspaceId,
+    remote,
+    refspec,
+  });
+}
+
+/** Commit a ref points to; annotated tags are peeled to their target. */
+export async function resolveRef(
+  workspaceId: string,
+  refName: string,
+): Promise<ResolvedRef> {
+  return invoke<ResolvedRef>("resolve_ref", { workspaceId, refName });
+}
+
+export async function getAheadBehindAgainst(
+  workspaceId: string,
+  otherRef: string,
+  limit?: number,
+): Promise<GitAheadBehind> {
+  return invoke<GitAheadBehind>("get_ahead_behind_against", {
+    workspaceId,
+    otherRef,
+    limit,
+  });
 }
 
 export async function getGitLog(
   workspace_id: string,
   limit = 40,
+  pathspec?: string[],
+  options?: { detail?: boolean; verify?: boolean },
 ): Promise<GitLogResponse> {
-  return invoke("get_git_log", { workspaceId: workspace_id, limit });
+  const payload: Record<string, unknown> = { workspaceId: workspace_id, limit };
+  if (pathspec && pathspec.length > 0) {
+    payload.pathspec = pathspec;
+  }
+  if (options?.detail) {
+    payload.detail = true;
+  }
+  if (options?.verify) {
+    payload.verify = true;
+  }
+  return invoke("get_git_log", payload);
 }
 
 export async function getGitCommitDiff(
   workspace_id: string,
   sha: string,
+  fullFile = false,
 ): Promise<GitCommitDiff[]> {
-  return invoke("get_git_commit_diff", { workspaceId: workspace_id, sha });
+  return invoke("get_git_commit_diff", { workspaceId: workspace_id, sha, fullFile });
+}
+
+/** Files changed between `baseRef` and the working tree; counts only, no patches. */
+export async function listChangedPathsAgainstRef(
+  workspace_id: string,
+  baseRef: string,
+): Promise<GitFileStatus[]> {
+  return invoke("list_changed_paths_against_ref", { workspaceId: workspace_id, baseRef });
+}
+
+/** Changes in `stash@{index}`, including untracked files stashed with `-u`. */
+export async function showStashDiff(
+  workspace_id: string,
+  index: number,
+): Promise<GitCommitDiff[]> {
+  return invoke("show_stash_diff", { workspaceId: workspace_id, index });
+}
+
+/** Applies `stash@{index}` and keeps it in the stash list. */
+export async function stashApplyGit(workspace_id: string, index: number): Promise<void> {
+  return invoke("stash_apply_git", { workspaceId: workspace_id, index });
 }
 
 export async function getGitRemote(workspace_id: string): Promise<string | null> {
   return invoke("get_git_remote", { workspaceId: workspace_id });
 }
 
+export async function diagnoseGit(): Promise<GitDiagnostics> {
+  return invoke<GitDiagnostics>("diagnose_git");
+}
+
+export async function diagnoseGitHub(): Promise<GitHubCliStatus> {
+  return invoke<GitHubCliStatus>("diagnose_github");
+}
+
+export async function readGitConfig(
+  workspaceId: string,
+  keys: string[],
+): Promise<GitConfigValues> {
+  return invoke<GitConfigValues>("read_git_config", { workspaceId, keys });
+}
+
+export async function writeGitConfig(
+  workspaceId: string,
+  key: string,
+  value: string,
+  scope: GitConfigScope = "local",
+): Promise<void> {
+  return invoke("write_git_config", { workspaceId, key, value, scope });
+}
+
+export async function bisectStart(
+  workspaceId: string,
+  bad: string,
+  good: string,
+): Promise<GitBisectState> {
+  return invoke<GitBisectState>("bisect_start", { workspaceId, bad, good });
+}
+
+export async function bisectMark(
+  workspaceId: string,
+  result: GitBisectResult,
+): Promise<GitBisectState> {
+  return invoke<GitBisectState>("bisect_mark", { workspaceId, result });
+}
+
+export async function bisectReset(workspaceId: string): Promise<GitBisectState> {
+  return invoke<GitBisectState>("bisect_reset", { workspaceId });
+}
+
+export async function getRepoOperationState(
+  workspaceId: string,
+): Promise<RepoOperation> {
+  return invoke<RepoOperation>("get_repo_operation_state", { workspaceId });
+}
+
+export async function operationContinue(
+  workspaceId: string,
+): Promise<RepoOperation> {
+  return invoke<RepoOperation>("operation_continue", { workspaceId });
+}
+
+export async function operationSkip(workspaceId: string): Promise<RepoOperation> {
+  return invoke<RepoOperation>("operation_skip", { workspaceId });
+}
+
+export async function operationAbort(workspaceId: string): Promise<RepoOperation> {
+  return invoke<RepoOperation>("operation_abort", { workspaceId });
+}
+
 export async function stageGitFile(workspaceId: string, path: string) {
   return invoke("stage_git_file", { workspaceId, path });
 }
@@ -383,6 +883,20 @@ export async function stageGitAll(workspaceId: string): Promise<void> {
   return invoke("stage_git_all", { workspaceId });
 }
 
+export async function stageGitPattern(
+  workspaceId: string,
+  pathspec: string[],
+): Promise<void> {
+  return invoke("stage_git_pattern", { workspaceId, pathspec });
+}
+
+export async function unstageGitPattern(
+  workspaceId: string,
+  pathspec: string[],
+): Promise<void> {
+  return invoke("unstage_git_pattern", { workspaceId, pathspec });
+}
+
 export async function unstageGitFile(workspaceId: string, path: string) {
   return invoke("unstage_git_file", { workspaceId, path });
 }
@@ -391,27 +905,134 @@ export async function revertGitFile(workspaceId: string, path: string) {
   return invoke("revert_git_file", { workspaceId, path });
 }
 
-export async function revertGitAll(workspaceId: string) {
+export async function restoreFileFromRev(
+  workspaceId: string,
+  path: string,
+  rev: string,
+  force = false,
+): Promise<void> {
+  return invoke("restore_file_from_rev", { workspaceId, path, rev, force });
+}
+
+export async function revertGitAll(
+  workspaceId: string,
+  overrideProtection = false,
+) {
+  if (overrideProtection) {
+    return invoke("revert_git_all", { workspaceId, overrideProtection });
+  }
   return invoke("revert_git_all", { workspaceId });
 }
 
 export async function commitGit(
   workspaceId: string,
   message: string,
+  paths?: string[],
 ): Promise<void> {
+  if (paths && paths.length > 0) {
+    return invoke("commit_git", { workspaceId, message, paths });
+  }
   return invoke("commit_git", { workspaceId, message });
 }
 
-export async function pushGit(workspaceId: string): Promise<void> {
-  return invoke("push_git", { workspaceId });
+/** Stage (optionally), commit and push; a failed push keeps the commit. */
+export async function commitAndPush(
+  workspaceId: string,
+  message: string,
+  stageAll = false,
+  coAuthors: string[] = [],
+  overrideProtection = false,
+): Promise<CommitAndPushResult> {
+  return invoke<CommitAndPushResult>("commit_and_push", {
+    workspaceId,
+    message,
+    stageAll,
+    coAuthors,
+    overrideProtection,
+  });
+}
+
+/** Commits with a subject and optional body; an overlong subject only warns. */
+export async function commitWithBody(
+  workspaceId: string,
+  subject: string,
+  body?: string,
+): Promise<CommitResult> {
+  return invoke<CommitResult>("commit_with_body", { workspaceId, subject, body });
+}
+
+export async function rewordHeadCommit(
+  workspaceId: string,
+  message: string,
+  allowMerge = false,
+  overrideProtection = false,
+): Promise<string> {
+  return invoke<string>("reword_head_commit", {
+    workspaceId,
+    message,
+    allowMerge,
+    overrideProtection,
+  });
+}
+
+export async function resetHeadAuthor(
+  workspaceId: string,
+  name: string,
+  email: string,
+  overrideProtection = false,
+): Promise<string> {
+  return invoke<string>("reset_head_author", {
+    workspaceId,
+    name,
+    email,
+    overrideProtection,
+  });
+}
+
+export async function resetHeadDate(
+  workspaceId: string,
+  timestamp: number,
+  overrideProtection = false,
+): Promise<string> {
+  return invoke<string>("reset_head_date", {
+    workspaceId,
+    timestamp,
+    overrideProtection,
+  });
+}
+
+export const PRE_PUSH_HOOK_FAILED_PREFIX = "Pre-push hook failed";
+
+export async function pushGit(
+  workspaceId: string,
+  noVerify?: boolean,
+): Promise<void> {
+  return invoke("push_git", { workspaceId, noVerify });
 }
 
 export async function pullGit(workspaceId: string): Promise<void> {
   return invoke("pull_git", { workspaceId });
 }
 
-export async function fetchGit(workspaceId: string): Promise<void> {
-  return invoke("fetch_git", { workspaceId });
+export async function fetchGit(
+  workspaceId: string,
+  fetchDepth?: number,
+): Promise<void> {
+  return invoke("fetch_git", { workspaceId, fetchDepth });
+}
+
+export async function fetchGitUnshallow(workspaceId: string): Promise<void> {
+  return invoke("fetch_git_unshallow", { workspaceId });
+}
+
+export async function fetchAllGit(
+  workspaceId: string,
+): Promise<RemoteFetchResult[]> {
+  return invoke<RemoteFetchResult[]>("fetch_all_git", { workspaceId });
+}
+
+export async function previewSyncGit(workspaceId: string): Promise<GitSyncPreview> {
+  return invoke<GitSyncPreview>("preview_sync_git", { workspaceId });
 }
 
 export async function syncGit(workspaceId: string): Promise<void> {
@@ -430,6 +1051,16 @@ export async function getGitHubPullRequests(
   return invoke("get_github_pull_requests", { workspaceId: workspace_id });
 }
 
+export async function getGitHubPullRequest(
+  workspace_id: string,
+  prNumber: number,
+): Promise<GitHubPullRequest> {
+  return invoke("get_github_pull_request", {
+    workspaceId: workspace_id,
+    prNumber,
+  });
+}
+
 export async function getGitHubPullRequestDiff(
   workspace_id: string,
   prNumber: number,
@@ -440,6 +1071,16 @@ export async function getGitHubPullRequestDiff(
   });
 }
 
+export async function getGitHubPullRequestRawDiff(
+  workspace_id: string,
+  prNumber: number,
+): Promise<string> {
+  return invoke("get_github_pull_request_raw_diff", {
+    workspaceId: workspace_id,
+    prNumber,
+  });
+}
+
 export async function getGitHubPullRequestComments(
   workspace_id: string,
   prNumber: number,
@@ -498,6 +1139,16 @@ export async function setCodexAuthStoreFile(): Promise<void> {
   await invoke("codex_auth_store_set_file");
 }
 
+export async function migrateCodexAuthStore(target: CodexAuthStore) {
+  return invoke<{
+    ok: boolean;
+    changed: boolean;
+    from: CodexAuthStore;
+    to: CodexAuthStore;
+    warnings: string[];
+  }>("codex_auth_store_migrate", { target });
+}
+
 export async function snapshotAuthProfile(profileId: string) {
   return invoke<{ ok: boolean; missing?: boolean }>(
     "codex_auth_profile_snapshot",
@@ -616,6 +1267,26 @@ export async function updateAppSettings(settings: AppSettings): Promise<AppSetti
   return invoke<AppSettings>("update_app_settings", { settings });
 }
 
+/** Re-reads config.toml; also broadcast as `settings-changed`. */
+export async function reloadCodexConfig(): Promise<CodexConfigReload> {
+  return invoke<CodexConfigReload>("reload_codex_config");
+}
+
+/** Current settings as a shareable profile; secrets are left out by default. */
+export async function exportSettingsProfile(
+  includeSecrets = false,
+): Promise<SettingsProfileExport> {
+  return invoke<SettingsProfileExport>("export_settings_profile", { includeSecrets });
+}
+
+/** Applies a profile; `merge: false` resets settings it doesn't mention. */
+export async function importSettingsProfile(
+  profile: unknown,
+  merge = true,
+): Promise<AppSettings> {
+  return invoke<AppSettings>("import_settings_profile", { profile, merge });
+}
+
 type MenuAcceleratorUpdate = {
   id: string;
   accelerator: string | null;
@@ -660,14 +1331,90 @@ export async function listGitBranches(workspaceId: string) {
   return invoke<any>("list_git_branches", { workspaceId });
 }
 
+export async function listRemoteBranches(workspaceId: string) {
+  return invoke<any>("list_remote_branches", { workspaceId });
+}
+
+export async function pruneRemoteBranches(
+  workspaceId: string,
+  remote: string,
+): Promise<string[]> {
+  return invoke<string[]>("prune_remote_branches", { workspaceId, remote });
+}
+
 export async function checkoutGitBranch(workspaceId: string, name: string) {
   return invoke("checkout_git_branch", { workspaceId, name });
 }
 
+/** Branches most recently checked out first (HEAD reflog order). */
+export async function listRecentBranches(
+  workspaceId: string,
+  limit?: number,
+): Promise<RecentBranch[]> {
+  return invoke<RecentBranch[]>("list_recent_branches", { workspaceId, limit });
+}
+
+/** Checks out a commit with a detached HEAD; `force` discards tracked changes. */
+export async function checkoutCommit(
+  workspaceId: string,
+  sha: string,
+  force = false,
+): Promise<CheckoutCommitResult> {
+  return invoke<CheckoutCommitResult>("checkout_commit", { workspaceId, sha, force });
+}
+
+export async function getGitUndoStack(workspaceId: string): Promise<GitUndoEntry[]> {
+  return invoke<GitUndoEntry[]>("get_git_undo_stack", { workspaceId });
+}
+
+export async function undoLastGitAction(
+  workspaceId: string,
+  confirm: boolean,
+  overrideProtection = false,
+): Promise<GitUndoEntry> {
+  return invoke<GitUndoEntry>("undo_last_git_action", {
+    workspaceId,
+    confirm,
+    overrideProtection,
+  });
+}
+
+export async function getDefaultBranch(
+  workspaceId: string,
+  refresh = false,
+): Promise<DefaultBranch> {
+  return invoke<DefaultBranch>("get_default_branch", { workspaceId, refresh });
+}
+
 export async function createGitBranch(workspaceId: string, name: string) {
   return invoke("create_git_branch", { workspaceId, name });
 }
 
+/**
+ * Switches to a new branch, keeping uncommitted changes. With `startPoint` the
+ * switch can conflict; pass `stash` to carry changes over via a stash.
+ */
+export async function moveChangesToNewBranch(
+  workspaceId: string,
+  name: string,
+  options?: { startPoint?: string; stash?: boolean; overrideProtection?: boolean },
+): Promise<string> {
+  return invoke<string>("move_changes_to_new_branch", {
+    workspaceId,
+    name,
+    startPoint: options?.startPoint ?? null,
+    stash: options?.stash ?? false,
+    overrideProtection: options?.overrideProtection ?? false,
+  });
+}
+
+export async function createBranchAtHead(
+  workspaceId: string,
+  name: string,
+): Promise<string> {
+  return invoke<string>("create_branch_at_head", { workspaceId, name });
+}
+
 function withModelId(modelId?: string | null) {
   return modelId ? { modelId } : {};
 }
@@ -8,6 +8,11 @@ export type WorkspaceSettings = {
   launchScript?: string | null;
   launchScripts?: LaunchScriptEntry[] | null;
   worktreeSetupScript?: string | null;
+  diffExcludeGlobs?: string[];
+  protectedBranches?: string[];
+  autoFetchIntervalSecs?: number | null;
+  /** Extra env for git/gh/codex runs; listed values come back masked. */
+  env?: Record<string, string>;
 };
 
 export type LaunchScriptIconId =
@@ -39,6 +44,8 @@ export type WorkspaceGroup = {
   name: string;
   sortOrder?: number | null;
   copiesFolder?: string | null;
+  color?: string | null;
+  collapsed?: boolean;
 };
 
 export type WorkspaceKind = "main" | "worktree";
@@ -59,6 +66,9 @@ export type WorkspaceInfo = {
   settings: WorkspaceSettings;
 };
 
+/** Unsaved workspace stub returned by workspace discovery. */
+export type DiscoveredWorkspace = Omit<WorkspaceInfo, "connected">;
+
 export type AppServerEvent = {
   workspace_id: string;
   message: Record<string, unknown>;
@@ -113,6 +123,8 @@ export type ReviewTarget =
 
 export type AccessMode = "read-only" | "current" | "full-access";
 export type BackendMode = "local" | "remote";
+
+export type RemoteBackendProvider = "tcp" | "unix";
 export type ThemePreference = "system" | "light" | "dark" | "dim" | "xp";
 export type PersonalityPreference = "friendly" | "pragmatic";
 
@@ -156,6 +168,10 @@ export type AppSettings = {
   activeCodexProfileId: string | null;
   backendMode: BackendMode;
   remoteBackendHost: string;
+  remoteBackendProvider?: RemoteBackendProvider;
+  remoteKeepaliveIntervalSecs?: number;
+  remoteKeepaliveTimeoutSecs?: number;
+  remoteKeepaliveMaxMissed?: number;
   remoteBackendToken: string | null;
   defaultAccessMode: AccessMode;
   reviewDeliveryMode: "inline" | "detached";
@@ -189,7 +205,8 @@ export type AppSettings = {
   notificationSoundsEnabled: boolean;
   systemNotificationsEnabled: boolean;
   preloadGitDiffs: boolean;
-  gitDiffIgnoreWhitespaceChanges: boolean;
+  gitDiffWhitespace: DiffWhitespace;
+  gitDiffNormalizeLineEndings?: boolean;
   experimentalCollabEnabled: boolean;
   collaborationModesEnabled: boolean;
   steerEnabled: boolean;
@@ -212,6 +229,14 @@ export type AppSettings = {
   workspaceGroups: WorkspaceGroup[];
   openAppTargets: OpenAppTarget[];
   selectedOpenAppId: string;
+  editorCommand?: string | null;
+  ghMaxAttempts?: number;
+  largeFileWarnBytes?: number | null;
+  gitBackend?: GitBackend;
+  commitSubjectMaxLen?: number;
+  maxConcurrentGitOps?: number;
+  diffContextLines?: number;
+  configWriteErrors?: string[];
 };
 
 export type CodexDoctorResult = {
@@ -267,11 +292,27 @@ export type RequestUserInputResponse = {
   answers: Record<string, RequestUserInputAnswer>;
 };
 
+export type DiffWhitespace = "none" | "ignoreEol" | "ignoreChange" | "ignoreAll";
+
+export type GitBackend = "libgit2" | "systemGit" | "auto";
+
 export type GitFileStatus = {
   path: string;
   status: string;
   additions: number;
   deletions: number;
+  isDir?: boolean;
+  isLarge?: boolean;
+  size?: number;
+};
+
+export type GitUntrackedMode = "all" | "normal" | "folders";
+
+export type BinaryDiffSummary = {
+  change: string;
+  oldSize: number | null;
+  newSize: number | null;
+  hexPreview: string | null;
 };
 
 export type GitFileDiff = {
@@ -285,6 +326,11 @@ export type GitFileDiff = {
   newImageData?: string | null;
   oldImageMime?: string | null;
   newImageMime?: string | null;
+  binarySummary?: BinaryDiffSummary;
+  encoding?: string;
+  lineEnding?: "lf" | "crlf" | "mixed" | "none";
+  submoduleCommits?: GitLogEntry[];
+  whitespaceErrors?: { line: number; kinds: string[] }[];
 };
 
 export type GitCommitDiff = {
@@ -299,6 +345,8 @@ export type GitCommitDiff = {
   newImageData?: string | null;
   oldImageMime?: string | null;
   newImageMime?: string | null;
+  binarySummary?: BinaryDiffSummary;
+  submoduleCommits?: GitLogEntry[];
 };
 
 export type GitLogEntry = {
@@ -306,18 +354,53 @@ export type GitLogEntry = {
   summary: string;
   author: string;
   timestamp: number;
+  timezoneOffset?: number;
+  body?: string;
+  filesChanged?: string[];
+  additions?: number;
+  deletions?: number;
+  signatureStatus?: "Good" | "Untrusted" | "Bad" | "Unknown" | "None";
+  signer?: string | null;
+  hasNotes?: boolean;
 };
 
 export type GitLogResponse = {
-  total: number;
+  // Null for pathspec-filtered logs; check `hasMore` instead.
+  total: number | null;
+  hasMore?: boolean;
   entries: GitLogEntry[];
   ahead: number;
   behind: number;
   aheadEntries: GitLogEntry[];
   behindEntries: GitLogEntry[];
   upstream: string | null;
+  shallow?: boolean;
+};
+
+export type GitUndoEntry = {
+  action: string;
+  kind: "resetHead" | "checkout" | "stashPop";
+  head: string;
+  branch: string | null;
+  stash: string | null;
+  after: string | null;
+  recordedAt: number;
 };
 
+export type RemoteFetchResult = {
+  remote: string;
+  ok: boolean;
+  error: string | null;
+};
+
+export type RepoOperation =
+  | { kind: "none" }
+  | { kind: "merge" }
+  | { kind: "rebase"; step: number | null; total: number | null }
+  | { kind: "cherryPick" }
+  | { kind: "revert" }
+  | { kind: "bisect" };
+
 export type GitHubIssue = {
   number: number;
   title: string;
@@ -345,6 +428,19 @@ export type GitHubPullRequest = {
   baseRefName: string;
   isDraft: boolean;
   author: GitHubUser | null;
+  mergeable?: "MERGEABLE" | "CONFLICTING" | "UNKNOWN" | null;
+  reviewDecision?: "APPROVED" | "CHANGES_REQUESTED" | "REVIEW_REQUIRED" | null;
+  statusCheckRollup?: GitHubStatusCheck[];
+};
+
+export type GitHubStatusCheck = {
+  name: string | null;
+  context: string | null;
+  status: string | null;
+  conclusion: string | null;
+  state: string | null;
+  detailsUrl: string | null;
+  targetUrl: string | null;
 };
 
 export type GitHubPullRequestsResponse = {
@@ -356,6 +452,7 @@ export type GitHubPullRequestDiff = {
   path: string;
   status: string;
   diff: string;
+  isBinary?: boolean;
 };
 
 export type GitHubPullRequestComment = {
@@ -505,6 +602,7 @@ export type CustomPromptOption = {
 export type BranchInfo = {
   name: string;
   lastCommit: number;
+  lastCommitOffset?: number;
 };
 
 export type DebugEntry = {
@@ -545,3 +643,257 @@ export type DictationTranscript = {
   id: string;
   text: string;
 };
+
+export type TextFileRangeResponse = {
+  exists: boolean;
+  content: string;
+  offset: number;
+  nextOffset: number;
+  totalSize: number;
+  hasMore: boolean;
+};
+
+export type TailFileResponse = {
+  exists: boolean;
+  content: string;
+  offset: number;
+  truncated: boolean;
+  reset: boolean;
+};
+
+export type FileTailLines = {
+  lines: string[];
+  offset: number;
+  reset: boolean;
+};
+
+export type FileScope = "workspace" | "global";
+
+export type FileKind = "agents" | "config";
+
+export type FileEdit = {
+  scope: FileScope;
+  kind: FileKind;
+  workspaceId?: string;
+  content: string;
+};
+
+export type EffectiveCodexConfig = {
+  path: string;
+  exists: boolean;
+  profile: string | null;
+  profileSource: "args" | "config" | null;
+  profileFound: boolean;
+  config: Record<string, unknown>;
+  sources: Record<string, "base" | "profile">;
+};
+
+export type FeatureFlag = {
+  key: string;
+  enabled: boolean;
+};
+
+export type CodexConfigDiff = {
+  basePath: string;
+  otherPath: string;
+  samePath: boolean;
+  added: Record<string, unknown>;
+  removed: Record<string, unknown>;
+  changed: Record<string, { from: unknown; to: unknown }>;
+};
+
+export type OpenPathMode = "editor" | "terminal" | "fileManager";
+
+export type WorkingTreeCleanliness = {
+  clean: boolean;
+  staged: number;
+  unstaged: number;
+  untracked: number;
+  conflicted: number;
+};
+
+export type GitStatusBatchEntry<T> = { status: T } | { error: string };
+
+export type WorkspaceGitValidation = {
+  ok: boolean;
+  repoRoot: string | null;
+  isBare: boolean;
+  headState: "branch" | "detached" | "unborn" | "unknown" | null;
+  error: string | null;
+  gitRootOverride: { path: string; valid: boolean; error: string | null } | null;
+};
+
+export type GitConflictVersion = {
+  present: boolean;
+  lines: string[] | null;
+  isBinary: boolean;
+  tooLarge: boolean;
+};
+
+export type GitConflictVersions = {
+  path: string;
+  base: GitConflictVersion;
+  ours: GitConflictVersion;
+  theirs: GitConflictVersion;
+};
+
+export type IgnoreExplanation = {
+  ignored: boolean;
+  source: string | null;
+  line: number | null;
+  pattern: string | null;
+};
+
+export type RepoStats = {
+  commitCount: number;
+  branchCount: number;
+  remoteBranchCount: number;
+  tagCount: number;
+  trackedFiles: number;
+  worktreeSize: number;
+  gitDirSize: number;
+};
+
+export type RepoRepairReport = {
+  indexLock: {
+    found: boolean;
+    ageSecs: number | null;
+    removed: boolean;
+    note: string | null;
+  };
+  fsckOk: boolean;
+  fsckOutput: string;
+};
+
+export type GcReport = {
+  gitDirSizeBefore: number;
+  gitDirSizeAfter: number;
+};
+
+export type GitChangeSummary = {
+  changedFiles: number;
+  staged: number;
+  unstaged: number;
+  conflicted: number;
+};
+
+export type GitRefComparison = {
+  base: string;
+  head: string;
+  diffs: GitCommitDiff[];
+  commits: GitLogEntry[];
+};
+
+export type PullRequestRefsFetch = {
+  remote: string;
+  refspec: string;
+  supported: boolean;
+  created: string[];
+  updated: string[];
+  total: number;
+};
+
+export type ResolvedRef = {
+  sha: string;
+  shortSha: string;
+  refType: "Branch" | "Tag" | "Commit" | "Remote";
+  commit: GitLogEntry;
+};
+
+export type GitAheadBehind = {
+  otherRef: string;
+  otherSha: string;
+  ahead: number;
+  behind: number;
+  aheadEntries: GitLogEntry[];
+  behindEntries: GitLogEntry[];
+};
+
+export type GitDiagnostics = {
+  ok: boolean;
+  path: string | null;
+  resolveError: string | null;
+  executable: boolean;
+  version: string | null;
+  versionError: string | null;
+  onPath: boolean;
+  envPath: string;
+  guidance: string | null;
+};
+
+export type GitHubCliStatus = {
+  installed: boolean;
+  authenticated: boolean;
+  login: string | null;
+  version: string | null;
+  error: string | null;
+};
+
+export type GitConfigScope = "local" | "global";
+
+export type GitConfigValues = Record<
+  string,
+  { value: string | null; scope: string } | null
+>;
+
+export type GitBisectResult = "good" | "bad" | "skip";
+
+export type GitBisectState = {
+  current: GitLogEntry | null;
+  remainingRevisions: number | null;
+  remainingSteps: number | null;
+  firstBad: string | null;
+  done: boolean;
+  output: string;
+};
+
+export type CommitResult = {
+  warnings: string[];
+};
+
+export type CommitAndPushResult = {
+  staged: boolean;
+  committed: boolean;
+  pushed: boolean;
+  commitSha: string | null;
+  failedStep: "stage" | "commit" | "push" | null;
+  error: string | null;
+};
+
+export type GitSyncPreview = {
+  upstream: string | null;
+  ahead: number;
+  behind: number;
+  wouldConflict: boolean;
+  conflictingPaths: string[];
+};
+
+export type CodexAuthStore = "file" | "keyring" | "auto";
+
+export type SettingsProfileExport = {
+  profile: { version: number; settings: Partial<AppSettings> };
+  omitted: string[];
+};
+
+export type CodexConfigReload = {
+  settings: AppSettings;
+  authStore: string | null;
+  changed: boolean;
+};
+
+export type RecentBranch = {
+  name: string;
+  lastUsed: number;
+};
+
+export type CheckoutCommitResult = {
+  detachedHead: boolean;
+  headSha: string;
+  summary: string;
+  previousBranch: string | null;
+};
+
+export type DefaultBranch = {
+  name: string;
+  inferred: boolean;
+};